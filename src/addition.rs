@@ -223,7 +223,6 @@ impl<
 }
 
 #[cfg(test)]
-
 mod test {
 
     use crate::layouts::RowMajor;
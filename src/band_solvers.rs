@@ -0,0 +1,702 @@
+//! Products and solvers for banded and tridiagonal matrices.
+//!
+//! Finite difference and finite element discretisations frequently lead to matrices
+//! whose nonzero elements are confined to a narrow band around the diagonal. For these
+//! matrices the dense algorithms are prohibitive in memory and time. This module
+//! provides the following routines for a [BandedMatrix] with `kl` subdiagonals and `ku`
+//! superdiagonals and for a [TridiagonalMatrix].
+//!
+//! - Band matrix-vector products `y = alpha * op(A) * x + beta * y` (Blas `gbmv`) through
+//!   [BandedMatrix::matvec] and [TridiagonalMatrix::matvec].
+//! - The LU decomposition with partial pivoting of a square banded matrix (Lapack `gbtrf`)
+//!   through [BandedMatrix::lu]. The resulting [BandedLu] solves linear systems with the
+//!   matrix, its transpose or its conjugate transpose (Lapack `gbtrs`). Row interchanges
+//!   increase the number of superdiagonals of the upper triangular factor to `kl + ku`.
+//! - The Thomas algorithm for tridiagonal systems through [TridiagonalMatrix::solve_thomas].
+//!   It does not pivot and is therefore only stable for e.g. diagonally dominant matrices.
+//! - The $LDL^H$ decomposition of a symmetric (Hermitian for complex types) tridiagonal
+//!   matrix (Lapack `pttrf`) through [TridiagonalMatrix::ldlt].
+//!
+//! All solvers overwrite a right-hand side matrix of dimension `(n, nrhs)` in place.
+//! A [LinAlgError::SingularMatrix] is returned if a zero pivot is encountered. The
+//! factorisations estimate their reciprocal condition number through `rcond`, see
+//! [norms](crate::norms).
+
+use crate::dense_solvers::{lu_rcond, lu_solve, LuFactors};
+use crate::error::LinAlgError;
+use crate::matrix::*;
+use crate::norms::{check_condition, inverse_norm_1_estimate, reciprocal_condition};
+use crate::traits::*;
+use crate::types::*;
+
+/// Compute `y = alpha * op(A) * x + beta * y` for a matrix `A` with `kl` subdiagonals
+/// and `ku` superdiagonals.
+#[allow(clippy::too_many_arguments)]
+fn band_matvec<
+    Item: HScalar,
+    MatImpl1: MatrixTrait<Item, L1, Dynamic, Dynamic>,
+    MatImpl2: MatrixTrait<Item, L2, RS2, CS2>,
+    MatImpl3: MatrixTraitMut<Item, L3, RS3, CS3>,
+    L1: LayoutType,
+    L2: LayoutType,
+    L3: LayoutType,
+    RS2: SizeIdentifier,
+    CS2: SizeIdentifier,
+    RS3: SizeIdentifier,
+    CS3: SizeIdentifier,
+>(
+    mat: &Matrix<Item, MatImpl1, L1, Dynamic, Dynamic>,
+    kl: IndexType,
+    ku: IndexType,
+    trans: TransposeMode,
+    alpha: Item,
+    vec_x: &Matrix<Item, MatImpl2, L2, RS2, CS2>,
+    beta: Item,
+    vec_y: &mut Matrix<Item, MatImpl3, L3, RS3, CS3>,
+) {
+    let (m, n) = mat.layout().dim();
+    let (len_x, len_y) = match trans {
+        TransposeMode::NoTrans => (n, m),
+        _ => (m, n),
+    };
+    assert!(
+        (vec_x.layout().number_of_elements() == len_x)
+            & (vec_y.layout().number_of_elements() == len_y),
+        "Incompatible dimensions for y = A * x: A = {:#?}, x = {:#?}, y = {:#?}",
+        (m, n),
+        vec_x.layout().dim(),
+        vec_y.layout().dim()
+    );
+
+    unsafe {
+        for index in 0..len_y {
+            let elem = vec_y.get1d_unchecked_mut(index);
+            *elem = if beta == Item::zero() {
+                Item::zero()
+            } else {
+                beta * *elem
+            };
+        }
+
+        for col in 0..n {
+            let rows = col.saturating_sub(ku)..m.min(col + kl + 1);
+            match trans {
+                TransposeMode::NoTrans => {
+                    let value = alpha * vec_x.get1d_unchecked(col);
+                    for row in rows {
+                        *vec_y.get1d_unchecked_mut(row) += mat.get_unchecked(row, col) * value;
+                    }
+                }
+                _ => {
+                    let mut value = Item::zero();
+                    for row in rows {
+                        let elem = mat.get_unchecked(row, col);
+                        let elem = if trans == TransposeMode::ConjTrans {
+                            elem.conj()
+                        } else {
+                            elem
+                        };
+                        value += elem * vec_x.get1d_unchecked(row);
+                    }
+                    *vec_y.get1d_unchecked_mut(col) += alpha * value;
+                }
+            }
+        }
+    }
+}
+
+/// Check that a right-hand side with dimension `dim` is compatible with an `n x n` system.
+fn check_rhs(n: IndexType, dim: (IndexType, IndexType)) {
+    assert_eq!(
+        n, dim.0,
+        "Right-hand side has {} rows but system has dimension {}.",
+        dim.0, n
+    );
+}
+
+/// The LU decomposition with partial pivoting of a square banded matrix.
+///
+/// The decomposition has the form `A = P * L * U`, where `L` is unit lower triangular
+/// with `kl` subdiagonals and `U` is upper triangular with `kl + ku` superdiagonals.
+/// Both factors are stored in a single [BandedMatrix] as in Lapack `gbtrf`.
+pub struct BandedLu<Item: HScalar> {
+    factor: BandedMatrix<Item>,
+    pivots: Vec<IndexType>,
+}
+
+impl<Item: HScalar> BandedLu<Item> {
+    /// Return the combined `L` and `U` factors. The strict lower part
+    /// holds the multipliers of `L`.
+    pub fn factor(&self) -> &BandedMatrix<Item> {
+        &self.factor
+    }
+
+    /// Return the pivot indices. Row `i` was interchanged with row `pivots[i]`.
+    pub fn pivots(&self) -> &[IndexType] {
+        &self.pivots
+    }
+
+    /// Solve `op(A) * X = B` in place, where `rhs` contains `B` on input
+    /// and `X` on output.
+    pub fn solve<
+        MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    >(
+        &self,
+        trans: TransposeMode,
+        rhs: &mut Matrix<Item, MatImpl, L, RS, CS>,
+    ) {
+        lu_solve(self, trans, rhs);
+    }
+}
+
+impl<Item: HScalar> BandedLu<Item> {
+    /// Estimate the reciprocal condition number `1 / (||A||_1 * ||A^{-1}||_1)`, where
+    /// `anorm` is the 1-norm of the factorised matrix (Lapack `gbcon`).
+    pub fn rcond(&self, anorm: <Item as Scalar>::Real) -> <Item as Scalar>::Real {
+        lu_rcond(self, anorm)
+    }
+
+    /// Solve `op(A) * X = B` in place if the estimated reciprocal condition number is at
+    /// least `threshold` and return the estimate. Otherwise `rhs` is not modified and a
+    /// [LinAlgError::IllConditioned] is returned.
+    pub fn solve_checked<
+        MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    >(
+        &self,
+        trans: TransposeMode,
+        rhs: &mut Matrix<Item, MatImpl, L, RS, CS>,
+        anorm: <Item as Scalar>::Real,
+        threshold: <Item as Scalar>::Real,
+    ) -> Result<<Item as Scalar>::Real, LinAlgError> {
+        let rcond = check_condition(self.rcond(anorm), threshold)?;
+        self.solve(trans, rhs);
+        Ok(rcond)
+    }
+}
+
+impl<Item: HScalar> LuFactors<Item> for BandedLu<Item> {
+    fn pivot_indices(&self) -> &[IndexType] {
+        &self.pivots
+    }
+
+    fn bandwidths(&self) -> (IndexType, IndexType) {
+        (self.factor.layout().kl(), self.factor.layout().ku())
+    }
+
+    fn interchanged_multipliers(&self) -> bool {
+        false
+    }
+
+    unsafe fn factor_unchecked(&self, row: IndexType, col: IndexType) -> Item {
+        self.factor.get_unchecked(row, col)
+    }
+}
+
+impl<Item: HScalar> BandedMatrix<Item> {
+    /// Compute `y = alpha * op(A) * x + beta * y`. The vectors are accessed
+    /// through their one-dimensional indexing.
+    pub fn matvec<
+        MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+        MatImpl2: MatrixTraitMut<Item, L2, RS2, CS2>,
+        L1: LayoutType,
+        L2: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    >(
+        &self,
+        trans: TransposeMode,
+        alpha: Item,
+        vec_x: &Matrix<Item, MatImpl1, L1, RS1, CS1>,
+        beta: Item,
+        vec_y: &mut Matrix<Item, MatImpl2, L2, RS2, CS2>,
+    ) {
+        let (kl, ku) = (self.layout().kl(), self.layout().ku());
+        band_matvec(self, kl, ku, trans, alpha, vec_x, beta, vec_y);
+    }
+
+    /// Compute the LU decomposition with partial pivoting of a square banded matrix.
+    pub fn lu(&self) -> Result<BandedLu<Item>, LinAlgError> {
+        let (n, cols) = self.layout().dim();
+        assert_eq!(
+            n,
+            cols,
+            "LU decomposition requires a square matrix. dim = {:#?}",
+            (n, cols)
+        );
+        let kl = self.layout().kl();
+        let ku = self.layout().ku();
+        let kv = kl + ku;
+
+        // The factor needs kl additional superdiagonals for the fill-in from row interchanges.
+        let mut factor = BandedMatrix::<Item>::zeros_from_band(n, n, kl, kv);
+        for col in 0..n {
+            for row in col.saturating_sub(ku)..n.min(col + kl + 1) {
+                unsafe { *factor.get_unchecked_mut(row, col) = self.get_unchecked(row, col) };
+            }
+        }
+
+        let mut pivots = Vec::with_capacity(n);
+        // Index of the last column affected by the row interchanges so far.
+        let mut last_col = 0;
+
+        unsafe {
+            for col in 0..n {
+                let last_row = n.min(col + kl + 1);
+
+                let mut pivot = col;
+                let mut max_value = factor.get_unchecked(col, col).abs();
+                for row in col + 1..last_row {
+                    let value = factor.get_unchecked(row, col).abs();
+                    if value > max_value {
+                        pivot = row;
+                        max_value = value;
+                    }
+                }
+                pivots.push(pivot);
+
+                if factor.get_unchecked(pivot, col) == Item::zero() {
+                    return Err(LinAlgError::SingularMatrix(col));
+                }
+
+                last_col = last_col.max((pivot + ku).min(n - 1));
+
+                if pivot != col {
+                    for c in col..last_col + 1 {
+                        let tmp = factor.get_unchecked(pivot, c);
+                        *factor.get_unchecked_mut(pivot, c) = factor.get_unchecked(col, c);
+                        *factor.get_unchecked_mut(col, c) = tmp;
+                    }
+                }
+
+                let diag = factor.get_unchecked(col, col);
+                for row in col + 1..last_row {
+                    *factor.get_unchecked_mut(row, col) /= diag;
+                }
+
+                for c in col + 1..last_col + 1 {
+                    let value = factor.get_unchecked(col, c);
+                    if value == Item::zero() {
+                        continue;
+                    }
+                    for row in col + 1..last_row {
+                        let multiplier = factor.get_unchecked(row, col);
+                        *factor.get_unchecked_mut(row, c) -= multiplier * value;
+                    }
+                }
+            }
+        }
+
+        Ok(BandedLu { factor, pivots })
+    }
+}
+
+/// The $LDL^H$ decomposition of a symmetric (Hermitian) tridiagonal matrix.
+///
+/// `L` is unit lower bidiagonal and `D` is diagonal. No pivoting is performed.
+pub struct TridiagonalLdlt<Item: HScalar> {
+    diag: Vec<Item>,
+    sub: Vec<Item>,
+}
+
+impl<Item: HScalar> TridiagonalLdlt<Item> {
+    /// Return the diagonal of `D`.
+    pub fn d(&self) -> &[Item] {
+        &self.diag
+    }
+
+    /// Return the subdiagonal of `L`.
+    pub fn l(&self) -> &[Item] {
+        &self.sub
+    }
+
+    /// Solve `A * X = B` in place, where `rhs` contains `B` on input and `X` on output.
+    pub fn solve<
+        MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    >(
+        &self,
+        rhs: &mut Matrix<Item, MatImpl, L, RS, CS>,
+    ) {
+        let n = self.diag.len();
+        let dim = rhs.layout().dim();
+        check_rhs(n, dim);
+
+        for rhs_col in 0..dim.1 {
+            unsafe {
+                for row in 1..n {
+                    let value = self.sub[row - 1] * rhs.get_unchecked(row - 1, rhs_col);
+                    *rhs.get_unchecked_mut(row, rhs_col) -= value;
+                }
+                for row in 0..n {
+                    *rhs.get_unchecked_mut(row, rhs_col) /= self.diag[row];
+                }
+                for row in (0..n.saturating_sub(1)).rev() {
+                    let value = self.sub[row].conj() * rhs.get_unchecked(row + 1, rhs_col);
+                    *rhs.get_unchecked_mut(row, rhs_col) -= value;
+                }
+            }
+        }
+    }
+}
+
+impl<Item: HScalar> TridiagonalLdlt<Item> {
+    /// Estimate the reciprocal condition number `1 / (||A||_1 * ||A^{-1}||_1)`, where
+    /// `anorm` is the 1-norm of the factorised matrix.
+    pub fn rcond(&self, anorm: <Item as Scalar>::Real) -> <Item as Scalar>::Real {
+        // The matrix is Hermitian, so that solves with A^H are solves with A.
+        let inverse_norm = inverse_norm_1_estimate(self.diag.len(), |_, x| self.solve(x));
+        reciprocal_condition(anorm, inverse_norm)
+    }
+
+    /// Solve `A * X = B` in place if the estimated reciprocal condition number is at
+    /// least `threshold` and return the estimate. Otherwise `rhs` is not modified and a
+    /// [LinAlgError::IllConditioned] is returned.
+    pub fn solve_checked<
+        MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    >(
+        &self,
+        rhs: &mut Matrix<Item, MatImpl, L, RS, CS>,
+        anorm: <Item as Scalar>::Real,
+        threshold: <Item as Scalar>::Real,
+    ) -> Result<<Item as Scalar>::Real, LinAlgError> {
+        let rcond = check_condition(self.rcond(anorm), threshold)?;
+        self.solve(rhs);
+        Ok(rcond)
+    }
+}
+
+impl<Item: HScalar> TridiagonalMatrix<Item> {
+    /// Compute `y = alpha * op(A) * x + beta * y`. The vectors are accessed
+    /// through their one-dimensional indexing.
+    pub fn matvec<
+        MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+        MatImpl2: MatrixTraitMut<Item, L2, RS2, CS2>,
+        L1: LayoutType,
+        L2: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    >(
+        &self,
+        trans: TransposeMode,
+        alpha: Item,
+        vec_x: &Matrix<Item, MatImpl1, L1, RS1, CS1>,
+        beta: Item,
+        vec_y: &mut Matrix<Item, MatImpl2, L2, RS2, CS2>,
+    ) {
+        band_matvec(self, 1, 1, trans, alpha, vec_x, beta, vec_y);
+    }
+
+    /// Solve `A * X = B` in place with the Thomas algorithm, where `rhs` contains `B`
+    /// on input and `X` on output.
+    ///
+    /// The Thomas algorithm is Gaussian elimination without pivoting. It requires `O(n)`
+    /// operations but is only guaranteed to be stable for diagonally dominant or
+    /// symmetric positive definite matrices.
+    /// If a zero pivot is encountered a [LinAlgError::SingularMatrix] is returned and
+    /// `rhs` is not modified.
+    pub fn solve_thomas<
+        MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    >(
+        &self,
+        rhs: &mut Matrix<Item, MatImpl, L, RS, CS>,
+    ) -> Result<(), LinAlgError> {
+        let n = self.layout().dim().0;
+        let dim = rhs.layout().dim();
+        check_rhs(n, dim);
+        if n == 0 {
+            return Ok(());
+        }
+
+        // Pivots and modified superdiagonal of the forward sweep. They only depend on the
+        // matrix, so that a zero pivot is detected before `rhs` is modified.
+        let mut pivot = vec![Item::zero(); n];
+        let mut sup = vec![Item::zero(); n];
+        unsafe {
+            for row in 0..n {
+                pivot[row] = self.get_unchecked(row, row);
+                if row > 0 {
+                    pivot[row] -= self.get_unchecked(row, row - 1) * sup[row - 1];
+                }
+                if pivot[row] == Item::zero() {
+                    return Err(LinAlgError::SingularMatrix(row));
+                }
+                if row + 1 < n {
+                    sup[row] = self.get_unchecked(row, row + 1) / pivot[row];
+                }
+            }
+
+            for rhs_col in 0..dim.1 {
+                *rhs.get_unchecked_mut(0, rhs_col) /= pivot[0];
+                for (row, &denom) in pivot.iter().enumerate().skip(1) {
+                    let value = (rhs.get_unchecked(row, rhs_col)
+                        - self.get_unchecked(row, row - 1) * rhs.get_unchecked(row - 1, rhs_col))
+                        / denom;
+                    *rhs.get_unchecked_mut(row, rhs_col) = value;
+                }
+
+                for row in (0..n - 1).rev() {
+                    let value = sup[row] * rhs.get_unchecked(row + 1, rhs_col);
+                    *rhs.get_unchecked_mut(row, rhs_col) -= value;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Compute the $LDL^H$ decomposition of a symmetric (Hermitian) tridiagonal matrix.
+    ///
+    /// Only the diagonal and the subdiagonal are read.
+    pub fn ldlt(&self) -> Result<TridiagonalLdlt<Item>, LinAlgError> {
+        let n = self.layout().dim().0;
+        let mut diag = vec![Item::zero(); n];
+        let mut sub = vec![Item::zero(); n.saturating_sub(1)];
+
+        unsafe {
+            for row in 0..n {
+                diag[row] = self.get_unchecked(row, row);
+                if row > 0 {
+                    let off = self.get_unchecked(row, row - 1);
+                    sub[row - 1] = off / diag[row - 1];
+                    diag[row] -= sub[row - 1] * off.conj();
+                }
+                if diag[row] == Item::zero() {
+                    return Err(LinAlgError::SingularMatrix(row));
+                }
+            }
+        }
+
+        Ok(TridiagonalLdlt { diag, sub })
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+    use crate::test_utils::{assert_close, op_element};
+    use rand::prelude::*;
+
+    /// Return a random banded matrix as dense matrix.
+    fn random_band<Item: HScalar>(
+        n: IndexType,
+        kl: IndexType,
+        ku: IndexType,
+        rng: &mut StdRng,
+    ) -> MatrixD<Item, ColumnMajor>
+    where
+        MatrixD<Item, ColumnMajor>: RandomFill,
+    {
+        let mut dense = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, n);
+        dense.random_fill(rng);
+        for row in 0..n {
+            for col in 0..n {
+                if (col > row + ku) | (row > col + kl) {
+                    *dense.get_mut(row, col) = Item::zero();
+                }
+            }
+        }
+        dense
+    }
+
+    trait RandomFill {
+        fn random_fill(&mut self, rng: &mut StdRng);
+    }
+
+    impl RandomFill for MatrixD<f64, ColumnMajor> {
+        fn random_fill(&mut self, rng: &mut StdRng) {
+            self.fill_from_rand_standard_normal(rng);
+        }
+    }
+
+    impl RandomFill for MatrixD<c64, ColumnMajor> {
+        fn random_fill(&mut self, rng: &mut StdRng) {
+            self.fill_from_rand_standard_normal(rng);
+        }
+    }
+
+    fn banded_lu_test<Item: HScalar>()
+    where
+        MatrixD<Item, ColumnMajor>: RandomFill,
+    {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (n, kl, ku) = (12, 2, 1);
+        let dense = random_band::<Item>(n, kl, ku, &mut rng);
+        let band = BandedMatrix::<Item>::from_dense(&dense, kl, ku);
+
+        let roundtrip = band.to_dense::<RowMajor>();
+        for row in 0..n {
+            for col in 0..n {
+                assert_eq!(roundtrip.get(row, col), dense.get(row, col));
+            }
+        }
+
+        let lu = band.lu().unwrap();
+
+        for trans in [
+            TransposeMode::NoTrans,
+            TransposeMode::Trans,
+            TransposeMode::ConjTrans,
+        ] {
+            let mut sol = ColumnVectorD::<Item>::zeros_from_length(n);
+            for index in 0..n {
+                *sol.get1d_mut(index) = Item::from_real(
+                    num::cast::<f64, <Item as Scalar>::Real>(1.0 + index as f64).unwrap(),
+                );
+            }
+
+            // Right-hand side from the band matvec and check against dense product.
+            let mut rhs = ColumnVectorD::<Item>::zeros_from_length(n);
+            band.matvec(trans, Item::one(), &sol, Item::zero(), &mut rhs);
+            for row in 0..n {
+                let mut expect = Item::zero();
+                for col in 0..n {
+                    expect += op_element(&dense, trans, row, col) * sol.get1d(col);
+                }
+                assert_close(rhs.get1d(row), expect, 1E-8);
+            }
+
+            lu.solve(trans, &mut rhs);
+            for index in 0..n {
+                assert_close(rhs.get1d(index), sol.get1d(index), 1E-8);
+            }
+        }
+    }
+
+    #[test]
+    fn test_banded_lu_f64() {
+        banded_lu_test::<f64>();
+    }
+
+    #[test]
+    fn test_banded_lu_c64() {
+        banded_lu_test::<c64>();
+    }
+
+    #[test]
+    fn test_banded_lu_requires_pivoting() {
+        // The leading element is zero so that pivoting is required.
+        let mut dense = MatrixD::<f64, RowMajor>::zeros_from_dim(3, 3);
+        *dense.get_mut(0, 1) = 1.0;
+        *dense.get_mut(1, 0) = 2.0;
+        *dense.get_mut(1, 1) = 1.0;
+        *dense.get_mut(1, 2) = 1.0;
+        *dense.get_mut(2, 1) = 3.0;
+        *dense.get_mut(2, 2) = 1.0;
+        let band = BandedMatrix::<f64>::from_dense(&dense, 1, 1);
+
+        let lu = band.lu().unwrap();
+        assert_eq!(lu.pivots()[0], 1);
+
+        let mut rhs = ColumnVectorD::<f64>::zeros_from_length(3);
+        *rhs.get1d_mut(0) = 2.0;
+        *rhs.get1d_mut(1) = 7.0;
+        *rhs.get1d_mut(2) = 9.0;
+        lu.solve(TransposeMode::NoTrans, &mut rhs);
+        for (index, &expect) in [1.0, 2.0, 3.0].iter().enumerate() {
+            assert_close(rhs.get1d(index), expect, 1E-8);
+        }
+    }
+
+    #[test]
+    fn test_singular_banded_matrix() {
+        let band = BandedMatrix::<f64>::zeros_from_band(4, 4, 1, 1);
+        assert_eq!(band.lu().err(), Some(LinAlgError::SingularMatrix(0)));
+    }
+
+    #[test]
+    fn test_thomas_algorithm() {
+        let n = 10;
+        let sub = vec![-1.0; n - 1];
+        let diag = vec![4.0; n];
+        let sup = vec![-2.0; n - 1];
+        let mat = TridiagonalMatrix::<f64>::from_diagonals(&sub, &diag, &sup);
+
+        let mut sol = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, 2);
+        for row in 0..n {
+            *sol.get_mut(row, 0) = row as f64;
+            *sol.get_mut(row, 1) = 1.0;
+        }
+        let dense = mat.to_dense::<RowMajor>();
+        let mut rhs = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, 2);
+        for row in 0..n {
+            for col in 0..2 {
+                *rhs.get_mut(row, col) = (0..n)
+                    .map(|index| dense.get(row, index) * sol.get(index, col))
+                    .sum();
+            }
+        }
+        mat.solve_thomas(&mut rhs).unwrap();
+        for row in 0..n {
+            for col in 0..2 {
+                assert_close(rhs.get(row, col), sol.get(row, col), 1E-8);
+            }
+        }
+    }
+
+    #[test]
+    fn test_singular_thomas_keeps_rhs() {
+        // The second pivot is 1 - 1 * 1 = 0.
+        let mat =
+            TridiagonalMatrix::<f64>::from_diagonals(&[1.0, 1.0], &[1.0, 1.0, 2.0], &[1.0, 1.0]);
+        let mut rhs = MatrixD::<f64, ColumnMajor>::zeros_from_dim(3, 2);
+        for index in 0..6 {
+            *rhs.get1d_mut(index) = 1.0 + index as f64;
+        }
+        assert_eq!(
+            mat.solve_thomas(&mut rhs).err(),
+            Some(LinAlgError::SingularMatrix(1))
+        );
+        for index in 0..6 {
+            assert_eq!(rhs.get1d(index), 1.0 + index as f64);
+        }
+    }
+
+    #[test]
+    fn test_hermitian_tridiagonal_ldlt() {
+        let n = 8;
+        let sub: Vec<c64> = (0..n - 1).map(|i| c64::new(1.0, 0.5 * i as f64)).collect();
+        let sup: Vec<c64> = sub.iter().map(|value| value.conj()).collect();
+        let diag = vec![c64::new(6.0, 0.0); n];
+        let mat = TridiagonalMatrix::<c64>::from_diagonals(&sub, &diag, &sup);
+
+        let ldlt = mat.ldlt().unwrap();
+        assert_eq!(ldlt.d().len(), n);
+        assert_eq!(ldlt.l().len(), n - 1);
+
+        let mut sol = ColumnVectorD::<c64>::zeros_from_length(n);
+        for index in 0..n {
+            *sol.get1d_mut(index) = c64::new(index as f64, 1.0);
+        }
+        let mut rhs = ColumnVectorD::<c64>::zeros_from_length(n);
+        mat.matvec(
+            TransposeMode::NoTrans,
+            c64::new(1.0, 0.0),
+            &sol,
+            c64::new(0.0, 0.0),
+            &mut rhs,
+        );
+        ldlt.solve(&mut rhs);
+        for index in 0..n {
+            assert_close(rhs.get1d(index), sol.get1d(index), 1E-8);
+        }
+    }
+}
@@ -37,10 +37,10 @@ impl<
 {
     pub fn new(data: Data, layout: L) -> Self {
         assert!(
-            layout.number_of_elements() <= data.number_of_elements(),
-            "Number of elements in data: {}. But layout number of elements is {})",
+            layout.raw_size() <= data.number_of_elements(),
+            "Number of elements in data: {}. But layout raw size is {})",
             data.number_of_elements(),
-            layout.number_of_elements(),
+            layout.raw_size(),
         );
         BaseMatrix::<Item, Data, L, RS, CS> {
             data,
@@ -129,8 +129,14 @@ impl<
 
     #[inline]
     unsafe fn get_unchecked(&self, row: IndexType, col: IndexType) -> Self::Item {
-        self.data
-            .get_unchecked(self.layout.convert_2d_raw(row, col))
+        let value = self
+            .data
+            .get_unchecked(self.layout.convert_2d_raw(row, col));
+        if self.layout.conjugate_2d(row, col) {
+            value.conj()
+        } else {
+            value
+        }
     }
 
     #[inline]
@@ -169,6 +175,12 @@ impl<
 
     #[inline]
     unsafe fn get_unchecked_mut(&mut self, row: IndexType, col: IndexType) -> &mut Self::Item {
+        debug_assert!(
+            !self.layout.conjugate_2d(row, col),
+            "Element ({}, {}) is not stored and cannot be mutably accessed.",
+            row,
+            col
+        );
         self.data
             .get_unchecked_mut(self.layout.convert_2d_raw(row, col))
     }
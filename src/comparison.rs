@@ -0,0 +1,269 @@
+//! Exact and approximate comparison of matrices.
+//!
+//! Two matrices are compared elementwise. They can have different layouts and
+//! implementations, e.g. a row major matrix can be compared with a column major matrix or
+//! with a lazy expression. Matrices of different dimensions are never equal. Elements
+//! outside the stored structure of band or triangular layouts are compared as zero.
+//!
+//! - [PartialEq] compares the elements exactly.
+//! - [AbsDiffEq], [RelativeEq] and [UlpsEq] of the [approx] crate compare the elements
+//!   with the corresponding scalar comparison. The tolerances are those of the scalar type.
+//!
+//! The macros [assert_matrix_abs_diff_eq](crate::assert_matrix_abs_diff_eq),
+//! [assert_matrix_relative_eq](crate::assert_matrix_relative_eq) and
+//! [assert_matrix_ulps_eq](crate::assert_matrix_ulps_eq) accept the same optional
+//! tolerance arguments as the scalar macros of [approx], e.g. `epsilon = 1E-10`. On failure
+//! they report the first offending index in column major order and the two values.
+//!
+//! ```
+//! # use householder::*;
+//! let mut a = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 2);
+//! let mut b = MatrixD::<f64, ColumnMajor>::zeros_from_dim(2, 2);
+//! *a.get_mut(1, 0) = 1.0;
+//! *b.get_mut(1, 0) = 1.0 + 1E-12;
+//! assert!(a != b);
+//! assert_matrix_relative_eq!(a, b, max_relative = 1E-10);
+//! assert_matrix_abs_diff_eq!(2.0 * &b, &b + &b, epsilon = 1E-10);
+//! ```
+
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::*;
+pub use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+
+#[doc(hidden)]
+pub use approx::{AbsDiff, Relative, Ulps};
+
+/// Return the first position in column major order at which `eq` fails. Matrices of
+/// different dimensions fail at `None`.
+fn find_mismatch<
+    Item: HScalar,
+    MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+    MatImpl2: MatrixTrait<Item, L2, RS2, CS2>,
+    L1: LayoutType,
+    L2: LayoutType,
+    RS1: SizeIdentifier,
+    CS1: SizeIdentifier,
+    RS2: SizeIdentifier,
+    CS2: SizeIdentifier,
+>(
+    left: &Matrix<Item, MatImpl1, L1, RS1, CS1>,
+    right: &Matrix<Item, MatImpl2, L2, RS2, CS2>,
+    eq: impl Fn(Item, Item) -> bool,
+) -> Option<Option<(IndexType, IndexType)>> {
+    let (m, n) = left.layout().dim();
+    if (m, n) != right.layout().dim() {
+        return Some(None);
+    }
+    for col in 0..n {
+        for row in 0..m {
+            if !eq(left.element(row, col), right.element(row, col)) {
+                return Some(Some((row, col)));
+            }
+        }
+    }
+    None
+}
+
+/// Panic with the first mismatch of `left` and `right` under `eq`. This is the
+/// implementation of the assertion macros.
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_elementwise<
+    Item: HScalar,
+    MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+    MatImpl2: MatrixTrait<Item, L2, RS2, CS2>,
+    L1: LayoutType,
+    L2: LayoutType,
+    RS1: SizeIdentifier,
+    CS1: SizeIdentifier,
+    RS2: SizeIdentifier,
+    CS2: SizeIdentifier,
+>(
+    left: &Matrix<Item, MatImpl1, L1, RS1, CS1>,
+    right: &Matrix<Item, MatImpl2, L2, RS2, CS2>,
+    eq: impl Fn(Item, Item) -> bool,
+    assertion: &str,
+) {
+    match find_mismatch(left, right, eq) {
+        None => (),
+        Some(None) => panic!(
+            "assertion failed: `{}`\n  dimensions differ: left = {:?}, right = {:?}",
+            assertion,
+            left.layout().dim(),
+            right.layout().dim()
+        ),
+        Some(Some((row, col))) => panic!(
+            "assertion failed: `{}`\n  first mismatch at ({}, {}): left = {:?}, right = {:?}",
+            assertion,
+            row,
+            col,
+            left.element(row, col),
+            right.element(row, col)
+        ),
+    }
+}
+
+impl<
+        Item: HScalar,
+        MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+        MatImpl2: MatrixTrait<Item, L2, RS2, CS2>,
+        L1: LayoutType,
+        L2: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    > PartialEq<Matrix<Item, MatImpl2, L2, RS2, CS2>> for Matrix<Item, MatImpl1, L1, RS1, CS1>
+{
+    fn eq(&self, other: &Matrix<Item, MatImpl2, L2, RS2, CS2>) -> bool {
+        find_mismatch(self, other, |x, y| x == y).is_none()
+    }
+}
+
+impl<
+        Item: HScalar + AbsDiffEq,
+        MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+        MatImpl2: MatrixTrait<Item, L2, RS2, CS2>,
+        L1: LayoutType,
+        L2: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    > AbsDiffEq<Matrix<Item, MatImpl2, L2, RS2, CS2>> for Matrix<Item, MatImpl1, L1, RS1, CS1>
+where
+    Item::Epsilon: Copy,
+{
+    type Epsilon = Item::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        Item::default_epsilon()
+    }
+
+    fn abs_diff_eq(
+        &self,
+        other: &Matrix<Item, MatImpl2, L2, RS2, CS2>,
+        epsilon: Self::Epsilon,
+    ) -> bool {
+        find_mismatch(self, other, |x, y| x.abs_diff_eq(&y, epsilon)).is_none()
+    }
+}
+
+impl<
+        Item: HScalar + RelativeEq,
+        MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+        MatImpl2: MatrixTrait<Item, L2, RS2, CS2>,
+        L1: LayoutType,
+        L2: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    > RelativeEq<Matrix<Item, MatImpl2, L2, RS2, CS2>> for Matrix<Item, MatImpl1, L1, RS1, CS1>
+where
+    Item::Epsilon: Copy,
+{
+    fn default_max_relative() -> Self::Epsilon {
+        Item::default_max_relative()
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Matrix<Item, MatImpl2, L2, RS2, CS2>,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool {
+        find_mismatch(self, other, |x, y| x.relative_eq(&y, epsilon, max_relative)).is_none()
+    }
+}
+
+impl<
+        Item: HScalar + UlpsEq,
+        MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+        MatImpl2: MatrixTrait<Item, L2, RS2, CS2>,
+        L1: LayoutType,
+        L2: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    > UlpsEq<Matrix<Item, MatImpl2, L2, RS2, CS2>> for Matrix<Item, MatImpl1, L1, RS1, CS1>
+where
+    Item::Epsilon: Copy,
+{
+    fn default_max_ulps() -> u32 {
+        Item::default_max_ulps()
+    }
+
+    fn ulps_eq(
+        &self,
+        other: &Matrix<Item, MatImpl2, L2, RS2, CS2>,
+        epsilon: Self::Epsilon,
+        max_ulps: u32,
+    ) -> bool {
+        find_mismatch(self, other, |x, y| x.ulps_eq(&y, epsilon, max_ulps)).is_none()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+    use crate::{assert_matrix_abs_diff_eq, assert_matrix_relative_eq, assert_matrix_ulps_eq};
+
+    #[test]
+    fn test_partial_eq() {
+        let mut a = MatrixD::<c64, RowMajor>::zeros_from_dim(2, 3);
+        let mut b = MatrixD::<c64, ColumnMajor>::zeros_from_dim(2, 3);
+        *a.get_mut(1, 2) = c64::new(1.0, 2.0);
+        assert!(a != b);
+        *b.get_mut(1, 2) = c64::new(1.0, 2.0);
+        assert!(a == b);
+        assert!(b == (c64::new(2.0, 0.0) * &b) * c64::new(0.5, 0.0));
+        assert!(a != MatrixD::<c64, RowMajor>::zeros_from_dim(3, 2));
+
+        let band = TridiagonalMatrix::<f64>::from_diagonals(&[1.0], &[2.0, 3.0], &[0.0]);
+        let mut dense = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 2);
+        *dense.get_mut(0, 0) = 2.0;
+        *dense.get_mut(1, 0) = 1.0;
+        *dense.get_mut(1, 1) = 3.0;
+        assert!(band == dense);
+    }
+
+    #[test]
+    fn test_approximate_eq() {
+        let mut a = MatrixD::<f64, RowMajor>::zeros_from_dim(3, 2);
+        let mut b = MatrixD::<f64, ColumnMajor>::zeros_from_dim(3, 2);
+        *a.get_mut(2, 1) = 1.0;
+        *b.get_mut(2, 1) = 1.0 + 4.0 * f64::EPSILON;
+        assert!(approx::abs_diff_eq!(a, b, epsilon = 1E-14));
+        assert!(approx::abs_diff_ne!(a, b));
+        assert!(approx::relative_eq!(a, b, max_relative = 1E-14));
+        assert!(approx::ulps_eq!(a, b, max_ulps = 4));
+        assert!(approx::ulps_ne!(a, b, max_ulps = 3));
+
+        assert_matrix_abs_diff_eq!(a, b, epsilon = 1E-14);
+        assert_matrix_relative_eq!(a, b, epsilon = 0.0, max_relative = 1E-14);
+        assert_matrix_ulps_eq!(a, b, max_ulps = 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "first mismatch at (1, 0): left = 0.0, right = 1.5")]
+    fn test_assert_mismatch() {
+        let a = MatrixD::<f32, ColumnMajor>::zeros_from_dim(2, 2);
+        let mut b = MatrixD::<f32, ColumnMajor>::zeros_from_dim(2, 2);
+        *b.get_mut(1, 0) = 1.5;
+        *b.get_mut(0, 1) = 2.0;
+        assert_matrix_relative_eq!(a, b);
+    }
+
+    #[test]
+    #[should_panic(expected = "dimensions differ: left = (2, 2), right = (2, 1)")]
+    fn test_assert_dimension_mismatch() {
+        let a = MatrixD::<f64, ColumnMajor>::zeros_from_dim(2, 2);
+        let b = ColumnVectorD::<f64>::zeros_from_length(2);
+        assert_matrix_ulps_eq!(a, b);
+    }
+}
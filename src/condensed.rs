@@ -0,0 +1,1030 @@
+//! Reductions of dense matrices to condensed forms.
+//!
+//! This module reduces a dense matrix by unitary transformations to a condensed form.
+//! These reductions are the first step of dense eigenvalue and singular value solvers.
+//! They are also useful on their own. For example, after a Hessenberg reduction the
+//! transfer function `C * (s * I - A)^{-1} * B` costs `O(n^2)` operations per frequency `s`.
+//!
+//! - `hessenberg` computes `A = Q * H * Q^H` of a square matrix, where `H` is upper
+//!   Hessenberg with a real subdiagonal (Lapack `gehrd`). The result is a
+//!   [HessenbergReduction].
+//! - `tridiagonalize` computes `A = Q * T * Q^H` of a Hermitian matrix, where `T` is real
+//!   symmetric tridiagonal (Lapack `hetrd`). Only the lower triangle of `A` is referenced.
+//!   The result is a [TridiagonalReduction].
+//! - `bidiagonalize` computes `A = Q * B * P^H` of an `m x n` matrix, where `B` is real
+//!   upper bidiagonal if `m >= n` and real lower bidiagonal otherwise (Lapack `gebrd`).
+//!   The result is a [BidiagonalReduction].
+//!
+//! The unitary factors are [HouseholderSequence]s `Q = H_0 * H_1 * ... * H_{k-1}`. Each
+//! `H_i = I - tau_i * v_i * v_i^H` is an elementary reflector in the convention of
+//! Lapack `larfg`. A sequence can be applied to a matrix or formed explicitly.
+//!
+//! The reductions are blocked as proposed by Dongarra, Sorensen and Hammarling. Within a
+//! panel of columns each reflector is generated from a column that is updated on the fly
+//! with the reflectors of the panel. The trailing matrix is updated once per panel by
+//! matrix products with [MatMul](crate::matrix_multiply::MatMul). A sequence of reflectors
+//! is applied in the compact WY representation `I - V * T * V^H` of Schreiber and Van Loan.
+//!
+//! ```
+//! # use householder::*;
+//! let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(4, 4);
+//! for col in 0..4 {
+//!     for row in 0..4 {
+//!         *mat.get_mut(row, col) = 1.0 / (1.0 + row as f64 + 2.0 * col as f64);
+//!     }
+//! }
+//! let hess = mat.hessenberg();
+//! assert_eq!(hess.h().get(3, 0), 0.0);
+//! assert_eq!(hess.h().get(3, 1), 0.0);
+//! let q = hess.q().to_matrix();
+//! assert_matrix_abs_diff_eq!(mat.dot(&q), q.dot(hess.h()), epsilon = 1E-14);
+//! ```
+
+use crate::data_container::{DataContainer, DataContainerMut};
+use crate::dense_solvers::{adjoint, column_major_copy, make_reflector};
+use crate::layouts::*;
+use crate::matrix::*;
+use crate::matrix_multiply::MatMul;
+use crate::traits::*;
+use crate::types::*;
+
+/// The number of columns that are reduced in one panel.
+const BLOCK_SIZE: IndexType = 32;
+
+/// A product `Q = H_0 * H_1 * ... * H_{k-1}` of elementary reflectors
+/// `H_i = I - tau_i * v_i * v_i^H` of dimension `n x n`.
+///
+/// The vector `v_i` is column `i` of [vectors](HouseholderSequence::vectors). Its first
+/// nonzero entry is one.
+pub struct HouseholderSequence<Item: HScalar> {
+    vectors: MatrixD<Item, ColumnMajor>,
+    taus: Vec<Item>,
+}
+
+impl<Item: HScalar> HouseholderSequence<Item> {
+    /// Return the dimension `n` of the `n x n` matrix `Q`.
+    pub fn dim(&self) -> IndexType {
+        self.vectors.layout().dim().0
+    }
+
+    /// Return the number `k` of reflectors.
+    pub fn len(&self) -> IndexType {
+        self.taus.len()
+    }
+
+    /// Return true if the sequence has no reflectors, i.e. `Q` is the identity.
+    pub fn is_empty(&self) -> bool {
+        self.taus.is_empty()
+    }
+
+    /// Return the `n x k` matrix of the Householder vectors `v_i`.
+    pub fn vectors(&self) -> &MatrixD<Item, ColumnMajor> {
+        &self.vectors
+    }
+
+    /// Return the scalar factors `tau_i`.
+    pub fn taus(&self) -> &[Item] {
+        &self.taus
+    }
+}
+
+/// Return the upper triangular `T` with `H_s * ... * H_{s+k-1} = I - V * T * V^H`, where
+/// `V` are the `k` columns of `vectors` starting at `s` (Lapack `larft`).
+fn triangular_factor<Item: HScalar>(
+    vectors: &MatrixD<Item, ColumnMajor>,
+    taus: &[Item],
+    start: IndexType,
+    count: IndexType,
+) -> MatrixD<Item, ColumnMajor> {
+    let n = vectors.layout().dim().0;
+    let mut t = MatrixD::<Item, ColumnMajor>::zeros_from_dim(count, count);
+    unsafe {
+        for col in 0..count {
+            let tau = taus[start + col];
+            // T[0..col, col] = -tau * T[0..col, 0..col] * V[:, 0..col]^H * v_col.
+            let products: Vec<Item> = (0..col)
+                .map(|index| {
+                    (0..n).fold(Item::zero(), |acc, row| {
+                        acc + vectors.get_unchecked(row, start + index).conj()
+                            * vectors.get_unchecked(row, start + col)
+                    })
+                })
+                .collect();
+            for row in 0..col {
+                let mut value = Item::zero();
+                for (index, &product) in products.iter().enumerate().skip(row) {
+                    value += t.get_unchecked(row, index) * product;
+                }
+                *t.get_unchecked_mut(row, col) = -tau * value;
+            }
+            *t.get_unchecked_mut(col, col) = tau;
+        }
+    }
+    t
+}
+
+/// Return a column major copy of the Hermitian matrix whose lower triangle is the lower
+/// triangle of `mat`. The imaginary parts of the diagonal are ignored.
+fn hermitian_copy<Item: HScalar, MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>, L: LayoutType>(
+    mat: &Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+) -> MatrixD<Item, ColumnMajor> {
+    let mut res = column_major_copy(mat);
+    let (m, n) = res.layout().dim();
+    unsafe {
+        for col in 0..m.min(n) {
+            *res.get_unchecked_mut(col, col) = Item::from_real(res.get_unchecked(col, col).re());
+            for row in 0..col {
+                *res.get_unchecked_mut(row, col) = res.get_unchecked(col, row).conj();
+            }
+        }
+    }
+    res
+}
+
+/// The reduction `A = Q * H * Q^H` of a square matrix to upper Hessenberg form.
+///
+/// The subdiagonal of `H` is real. `Q` is the product of `n - 1` reflectors, where
+/// `H_i` acts on the rows `i + 1..n`.
+pub struct HessenbergReduction<Item: HScalar> {
+    h: MatrixD<Item, ColumnMajor>,
+    q: HouseholderSequence<Item>,
+}
+
+impl<Item: HScalar> HessenbergReduction<Item> {
+    /// Return the dimension `n` of the reduced `n x n` matrix.
+    pub fn dim(&self) -> IndexType {
+        self.h.layout().dim().0
+    }
+
+    /// Return the upper Hessenberg matrix `H`.
+    pub fn h(&self) -> &MatrixD<Item, ColumnMajor> {
+        &self.h
+    }
+
+    /// Return the unitary factor `Q`.
+    pub fn q(&self) -> &HouseholderSequence<Item> {
+        &self.q
+    }
+}
+
+/// Reduce the `ib` columns starting at `start` of the Hessenberg reduction of `a` (Lapack
+/// `lahr2`) and append their reflectors to `q`. Return `(Y, T)`, where `T` is the
+/// triangular factor of the reflectors `V` of the panel and `Y = A * V * T`.
+fn hessenberg_panel<Item: HScalar>(
+    a: &mut MatrixD<Item, ColumnMajor>,
+    q: &mut HouseholderSequence<Item>,
+    start: IndexType,
+    ib: IndexType,
+) -> (MatrixD<Item, ColumnMajor>, MatrixD<Item, ColumnMajor>) {
+    let n = a.layout().dim().0;
+    let vectors = &mut q.vectors;
+    let taus = &mut q.taus;
+    // The panel satisfies Q^H * A * Q = Q^H * (A - Y * V^H) with Y = A * V * T.
+    let mut y = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, ib);
+    let mut t = MatrixD::<Item, ColumnMajor>::zeros_from_dim(ib, ib);
+    unsafe {
+        for j in 0..ib {
+            let col = start + j;
+            // Apply the previous reflectors of the panel to column `col`.
+            let mut b: Vec<Item> = (0..n).map(|row| a.get_unchecked(row, col)).collect();
+            for index in 0..j {
+                let factor = vectors.get_unchecked(col, start + index).conj();
+                for (row, value) in b.iter_mut().enumerate() {
+                    *value -= y.get_unchecked(row, index) * factor;
+                }
+            }
+            let mut w: Vec<Item> = (0..j)
+                .map(|index| {
+                    b.iter()
+                        .enumerate()
+                        .fold(Item::zero(), |acc, (row, &value)| {
+                            acc + vectors.get_unchecked(row, start + index).conj() * value
+                        })
+                })
+                .collect();
+            for index in (0..j).rev() {
+                let mut value = Item::zero();
+                for (other, &entry) in w.iter().enumerate().take(index + 1) {
+                    value += t.get_unchecked(other, index).conj() * entry;
+                }
+                w[index] = value;
+            }
+            for (row, value) in b.iter_mut().enumerate() {
+                for (index, &entry) in w.iter().enumerate() {
+                    *value -= vectors.get_unchecked(row, start + index) * entry;
+                }
+            }
+
+            let (beta, tau) = make_reflector(b[col + 1], &mut b[col + 2..]);
+            *vectors.get_unchecked_mut(col + 1, col) = Item::one();
+            for (row, &value) in b.iter().enumerate().skip(col + 2) {
+                *vectors.get_unchecked_mut(row, col) = value;
+            }
+            taus.push(tau);
+
+            // Column `col` is final. The columns to its right are still those of the
+            // matrix at the start of the panel.
+            for (row, &value) in b.iter().enumerate().take(col + 1) {
+                *a.get_unchecked_mut(row, col) = value;
+            }
+            *a.get_unchecked_mut(col + 1, col) = Item::from_real(beta);
+            for row in col + 2..n {
+                *a.get_unchecked_mut(row, col) = Item::zero();
+            }
+
+            // Y[:, j] = tau * (A * v - Y * V^H * v) and
+            // T[0..j, j] = -tau * T[0..j, 0..j] * V^H * v.
+            let products: Vec<Item> = (0..j)
+                .map(|index| {
+                    (col + 1..n).fold(Item::zero(), |acc, row| {
+                        acc + vectors.get_unchecked(row, start + index).conj()
+                            * vectors.get_unchecked(row, col)
+                    })
+                })
+                .collect();
+            let mut yv = vec![Item::zero(); n];
+            for other in col + 1..n {
+                let entry = vectors.get_unchecked(other, col);
+                for (row, value) in yv.iter_mut().enumerate() {
+                    *value += a.get_unchecked(row, other) * entry;
+                }
+            }
+            for (index, &product) in products.iter().enumerate() {
+                for (row, value) in yv.iter_mut().enumerate() {
+                    *value -= y.get_unchecked(row, index) * product;
+                }
+            }
+            for (row, &value) in yv.iter().enumerate() {
+                *y.get_unchecked_mut(row, j) = tau * value;
+            }
+            for row in 0..j {
+                let mut value = Item::zero();
+                for (index, &product) in products.iter().enumerate().skip(row) {
+                    value += t.get_unchecked(row, index) * product;
+                }
+                *t.get_unchecked_mut(row, j) = -tau * value;
+            }
+            *t.get_unchecked_mut(j, j) = tau;
+        }
+    }
+    (y, t)
+}
+
+/// The reduction `A = Q * T * Q^H` of a Hermitian matrix to real symmetric
+/// tridiagonal form.
+///
+/// `Q` is the product of `n - 1` reflectors, where `H_i` acts on the rows `i + 1..n`.
+pub struct TridiagonalReduction<Item: HScalar> {
+    diagonal: Vec<<Item as Scalar>::Real>,
+    off_diagonal: Vec<<Item as Scalar>::Real>,
+    q: HouseholderSequence<Item>,
+}
+
+impl<Item: HScalar> TridiagonalReduction<Item> {
+    /// Return the dimension `n` of the reduced `n x n` matrix.
+    pub fn dim(&self) -> IndexType {
+        self.diagonal.len()
+    }
+
+    /// Return the diagonal of `T`.
+    pub fn diagonal(&self) -> &[<Item as Scalar>::Real] {
+        &self.diagonal
+    }
+
+    /// Return the subdiagonal of `T`, which is equal to its superdiagonal.
+    pub fn off_diagonal(&self) -> &[<Item as Scalar>::Real] {
+        &self.off_diagonal
+    }
+
+    /// Return the tridiagonal matrix `T`.
+    pub fn t(&self) -> TridiagonalMatrix<Item> {
+        if self.dim() == 0 {
+            return TridiagonalMatrix::<Item>::zeros_from_dim(0, 0);
+        }
+        let diag: Vec<Item> = self
+            .diagonal
+            .iter()
+            .map(|&value| Item::from_real(value))
+            .collect();
+        let off: Vec<Item> = self
+            .off_diagonal
+            .iter()
+            .map(|&value| Item::from_real(value))
+            .collect();
+        TridiagonalMatrix::from_diagonals(&off, &diag, &off)
+    }
+
+    /// Return the unitary factor `Q`.
+    pub fn q(&self) -> &HouseholderSequence<Item> {
+        &self.q
+    }
+}
+
+/// Reduce the `ib` columns starting at `start` of the tridiagonal reduction of the
+/// Hermitian `a` (Lapack `latrd`) and append the entries of `T` and the reflectors to
+/// `reduction`. Return `W`, for which the trailing matrix of the panel is
+/// `A - V * W^H - W * V^H`.
+fn tridiagonal_panel<Item: HScalar>(
+    a: &MatrixD<Item, ColumnMajor>,
+    reduction: &mut TridiagonalReduction<Item>,
+    start: IndexType,
+    ib: IndexType,
+) -> MatrixD<Item, ColumnMajor> {
+    let n = a.layout().dim().0;
+    let vectors = &mut reduction.q.vectors;
+    let taus = &mut reduction.q.taus;
+    let diagonal = &mut reduction.diagonal;
+    let off_diagonal = &mut reduction.off_diagonal;
+    let half = Item::from_real(num::cast::<f64, <Item as Scalar>::Real>(0.5).unwrap());
+    let mut w = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, ib);
+    unsafe {
+        for j in 0..ib {
+            let col = start + j;
+            let mut b: Vec<Item> = (0..n).map(|row| a.get_unchecked(row, col)).collect();
+            for index in 0..j {
+                let w_col = w.get_unchecked(col, index).conj();
+                let v_col = vectors.get_unchecked(col, start + index).conj();
+                for (row, value) in b.iter_mut().enumerate().skip(col) {
+                    *value -= vectors.get_unchecked(row, start + index) * w_col
+                        + w.get_unchecked(row, index) * v_col;
+                }
+            }
+            diagonal.push(b[col].re());
+
+            let (beta, tau) = make_reflector(b[col + 1], &mut b[col + 2..]);
+            off_diagonal.push(beta);
+            *vectors.get_unchecked_mut(col + 1, col) = Item::one();
+            for (row, &value) in b.iter().enumerate().skip(col + 2) {
+                *vectors.get_unchecked_mut(row, col) = value;
+            }
+            taus.push(tau);
+
+            // x = tau * (A - V * W^H - W * V^H) * v on the rows col + 1..n.
+            let v = |row: IndexType| vectors.get_unchecked(row, col);
+            let mut x = vec![Item::zero(); n];
+            for other in col + 1..n {
+                let entry = v(other);
+                for (row, value) in x.iter_mut().enumerate().skip(col + 1) {
+                    *value += a.get_unchecked(row, other) * entry;
+                }
+            }
+            for index in 0..j {
+                let mut w_v = Item::zero();
+                let mut v_v = Item::zero();
+                for row in col + 1..n {
+                    w_v += w.get_unchecked(row, index).conj() * v(row);
+                    v_v += vectors.get_unchecked(row, start + index).conj() * v(row);
+                }
+                for (row, value) in x.iter_mut().enumerate().skip(col + 1) {
+                    *value -= vectors.get_unchecked(row, start + index) * w_v
+                        + w.get_unchecked(row, index) * v_v;
+                }
+            }
+            // W[:, j] = x - tau / 2 * (x^H * v) * v.
+            let mut x_v = Item::zero();
+            for (row, value) in x.iter_mut().enumerate().skip(col + 1) {
+                *value *= tau;
+                x_v += value.conj() * v(row);
+            }
+            let alpha = -half * tau * x_v;
+            for (row, &value) in x.iter().enumerate().skip(col + 1) {
+                *w.get_unchecked_mut(row, j) = value + alpha * v(row);
+            }
+        }
+    }
+    w
+}
+
+/// The reduction `A = Q * B * P^H` of an `m x n` matrix to real bidiagonal form.
+///
+/// If `m >= n` the `n x n` matrix `B` is upper bidiagonal and `A = Q * [B; 0] * P^H`.
+/// Otherwise the `m x m` matrix `B` is lower bidiagonal and `A = Q * [B, 0] * P^H`.
+pub struct BidiagonalReduction<Item: HScalar> {
+    diagonal: Vec<<Item as Scalar>::Real>,
+    off_diagonal: Vec<<Item as Scalar>::Real>,
+    q: HouseholderSequence<Item>,
+    p: HouseholderSequence<Item>,
+}
+
+impl<Item: HScalar> BidiagonalReduction<Item> {
+    /// Return the dimension `(m, n)` of the reduced matrix.
+    pub fn dim(&self) -> (IndexType, IndexType) {
+        (self.q.dim(), self.p.dim())
+    }
+
+    /// Return true if `B` is upper bidiagonal, i.e. if `m >= n`.
+    pub fn is_upper(&self) -> bool {
+        self.q.dim() >= self.p.dim()
+    }
+
+    /// Return the diagonal of `B`.
+    pub fn diagonal(&self) -> &[<Item as Scalar>::Real] {
+        &self.diagonal
+    }
+
+    /// Return the superdiagonal of an upper or the subdiagonal of a lower bidiagonal `B`.
+    pub fn off_diagonal(&self) -> &[<Item as Scalar>::Real] {
+        &self.off_diagonal
+    }
+
+    /// Return the bidiagonal matrix `B`.
+    pub fn b(&self) -> BandedMatrix<Item> {
+        let k = self.diagonal.len();
+        let (kl, ku) = if self.is_upper() { (0, 1) } else { (1, 0) };
+        let mut res = BandedMatrix::<Item>::zeros_from_band(k, k, kl, ku);
+        for (index, &value) in self.diagonal.iter().enumerate() {
+            *res.get_mut(index, index) = Item::from_real(value);
+        }
+        for (index, &value) in self.off_diagonal.iter().enumerate() {
+            let (row, col) = if self.is_upper() {
+                (index, index + 1)
+            } else {
+                (index + 1, index)
+            };
+            *res.get_mut(row, col) = Item::from_real(value);
+        }
+        res
+    }
+
+    /// Return the unitary factor `Q` of dimension `m x m`.
+    pub fn q(&self) -> &HouseholderSequence<Item> {
+        &self.q
+    }
+
+    /// Return the unitary factor `P` of dimension `n x n`.
+    pub fn p(&self) -> &HouseholderSequence<Item> {
+        &self.p
+    }
+}
+
+/// Reduce the `ib` columns and rows starting at `start` of the upper bidiagonal reduction
+/// of the `m x n` matrix `a` with `m >= n` (Lapack `labrd`) and append the entries of `B`
+/// and the reflectors to `reduction`. Return `(X, Y)`, for which the trailing matrix of
+/// the panel is `A - V * Y^H - X * U^H`, where `V` are the vectors of `Q` and `U` those
+/// of `P`.
+fn bidiagonal_panel<Item: HScalar>(
+    a: &MatrixD<Item, ColumnMajor>,
+    reduction: &mut BidiagonalReduction<Item>,
+    start: IndexType,
+    ib: IndexType,
+) -> (MatrixD<Item, ColumnMajor>, MatrixD<Item, ColumnMajor>) {
+    let (m, n) = a.layout().dim();
+    let q_vectors = &mut reduction.q.vectors;
+    let q_taus = &mut reduction.q.taus;
+    let p_vectors = &mut reduction.p.vectors;
+    let p_taus = &mut reduction.p.taus;
+    let diagonal = &mut reduction.diagonal;
+    let off_diagonal = &mut reduction.off_diagonal;
+    let mut y = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, ib);
+    let mut x = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, ib);
+    unsafe {
+        for j in 0..ib {
+            let col = start + j;
+
+            // Column `col` of the current matrix and the reflector of Q.
+            let mut b: Vec<Item> = (0..m).map(|row| a.get_unchecked(row, col)).collect();
+            for index in 0..j {
+                let y_col = y.get_unchecked(col, index).conj();
+                let u_col = p_vectors.get_unchecked(col, start + index).conj();
+                for (row, value) in b.iter_mut().enumerate().skip(col) {
+                    *value -= q_vectors.get_unchecked(row, start + index) * y_col
+                        + x.get_unchecked(row, index) * u_col;
+                }
+            }
+            let (beta, tau_q) = make_reflector(b[col], &mut b[col + 1..]);
+            diagonal.push(beta);
+            *q_vectors.get_unchecked_mut(col, col) = Item::one();
+            for (row, &value) in b.iter().enumerate().skip(col + 1) {
+                *q_vectors.get_unchecked_mut(row, col) = value;
+            }
+            q_taus.push(tau_q);
+            if col + 1 == n {
+                continue;
+            }
+
+            // Y[:, j] = tau_q * (A^H * v - Y * V^H * v - U * X^H * v) on the rows col + 1..n.
+            let v = |row: IndexType| q_vectors.get_unchecked(row, col);
+            let mut yv = vec![Item::zero(); n];
+            for (other, value) in yv.iter_mut().enumerate().skip(col + 1) {
+                for row in col..m {
+                    *value += a.get_unchecked(row, other).conj() * v(row);
+                }
+            }
+            for index in 0..j {
+                let mut v_v = Item::zero();
+                let mut x_v = Item::zero();
+                for row in col..m {
+                    v_v += q_vectors.get_unchecked(row, start + index).conj() * v(row);
+                    x_v += x.get_unchecked(row, index).conj() * v(row);
+                }
+                for (other, value) in yv.iter_mut().enumerate().skip(col + 1) {
+                    *value -= y.get_unchecked(other, index) * v_v
+                        + p_vectors.get_unchecked(other, start + index) * x_v;
+                }
+            }
+            for (other, &value) in yv.iter().enumerate().skip(col + 1) {
+                *y.get_unchecked_mut(other, j) = tau_q * value;
+            }
+
+            // Row `col` of the current matrix and the reflector of P.
+            let mut r: Vec<Item> = (0..n).map(|other| a.get_unchecked(col, other)).collect();
+            for (other, value) in r.iter_mut().enumerate().skip(col + 1) {
+                for index in 0..=j {
+                    *value -= q_vectors.get_unchecked(col, start + index)
+                        * y.get_unchecked(other, index).conj();
+                }
+                for index in 0..j {
+                    *value -= x.get_unchecked(col, index)
+                        * p_vectors.get_unchecked(other, start + index).conj();
+                }
+                *value = value.conj();
+            }
+            let (beta, tau_p) = make_reflector(r[col + 1], &mut r[col + 2..]);
+            off_diagonal.push(beta);
+            *p_vectors.get_unchecked_mut(col + 1, col) = Item::one();
+            for (other, &value) in r.iter().enumerate().skip(col + 2) {
+                *p_vectors.get_unchecked_mut(other, col) = value;
+            }
+            p_taus.push(tau_p);
+
+            // X[:, j] = tau_p * (A * u - V * Y^H * u - X * U^H * u) on the rows col + 1..m.
+            let u = |other: IndexType| p_vectors.get_unchecked(other, col);
+            let mut xv = vec![Item::zero(); m];
+            for other in col + 1..n {
+                let entry = u(other);
+                for (row, value) in xv.iter_mut().enumerate().skip(col + 1) {
+                    *value += a.get_unchecked(row, other) * entry;
+                }
+            }
+            for index in 0..=j {
+                let mut y_u = Item::zero();
+                for other in col + 1..n {
+                    y_u += y.get_unchecked(other, index).conj() * u(other);
+                }
+                for (row, value) in xv.iter_mut().enumerate().skip(col + 1) {
+                    *value -= q_vectors.get_unchecked(row, start + index) * y_u;
+                }
+            }
+            for index in 0..j {
+                let mut u_u = Item::zero();
+                for other in col + 1..n {
+                    u_u += p_vectors.get_unchecked(other, start + index).conj() * u(other);
+                }
+                for (row, value) in xv.iter_mut().enumerate().skip(col + 1) {
+                    *value -= x.get_unchecked(row, index) * u_u;
+                }
+            }
+            for (row, &value) in xv.iter().enumerate().skip(col + 1) {
+                *x.get_unchecked_mut(row, j) = tau_p * value;
+            }
+        }
+    }
+    (x, y)
+}
+
+macro_rules! condensed_impl {
+    ($HScalar:ty) => {
+        impl HouseholderSequence<$HScalar> {
+            /// Overwrite the `n x p` matrix `B` with `Q * B`.
+            pub fn apply<L: StridedLayoutType, Data: DataContainerMut<Item = $HScalar>>(
+                &self,
+                mat: &mut GenericBaseMatrixMut<$HScalar, L, Data, Dynamic, Dynamic>,
+            ) {
+                self.apply_blocks(mat, false);
+            }
+
+            /// Overwrite the `n x p` matrix `B` with `Q^H * B`.
+            pub fn apply_adjoint<L: StridedLayoutType, Data: DataContainerMut<Item = $HScalar>>(
+                &self,
+                mat: &mut GenericBaseMatrixMut<$HScalar, L, Data, Dynamic, Dynamic>,
+            ) {
+                self.apply_blocks(mat, true);
+            }
+
+            /// Return the first `ncols` columns of `Q`.
+            pub fn columns(&self, ncols: IndexType) -> MatrixD<$HScalar, ColumnMajor> {
+                let n = self.dim();
+                assert!(
+                    ncols <= n,
+                    "Number of columns {} exceeds the dimension {}.",
+                    ncols,
+                    n
+                );
+                let mut res = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, ncols);
+                for index in 0..ncols {
+                    *res.get_mut(index, index) = <$HScalar as num::One>::one();
+                }
+                self.apply(&mut res);
+                res
+            }
+
+            /// Return the `n x n` matrix `Q`.
+            pub fn to_matrix(&self) -> MatrixD<$HScalar, ColumnMajor> {
+                self.columns(self.dim())
+            }
+
+            /// Overwrite `B` with `Q * B`, or with `Q^H * B` if `adjoint` is true. The
+            /// reflectors are applied in blocks of [BLOCK_SIZE].
+            fn apply_blocks<L: StridedLayoutType, Data: DataContainerMut<Item = $HScalar>>(
+                &self,
+                mat: &mut GenericBaseMatrixMut<$HScalar, L, Data, Dynamic, Dynamic>,
+                adjoint: bool,
+            ) {
+                assert_eq!(
+                    mat.layout().dim().0,
+                    self.dim(),
+                    "Matrix has {} rows but Q has dimension {}.",
+                    mat.layout().dim().0,
+                    self.dim()
+                );
+                let starts: Vec<IndexType> = (0..self.len()).step_by(BLOCK_SIZE).collect();
+                let mut apply = |start: IndexType| {
+                    let count = BLOCK_SIZE.min(self.len() - start);
+                    let t = triangular_factor(&self.vectors, &self.taus, start, count);
+                    self.apply_block(start, count, &t, adjoint, mat);
+                };
+                if adjoint {
+                    starts.into_iter().for_each(&mut apply);
+                } else {
+                    starts.into_iter().rev().for_each(&mut apply);
+                }
+            }
+
+            /// Overwrite the `n x p` matrix `B` with `(I - V * T * V^H) * B`, or with
+            /// `(I - V * T^H * V^H) * B` if `adjoint_t` is true, where `V` are the `count`
+            /// reflectors starting at `start`.
+            fn apply_block<L: StridedLayoutType, Data: DataContainerMut<Item = $HScalar>>(
+                &self,
+                start: IndexType,
+                count: IndexType,
+                t: &MatrixD<$HScalar, ColumnMajor>,
+                adjoint_t: bool,
+                mat: &mut GenericBaseMatrixMut<$HScalar, L, Data, Dynamic, Dynamic>,
+            ) {
+                let (m, p) = mat.layout().dim();
+                if (count == 0) | (p == 0) | (m == 0) {
+                    return;
+                }
+                let zero = <$HScalar as num::Zero>::zero();
+                let one = <$HScalar as num::One>::one();
+                let v = self.vectors.block((0, start), (m, count));
+                let mut w = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(count, p);
+                <$HScalar>::matmul(one, &adjoint(&v), mat, zero, &mut w);
+                let mut tw = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(count, p);
+                if adjoint_t {
+                    <$HScalar>::matmul(one, &adjoint(t), &w, zero, &mut tw);
+                } else {
+                    <$HScalar>::matmul(one, t, &w, zero, &mut tw);
+                }
+                <$HScalar>::matmul(-one, &v, &tw, one, mat);
+            }
+        }
+
+        impl HessenbergReduction<$HScalar> {
+            /// Compute the Hessenberg reduction of the square matrix `a` (Lapack `gehrd`).
+            fn new(mut a: MatrixD<$HScalar, ColumnMajor>) -> Self {
+                let (n, cols) = a.layout().dim();
+                assert_eq!(
+                    n,
+                    cols,
+                    "Hessenberg reduction requires a square matrix. dim = {:?}",
+                    (n, cols)
+                );
+                let one = <$HScalar as num::One>::one();
+                let count = n.saturating_sub(1);
+                let mut q = HouseholderSequence {
+                    vectors: MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, count),
+                    taus: Vec::with_capacity(count),
+                };
+
+                let mut start = 0;
+                while start < count {
+                    let ib = BLOCK_SIZE.min(count - start);
+                    let (y, t) = hessenberg_panel(&mut a, &mut q, start, ib);
+
+                    // Update the trailing columns to Q^H * (A - Y * V^H).
+                    let first = start + ib;
+                    if first < n {
+                        let cols = n - first;
+                        let vh = adjoint(&q.vectors.block((first, start), (cols, ib)));
+                        let mut trailing = a.block_mut((0, first), (n, cols));
+                        <$HScalar>::matmul(-one, &y, &vh, one, &mut trailing);
+                        q.apply_block(start, ib, &t, true, &mut trailing);
+                    }
+                    start += ib;
+                }
+
+                Self { h: a, q }
+            }
+        }
+
+        impl TridiagonalReduction<$HScalar> {
+            /// Compute the tridiagonal reduction of the Hermitian matrix `a` (Lapack
+            /// `hetrd`).
+            fn new(mut a: MatrixD<$HScalar, ColumnMajor>) -> Self {
+                let (n, cols) = a.layout().dim();
+                assert_eq!(
+                    n,
+                    cols,
+                    "Tridiagonal reduction requires a square matrix. dim = {:?}",
+                    (n, cols)
+                );
+                let one = <$HScalar as num::One>::one();
+                let count = n.saturating_sub(1);
+                let mut reduction = Self {
+                    diagonal: Vec::with_capacity(n),
+                    off_diagonal: Vec::with_capacity(count),
+                    q: HouseholderSequence {
+                        vectors: MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, count),
+                        taus: Vec::with_capacity(count),
+                    },
+                };
+
+                let mut start = 0;
+                while start < count {
+                    let ib = BLOCK_SIZE.min(count - start);
+                    let w = tridiagonal_panel(&a, &mut reduction, start, ib);
+
+                    // Update the trailing matrix by the rank 2 * ib update A - V * W^H - W * V^H.
+                    let first = start + ib;
+                    if first < n {
+                        let rows = n - first;
+                        let v = reduction.q.vectors.block((first, start), (rows, ib));
+                        let w = w.block((first, 0), (rows, ib));
+                        let mut trailing = a.block_mut((first, first), (rows, rows));
+                        <$HScalar>::matmul(-one, &v, &adjoint(&w), one, &mut trailing);
+                        <$HScalar>::matmul(-one, &w, &adjoint(&v), one, &mut trailing);
+                    }
+                    start += ib;
+                }
+                if n > 0 {
+                    reduction.diagonal.push(a.get(n - 1, n - 1).re());
+                }
+
+                reduction
+            }
+        }
+
+        impl BidiagonalReduction<$HScalar> {
+            /// Compute the upper bidiagonal reduction `A = Q * [B; 0] * P^H` of an `m x n`
+            /// matrix with `m >= n` (Lapack `gebrd`). `Q` consists of `n` and `P` of
+            /// `n - 1` reflectors.
+            fn upper(mut a: MatrixD<$HScalar, ColumnMajor>) -> Self {
+                let (m, n) = a.layout().dim();
+                assert!(m >= n, "Upper bidiagonal form requires m >= n.");
+                let one = <$HScalar as num::One>::one();
+                let count = n.saturating_sub(1);
+                let mut reduction = Self {
+                    diagonal: Vec::with_capacity(n),
+                    off_diagonal: Vec::with_capacity(count),
+                    q: HouseholderSequence {
+                        vectors: MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(m, n),
+                        taus: Vec::with_capacity(n),
+                    },
+                    p: HouseholderSequence {
+                        vectors: MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, count),
+                        taus: Vec::with_capacity(count),
+                    },
+                };
+
+                let mut start = 0;
+                while start < n {
+                    let ib = BLOCK_SIZE.min(n - start);
+                    let (x, y) = bidiagonal_panel(&a, &mut reduction, start, ib);
+
+                    // Update the trailing matrix to A - V * Y^H - X * U^H.
+                    let first = start + ib;
+                    if first < n {
+                        let (rows, cols) = (m - first, n - first);
+                        let mut trailing = a.block_mut((first, first), (rows, cols));
+                        <$HScalar>::matmul(
+                            -one,
+                            &reduction.q.vectors.block((first, start), (rows, ib)),
+                            &adjoint(&y.block((first, 0), (cols, ib))),
+                            one,
+                            &mut trailing,
+                        );
+                        <$HScalar>::matmul(
+                            -one,
+                            &x.block((first, 0), (rows, ib)),
+                            &adjoint(&reduction.p.vectors.block((first, start), (cols, ib))),
+                            one,
+                            &mut trailing,
+                        );
+                    }
+                    start += ib;
+                }
+
+                reduction
+            }
+        }
+
+        impl<L: StridedLayoutType, Data: DataContainer<Item = $HScalar>>
+            GenericBaseMatrix<$HScalar, L, Data, Dynamic, Dynamic>
+        {
+            /// Compute the reduction `A = Q * H * Q^H` to upper Hessenberg form.
+            pub fn hessenberg(&self) -> HessenbergReduction<$HScalar> {
+                HessenbergReduction::<$HScalar>::new(column_major_copy(self))
+            }
+
+            /// Compute the reduction `A = Q * T * Q^H` of a Hermitian matrix to real
+            /// tridiagonal form. Only the lower triangle of `A` is referenced.
+            pub fn tridiagonalize(&self) -> TridiagonalReduction<$HScalar> {
+                TridiagonalReduction::<$HScalar>::new(hermitian_copy(self))
+            }
+
+            /// Compute the reduction `A = Q * B * P^H` to real bidiagonal form. If `m < n`
+            /// the upper bidiagonal reduction `A^H = P * B^T * Q^H` of the adjoint is
+            /// computed.
+            pub fn bidiagonalize(&self) -> BidiagonalReduction<$HScalar> {
+                let (m, n) = self.layout().dim();
+                if m >= n {
+                    BidiagonalReduction::<$HScalar>::upper(column_major_copy(self))
+                } else {
+                    let res = BidiagonalReduction::<$HScalar>::upper(adjoint(self));
+                    BidiagonalReduction {
+                        diagonal: res.diagonal,
+                        off_diagonal: res.off_diagonal,
+                        q: res.p,
+                        p: res.q,
+                    }
+                }
+            }
+        }
+    };
+}
+
+condensed_impl!(f32);
+condensed_impl!(f64);
+condensed_impl!(c32);
+condensed_impl!(c64);
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::assert_matrix_abs_diff_eq;
+    use crate::data_container::VectorContainer;
+    use rand::prelude::*;
+
+    /// Return `A * B` for column major matrices.
+    fn product<Item>(
+        mat_a: &MatrixD<Item, ColumnMajor>,
+        mat_b: &MatrixD<Item, ColumnMajor>,
+    ) -> MatrixD<Item, ColumnMajor>
+    where
+        Item: HScalar
+            + MatMul<
+                Item,
+                ColumnMajor,
+                ColumnMajor,
+                ColumnMajor,
+                VectorContainer<Item>,
+                VectorContainer<Item>,
+                VectorContainer<Item>,
+                Dynamic,
+                Dynamic,
+                Dynamic,
+                Dynamic,
+                Dynamic,
+                Dynamic,
+            >,
+    {
+        let mut res = MatrixD::<Item, ColumnMajor>::zeros_from_dim(
+            mat_a.layout().dim().0,
+            mat_b.layout().dim().1,
+        );
+        Item::matmul(Item::one(), mat_a, mat_b, Item::zero(), &mut res);
+        res
+    }
+
+    fn identity<Item: HScalar>(n: IndexType) -> MatrixD<Item, ColumnMajor> {
+        let mut res = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, n);
+        for index in 0..n {
+            *res.get_mut(index, index) = Item::one();
+        }
+        res
+    }
+
+    macro_rules! condensed_test {
+        ($HScalar:ty, $Real:ty, $tol:expr, $hessenberg:ident, $tridiagonal:ident, $bidiagonal:ident) => {
+            #[test]
+            fn $hessenberg() {
+                let mut rng = StdRng::seed_from_u64(0);
+                for n in [0, 1, 2, 5, 70] {
+                    let mut mat = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                    mat.fill_from_rand_standard_normal(&mut rng);
+                    let hess = mat.hessenberg();
+                    let h = hess.h();
+                    assert_eq!(hess.q().len(), n.saturating_sub(1));
+                    for col in 0..n {
+                        for row in col + 2..n {
+                            assert_eq!(h.get(row, col), <$HScalar as num::Zero>::zero());
+                        }
+                        if col + 1 < n {
+                            assert_eq!(h.get(col + 1, col).im(), 0.0);
+                        }
+                    }
+
+                    // A * Q = Q * H and Q^H * Q = I.
+                    let q = hess.q().to_matrix();
+                    let a = column_major_copy(&mat);
+                    let tol = $tol * (1.0 + n as $Real);
+                    assert_matrix_abs_diff_eq!(product(&a, &q), product(&q, h), epsilon = tol);
+                    let mut qhq = q.eval();
+                    hess.q().apply_adjoint(&mut qhq);
+                    assert_matrix_abs_diff_eq!(qhq, identity::<$HScalar>(n), epsilon = tol);
+                }
+            }
+
+            #[test]
+            fn $tridiagonal() {
+                let mut rng = StdRng::seed_from_u64(1);
+                for n in [1, 2, 6, 70] {
+                    // Only the lower triangle is referenced.
+                    let mut mat = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, n);
+                    mat.fill_from_rand_standard_normal(&mut rng);
+                    let reduction = mat.tridiagonalize();
+                    assert_eq!(reduction.dim(), n);
+                    assert_eq!(reduction.off_diagonal().len(), n - 1);
+
+                    let a = hermitian_copy(&mat);
+                    let q = reduction.q().to_matrix();
+                    let t = reduction.t().to_dense::<ColumnMajor>();
+                    let tol = $tol * (1.0 + n as $Real);
+                    assert_matrix_abs_diff_eq!(product(&a, &q), product(&q, &t), epsilon = tol);
+                }
+            }
+
+            #[test]
+            fn $bidiagonal() {
+                let mut rng = StdRng::seed_from_u64(2);
+                for (m, n) in [(1, 1), (4, 3), (3, 5), (75, 40), (40, 75)] {
+                    let mut mat = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(m, n);
+                    mat.fill_from_rand_standard_normal(&mut rng);
+                    let reduction = mat.bidiagonalize();
+                    let k = m.min(n);
+                    assert_eq!(reduction.dim(), (m, n));
+                    assert_eq!(reduction.is_upper(), m >= n);
+                    assert_eq!(reduction.diagonal().len(), k);
+                    assert_eq!(reduction.off_diagonal().len(), k - 1);
+
+                    // A * P[:, 0..k] = Q[:, 0..k] * B.
+                    let p = reduction.p().columns(k);
+                    let q = reduction.q().columns(k);
+                    let b = reduction.b().to_dense::<ColumnMajor>();
+                    let tol = $tol * (1.0 + m.max(n) as $Real);
+                    assert_matrix_abs_diff_eq!(product(&mat, &p), product(&q, &b), epsilon = tol);
+
+                    // The remaining columns of P (m < n) or Q (m > n) are orthogonal to A.
+                    let ap = product(&mat, &reduction.p().to_matrix());
+                    for col in k..n {
+                        for row in 0..m {
+                            assert!(ap.get(row, col).abs() < tol);
+                        }
+                    }
+                    let mut qha = mat.eval();
+                    reduction.q().apply_adjoint(&mut qha);
+                    for col in 0..n {
+                        for row in k..m {
+                            assert!(qha.get(row, col).abs() < tol);
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    condensed_test!(
+        f64,
+        f64,
+        1E-13,
+        test_hessenberg_f64,
+        test_tridiagonal_f64,
+        test_bidiagonal_f64
+    );
+    condensed_test!(
+        c64,
+        f64,
+        1E-13,
+        test_hessenberg_c64,
+        test_tridiagonal_c64,
+        test_bidiagonal_c64
+    );
+    condensed_test!(
+        f32,
+        f32,
+        1E-5,
+        test_hessenberg_f32,
+        test_tridiagonal_f32,
+        test_bidiagonal_f32
+    );
+    condensed_test!(
+        c32,
+        f32,
+        1E-5,
+        test_hessenberg_c32,
+        test_tridiagonal_c32,
+        test_bidiagonal_c32
+    );
+}
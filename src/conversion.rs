@@ -0,0 +1,579 @@
+//! Elementwise type conversions and complex projections of matrices.
+//!
+//! Like [ScalarMult](crate::scalar_mult::ScalarMult) and [Addition](crate::addition::Addition),
+//! the types in this module are lazy. They store the original matrix and apply the
+//! conversion on element access. Since the element type of the result differs from that
+//! of the original matrix, they are not restricted to a single scalar type.
+//!
+//! - `cast::<Target>()` converts every element into the scalar type `Target` through
+//!   [ScalarCast]. Real types can be cast into each other and into complex types, and
+//!   complex types into each other.
+//! - `re()`, `im()` and `abs()` return the real parts, the imaginary parts and the absolute
+//!   values. Their element type is the real type `HScalar::Real` of the same precision.
+//! - `conj()` returns the elementwise complex conjugate.
+//! - `Matrix::from_re_im(re, im)` combines two real matrices of the same dimension and
+//!   index layout into a complex matrix.
+//!
+//! All conversions in the crate follow one overflow policy. [ScalarCast::cast] rounds to
+//! nearest, so finite values that are too large for single precision become infinite,
+//! just like an `as` cast. [ScalarCast::checked_cast] reports such an overflow with `None`
+//! instead. The eager precision conversions of [refinement](crate::refinement) and the
+//! mixed precision solvers are built on `checked_cast` and return a
+//! [LinAlgError::PrecisionOverflow](crate::error::LinAlgError::PrecisionOverflow).
+//!
+//! All methods take the matrix by value, so that they can be chained with other lazy
+//! operations. To keep the original matrix, apply them to a reference created with
+//! [Matrix::from_ref].
+//!
+//! ```
+//! # use householder::*;
+//! # use householder::types::{c32, c64};
+//! let mut mat = MatrixD::<c64, RowMajor>::zeros_from_dim(2, 2);
+//! *mat.get_mut(0, 1) = c64::new(3.0, -4.0);
+//! assert_eq!(Matrix::from_ref(&mat).abs().get(0, 1), 5.0);
+//! let real = Matrix::from_ref(&mat).re().eval();
+//! let imag = (2.0 * Matrix::from_ref(&mat).im()).eval();
+//! let combined = Matrix::from_re_im(real, imag).eval();
+//! assert_eq!(combined.get(0, 1), c64::new(3.0, -8.0));
+//! let single = mat.conj().cast::<c32>().eval();
+//! assert_eq!(single.get(0, 1), c32::new(3.0, 4.0));
+//! ```
+
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::*;
+use num::Float;
+use std::marker::PhantomData;
+
+/// Elementwise conversion into the scalar type `Target`.
+pub trait ScalarCast<Target: HScalar>: HScalar {
+    /// Convert into `Target`. Conversions into single precision round to nearest, finite
+    /// values that are too large become infinite.
+    fn cast(self) -> Target;
+
+    /// Convert into `Target` like [cast](ScalarCast::cast), but return `None` if a finite
+    /// value overflows. Infinite and NaN values are converted.
+    fn checked_cast(self) -> Option<Target> {
+        let value: Target = self.cast();
+        let finite = self.re().is_finite() & self.im().is_finite();
+        let overflow = value.re().is_infinite() | value.im().is_infinite();
+        if finite & overflow {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+macro_rules! real_cast_impl {
+    ($Source:ty, $Target:ty) => {
+        impl ScalarCast<$Target> for $Source {
+            #[inline]
+            fn cast(self) -> $Target {
+                self as $Target
+            }
+        }
+    };
+}
+
+macro_rules! real_to_complex_cast_impl {
+    ($Source:ty, $Target:ident, $Real:ty) => {
+        impl ScalarCast<$Target> for $Source {
+            #[inline]
+            fn cast(self) -> $Target {
+                $Target::new(self as $Real, 0.0)
+            }
+        }
+    };
+}
+
+macro_rules! complex_cast_impl {
+    ($Source:ty, $Target:ident, $Real:ty) => {
+        impl ScalarCast<$Target> for $Source {
+            #[inline]
+            fn cast(self) -> $Target {
+                $Target::new(self.re as $Real, self.im as $Real)
+            }
+        }
+    };
+}
+
+real_cast_impl!(f32, f32);
+real_cast_impl!(f32, f64);
+real_cast_impl!(f64, f32);
+real_cast_impl!(f64, f64);
+
+real_to_complex_cast_impl!(f32, c32, f32);
+real_to_complex_cast_impl!(f32, c64, f64);
+real_to_complex_cast_impl!(f64, c32, f32);
+real_to_complex_cast_impl!(f64, c64, f64);
+
+complex_cast_impl!(c32, c32, f32);
+complex_cast_impl!(c32, c64, f64);
+complex_cast_impl!(c64, c32, f32);
+complex_cast_impl!(c64, c64, f64);
+
+/// A matrix whose elements are converted into the scalar type `Target`.
+pub type CastMat<Item, Target, MatImpl, L, RS, CS> =
+    Matrix<Target, Cast<Item, Target, MatImpl, L, RS, CS>, L, RS, CS>;
+
+/// A structure holding a matrix whose elements are converted into `Target` on access.
+pub struct Cast<Item, Target, MatImpl, L, RS, CS>(
+    Matrix<Item, MatImpl, L, RS, CS>,
+    PhantomData<Target>,
+)
+where
+    Item: ScalarCast<Target>,
+    Target: HScalar,
+    L: LayoutType,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+    MatImpl: MatrixTrait<Item, L, RS, CS>;
+
+impl<
+        Item: ScalarCast<Target>,
+        Target: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > Cast<Item, Target, MatImpl, L, RS, CS>
+{
+    pub fn new(mat: Matrix<Item, MatImpl, L, RS, CS>) -> Self {
+        Self(mat, PhantomData)
+    }
+}
+
+impl<
+        Item: ScalarCast<Target>,
+        Target: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > SizeType for Cast<Item, Target, MatImpl, L, RS, CS>
+{
+    type R = RS;
+    type C = CS;
+}
+
+impl<
+        Item: ScalarCast<Target>,
+        Target: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > Layout for Cast<Item, Target, MatImpl, L, RS, CS>
+{
+    type Impl = L;
+
+    #[inline]
+    fn layout(&self) -> &Self::Impl {
+        self.0.layout()
+    }
+}
+
+impl<
+        Item: ScalarCast<Target>,
+        Target: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > UnsafeRandomAccess for Cast<Item, Target, MatImpl, L, RS, CS>
+{
+    type Item = Target;
+
+    #[inline]
+    unsafe fn get_unchecked(&self, row: IndexType, col: IndexType) -> Self::Item {
+        self.0.get_unchecked(row, col).cast()
+    }
+
+    #[inline]
+    unsafe fn get1d_unchecked(&self, index: IndexType) -> Self::Item {
+        self.0.get1d_unchecked(index).cast()
+    }
+}
+
+// Define a lazy elementwise map with a fixed element type of the result.
+macro_rules! elementwise_impl {
+    ($(#[$attr:meta])* $Name:ident, $(#[$alias_attr:meta])* $Alias:ident, $Output:ty, |$value:ident| $map:expr) => {
+        $(#[$alias_attr])*
+        pub type $Alias<Item, MatImpl, L, RS, CS> =
+            Matrix<$Output, $Name<Item, MatImpl, L, RS, CS>, L, RS, CS>;
+
+        $(#[$attr])*
+        pub struct $Name<Item, MatImpl, L, RS, CS>(Matrix<Item, MatImpl, L, RS, CS>)
+        where
+            Item: HScalar,
+            L: LayoutType,
+            RS: SizeIdentifier,
+            CS: SizeIdentifier,
+            MatImpl: MatrixTrait<Item, L, RS, CS>;
+
+        impl<
+                Item: HScalar,
+                MatImpl: MatrixTrait<Item, L, RS, CS>,
+                L: LayoutType,
+                RS: SizeIdentifier,
+                CS: SizeIdentifier,
+            > $Name<Item, MatImpl, L, RS, CS>
+        {
+            pub fn new(mat: Matrix<Item, MatImpl, L, RS, CS>) -> Self {
+                Self(mat)
+            }
+        }
+
+        impl<
+                Item: HScalar,
+                MatImpl: MatrixTrait<Item, L, RS, CS>,
+                L: LayoutType,
+                RS: SizeIdentifier,
+                CS: SizeIdentifier,
+            > SizeType for $Name<Item, MatImpl, L, RS, CS>
+        {
+            type R = RS;
+            type C = CS;
+        }
+
+        impl<
+                Item: HScalar,
+                MatImpl: MatrixTrait<Item, L, RS, CS>,
+                L: LayoutType,
+                RS: SizeIdentifier,
+                CS: SizeIdentifier,
+            > Layout for $Name<Item, MatImpl, L, RS, CS>
+        {
+            type Impl = L;
+
+            #[inline]
+            fn layout(&self) -> &Self::Impl {
+                self.0.layout()
+            }
+        }
+
+        impl<
+                Item: HScalar,
+                MatImpl: MatrixTrait<Item, L, RS, CS>,
+                L: LayoutType,
+                RS: SizeIdentifier,
+                CS: SizeIdentifier,
+            > UnsafeRandomAccess for $Name<Item, MatImpl, L, RS, CS>
+        {
+            type Item = $Output;
+
+            #[inline]
+            unsafe fn get_unchecked(&self, row: IndexType, col: IndexType) -> Self::Item {
+                let $value = self.0.get_unchecked(row, col);
+                $map
+            }
+
+            #[inline]
+            unsafe fn get1d_unchecked(&self, index: IndexType) -> Self::Item {
+                let $value = self.0.get1d_unchecked(index);
+                $map
+            }
+        }
+    };
+}
+
+elementwise_impl!(
+    /// A structure holding a matrix whose real parts are returned on access.
+    RealPart,
+    /// A matrix of the real parts of another matrix.
+    RealPartMat,
+    <Item as HScalar>::Real,
+    |value| value.real_part()
+);
+
+elementwise_impl!(
+    /// A structure holding a matrix whose imaginary parts are returned on access.
+    ImagPart,
+    /// A matrix of the imaginary parts of another matrix.
+    ImagPartMat,
+    <Item as HScalar>::Real,
+    |value| value.imag_part()
+);
+
+elementwise_impl!(
+    /// A structure holding a matrix whose absolute values are returned on access.
+    AbsValue,
+    /// A matrix of the absolute values of another matrix.
+    AbsValueMat,
+    <Item as HScalar>::Real,
+    |value| value.modulus()
+);
+
+elementwise_impl!(
+    /// A structure holding a matrix whose complex conjugates are returned on access.
+    Conjugate,
+    /// A matrix of the complex conjugates of another matrix.
+    ConjugateMat,
+    Item,
+    |value| value.conj()
+);
+
+impl<
+        Item: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > Matrix<Item, MatImpl, L, RS, CS>
+{
+    /// Convert the elements into the scalar type `Target`.
+    pub fn cast<Target: HScalar>(self) -> CastMat<Item, Target, MatImpl, L, RS, CS>
+    where
+        Item: ScalarCast<Target>,
+    {
+        Matrix::new(Cast::new(self))
+    }
+
+    /// Return the real parts of the elements.
+    pub fn re(self) -> RealPartMat<Item, MatImpl, L, RS, CS> {
+        Matrix::new(RealPart::new(self))
+    }
+
+    /// Return the imaginary parts of the elements, which are zero for real types.
+    pub fn im(self) -> ImagPartMat<Item, MatImpl, L, RS, CS> {
+        Matrix::new(ImagPart::new(self))
+    }
+
+    /// Return the absolute values of the elements.
+    pub fn abs(self) -> AbsValueMat<Item, MatImpl, L, RS, CS> {
+        Matrix::new(AbsValue::new(self))
+    }
+
+    /// Return the complex conjugates of the elements.
+    pub fn conj(self) -> ConjugateMat<Item, MatImpl, L, RS, CS> {
+        Matrix::new(Conjugate::new(self))
+    }
+}
+
+/// A complex matrix combined from matrices of real and imaginary parts.
+pub type ComplexMat<Item, MatImpl1, MatImpl2, B, L1, L2, RS, CS> = Matrix<
+    <Item as Scalar>::Complex,
+    ComplexParts<Item, MatImpl1, MatImpl2, B, L1, L2, RS, CS>,
+    B,
+    RS,
+    CS,
+>;
+
+/// A structure holding two real matrices, which are combined into complex numbers on
+/// access. As for [Addition](crate::addition::Addition) both matrices must have the same
+/// index layout.
+pub struct ComplexParts<Item, MatImpl1, MatImpl2, B, L1, L2, RS, CS>(
+    Matrix<Item, MatImpl1, L1, RS, CS>,
+    Matrix<Item, MatImpl2, L2, RS, CS>,
+    B,
+)
+where
+    Item: HScalar<Real = Item>,
+    <Item as Scalar>::Complex: HScalar,
+    B: BaseLayoutType,
+    L1: LayoutType<IndexLayout = B>,
+    L2: LayoutType<IndexLayout = B>,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+    MatImpl1: MatrixTrait<Item, L1, RS, CS>,
+    MatImpl2: MatrixTrait<Item, L2, RS, CS>;
+
+impl<
+        Item: HScalar<Real = Item>,
+        B: BaseLayoutType,
+        MatImpl1: MatrixTrait<Item, L1, RS, CS>,
+        MatImpl2: MatrixTrait<Item, L2, RS, CS>,
+        L1: LayoutType<IndexLayout = B>,
+        L2: LayoutType<IndexLayout = B>,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > ComplexParts<Item, MatImpl1, MatImpl2, B, L1, L2, RS, CS>
+where
+    <Item as Scalar>::Complex: HScalar,
+{
+    pub fn new(
+        re: Matrix<Item, MatImpl1, L1, RS, CS>,
+        im: Matrix<Item, MatImpl2, L2, RS, CS>,
+    ) -> Self {
+        assert_eq!(
+            re.layout().dim(),
+            im.layout().dim(),
+            "Dimensions not identical for real and imaginary parts with re.dim() = {:#?}, im.dim() = {:#?}",
+            re.layout().dim(),
+            im.layout().dim()
+        );
+        let layout = re.layout().index_layout();
+        Self(re, im, layout)
+    }
+}
+
+impl<
+        Item: HScalar<Real = Item>,
+        B: BaseLayoutType,
+        MatImpl1: MatrixTrait<Item, L1, RS, CS>,
+        MatImpl2: MatrixTrait<Item, L2, RS, CS>,
+        L1: LayoutType<IndexLayout = B>,
+        L2: LayoutType<IndexLayout = B>,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > Layout for ComplexParts<Item, MatImpl1, MatImpl2, B, L1, L2, RS, CS>
+where
+    <Item as Scalar>::Complex: HScalar,
+{
+    type Impl = B;
+
+    fn layout(&self) -> &Self::Impl {
+        &self.2
+    }
+}
+
+impl<
+        Item: HScalar<Real = Item>,
+        B: BaseLayoutType,
+        MatImpl1: MatrixTrait<Item, L1, RS, CS>,
+        MatImpl2: MatrixTrait<Item, L2, RS, CS>,
+        L1: LayoutType<IndexLayout = B>,
+        L2: LayoutType<IndexLayout = B>,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > SizeType for ComplexParts<Item, MatImpl1, MatImpl2, B, L1, L2, RS, CS>
+where
+    <Item as Scalar>::Complex: HScalar,
+{
+    type R = RS;
+    type C = CS;
+}
+
+impl<
+        Item: HScalar<Real = Item>,
+        B: BaseLayoutType,
+        MatImpl1: MatrixTrait<Item, L1, RS, CS>,
+        MatImpl2: MatrixTrait<Item, L2, RS, CS>,
+        L1: LayoutType<IndexLayout = B>,
+        L2: LayoutType<IndexLayout = B>,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > UnsafeRandomAccess for ComplexParts<Item, MatImpl1, MatImpl2, B, L1, L2, RS, CS>
+where
+    <Item as Scalar>::Complex: HScalar,
+{
+    type Item = <Item as Scalar>::Complex;
+
+    #[inline]
+    unsafe fn get_unchecked(&self, row: IndexType, col: IndexType) -> Self::Item {
+        Item::complex(
+            self.0.get_unchecked(row, col),
+            self.1.get_unchecked(row, col),
+        )
+    }
+
+    #[inline]
+    unsafe fn get1d_unchecked(&self, index: IndexType) -> Self::Item {
+        Item::complex(self.0.get1d_unchecked(index), self.1.get1d_unchecked(index))
+    }
+}
+
+impl<
+        Item: HScalar<Real = Item>,
+        B: BaseLayoutType,
+        MatImpl1: MatrixTrait<Item, L1, RS, CS>,
+        MatImpl2: MatrixTrait<Item, L2, RS, CS>,
+        L1: LayoutType<IndexLayout = B>,
+        L2: LayoutType<IndexLayout = B>,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > ComplexMat<Item, MatImpl1, MatImpl2, B, L1, L2, RS, CS>
+where
+    <Item as Scalar>::Complex: HScalar,
+{
+    /// Combine matrices of real and imaginary parts into a complex matrix.
+    pub fn from_re_im(
+        re: Matrix<Item, MatImpl1, L1, RS, CS>,
+        im: Matrix<Item, MatImpl2, L2, RS, CS>,
+    ) -> Self {
+        Matrix::new(ComplexParts::new(re, im))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_cast() {
+        let mut mat = MatrixD::<f32, RowMajor>::zeros_from_dim(2, 3);
+        *mat.get_mut(1, 2) = 0.1;
+        let double = Matrix::from_ref(&mat).cast::<f64>().eval();
+        assert_eq!(double.layout().dim(), (2, 3));
+        assert_eq!(double.get(1, 2), 0.1f32 as f64);
+        let complex = mat.cast::<c64>();
+        assert_eq!(complex.get(1, 2), c64::new(0.1f32 as f64, 0.0));
+
+        // Casts compose with the other lazy operations.
+        let single = (c64::new(0.0, 2.0) * complex).cast::<c32>().eval();
+        assert_eq!(single.get(1, 2), c32::new(0.0, 0.2));
+    }
+
+    #[test]
+    fn test_projections() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut mat = MatrixD::<c64, ColumnMajor>::zeros_from_dim(4, 3);
+        mat.fill_from_rand_standard_normal(&mut rng);
+
+        let re = Matrix::from_ref(&mat).re().eval();
+        let im = Matrix::from_ref(&mat).im().eval();
+        let abs = Matrix::from_ref(&mat).abs();
+        let conj = Matrix::from_ref(&mat).conj();
+        let combined = Matrix::from_re_im(Matrix::from_ref(&re), -1.0 * Matrix::from_ref(&im));
+        for col in 0..3 {
+            for row in 0..4 {
+                let value = mat.get(row, col);
+                assert_eq!(re.get(row, col), value.re);
+                assert_eq!(im.get(row, col), value.im);
+                assert_eq!(abs.get(row, col), value.norm());
+                assert_eq!(conj.get(row, col), value.conj());
+                assert_eq!(combined.get(row, col), value.conj());
+            }
+        }
+
+        // A lazy sum as real part.
+        let mut other = MatrixD::<f64, ColumnMajor>::zeros_from_dim(4, 3);
+        *other.get_mut(1, 2) = 1.0;
+        let sum = Matrix::from_re_im(&re + other, im).eval();
+        assert_eq!(sum.get(1, 2), mat.get(1, 2) + 1.0);
+    }
+
+    #[test]
+    fn test_real_projections() {
+        let mut mat = MatrixD::<f32, RowMajor>::zeros_from_dim(2, 2);
+        *mat.get_mut(0, 1) = -3.0;
+        let abs = Matrix::from_ref(&mat).abs();
+        assert_eq!(abs.get(0, 1), 3.0);
+        assert_eq!(Matrix::from_ref(&mat).im().get(0, 1), 0.0);
+        assert_eq!(mat.conj().re().get(0, 1), -3.0);
+    }
+
+    #[test]
+    fn test_overflow_policy() {
+        assert_eq!(ScalarCast::<f32>::cast(1E300f64), f32::INFINITY);
+        assert_eq!(ScalarCast::<f32>::checked_cast(1E300f64), None);
+        assert_eq!(ScalarCast::<f32>::checked_cast(2.5f64), Some(2.5f32));
+        assert_eq!(
+            ScalarCast::<f32>::checked_cast(f64::NEG_INFINITY),
+            Some(f32::NEG_INFINITY)
+        );
+        assert_eq!(ScalarCast::<c32>::checked_cast(c64::new(1.0, -1E40)), None);
+        assert_eq!(
+            ScalarCast::<c32>::checked_cast(c64::new(1.0, -2.0)),
+            Some(c32::new(1.0, -2.0))
+        );
+        assert_eq!(
+            ScalarCast::<c64>::checked_cast(f32::MAX),
+            Some(c64::new(f32::MAX as f64, 0.0))
+        );
+    }
+}
@@ -14,6 +14,9 @@ pub trait DataContainer {
     type Item: HScalar;
 
     /// Access the container unchecked.
+    ///
+    /// # Safety
+    /// `index` must be smaller than the number of elements in the container.
     unsafe fn get_unchecked(&self, index: IndexType) -> Self::Item;
 
     /// Get pointer to data.
@@ -44,6 +47,9 @@ pub trait DataContainer {
 
 pub trait DataContainerMut: DataContainer {
     /// Access the container mutably unchecked.
+    ///
+    /// # Safety
+    /// `index` must be smaller than the number of elements in the container.
     unsafe fn get_unchecked_mut(&mut self, index: IndexType) -> &mut Self::Item;
 
     /// Get mutable pointer to data.
@@ -122,14 +128,14 @@ impl<Item: HScalar, const N: usize> Default for ArrayContainer<Item, N> {
 
 impl<'a, Item: HScalar> SliceContainer<'a, Item> {
     /// New slice container from a reference.
-    pub fn new(slice: &'a [Item]) -> SliceContainer<Item> {
+    pub fn new(slice: &'a [Item]) -> SliceContainer<'a, Item> {
         SliceContainer::<Item> { data: slice }
     }
 }
 
 impl<'a, Item: HScalar> SliceContainerMut<'a, Item> {
     /// New mutable slice container from mutable reference.
-    pub fn new(slice: &'a mut [Item]) -> SliceContainerMut<Item> {
+    pub fn new(slice: &'a mut [Item]) -> SliceContainerMut<'a, Item> {
         SliceContainerMut::<Item> { data: slice }
     }
 }
@@ -0,0 +1,1212 @@
+//! Factorisations and solvers for dense matrices.
+//!
+//! This module provides the LU decomposition with partial pivoting of a square dense
+//! matrix (Lapack `getrf`) through the `lu` method of dynamic row major and column major
+//! matrices and of their subblocks. The resulting [DenseLu] solves linear systems with
+//! the matrix, its transpose or its conjugate transpose (Lapack `getrs`).
+//!
+//! The solvers overwrite a right-hand side matrix of dimension `(n, nrhs)` in place.
+//! A [LinAlgError::SingularMatrix] is returned if a zero pivot is encountered. The
+//! reciprocal condition number is estimated through [DenseLu::rcond], see
+//! [norms](crate::norms).
+//!
+//! The `cholesky` method computes the Cholesky decomposition `A = L * L^H` of a Hermitian
+//! positive definite matrix (Lapack `potrf`) from its lower triangle. The resulting
+//! [DenseCholesky] solves linear systems with half the operations of the LU decomposition
+//! (Lapack `potrs`) and estimates its reciprocal condition number (Lapack `pocon`). A
+//! [LinAlgError::NotPositiveDefinite] is returned if a pivot is not positive.
+//!
+//! The `pivoted_qr` method computes the Householder QR decomposition with column pivoting
+//! of a rectangular matrix (Lapack `geqp3`) as a [PivotedQr]. It is the basis of the
+//! interpolative decompositions in [interpolative](crate::interpolative).
+//!
+//! ```
+//! # use householder::*;
+//! let mut mat = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 2);
+//! *mat.get_mut(0, 1) = 2.0;
+//! *mat.get_mut(1, 0) = 1.0;
+//! *mat.get_mut(1, 1) = 1.0;
+//! let lu = mat.lu().unwrap();
+//! let mut rhs = ColumnVectorD::<f64>::zeros_from_length(2);
+//! *rhs.get1d_mut(0) = 2.0;
+//! *rhs.get1d_mut(1) = 3.0;
+//! lu.solve(TransposeMode::NoTrans, &mut rhs);
+//! assert_eq!(rhs.get1d(0), 2.0);
+//! assert_eq!(rhs.get1d(1), 1.0);
+//! ```
+
+use crate::data_container::DataContainer;
+use crate::error::LinAlgError;
+use crate::layouts::*;
+use crate::matrix::*;
+use crate::norms::{check_condition, inverse_norm_1_estimate, nan_first_cmp, reciprocal_condition};
+use crate::permutation::Permutation;
+use crate::traits::*;
+use crate::types::*;
+
+/// The LU decomposition with partial pivoting of a square dense matrix.
+///
+/// The decomposition has the form `A = P * L * U`, where `L` is unit lower triangular
+/// and `U` is upper triangular. Both factors are stored in a single column major matrix
+/// as in Lapack `getrf`. In contrast to [BandedLu](crate::band_solvers::BandedLu) the
+/// row interchanges are also applied to the multipliers of `L`.
+pub struct DenseLu<Item: HScalar> {
+    factor: MatrixD<Item, ColumnMajor>,
+    pivots: Vec<IndexType>,
+}
+
+impl<Item: HScalar> DenseLu<Item> {
+    /// Return the combined `L` and `U` factors. The strict lower part
+    /// holds the multipliers of `L`.
+    pub fn factor(&self) -> &MatrixD<Item, ColumnMajor> {
+        &self.factor
+    }
+
+    /// Return the pivot indices. Row `i` was interchanged with row `pivots[i]`.
+    pub fn pivots(&self) -> &[IndexType] {
+        &self.pivots
+    }
+
+    /// Return the permutation `P` of `A = P * L * U`.
+    pub fn permutation(&self) -> Permutation {
+        Permutation::from_pivots(&self.pivots)
+    }
+
+    /// Return the dimension `n` of the factorised `n x n` matrix.
+    pub fn dim(&self) -> IndexType {
+        self.pivots.len()
+    }
+
+    /// Solve `op(A) * X = B` in place, where `rhs` contains `B` on input
+    /// and `X` on output.
+    pub fn solve<
+        MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    >(
+        &self,
+        trans: TransposeMode,
+        rhs: &mut Matrix<Item, MatImpl, L, RS, CS>,
+    ) {
+        lu_solve(self, trans, rhs);
+    }
+}
+
+impl<Item: HScalar> DenseLu<Item> {
+    /// Estimate the reciprocal condition number `1 / (||A||_1 * ||A^{-1}||_1)`, where
+    /// `anorm` is the 1-norm of the factorised matrix (Lapack `gecon`).
+    pub fn rcond(&self, anorm: <Item as Scalar>::Real) -> <Item as Scalar>::Real {
+        lu_rcond(self, anorm)
+    }
+
+    /// Solve `op(A) * X = B` in place if the estimated reciprocal condition number is at
+    /// least `threshold` and return the estimate. Otherwise `rhs` is not modified and a
+    /// [LinAlgError::IllConditioned] is returned.
+    pub fn solve_checked<
+        MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    >(
+        &self,
+        trans: TransposeMode,
+        rhs: &mut Matrix<Item, MatImpl, L, RS, CS>,
+        anorm: <Item as Scalar>::Real,
+        threshold: <Item as Scalar>::Real,
+    ) -> Result<<Item as Scalar>::Real, LinAlgError> {
+        let rcond = check_condition(self.rcond(anorm), threshold)?;
+        self.solve(trans, rhs);
+        Ok(rcond)
+    }
+}
+
+/// Access to the factors of an LU decomposition `A = P * L * U` with partial pivoting,
+/// where `L` and `U` are stored in a single matrix. Implemented by [DenseLu] and
+/// [BandedLu](crate::band_solvers::BandedLu).
+pub(crate) trait LuFactors<Item: HScalar> {
+    /// Return the pivot indices. Row `i` was interchanged with row `pivots[i]`.
+    fn pivot_indices(&self) -> &[IndexType];
+
+    /// Return the number of subdiagonals of `L` and superdiagonals of `U`.
+    fn bandwidths(&self) -> (IndexType, IndexType);
+
+    /// Return `true` if the row interchanges were also applied to the multipliers of `L`
+    /// (Lapack `getrf`) and `false` if they are interleaved with the elimination steps
+    /// (Lapack `gbtrf`).
+    fn interchanged_multipliers(&self) -> bool;
+
+    /// Return the element `(row, col)` of the combined factors without bounds checks.
+    ///
+    /// # Safety
+    /// `row` and `col` must be smaller than the dimension.
+    unsafe fn factor_unchecked(&self, row: IndexType, col: IndexType) -> Item;
+}
+
+impl<Item: HScalar> LuFactors<Item> for DenseLu<Item> {
+    fn pivot_indices(&self) -> &[IndexType] {
+        &self.pivots
+    }
+
+    fn bandwidths(&self) -> (IndexType, IndexType) {
+        let n = self.dim().saturating_sub(1);
+        (n, n)
+    }
+
+    fn interchanged_multipliers(&self) -> bool {
+        true
+    }
+
+    unsafe fn factor_unchecked(&self, row: IndexType, col: IndexType) -> Item {
+        self.factor.get_unchecked(row, col)
+    }
+}
+
+/// Swap the rows `first` and `second` of the column `col` of `rhs`.
+///
+/// # Safety
+/// The indices must be in bounds.
+unsafe fn swap_rows<
+    Item: HScalar,
+    MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+    L: LayoutType,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+>(
+    rhs: &mut Matrix<Item, MatImpl, L, RS, CS>,
+    first: IndexType,
+    second: IndexType,
+    col: IndexType,
+) {
+    if first != second {
+        let tmp = rhs.get_unchecked(second, col);
+        *rhs.get_unchecked_mut(second, col) = rhs.get_unchecked(first, col);
+        *rhs.get_unchecked_mut(first, col) = tmp;
+    }
+}
+
+/// Solve `op(A) * X = B` in place with the LU factors of `A` (Lapack `getrs` and `gbtrs`).
+pub(crate) fn lu_solve<
+    Item: HScalar,
+    Lu: LuFactors<Item>,
+    MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+    L: LayoutType,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+>(
+    lu: &Lu,
+    trans: TransposeMode,
+    rhs: &mut Matrix<Item, MatImpl, L, RS, CS>,
+) {
+    let pivots = lu.pivot_indices();
+    let n = pivots.len();
+    let (kl, ku) = lu.bandwidths();
+    let interchanged = lu.interchanged_multipliers();
+    let dim = rhs.layout().dim();
+    assert_eq!(
+        n, dim.0,
+        "Right-hand side has {} rows but system has dimension {}.",
+        dim.0, n
+    );
+
+    let conj = |value: Item| {
+        if trans == TransposeMode::ConjTrans {
+            value.conj()
+        } else {
+            value
+        }
+    };
+
+    for rhs_col in 0..dim.1 {
+        unsafe {
+            match trans {
+                TransposeMode::NoTrans => {
+                    // Apply the row interchanges and L^{-1}.
+                    if interchanged {
+                        for (col, &pivot) in pivots.iter().enumerate() {
+                            swap_rows(rhs, col, pivot, rhs_col);
+                        }
+                    }
+                    for (col, &pivot) in pivots.iter().enumerate() {
+                        if !interchanged {
+                            swap_rows(rhs, col, pivot, rhs_col);
+                        }
+                        let value = rhs.get_unchecked(col, rhs_col);
+                        for row in col + 1..n.min(col + kl + 1) {
+                            *rhs.get_unchecked_mut(row, rhs_col) -=
+                                lu.factor_unchecked(row, col) * value;
+                        }
+                    }
+                    // Solve with U.
+                    for col in (0..n).rev() {
+                        let value = rhs.get_unchecked(col, rhs_col) / lu.factor_unchecked(col, col);
+                        *rhs.get_unchecked_mut(col, rhs_col) = value;
+                        for row in col.saturating_sub(ku)..col {
+                            *rhs.get_unchecked_mut(row, rhs_col) -=
+                                lu.factor_unchecked(row, col) * value;
+                        }
+                    }
+                }
+                _ => {
+                    // Solve with op(U).
+                    for col in 0..n {
+                        let mut value = rhs.get_unchecked(col, rhs_col);
+                        for row in col.saturating_sub(ku)..col {
+                            value -= conj(lu.factor_unchecked(row, col))
+                                * rhs.get_unchecked(row, rhs_col);
+                        }
+                        *rhs.get_unchecked_mut(col, rhs_col) =
+                            value / conj(lu.factor_unchecked(col, col));
+                    }
+                    // Solve with op(L) and apply the row interchanges in reverse order.
+                    for col in (0..n).rev() {
+                        let mut value = rhs.get_unchecked(col, rhs_col);
+                        for row in col + 1..n.min(col + kl + 1) {
+                            value -= conj(lu.factor_unchecked(row, col))
+                                * rhs.get_unchecked(row, rhs_col);
+                        }
+                        *rhs.get_unchecked_mut(col, rhs_col) = value;
+                        if !interchanged {
+                            swap_rows(rhs, col, pivots[col], rhs_col);
+                        }
+                    }
+                    if interchanged {
+                        for col in (0..n).rev() {
+                            swap_rows(rhs, col, pivots[col], rhs_col);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Estimate the reciprocal condition number of `A` from its LU factors, where `anorm`
+/// is the 1-norm of `A` (Lapack `gecon` and `gbcon`).
+pub(crate) fn lu_rcond<Item: HScalar, Lu: LuFactors<Item>>(
+    lu: &Lu,
+    anorm: <Item as Scalar>::Real,
+) -> <Item as Scalar>::Real {
+    let inverse_norm =
+        inverse_norm_1_estimate(lu.pivot_indices().len(), |trans, x| lu_solve(lu, trans, x));
+    reciprocal_condition(anorm, inverse_norm)
+}
+
+/// Compute the LU decomposition with partial pivoting of a square matrix.
+fn lu_decomposition<
+    Item: HScalar,
+    MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>,
+    L: LayoutType,
+>(
+    mat: &Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+) -> Result<DenseLu<Item>, LinAlgError> {
+    let (n, cols) = mat.layout().dim();
+    assert_eq!(
+        n,
+        cols,
+        "LU decomposition requires a square matrix. dim = {:#?}",
+        (n, cols)
+    );
+
+    let mut factor = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, n);
+    for col in 0..n {
+        for row in 0..n {
+            unsafe { *factor.get_unchecked_mut(row, col) = mat.get_unchecked(row, col) };
+        }
+    }
+
+    let mut pivots = Vec::with_capacity(n);
+
+    unsafe {
+        for col in 0..n {
+            let mut pivot = col;
+            let mut max_value = factor.get_unchecked(col, col).abs();
+            for row in col + 1..n {
+                let value = factor.get_unchecked(row, col).abs();
+                if value > max_value {
+                    pivot = row;
+                    max_value = value;
+                }
+            }
+            pivots.push(pivot);
+
+            if factor.get_unchecked(pivot, col) == Item::zero() {
+                return Err(LinAlgError::SingularMatrix(col));
+            }
+
+            if pivot != col {
+                for c in 0..n {
+                    let tmp = factor.get_unchecked(pivot, c);
+                    *factor.get_unchecked_mut(pivot, c) = factor.get_unchecked(col, c);
+                    *factor.get_unchecked_mut(col, c) = tmp;
+                }
+            }
+
+            let diag = factor.get_unchecked(col, col);
+            for row in col + 1..n {
+                *factor.get_unchecked_mut(row, col) /= diag;
+            }
+
+            for c in col + 1..n {
+                let value = factor.get_unchecked(col, c);
+                if value == Item::zero() {
+                    continue;
+                }
+                for row in col + 1..n {
+                    let multiplier = factor.get_unchecked(row, col);
+                    *factor.get_unchecked_mut(row, c) -= multiplier * value;
+                }
+            }
+        }
+    }
+
+    Ok(DenseLu { factor, pivots })
+}
+
+/// The Cholesky decomposition of a Hermitian positive definite matrix.
+///
+/// The decomposition has the form `A = L * L^H`, where `L` is lower triangular with a
+/// positive real diagonal. `L` is stored in the lower triangle of a column major matrix
+/// as in Lapack `potrf` with `uplo = 'L'`. The strict upper triangle is zero.
+pub struct DenseCholesky<Item: HScalar> {
+    factor: MatrixD<Item, ColumnMajor>,
+}
+
+impl<Item: HScalar> DenseCholesky<Item> {
+    /// Return the lower triangular factor `L`.
+    pub fn factor(&self) -> &MatrixD<Item, ColumnMajor> {
+        &self.factor
+    }
+
+    /// Return the dimension `n` of the factorised `n x n` matrix.
+    pub fn dim(&self) -> IndexType {
+        self.factor.layout().dim().0
+    }
+
+    /// Solve `A * X = B` in place, where `rhs` contains `B` on input
+    /// and `X` on output.
+    pub fn solve<
+        MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    >(
+        &self,
+        rhs: &mut Matrix<Item, MatImpl, L, RS, CS>,
+    ) {
+        let n = self.dim();
+        let dim = rhs.layout().dim();
+        assert_eq!(
+            n, dim.0,
+            "Right-hand side has {} rows but system has dimension {}.",
+            dim.0, n
+        );
+
+        for rhs_col in 0..dim.1 {
+            unsafe {
+                // Solve with L.
+                for col in 0..n {
+                    let value =
+                        rhs.get_unchecked(col, rhs_col) / self.factor.get_unchecked(col, col);
+                    *rhs.get_unchecked_mut(col, rhs_col) = value;
+                    for row in col + 1..n {
+                        *rhs.get_unchecked_mut(row, rhs_col) -=
+                            self.factor.get_unchecked(row, col) * value;
+                    }
+                }
+                // Solve with L^H.
+                for col in (0..n).rev() {
+                    let mut value = rhs.get_unchecked(col, rhs_col);
+                    for row in col + 1..n {
+                        value -= self.factor.get_unchecked(row, col).conj()
+                            * rhs.get_unchecked(row, rhs_col);
+                    }
+                    *rhs.get_unchecked_mut(col, rhs_col) =
+                        value / self.factor.get_unchecked(col, col);
+                }
+            }
+        }
+    }
+}
+
+impl<Item: HScalar> DenseCholesky<Item> {
+    /// Estimate the reciprocal condition number `1 / (||A||_1 * ||A^{-1}||_1)`, where
+    /// `anorm` is the 1-norm of the factorised matrix (Lapack `pocon`).
+    pub fn rcond(&self, anorm: <Item as Scalar>::Real) -> <Item as Scalar>::Real {
+        // The matrix is Hermitian, so that solves with A^H are solves with A.
+        let inverse_norm = inverse_norm_1_estimate(self.dim(), |_, x| self.solve(x));
+        reciprocal_condition(anorm, inverse_norm)
+    }
+
+    /// Solve `A * X = B` in place if the estimated reciprocal condition number is at
+    /// least `threshold` and return the estimate. Otherwise `rhs` is not modified and a
+    /// [LinAlgError::IllConditioned] is returned.
+    pub fn solve_checked<
+        MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    >(
+        &self,
+        rhs: &mut Matrix<Item, MatImpl, L, RS, CS>,
+        anorm: <Item as Scalar>::Real,
+        threshold: <Item as Scalar>::Real,
+    ) -> Result<<Item as Scalar>::Real, LinAlgError> {
+        let rcond = check_condition(self.rcond(anorm), threshold)?;
+        self.solve(rhs);
+        Ok(rcond)
+    }
+}
+
+/// Compute the Cholesky decomposition of a Hermitian positive definite matrix. Only the
+/// lower triangle of the matrix is read.
+fn cholesky_decomposition<
+    Item: HScalar,
+    MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>,
+    L: LayoutType,
+>(
+    mat: &Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+) -> Result<DenseCholesky<Item>, LinAlgError> {
+    let (n, cols) = mat.layout().dim();
+    assert_eq!(
+        n,
+        cols,
+        "Cholesky decomposition requires a square matrix. dim = {:#?}",
+        (n, cols)
+    );
+
+    let mut factor = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, n);
+    for col in 0..n {
+        for row in col..n {
+            unsafe { *factor.get_unchecked_mut(row, col) = mat.get_unchecked(row, col) };
+        }
+    }
+
+    unsafe {
+        for col in 0..n {
+            // Subtract the contributions of the previous columns of L.
+            for k in 0..col {
+                let value = factor.get_unchecked(col, k).conj();
+                if value == Item::zero() {
+                    continue;
+                }
+                for row in col..n {
+                    let multiplier = factor.get_unchecked(row, k);
+                    *factor.get_unchecked_mut(row, col) -= multiplier * value;
+                }
+            }
+
+            let pivot = factor.get_unchecked(col, col).re();
+            if num::Float::is_nan(pivot) || pivot <= num::Zero::zero() {
+                return Err(LinAlgError::NotPositiveDefinite(col));
+            }
+            let diag = Item::from_real(num::Float::sqrt(pivot));
+            *factor.get_unchecked_mut(col, col) = diag;
+            for row in col + 1..n {
+                *factor.get_unchecked_mut(row, col) /= diag;
+            }
+        }
+    }
+
+    Ok(DenseCholesky { factor })
+}
+
+/// Compute the thin QR decomposition `A = Q * R` of an `m x n` matrix with `m >= n`
+/// through Householder reflections. On output `mat` is overwritten with the `m x n`
+/// matrix `Q` with orthonormal columns and the `n x n` upper triangular `R` is returned.
+pub(crate) fn thin_qr<Item: HScalar>(
+    mat: &mut MatrixD<Item, ColumnMajor>,
+) -> MatrixD<Item, ColumnMajor> {
+    let (m, n) = mat.layout().dim();
+    assert!(
+        m >= n,
+        "Thin QR requires at least as many rows as columns. dim = {:#?}",
+        (m, n)
+    );
+
+    let mut reflectors = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, n);
+    let mut taus = Vec::with_capacity(n);
+    for col in 0..n {
+        taus.push(reduce_column(mat, &mut reflectors, col));
+    }
+
+    let mut r = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, n);
+    unsafe {
+        for col in 0..n {
+            for row in 0..=col {
+                *r.get_unchecked_mut(row, col) = mat.get_unchecked(row, col);
+            }
+        }
+    }
+
+    // Accumulate Q = H_0 * ... * H_{n-1} * [I; 0] backwards.
+    mat.for_each(|elem| *elem = Item::zero());
+    for index in 0..n {
+        *mat.get_mut(index, index) = Item::one();
+    }
+    for col in (0..n).rev() {
+        apply_reflector(&reflectors, col, taus[col], mat, col);
+    }
+    r
+}
+
+/// Generate the elementary reflector `H = I - tau * v * v^H` with `v = [1, u]` for which
+/// `H^H * [alpha, x] = [beta, 0]` with a real `beta` (Lapack `larfg`). On output `x` is
+/// overwritten with `u`. Return `(beta, tau)`. If `x` is zero and `alpha` is real, `tau`
+/// is zero and `H` is the identity.
+pub(crate) fn make_reflector<Item: HScalar>(
+    alpha: Item,
+    x: &mut [Item],
+) -> (<Item as Scalar>::Real, Item) {
+    let zero = <<Item as Scalar>::Real as num::Zero>::zero();
+    if x.iter().all(|value| *value == Item::zero()) & (alpha.im() == zero) {
+        return (alpha.re(), Item::zero());
+    }
+    let norm = x
+        .iter()
+        .fold(alpha.square(), |acc, value| acc + value.square());
+    let norm = num::Float::sqrt(norm);
+    let beta = if alpha.re() >= zero { -norm } else { norm };
+    let tau = (Item::from_real(beta) - alpha) / Item::from_real(beta);
+    let scale = Item::one() / (alpha - Item::from_real(beta));
+    for value in x.iter_mut() {
+        *value *= scale;
+    }
+    (beta, tau)
+}
+
+/// Generate the reflector `H` for the entries `index..` of column `index` of `mat` with
+/// [make_reflector] and store its vector in column `index` of `reflectors`. Column
+/// `index` of `mat` is overwritten with `H^H` times itself and `H^H` is applied to the
+/// columns to its right. Return `tau`.
+fn reduce_column<Item: HScalar>(
+    mat: &mut MatrixD<Item, ColumnMajor>,
+    reflectors: &mut MatrixD<Item, ColumnMajor>,
+    index: IndexType,
+) -> Item {
+    let m = mat.layout().dim().0;
+    unsafe {
+        let mut x: Vec<Item> = (index + 1..m)
+            .map(|row| mat.get_unchecked(row, index))
+            .collect();
+        let (beta, tau) = make_reflector(mat.get_unchecked(index, index), &mut x);
+        *reflectors.get_unchecked_mut(index, index) = Item::one();
+        *mat.get_unchecked_mut(index, index) = Item::from_real(beta);
+        for (row, value) in (index + 1..m).zip(x) {
+            *reflectors.get_unchecked_mut(row, index) = value;
+            *mat.get_unchecked_mut(row, index) = Item::zero();
+        }
+        apply_reflector(reflectors, index, tau.conj(), mat, index + 1);
+        tau
+    }
+}
+
+/// Apply the reflector `I - tau * v * v^H` from the left to the columns `first_col..` of
+/// `mat`, where `v` is stored in the rows `index..` of column `index` of `reflectors`.
+/// The adjoint of the reflector is applied by passing `conj(tau)`.
+pub(crate) fn apply_reflector<Item: HScalar>(
+    reflectors: &MatrixD<Item, ColumnMajor>,
+    index: IndexType,
+    tau: Item,
+    mat: &mut MatrixD<Item, ColumnMajor>,
+    first_col: IndexType,
+) {
+    if tau == Item::zero() {
+        return;
+    }
+    let (m, n) = mat.layout().dim();
+    unsafe {
+        for col in first_col..n {
+            let mut value = Item::zero();
+            for row in index..m {
+                value += reflectors.get_unchecked(row, index).conj() * mat.get_unchecked(row, col);
+            }
+            if value == Item::zero() {
+                continue;
+            }
+            let value = tau * value;
+            for row in index..m {
+                *mat.get_unchecked_mut(row, col) -= value * reflectors.get_unchecked(row, index);
+            }
+        }
+    }
+}
+
+/// The QR decomposition with column pivoting of a dense matrix.
+///
+/// The decomposition has the form `A * P = Q * R` (Lapack `geqp3`), where `P` is a
+/// permutation, `Q` has orthonormal columns and `R` is upper trapezoidal. In every step
+/// the remaining column with the largest norm is moved to the front. The absolute values
+/// of the diagonal of `R` are therefore non-increasing and reveal the numerical rank.
+/// The column norms are downdated after each step and only recomputed when cancellation
+/// makes the downdate inaccurate.
+///
+/// A truncated decomposition that stops after `k` steps satisfies
+/// `A * P = Q * R + [0, E]`, where `Q` is `m x k`, `R` is `k x n` and the columns of the
+/// residual `E` have the norms that remained when the decomposition was stopped.
+pub struct PivotedQr<Item: HScalar> {
+    reflectors: MatrixD<Item, ColumnMajor>,
+    taus: Vec<Item>,
+    r: MatrixD<Item, ColumnMajor>,
+    permutation: Vec<IndexType>,
+}
+
+impl<Item: HScalar> PivotedQr<Item> {
+    /// Return the dimension `(m, n)` of the factorised matrix.
+    pub fn dim(&self) -> (IndexType, IndexType) {
+        (self.reflectors.layout().dim().0, self.permutation.len())
+    }
+
+    /// Return the number `k` of computed Householder steps.
+    pub fn steps(&self) -> IndexType {
+        self.r.layout().dim().0
+    }
+
+    /// Return the upper trapezoidal `k x n` factor `R`.
+    pub fn r(&self) -> &MatrixD<Item, ColumnMajor> {
+        &self.r
+    }
+
+    /// Return the column permutation. Column `j` of `A * P` is column `permutation[j]` of `A`.
+    pub fn permutation(&self) -> &[IndexType] {
+        &self.permutation
+    }
+
+    /// Return the `m x k` factor `Q` with orthonormal columns.
+    pub fn q(&self) -> MatrixD<Item, ColumnMajor> {
+        let m = self.dim().0;
+        let k = self.steps();
+        let mut q = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, k);
+        for index in 0..k {
+            *q.get_mut(index, index) = Item::one();
+        }
+        for index in (0..k).rev() {
+            apply_reflector(&self.reflectors, index, self.taus[index], &mut q, index);
+        }
+        q
+    }
+
+    /// Overwrite the `m x p` matrix `B` with `H_{k-1}^H * ... * H_0^H * B`, where
+    /// `Q = H_0 * ... * H_{k-1}` are the Householder reflections. The first `k` rows of the
+    /// result are `Q^H * B`.
+    pub fn apply_adjoint_q(&self, mat: &mut MatrixD<Item, ColumnMajor>) {
+        assert_eq!(
+            mat.layout().dim().0,
+            self.dim().0,
+            "Matrix has {} rows but Q has {} rows.",
+            mat.layout().dim().0,
+            self.dim().0
+        );
+        for index in 0..self.steps() {
+            apply_reflector(&self.reflectors, index, self.taus[index].conj(), mat, 0);
+        }
+    }
+
+    /// Return the number of diagonal entries of `R` whose absolute value is larger than
+    /// `tol` times the absolute value of the first diagonal entry.
+    pub fn rank(&self, tol: <Item as Scalar>::Real) -> IndexType {
+        if self.steps() == 0 {
+            return 0;
+        }
+        let first = self.r.get(0, 0).abs();
+        (0..self.steps())
+            .take_while(|&index| {
+                let value = self.r.get(index, index).abs();
+                (value > tol * first) & (value > num::Zero::zero())
+            })
+            .count()
+    }
+}
+
+/// Compute the QR decomposition with column pivoting of an `m x n` matrix. The
+/// decomposition stops after `max_steps` steps, or as soon as all remaining column norms
+/// are at most `tol` times the largest column norm of `A` if a tolerance is given.
+pub(crate) fn pivoted_qr_decomposition<
+    Item: HScalar,
+    MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>,
+    L: LayoutType,
+>(
+    mat: &Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+    max_steps: IndexType,
+    tol: Option<<Item as Scalar>::Real>,
+) -> PivotedQr<Item> {
+    type Real<Item> = <Item as Scalar>::Real;
+    let (m, n) = mat.layout().dim();
+    let max_steps = max_steps.min(m).min(n);
+    let zero = <Real<Item> as num::Zero>::zero();
+    let one = <Real<Item> as num::One>::one();
+    let tol3z = num::Float::sqrt(<Real<Item> as num::Float>::epsilon());
+
+    let mut work = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, n);
+    for col in 0..n {
+        for row in 0..m {
+            unsafe { *work.get_unchecked_mut(row, col) = mat.get_unchecked(row, col) };
+        }
+    }
+    let column_norm = |work: &MatrixD<Item, ColumnMajor>, col: IndexType, first_row: IndexType| {
+        let mut norm = zero;
+        for row in first_row..m {
+            norm += unsafe { work.get_unchecked(row, col) }.square();
+        }
+        num::Float::sqrt(norm)
+    };
+
+    // The partial column norms and the norms at their last recomputation (vn1 and vn2 in Lapack).
+    let mut norms: Vec<Real<Item>> = (0..n).map(|col| column_norm(&work, col, 0)).collect();
+    let mut exact_norms = norms.clone();
+    let threshold = tol.map(|tol| {
+        tol * norms
+            .iter()
+            .fold(zero, |acc, &norm| num::Float::max(acc, norm))
+    });
+    let mut permutation: Vec<IndexType> = (0..n).collect();
+    let mut reflectors = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, max_steps);
+    let mut taus = Vec::with_capacity(max_steps);
+
+    let mut steps = 0;
+    while steps < max_steps {
+        let step = steps;
+        let pivot = (step..n)
+            .max_by(|&first, &second| nan_first_cmp(norms[first], norms[second]))
+            .unwrap();
+        if threshold.is_some_and(|threshold| norms[pivot] <= threshold) {
+            break;
+        }
+        if pivot != step {
+            for row in 0..m {
+                unsafe {
+                    let tmp = work.get_unchecked(row, pivot);
+                    *work.get_unchecked_mut(row, pivot) = work.get_unchecked(row, step);
+                    *work.get_unchecked_mut(row, step) = tmp;
+                }
+            }
+            permutation.swap(pivot, step);
+            norms.swap(pivot, step);
+            exact_norms.swap(pivot, step);
+        }
+
+        taus.push(reduce_column(&mut work, &mut reflectors, step));
+        steps += 1;
+
+        for col in step + 1..n {
+            if norms[col] == zero {
+                continue;
+            }
+            let ratio = unsafe { work.get_unchecked(step, col) }.abs() / norms[col];
+            let temp = num::Float::max(one - ratio * ratio, zero);
+            let ratio = norms[col] / exact_norms[col];
+            if temp * ratio * ratio <= tol3z {
+                norms[col] = column_norm(&work, col, step + 1);
+                exact_norms[col] = norms[col];
+            } else {
+                norms[col] *= num::Float::sqrt(temp);
+            }
+        }
+    }
+
+    let mut r = MatrixD::<Item, ColumnMajor>::zeros_from_dim(steps, n);
+    for col in 0..n {
+        for row in 0..steps.min(col + 1) {
+            unsafe { *r.get_unchecked_mut(row, col) = work.get_unchecked(row, col) };
+        }
+    }
+    if steps < max_steps {
+        let mut truncated = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, steps);
+        for col in 0..steps {
+            for row in col..m {
+                unsafe {
+                    *truncated.get_unchecked_mut(row, col) = reflectors.get_unchecked(row, col)
+                };
+            }
+        }
+        reflectors = truncated;
+    }
+
+    PivotedQr {
+        reflectors,
+        taus,
+        r,
+        permutation,
+    }
+}
+
+/// Return the conjugate transpose of a matrix as column major matrix.
+pub(crate) fn adjoint<
+    Item: HScalar,
+    MatImpl: MatrixTrait<Item, L, RS, CS>,
+    L: LayoutType,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+>(
+    mat: &Matrix<Item, MatImpl, L, RS, CS>,
+) -> MatrixD<Item, ColumnMajor> {
+    let (m, n) = mat.layout().dim();
+    let mut res = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, m);
+    for col in 0..n {
+        for row in 0..m {
+            unsafe { *res.get_unchecked_mut(col, row) = mat.element(row, col).conj() };
+        }
+    }
+    res
+}
+
+/// Return a copy of a matrix in column major layout. Elements that are not stored by the
+/// layout of `mat` are zero.
+pub(crate) fn column_major_copy<
+    Item: HScalar,
+    MatImpl: MatrixTrait<Item, L, RS, CS>,
+    L: LayoutType,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+>(
+    mat: &Matrix<Item, MatImpl, L, RS, CS>,
+) -> MatrixD<Item, ColumnMajor> {
+    let (m, n) = mat.layout().dim();
+    let mut res = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, n);
+    for col in 0..n {
+        for row in 0..m {
+            unsafe { *res.get_unchecked_mut(row, col) = mat.element(row, col) };
+        }
+    }
+    res
+}
+
+/// The factors `(U, S, V)` of a singular value decomposition.
+pub(crate) type SvdFactors<Item> = (
+    MatrixD<Item, ColumnMajor>,
+    Vec<<Item as Scalar>::Real>,
+    MatrixD<Item, ColumnMajor>,
+);
+
+/// Compute the thin singular value decomposition `A = U * S * V^H` of an `m x n` matrix
+/// with `m >= n` by the one-sided Jacobi method. Return `U` (`m x n`), the singular
+/// values in descending order and `V` (`n x n`). The columns of `U` that belong to zero
+/// singular values are zero.
+pub(crate) fn jacobi_svd<Item: HScalar>(mat: &MatrixD<Item, ColumnMajor>) -> SvdFactors<Item> {
+    type Real<Item> = <Item as Scalar>::Real;
+    let (m, n) = mat.layout().dim();
+    assert!(
+        m >= n,
+        "Jacobi SVD requires at least as many rows as columns. dim = {:#?}",
+        (m, n)
+    );
+    let zero = <Real<Item> as num::Zero>::zero();
+    let one = <Real<Item> as num::One>::one();
+    let eps = <Real<Item> as num::Float>::epsilon();
+    // Inner products below this threshold are not rotated away to avoid underflow.
+    let tiny = <Real<Item> as num::Float>::min_positive_value() / eps;
+
+    let mut a = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, n);
+    let mut v = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, n);
+    unsafe {
+        for col in 0..n {
+            for row in 0..m {
+                *a.get_unchecked_mut(row, col) = mat.get_unchecked(row, col);
+            }
+            *v.get_unchecked_mut(col, col) = Item::one();
+        }
+
+        for _ in 0..60 {
+            let mut rotated = false;
+            for p in 0..n {
+                for q in p + 1..n {
+                    let mut alpha = zero;
+                    let mut beta = zero;
+                    let mut gamma = Item::zero();
+                    for row in 0..m {
+                        let (ap, aq) = (a.get_unchecked(row, p), a.get_unchecked(row, q));
+                        alpha += ap.square();
+                        beta += aq.square();
+                        gamma += ap.conj() * aq;
+                    }
+                    let g = gamma.abs();
+                    if g <= num::Float::max(
+                        eps * num::Float::sqrt(alpha) * num::Float::sqrt(beta),
+                        tiny,
+                    ) {
+                        continue;
+                    }
+                    rotated = true;
+
+                    // Rotate the columns p and conj(phase) * q, whose inner product is real.
+                    // Multiplying with 1 / g avoids the underflow of a complex division for
+                    // tiny columns.
+                    let phase = gamma * Item::from_real(one / g);
+                    let two = one + one;
+                    let zeta = (beta - alpha) / (two * g);
+                    let t = num::Float::signum(zeta)
+                        / (num::Float::abs(zeta) + num::Float::sqrt(one + zeta * zeta));
+                    let c = one / num::Float::sqrt(one + t * t);
+                    let (c, s) = (Item::from_real(c), Item::from_real(c * t));
+                    for row in 0..m {
+                        let ap = a.get_unchecked(row, p);
+                        let bq = phase.conj() * a.get_unchecked(row, q);
+                        *a.get_unchecked_mut(row, p) = c * ap - s * bq;
+                        *a.get_unchecked_mut(row, q) = phase * (s * ap + c * bq);
+                    }
+                    for row in 0..n {
+                        let vp = v.get_unchecked(row, p);
+                        let wq = phase.conj() * v.get_unchecked(row, q);
+                        *v.get_unchecked_mut(row, p) = c * vp - s * wq;
+                        *v.get_unchecked_mut(row, q) = phase * (s * vp + c * wq);
+                    }
+                }
+            }
+            if !rotated {
+                break;
+            }
+        }
+
+        let norms: Vec<Real<Item>> = (0..n)
+            .map(|col| {
+                let mut norm = zero;
+                for row in 0..m {
+                    norm += a.get_unchecked(row, col).square();
+                }
+                num::Float::sqrt(norm)
+            })
+            .collect();
+        let mut order: Vec<IndexType> = (0..n).collect();
+        order.sort_by(|&first, &second| nan_first_cmp(norms[second], norms[first]));
+
+        let mut u = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, n);
+        let mut v_sorted = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, n);
+        let mut values = Vec::with_capacity(n);
+        for (col, &index) in order.iter().enumerate() {
+            let norm = norms[index];
+            values.push(norm);
+            for row in 0..m {
+                *u.get_unchecked_mut(row, col) = if norm == zero {
+                    Item::zero()
+                } else {
+                    a.get_unchecked(row, index) / Item::from_real(norm)
+                };
+            }
+            for row in 0..n {
+                *v_sorted.get_unchecked_mut(row, col) = v.get_unchecked(row, index);
+            }
+        }
+        (u, values, v_sorted)
+    }
+}
+
+macro_rules! dense_lu {
+    ($Layout:ident) => {
+        impl<Item: HScalar, Data: DataContainer<Item = Item>>
+            GenericBaseMatrix<Item, $Layout, Data, Dynamic, Dynamic>
+        {
+            /// Compute the LU decomposition with partial pivoting of a square matrix.
+            pub fn lu(&self) -> Result<DenseLu<Item>, LinAlgError> {
+                lu_decomposition(self)
+            }
+        }
+    };
+}
+
+dense_lu!(RowMajor);
+dense_lu!(ColumnMajor);
+dense_lu!(ArbitraryStrideRowMajor);
+dense_lu!(ArbitraryStrideColumnMajor);
+
+macro_rules! dense_cholesky {
+    ($Layout:ident) => {
+        impl<Item: HScalar, Data: DataContainer<Item = Item>>
+            GenericBaseMatrix<Item, $Layout, Data, Dynamic, Dynamic>
+        {
+            /// Compute the Cholesky decomposition of a Hermitian positive definite matrix.
+            /// Only the lower triangle is read.
+            pub fn cholesky(&self) -> Result<DenseCholesky<Item>, LinAlgError> {
+                cholesky_decomposition(self)
+            }
+        }
+    };
+}
+
+dense_cholesky!(RowMajor);
+dense_cholesky!(ColumnMajor);
+dense_cholesky!(ArbitraryStrideRowMajor);
+dense_cholesky!(ArbitraryStrideColumnMajor);
+
+macro_rules! dense_qr {
+    ($Layout:ident) => {
+        impl<Item: HScalar, Data: DataContainer<Item = Item>>
+            GenericBaseMatrix<Item, $Layout, Data, Dynamic, Dynamic>
+        {
+            /// Compute the QR decomposition with column pivoting `A * P = Q * R`.
+            pub fn pivoted_qr(&self) -> PivotedQr<Item> {
+                let (m, n) = self.layout().dim();
+                pivoted_qr_decomposition(self, m.min(n), None)
+            }
+        }
+    };
+}
+
+dense_qr!(RowMajor);
+dense_qr!(ColumnMajor);
+dense_qr!(ArbitraryStrideRowMajor);
+dense_qr!(ArbitraryStrideColumnMajor);
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::test_utils::assert_close;
+    use rand::prelude::*;
+
+    macro_rules! dense_lu_test {
+        ($HScalar:ty, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let n = 10;
+                let mut rng = StdRng::seed_from_u64(0);
+                let mut mat = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                mat.fill_from_rand_standard_normal(&mut rng);
+                let lu = mat.lu().unwrap();
+
+                for trans in [
+                    TransposeMode::NoTrans,
+                    TransposeMode::Trans,
+                    TransposeMode::ConjTrans,
+                ] {
+                    let mut sol = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, 2);
+                    sol.fill_from_rand_standard_normal(&mut rng);
+                    let mut rhs = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, 2);
+                    for row in 0..n {
+                        for col in 0..2 {
+                            let mut value = <$HScalar as num::Zero>::zero();
+                            for index in 0..n {
+                                let elem = match trans {
+                                    TransposeMode::NoTrans => mat.get(row, index),
+                                    TransposeMode::Trans => mat.get(index, row),
+                                    TransposeMode::ConjTrans => mat.get(index, row).conj(),
+                                };
+                                value += elem * sol.get(index, col);
+                            }
+                            *rhs.get_mut(row, col) = value;
+                        }
+                    }
+
+                    lu.solve(trans, &mut rhs);
+                    for row in 0..n {
+                        for col in 0..2 {
+                            assert_close(rhs.get(row, col), sol.get(row, col), $tol);
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    dense_lu_test!(f32, 1E-3, test_dense_lu_f32);
+    dense_lu_test!(f64, 1E-10, test_dense_lu_f64);
+    dense_lu_test!(c32, 1E-3, test_dense_lu_c32);
+    dense_lu_test!(c64, 1E-10, test_dense_lu_c64);
+
+    #[test]
+    fn test_lu_of_block() {
+        let n = 6;
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+        for index in 0..n {
+            *mat.get_mut(index, index) = 1.0 + index as f64;
+        }
+        *mat.get_mut(3, 2) = 5.0;
+
+        let lu = mat.block((2, 2), (2, 2)).lu().unwrap();
+        assert_eq!(lu.pivots(), &[1, 1]);
+        let mut rhs = ColumnVectorD::<f64>::zeros_from_length(2);
+        *rhs.get1d_mut(0) = 3.0;
+        *rhs.get1d_mut(1) = 9.0;
+        lu.solve(TransposeMode::NoTrans, &mut rhs);
+        assert_close(rhs.get1d(0), 1.0, 1E-14);
+        assert_close(rhs.get1d(1), 1.0, 1E-14);
+    }
+
+    macro_rules! dense_cholesky_test {
+        ($HScalar:ty, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let n = 10;
+                let mut rng = StdRng::seed_from_u64(0);
+                let mut mat = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, n);
+                mat.fill_from_rand_standard_normal(&mut rng);
+                // The matrix B^H * B + n * I is Hermitian positive definite.
+                let mut spd = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                for row in 0..n {
+                    for col in 0..n {
+                        let mut value = <$HScalar as num::Zero>::zero();
+                        for index in 0..n {
+                            value += mat.get(index, row).conj() * mat.get(index, col);
+                        }
+                        if row == col {
+                            value += num::cast::<f64, $HScalar>(n as f64).unwrap();
+                        }
+                        *spd.get_mut(row, col) = value;
+                    }
+                }
+                let chol = spd.cholesky().unwrap();
+                assert_eq!(chol.dim(), n);
+                assert_eq!(chol.factor().get(0, 1), <$HScalar as num::Zero>::zero());
+
+                let mut sol = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, 2);
+                sol.fill_from_rand_standard_normal(&mut rng);
+                let mut rhs = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, 2);
+                for row in 0..n {
+                    for col in 0..2 {
+                        let mut value = <$HScalar as num::Zero>::zero();
+                        for index in 0..n {
+                            value += spd.get(row, index) * sol.get(index, col);
+                        }
+                        *rhs.get_mut(row, col) = value;
+                    }
+                }
+
+                chol.solve(&mut rhs);
+                for row in 0..n {
+                    for col in 0..2 {
+                        assert_close(rhs.get(row, col), sol.get(row, col), $tol);
+                    }
+                }
+            }
+        };
+    }
+
+    dense_cholesky_test!(f32, 1E-3, test_dense_cholesky_f32);
+    dense_cholesky_test!(f64, 1E-10, test_dense_cholesky_f64);
+    dense_cholesky_test!(c32, 1E-3, test_dense_cholesky_c32);
+    dense_cholesky_test!(c64, 1E-10, test_dense_cholesky_c64);
+
+    #[test]
+    fn test_not_positive_definite() {
+        let mut mat = MatrixD::<f64, RowMajor>::zeros_from_dim(3, 3);
+        *mat.get_mut(0, 0) = 4.0;
+        *mat.get_mut(1, 0) = 2.0;
+        *mat.get_mut(1, 1) = 1.0;
+        *mat.get_mut(2, 2) = 1.0;
+        assert_eq!(
+            mat.cholesky().err(),
+            Some(LinAlgError::NotPositiveDefinite(1))
+        );
+
+        *mat.get_mut(1, 1) = f64::NAN;
+        assert_eq!(
+            mat.cholesky().err(),
+            Some(LinAlgError::NotPositiveDefinite(1))
+        );
+
+        // The upper triangle is not read.
+        *mat.get_mut(1, 1) = 2.0;
+        *mat.get_mut(0, 2) = f64::NAN;
+        let chol = mat.cholesky().unwrap();
+        assert_eq!(chol.factor().get(0, 0), 2.0);
+        assert_eq!(chol.factor().get(1, 0), 1.0);
+        assert_eq!(chol.factor().get(1, 1), 1.0);
+    }
+
+    #[test]
+    fn test_singular_dense_matrix() {
+        let mut mat = MatrixD::<f64, RowMajor>::zeros_from_dim(3, 3);
+        *mat.get_mut(0, 0) = 1.0;
+        *mat.get_mut(1, 0) = 2.0;
+        assert_eq!(mat.lu().err(), Some(LinAlgError::SingularMatrix(1)));
+    }
+}
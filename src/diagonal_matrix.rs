@@ -0,0 +1,457 @@
+//! Diagonal matrices.
+//!
+//! A [DiagonalMatrix] stores only the diagonal elements of a square matrix
+//! in a [VectorContainer]. Its layout is [Diagonal], which takes the logical
+//! indexing from a base layout `B` (by default [RowMajor]). Reads of off-diagonal
+//! elements return zero. A mutable access to an off-diagonal element returns a scratch
+//! value that is reset to zero on every access, so that writes to it are discarded. This
+//! allows 1d traversals such as [eval_into](Matrix::eval_into) to target a diagonal
+//! matrix, in which case only the diagonal of the source is copied.
+//!
+//! Since the index layout is `B`, a diagonal matrix can be combined with dense
+//! matrices of the same index layout in expressions, e.g. `2.0 * diag + dense`.
+//! Products of a diagonal matrix with dense matrices are implemented through the
+//! [Dot] trait as row or column scalings and require only `O(n^2)` operations.
+//!
+//! The following example scales the rows of a dense matrix.
+//! ```
+//! # use householder::*;
+//! let diag = DiagonalMatrix::<f64>::from_diagonal(&[1.0, 2.0]);
+//! let mut dense = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 3);
+//! *dense.get_mut(1, 2) = 3.0;
+//! let res = diag.dot(&dense);
+//! assert_eq!(res.get(1, 2), 6.0);
+//! ```
+
+use crate::data_container::{DataContainer, DataContainerMut, VectorContainer};
+use crate::error::LinAlgError;
+use crate::layouts::*;
+use crate::matrix::*;
+use crate::matrix_multiply::Dot;
+use crate::traits::*;
+use crate::types::*;
+
+/// A dynamic square diagonal matrix with logical indexing `B`.
+pub type DiagonalMatrix<Item, B = RowMajor> =
+    Matrix<Item, DiagonalImpl<Item, B>, Diagonal<B>, Dynamic, Dynamic>;
+
+/// The implementation type of a [DiagonalMatrix].
+pub struct DiagonalImpl<Item: HScalar, B: BaseLayoutType> {
+    data: VectorContainer<Item>,
+    layout: Diagonal<B>,
+    scratch: Item,
+}
+
+impl<Item: HScalar, B: BaseLayoutType> DiagonalImpl<Item, B> {
+    pub fn new(data: VectorContainer<Item>, layout: Diagonal<B>) -> Self {
+        assert_eq!(
+            layout.dim().0,
+            data.number_of_elements(),
+            "Number of elements in data: {}. But diagonal has length {}.",
+            data.number_of_elements(),
+            layout.dim().0
+        );
+        Self {
+            data,
+            layout,
+            scratch: Item::zero(),
+        }
+    }
+
+    /// Return the scratch value used for mutable accesses to off-diagonal elements.
+    #[inline]
+    fn discarded(&mut self) -> &mut Item {
+        self.scratch = Item::zero();
+        &mut self.scratch
+    }
+}
+
+impl<Item: HScalar, B: BaseLayoutType> Layout for DiagonalImpl<Item, B> {
+    type Impl = Diagonal<B>;
+
+    #[inline]
+    fn layout(&self) -> &Self::Impl {
+        &self.layout
+    }
+}
+
+impl<Item: HScalar, B: BaseLayoutType> SizeType for DiagonalImpl<Item, B> {
+    type R = Dynamic;
+    type C = Dynamic;
+}
+
+impl<Item: HScalar, B: BaseLayoutType> UnsafeRandomAccess for DiagonalImpl<Item, B> {
+    type Item = Item;
+
+    #[inline]
+    unsafe fn get_unchecked(&self, row: IndexType, col: IndexType) -> Self::Item {
+        if self.layout.is_stored(row, col) {
+            self.data
+                .get_unchecked(self.layout.convert_2d_raw(row, col))
+        } else {
+            Item::zero()
+        }
+    }
+
+    #[inline]
+    unsafe fn get1d_unchecked(&self, index: IndexType) -> Self::Item {
+        let (row, col) = self.layout.convert_1d_2d(index);
+        self.get_unchecked(row, col)
+    }
+}
+
+impl<Item: HScalar, B: BaseLayoutType> UnsafeRandomAccessMut for DiagonalImpl<Item, B> {
+    type Item = Item;
+
+    #[inline]
+    unsafe fn get_unchecked_mut(&mut self, row: IndexType, col: IndexType) -> &mut Self::Item {
+        if self.layout.is_stored(row, col) {
+            self.data
+                .get_unchecked_mut(self.layout.convert_2d_raw(row, col))
+        } else {
+            self.discarded()
+        }
+    }
+
+    #[inline]
+    unsafe fn get1d_unchecked_mut(&mut self, index: IndexType) -> &mut Self::Item {
+        let (row, col) = self.layout.convert_1d_2d(index);
+        self.get_unchecked_mut(row, col)
+    }
+}
+
+impl<Item: HScalar, B: BaseLayoutType> DiagonalMatrix<Item, B> {
+    /// Create a new square diagonal matrix with zero diagonal.
+    pub fn zeros_from_dim(rows: IndexType, cols: IndexType) -> Self {
+        let layout = Diagonal::<B>::from_dimension((rows, cols));
+        Self::new(DiagonalImpl::new(VectorContainer::new(rows), layout))
+    }
+
+    /// Create the identity matrix of dimension `n x n`.
+    pub fn identity(n: IndexType) -> Self {
+        Self::from_diagonal(&vec![Item::one(); n])
+    }
+
+    /// Create a diagonal matrix from its diagonal elements.
+    pub fn from_diagonal(diag: &[Item]) -> Self {
+        let mut mat = Self::zeros_from_dim(diag.len(), diag.len());
+        for (index, &value) in diag.iter().enumerate() {
+            *mat.get_mut(index, index) = value;
+        }
+        mat
+    }
+
+    /// Return the diagonal elements.
+    pub fn diagonal(&self) -> Vec<Item> {
+        (0..self.layout().dim().0)
+            .map(|index| unsafe { self.get_unchecked(index, index) })
+            .collect()
+    }
+
+    /// Return the first index of a zero diagonal element or `None`
+    /// if all diagonal elements are nonzero.
+    fn first_zero(&self) -> Option<IndexType> {
+        (0..self.layout().dim().0)
+            .find(|&index| unsafe { self.get_unchecked(index, index) } == Item::zero())
+    }
+
+    /// Return the inverse of the matrix.
+    pub fn inverse(&self) -> Result<Self, LinAlgError> {
+        if let Some(index) = self.first_zero() {
+            return Err(LinAlgError::SingularMatrix(index));
+        }
+        let diag: Vec<Item> = self
+            .diagonal()
+            .iter()
+            .map(|&value| Item::one() / value)
+            .collect();
+        Ok(Self::from_diagonal(&diag))
+    }
+
+    /// Solve `D * X = B` in place, where `rhs` contains `B` on input
+    /// and `X` on output.
+    pub fn solve<
+        MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    >(
+        &self,
+        rhs: &mut Matrix<Item, MatImpl, L, RS, CS>,
+    ) -> Result<(), LinAlgError> {
+        let dim = rhs.layout().dim();
+        assert_eq!(
+            self.layout().dim().0,
+            dim.0,
+            "Incompatible dimensions for D * X = B: D = {:#?}, B = {:#?}",
+            self.layout().dim(),
+            dim
+        );
+        if let Some(index) = self.first_zero() {
+            return Err(LinAlgError::SingularMatrix(index));
+        }
+        for row in 0..dim.0 {
+            let value = unsafe { self.get_unchecked(row, row) };
+            for col in 0..dim.1 {
+                unsafe { *rhs.get_unchecked_mut(row, col) /= value };
+            }
+        }
+        Ok(())
+    }
+
+    /// Overwrite `mat` with `D * mat`, i.e. scale the rows of `mat`.
+    pub fn scale_rows<
+        MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    >(
+        &self,
+        mat: &mut Matrix<Item, MatImpl, L, RS, CS>,
+    ) {
+        let dim = mat.layout().dim();
+        assert_eq!(
+            self.layout().dim().1,
+            dim.0,
+            "Incompatible dimensions for D * A: D = {:#?}, A = {:#?}",
+            self.layout().dim(),
+            dim
+        );
+        for row in 0..dim.0 {
+            let value = unsafe { self.get_unchecked(row, row) };
+            for col in 0..dim.1 {
+                unsafe { *mat.get_unchecked_mut(row, col) *= value };
+            }
+        }
+    }
+
+    /// Overwrite `mat` with `mat * D`, i.e. scale the columns of `mat`.
+    pub fn scale_cols<
+        MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    >(
+        &self,
+        mat: &mut Matrix<Item, MatImpl, L, RS, CS>,
+    ) {
+        let dim = mat.layout().dim();
+        assert_eq!(
+            dim.1,
+            self.layout().dim().0,
+            "Incompatible dimensions for A * D: A = {:#?}, D = {:#?}",
+            dim,
+            self.layout().dim()
+        );
+        for col in 0..dim.1 {
+            let value = unsafe { self.get_unchecked(col, col) };
+            for row in 0..dim.0 {
+                unsafe { *mat.get_unchecked_mut(row, col) *= value };
+            }
+        }
+    }
+}
+
+// Diagonal x Matrix = Matrix
+impl<Item: HScalar, B: BaseLayoutType, L: LayoutType, Data: DataContainer<Item = Item>>
+    Dot<GenericBaseMatrix<Item, L, Data, Dynamic, Dynamic>> for DiagonalMatrix<Item, B>
+{
+    type Output = MatrixD<Item, L::IndexLayout>;
+
+    fn dot(&self, rhs: &GenericBaseMatrix<Item, L, Data, Dynamic, Dynamic>) -> Self::Output {
+        let dim = rhs.layout().dim();
+        assert_eq!(
+            self.layout().dim().1,
+            dim.0,
+            "Incompatible dimensions for D * A: D = {:#?}, A = {:#?}",
+            self.layout().dim(),
+            dim
+        );
+        let mut res = Self::Output::zeros_from_layout(rhs.layout().index_layout());
+        unsafe {
+            for index in 0..res.layout().number_of_elements() {
+                let (row, col) = res.layout().convert_1d_2d(index);
+                *res.get1d_unchecked_mut(index) =
+                    self.get_unchecked(row, row) * rhs.get_unchecked(row, col);
+            }
+        }
+        res
+    }
+}
+
+// Diagonal x ColumnVector = ColumnVector
+impl<Item: HScalar, B: BaseLayoutType, L: LayoutType, Data: DataContainer<Item = Item>>
+    Dot<GenericBaseMatrix<Item, L, Data, Dynamic, Fixed1>> for DiagonalMatrix<Item, B>
+{
+    type Output = ColumnVectorD<Item>;
+
+    fn dot(&self, rhs: &GenericBaseMatrix<Item, L, Data, Dynamic, Fixed1>) -> Self::Output {
+        let length = rhs.layout().number_of_elements();
+        assert_eq!(
+            self.layout().dim().1,
+            length,
+            "Incompatible dimensions for D * x: D = {:#?}, x = {:#?}",
+            self.layout().dim(),
+            rhs.layout().dim()
+        );
+        let mut res = Self::Output::zeros_from_length(length);
+        unsafe {
+            for index in 0..length {
+                *res.get1d_unchecked_mut(index) =
+                    self.get_unchecked(index, index) * rhs.get1d_unchecked(index);
+            }
+        }
+        res
+    }
+}
+
+// Matrix x Diagonal = Matrix
+impl<Item: HScalar, B: BaseLayoutType, L: LayoutType, Data: DataContainer<Item = Item>>
+    Dot<DiagonalMatrix<Item, B>> for GenericBaseMatrix<Item, L, Data, Dynamic, Dynamic>
+{
+    type Output = MatrixD<Item, L::IndexLayout>;
+
+    fn dot(&self, rhs: &DiagonalMatrix<Item, B>) -> Self::Output {
+        let dim = self.layout().dim();
+        assert_eq!(
+            dim.1,
+            rhs.layout().dim().0,
+            "Incompatible dimensions for A * D: A = {:#?}, D = {:#?}",
+            dim,
+            rhs.layout().dim()
+        );
+        let mut res = Self::Output::zeros_from_layout(self.layout().index_layout());
+        unsafe {
+            for index in 0..res.layout().number_of_elements() {
+                let (row, col) = res.layout().convert_1d_2d(index);
+                *res.get1d_unchecked_mut(index) =
+                    self.get_unchecked(row, col) * rhs.get_unchecked(col, col);
+            }
+        }
+        res
+    }
+}
+
+// Diagonal x Diagonal = Diagonal
+impl<Item: HScalar, B: BaseLayoutType> Dot<DiagonalMatrix<Item, B>> for DiagonalMatrix<Item, B> {
+    type Output = DiagonalMatrix<Item, B>;
+
+    fn dot(&self, rhs: &DiagonalMatrix<Item, B>) -> Self::Output {
+        assert_eq!(
+            self.layout().dim(),
+            rhs.layout().dim(),
+            "Incompatible dimensions for D1 * D2: D1 = {:#?}, D2 = {:#?}",
+            self.layout().dim(),
+            rhs.layout().dim()
+        );
+        let diag: Vec<Item> = self
+            .diagonal()
+            .iter()
+            .zip(rhs.diagonal().iter())
+            .map(|(&first, &second)| first * second)
+            .collect();
+        Self::Output::from_diagonal(&diag)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use rand::prelude::*;
+
+    macro_rules! diagonal_product_test {
+        ($HScalar:ty, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let diag_values: Vec<$HScalar> = (1..4)
+                    .map(|index| num::cast::<f64, $HScalar>(index as f64).unwrap())
+                    .collect();
+                let diag = DiagonalMatrix::<$HScalar, ColumnMajor>::from_diagonal(&diag_values);
+                let mut dense = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(3, 3);
+                let mut rng = StdRng::seed_from_u64(0);
+                dense.fill_from_rand_standard_normal(&mut rng);
+
+                let left = diag.dot(&dense);
+                let right = dense.dot(&diag);
+                for row in 0..3 {
+                    for col in 0..3 {
+                        assert_eq!(left.get(row, col), diag_values[row] * dense.get(row, col));
+                        assert_eq!(right.get(row, col), dense.get(row, col) * diag_values[col]);
+                    }
+                }
+
+                let mut rhs = diag.dot(&dense);
+                diag.solve(&mut rhs).unwrap();
+                for index in 0..9 {
+                    let diff = (rhs.get1d(index) - dense.get1d(index)).abs();
+                    assert!(diff < 1E-5);
+                }
+            }
+        };
+    }
+
+    diagonal_product_test!(f32, test_diagonal_product_f32);
+    diagonal_product_test!(f64, test_diagonal_product_f64);
+    diagonal_product_test!(c32, test_diagonal_product_c32);
+    diagonal_product_test!(c64, test_diagonal_product_c64);
+
+    #[test]
+    fn test_diagonal_reads_and_addition() {
+        let diag = DiagonalMatrix::<f64>::from_diagonal(&[1.0, 2.0, 3.0]);
+        assert_eq!(diag.get(0, 1), 0.0);
+        assert_eq!(diag.get(2, 2), 3.0);
+
+        let mut dense = MatrixD::<f64, RowMajor>::zeros_from_dim(3, 3);
+        *dense.get_mut(0, 1) = 5.0;
+        *dense.get_mut(1, 1) = 1.0;
+
+        let res = (2.0 * diag + dense).eval();
+        assert_eq!(res.get(0, 0), 2.0);
+        assert_eq!(res.get(0, 1), 5.0);
+        assert_eq!(res.get(1, 1), 5.0);
+        assert_eq!(res.get(2, 0), 0.0);
+    }
+
+    #[test]
+    fn test_diagonal_off_diagonal_writes() {
+        let mut diag = DiagonalMatrix::<f64>::from_diagonal(&[1.0, 2.0, 3.0]);
+        *diag.get1d_mut(1) = 5.0;
+        *diag.get_mut(2, 0) += 7.0;
+        assert_eq!(*diag.get1d_mut(1), 0.0);
+        *diag.get1d_mut(4) = 4.0;
+        assert_eq!(diag.diagonal(), vec![1.0, 4.0, 3.0]);
+        assert_eq!(diag.get(0, 1), 0.0);
+        assert_eq!(diag.get(2, 0), 0.0);
+
+        let mut dense = MatrixD::<f64, RowMajor>::zeros_from_dim(3, 3);
+        for index in 0..9 {
+            *dense.get1d_mut(index) = 1.0 + index as f64;
+        }
+        dense.eval_into(&mut diag);
+        assert_eq!(diag.diagonal(), vec![1.0, 5.0, 9.0]);
+        assert_eq!(diag.get(1, 2), 0.0);
+    }
+
+    #[test]
+    fn test_diagonal_inverse() {
+        let diag = DiagonalMatrix::<f64>::from_diagonal(&[1.0, 2.0, 4.0]);
+        let inverse = diag.inverse().unwrap();
+        assert_eq!(inverse.diagonal(), vec![1.0, 0.5, 0.25]);
+        assert_eq!(diag.dot(&inverse).diagonal(), vec![1.0; 3]);
+
+        let singular = DiagonalMatrix::<f64>::from_diagonal(&[1.0, 0.0, 4.0]);
+        assert_eq!(
+            singular.inverse().err(),
+            Some(LinAlgError::SingularMatrix(1))
+        );
+
+        let mut rhs = MatrixD::<f64, RowMajor>::zeros_from_dim(3, 2);
+        *rhs.get_mut(0, 0) = 1.0;
+        assert_eq!(
+            singular.solve(&mut rhs).err(),
+            Some(LinAlgError::SingularMatrix(1))
+        );
+        assert_eq!(rhs.get(0, 0), 1.0);
+    }
+}
@@ -0,0 +1,26 @@
+//! Error types.
+//!
+//! Routines that can fail for numerical reasons (e.g. factorisations of singular
+//! matrices) return a [LinAlgError] instead of panicking. Violations of preconditions
+//! such as incompatible dimensions are programming errors and are handled with
+//! assertions.
+
+use crate::types::IndexType;
+use thiserror::Error;
+
+/// Numerical errors of linear algebra routines.
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum LinAlgError {
+    /// A pivot in the given column is exactly zero.
+    #[error("Matrix is singular. Zero pivot in column {0}.")]
+    SingularMatrix(IndexType),
+    /// The pivot in the given column of a Cholesky decomposition is not positive.
+    #[error("Matrix is not positive definite. Nonpositive pivot in column {0}.")]
+    NotPositiveDefinite(IndexType),
+    /// The estimated reciprocal condition number is below the requested threshold.
+    #[error("Matrix is ill-conditioned. Estimated reciprocal condition number {0:e}.")]
+    IllConditioned(f64),
+    /// The element `(row, col)` is finite but too large for single precision.
+    #[error("Element ({0}, {1}) overflows in single precision.")]
+    PrecisionOverflow(IndexType, IndexType),
+}
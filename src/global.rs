@@ -1,8 +1,34 @@
 //! Re-exports for definitions to be made available on crate level.
 
+pub use crate::band_solvers::{BandedLu, TridiagonalLdlt};
+pub use crate::condensed::{
+    BidiagonalReduction, HessenbergReduction, HouseholderSequence, TridiagonalReduction,
+};
+pub use crate::conversion::ScalarCast;
 pub use crate::data_container::*;
+pub use crate::dense_solvers::{DenseCholesky, DenseLu, PivotedQr};
+pub use crate::diagonal_matrix::DiagonalMatrix;
+pub use crate::error::LinAlgError;
+pub use crate::hmatrix::{
+    Admissibility, BlockData, Cluster, ClusterTree, HBlock, HMatrix, HMatrixStats,
+    StrongAdmissibility, WeakAdmissibility,
+};
+pub use crate::interpolative::{ColumnId, RowId};
+pub use crate::iterative::{
+    Arnoldi, BiCgStab, BlockJacobi, ConjugateGradient, ConvergenceReport, EigenReport, FnOperator,
+    Gmres, IdentityOperator, Ilu0, Jacobi, Lanczos, LinearOperator, Preconditioner, Ssor, Which,
+};
 pub use crate::layouts::*;
+pub use crate::least_squares::{LstsqMethod, LstsqSolution};
+pub use crate::low_rank::{Aca, AcaVariant, LowRankMatrix};
 pub use crate::matrix::*;
 pub use crate::matrix_multiply::{Dot, MatMul};
+pub use crate::permutation::Permutation;
+pub use crate::randomized::{RandomizedOptions, RandomizedSvd};
+pub use crate::refinement::{IterativeRefinement, MixedPrecision, MixedPrecisionLu};
+pub use crate::sparse::{CooMatrix, CscMatrix, CsrMatrix};
+pub use crate::symmetric_multiply::SymmetricMatMul;
 pub use crate::tools::*;
 pub use crate::traits::*;
+pub use crate::triangular_multiply::TriangularMatMul;
+pub use crate::types::{DiagonalType, Side, TransposeMode, TriangularType};
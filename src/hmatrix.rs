@@ -0,0 +1,491 @@
+//! Hierarchical matrices.
+//!
+//! An [HMatrix] approximates an `m x n` matrix whose rows and columns are associated with
+//! points in space, e.g. the matrix of a boundary element discretisation of an integral
+//! operator with a smooth kernel. The index sets are organised in [ClusterTree]s and the
+//! matrix is partitioned into blocks `s x t` of a row cluster `s` and a column cluster `t`.
+//! Starting with the roots, a block is stored as a [LowRankMatrix] if it is admissible,
+//! i.e. if the clusters are well separated compared to their size. Otherwise it is
+//! subdivided into the blocks of the children. Inadmissible blocks of leaf clusters form
+//! the near field and are stored as dense matrices.
+//!
+//! The admissibility condition is described by the [Admissibility] trait. The standard
+//! condition [StrongAdmissibility] and the [WeakAdmissibility] are provided.
+//!
+//! The low-rank blocks are assembled with the adaptive cross approximation ([Aca]) from a
+//! closure that evaluates the kernel for an original row and column index. The blocks are
+//! then recompressed to the requested tolerance. An [HMatrix] implements
+//! [LinearOperator](crate::iterative::LinearOperator) and can therefore be used with the
+//! iterative solvers.
+//!
+//! ```
+//! # use householder::*;
+//! let points: Vec<f64> = (0..200).map(|index| index as f64 / 200.0).collect();
+//! let tree = ClusterTree::new(1, &points, 16);
+//! let kernel = |row: usize, col: usize| 1.0 / (1E-2 + (points[row] - points[col]).abs());
+//! let mat = HMatrix::assemble(&tree, &tree, &StrongAdmissibility::default(), kernel, 1E-8);
+//! assert!(mat.stats().compression() < 1.0);
+//! ```
+
+pub mod cluster_tree;
+
+pub use cluster_tree::*;
+
+use crate::layouts::*;
+use crate::low_rank::{Aca, LowRankMatrix};
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::*;
+
+/// The storage of a block of an [HMatrix].
+pub enum BlockData<Item: HScalar> {
+    /// A dense block of the near field.
+    Dense(MatrixD<Item, ColumnMajor>),
+    /// A low-rank block of the far field.
+    LowRank(LowRankMatrix<Item>),
+}
+
+/// A leaf of the block tree of an [HMatrix].
+pub struct HBlock<Item: HScalar> {
+    rows: IndexType,
+    cols: IndexType,
+    data: BlockData<Item>,
+}
+
+impl<Item: HScalar> HBlock<Item> {
+    /// Return the index of the row cluster.
+    pub fn rows(&self) -> IndexType {
+        self.rows
+    }
+
+    /// Return the index of the column cluster.
+    pub fn cols(&self) -> IndexType {
+        self.cols
+    }
+
+    /// Return the data of the block. Its rows and columns are in the permuted numbering
+    /// of the cluster trees.
+    pub fn data(&self) -> &BlockData<Item> {
+        &self.data
+    }
+}
+
+/// Memory statistics of an [HMatrix].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HMatrixStats {
+    /// The dimension `(m, n)` of the matrix.
+    pub dim: (IndexType, IndexType),
+    /// The number of dense blocks.
+    pub dense_blocks: IndexType,
+    /// The number of low-rank blocks.
+    pub low_rank_blocks: IndexType,
+    /// The number of stored entries of the dense blocks.
+    pub dense_entries: IndexType,
+    /// The number of stored entries of the factors of the low-rank blocks.
+    pub low_rank_entries: IndexType,
+    /// The largest rank of a low-rank block.
+    pub max_rank: IndexType,
+}
+
+impl HMatrixStats {
+    /// Return the number of stored entries relative to the `m * n` entries of the dense matrix.
+    pub fn compression(&self) -> f64 {
+        (self.dense_entries + self.low_rank_entries) as f64 / (self.dim.0 * self.dim.1) as f64
+    }
+}
+
+/// A hierarchical matrix with dense near-field blocks and low-rank far-field blocks.
+pub struct HMatrix<Item: HScalar> {
+    row_tree: ClusterTree,
+    col_tree: ClusterTree,
+    blocks: Vec<HBlock<Item>>,
+}
+
+impl<Item: HScalar> HMatrix<Item> {
+    /// Assemble the matrix whose entry `(row, col)` in the original numbering is
+    /// `kernel(row, col)`. Admissible blocks are approximated with ACA+ and recompressed
+    /// with the relative tolerance `tol`.
+    pub fn assemble(
+        row_tree: &ClusterTree,
+        col_tree: &ClusterTree,
+        admissibility: &dyn Admissibility,
+        kernel: impl Fn(IndexType, IndexType) -> Item,
+        tol: <Item as Scalar>::Real,
+    ) -> Self {
+        let mut mat = Self {
+            row_tree: row_tree.clone(),
+            col_tree: col_tree.clone(),
+            blocks: Vec::new(),
+        };
+        let mut stack = vec![(row_tree.root(), col_tree.root())];
+        while let Some((rows, cols)) = stack.pop() {
+            let (row_cluster, col_cluster) = (row_tree.cluster(rows), col_tree.cluster(cols));
+            if (row_cluster.size() == 0) | (col_cluster.size() == 0) {
+                continue;
+            }
+            let (row_indices, col_indices) = (row_tree.indices(rows), col_tree.indices(cols));
+            let entry = |row: IndexType, col: IndexType| kernel(row_indices[row], col_indices[col]);
+
+            if admissibility.is_admissible(row_cluster, col_cluster) {
+                let low_rank = Aca::new((row_cluster.size(), col_cluster.size()), entry)
+                    .with_tol(tol)
+                    .compute()
+                    .recompress(tol);
+                mat.blocks.push(HBlock {
+                    rows,
+                    cols,
+                    data: BlockData::LowRank(low_rank),
+                });
+                continue;
+            }
+
+            match (row_cluster.children(), col_cluster.children()) {
+                (None, None) => {
+                    let mut dense = MatrixD::<Item, ColumnMajor>::zeros_from_dim(
+                        row_cluster.size(),
+                        col_cluster.size(),
+                    );
+                    for col in 0..col_cluster.size() {
+                        for row in 0..row_cluster.size() {
+                            *dense.get_mut(row, col) = entry(row, col);
+                        }
+                    }
+                    mat.blocks.push(HBlock {
+                        rows,
+                        cols,
+                        data: BlockData::Dense(dense),
+                    });
+                }
+                (Some((first, second)), None) => {
+                    stack.push((second, cols));
+                    stack.push((first, cols));
+                }
+                (None, Some((first, second))) => {
+                    stack.push((rows, second));
+                    stack.push((rows, first));
+                }
+                (Some(row_children), Some(col_children)) => {
+                    for child_rows in [row_children.1, row_children.0] {
+                        for child_cols in [col_children.1, col_children.0] {
+                            stack.push((child_rows, child_cols));
+                        }
+                    }
+                }
+            }
+        }
+        mat
+    }
+
+    /// Return the dimension `(m, n)`.
+    pub fn dim(&self) -> (IndexType, IndexType) {
+        (
+            self.row_tree.number_of_indices(),
+            self.col_tree.number_of_indices(),
+        )
+    }
+
+    /// Return the cluster tree of the rows.
+    pub fn row_tree(&self) -> &ClusterTree {
+        &self.row_tree
+    }
+
+    /// Return the cluster tree of the columns.
+    pub fn col_tree(&self) -> &ClusterTree {
+        &self.col_tree
+    }
+
+    /// Return the leaves of the block tree.
+    pub fn blocks(&self) -> &[HBlock<Item>] {
+        &self.blocks
+    }
+
+    /// Return the memory statistics.
+    pub fn stats(&self) -> HMatrixStats {
+        let mut stats = HMatrixStats {
+            dim: self.dim(),
+            dense_blocks: 0,
+            low_rank_blocks: 0,
+            dense_entries: 0,
+            low_rank_entries: 0,
+            max_rank: 0,
+        };
+        for block in &self.blocks {
+            match &block.data {
+                BlockData::Dense(dense) => {
+                    stats.dense_blocks += 1;
+                    stats.dense_entries += dense.layout().number_of_elements();
+                }
+                BlockData::LowRank(low_rank) => {
+                    let (m, n) = low_rank.dim();
+                    stats.low_rank_blocks += 1;
+                    stats.low_rank_entries += (m + n) * low_rank.rank();
+                    stats.max_rank = stats.max_rank.max(low_rank.rank());
+                }
+            }
+        }
+        stats
+    }
+
+    /// Compute `y = alpha * op(A) * x + beta * y`.
+    pub fn matvec<
+        MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+        MatImpl2: MatrixTraitMut<Item, L2, RS2, CS2>,
+        L1: LayoutType,
+        L2: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    >(
+        &self,
+        trans: TransposeMode,
+        alpha: Item,
+        vec_x: &Matrix<Item, MatImpl1, L1, RS1, CS1>,
+        beta: Item,
+        vec_y: &mut Matrix<Item, MatImpl2, L2, RS2, CS2>,
+    ) {
+        // The trees of the rows and columns of op(A).
+        let (out_tree, in_tree) = match trans {
+            TransposeMode::NoTrans => (&self.row_tree, &self.col_tree),
+            _ => (&self.col_tree, &self.row_tree),
+        };
+        let (len_x, len_y) = (
+            vec_x.layout().number_of_elements(),
+            vec_y.layout().number_of_elements(),
+        );
+        assert!(
+            (in_tree.number_of_indices() == len_x) & (out_tree.number_of_indices() == len_y),
+            "Incompatible dimensions for y = op(A) * x: op(A) = {:#?}, x = {}, y = {}",
+            (out_tree.number_of_indices(), in_tree.number_of_indices()),
+            len_x,
+            len_y
+        );
+
+        // Work in the permuted numbering of the trees.
+        let x: Vec<Item> = in_tree
+            .permutation()
+            .iter()
+            .map(|&index| unsafe { vec_x.get1d_unchecked(index) })
+            .collect();
+        let mut y = vec![Item::zero(); len_y];
+
+        for block in &self.blocks {
+            let (out_cluster, in_cluster) = match trans {
+                TransposeMode::NoTrans => (block.rows, block.cols),
+                _ => (block.cols, block.rows),
+            };
+            let (out_range, in_range) = (
+                out_tree.cluster(out_cluster).range(),
+                in_tree.cluster(in_cluster).range(),
+            );
+            match &block.data {
+                BlockData::Dense(dense) => {
+                    for (out_index, elem) in y[out_range.clone()].iter_mut().enumerate() {
+                        for (in_index, &value) in x[in_range.clone()].iter().enumerate() {
+                            let entry = match trans {
+                                TransposeMode::NoTrans => dense.get(out_index, in_index),
+                                TransposeMode::Trans => dense.get(in_index, out_index),
+                                TransposeMode::ConjTrans => dense.get(in_index, out_index).conj(),
+                            };
+                            *elem += entry * value;
+                        }
+                    }
+                }
+                BlockData::LowRank(low_rank) => {
+                    let mut block_x = ColumnVectorD::<Item>::zeros_from_length(in_range.len());
+                    for (index, &value) in x[in_range].iter().enumerate() {
+                        *block_x.get1d_mut(index) = value;
+                    }
+                    let mut block_y = ColumnVectorD::<Item>::zeros_from_length(out_range.len());
+                    low_rank.matvec(trans, Item::one(), &block_x, Item::zero(), &mut block_y);
+                    for (index, elem) in y[out_range].iter_mut().enumerate() {
+                        *elem += block_y.get1d(index);
+                    }
+                }
+            }
+        }
+
+        for (&index, &value) in out_tree.permutation().iter().zip(y.iter()) {
+            let elem = unsafe { vec_y.get1d_unchecked_mut(index) };
+            *elem = if beta == Item::zero() {
+                alpha * value
+            } else {
+                alpha * value + beta * *elem
+            };
+        }
+    }
+
+    /// Return the dense `m x n` matrix in the original numbering.
+    pub fn to_dense(&self) -> MatrixD<Item, ColumnMajor> {
+        let (m, n) = self.dim();
+        let mut permuted = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, n);
+        for block in &self.blocks {
+            let (rows, cols) = (
+                self.row_tree.cluster(block.rows),
+                self.col_tree.cluster(block.cols),
+            );
+            let expanded;
+            let data = match &block.data {
+                BlockData::Dense(dense) => dense,
+                BlockData::LowRank(low_rank) => {
+                    expanded = low_rank.to_dense();
+                    &expanded
+                }
+            };
+            let mut view = permuted.block_mut(
+                (rows.range().start, cols.range().start),
+                (rows.size(), cols.size()),
+            );
+            for col in 0..cols.size() {
+                for row in 0..rows.size() {
+                    *view.get_mut(row, col) = data.get(row, col);
+                }
+            }
+        }
+
+        let mut res = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, n);
+        let (row_perm, col_perm) = (self.row_tree.permutation(), self.col_tree.permutation());
+        for (col, &orig_col) in col_perm.iter().enumerate() {
+            for (row, &orig_row) in row_perm.iter().enumerate() {
+                *res.get_mut(orig_row, orig_col) = permuted.get(row, col);
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    /// Return points on the unit circle.
+    fn circle(n: IndexType) -> Vec<f64> {
+        (0..n)
+            .flat_map(|index| {
+                let phi = 2.0 * std::f64::consts::PI * index as f64 / n as f64;
+                vec![phi.cos(), phi.sin()]
+            })
+            .collect()
+    }
+
+    macro_rules! hmatrix_test {
+        ($HScalar:ty, $wavenumber:expr, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let n = 400;
+                let points = circle(n);
+                let tree = ClusterTree::new(2, &points, 20);
+                // A regularised single layer kernel.
+                let kernel = |row: IndexType, col: IndexType| {
+                    let dx = points[2 * row] - points[2 * col];
+                    let dy = points[2 * row + 1] - points[2 * col + 1];
+                    let dist = (dx * dx + dy * dy).sqrt() + 1E-2;
+                    let value = num::cast::<f64, $HScalar>(-dist.ln()).unwrap();
+                    value
+                        * (num::cast::<f64, $HScalar>(1.0).unwrap()
+                            + $wavenumber * num::cast::<f64, $HScalar>(dist).unwrap())
+                };
+                let mat =
+                    HMatrix::assemble(&tree, &tree, &StrongAdmissibility::default(), kernel, $tol);
+                assert_eq!(mat.dim(), (n, n));
+
+                let stats = mat.stats();
+                assert_eq!(
+                    stats.dense_blocks + stats.low_rank_blocks,
+                    mat.blocks().len()
+                );
+                assert!(stats.low_rank_blocks > 0);
+                assert!(stats.compression() < 0.6);
+
+                let dense = mat.to_dense();
+                let (mut diff, mut norm) = (0.0, 0.0);
+                for col in 0..n {
+                    for row in 0..n {
+                        let expect = kernel(row, col);
+                        diff +=
+                            num::cast::<_, f64>((dense.get(row, col) - expect).square()).unwrap();
+                        norm += num::cast::<_, f64>(expect.square()).unwrap();
+                    }
+                }
+                assert!((diff / norm).sqrt() < 10.0 * $tol);
+
+                for trans in [
+                    TransposeMode::NoTrans,
+                    TransposeMode::Trans,
+                    TransposeMode::ConjTrans,
+                ] {
+                    let mut x = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                    for index in 0..n {
+                        *x.get1d_mut(index) =
+                            num::cast::<f64, $HScalar>((index % 7) as f64).unwrap();
+                    }
+                    let mut y = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                    y.for_each(|elem| *elem = num::cast::<f64, $HScalar>(1.0).unwrap());
+                    let beta = num::cast::<f64, $HScalar>(2.0).unwrap();
+                    mat.matvec(trans, <$HScalar as num::One>::one(), &x, beta, &mut y);
+
+                    let (mut diff, mut norm) = (0.0, 0.0);
+                    for row in 0..n {
+                        let mut expect = beta;
+                        for col in 0..n {
+                            let elem = match trans {
+                                TransposeMode::NoTrans => dense.get(row, col),
+                                TransposeMode::Trans => dense.get(col, row),
+                                TransposeMode::ConjTrans => dense.get(col, row).conj(),
+                            };
+                            expect += elem * x.get1d(col);
+                        }
+                        diff += num::cast::<_, f64>((y.get1d(row) - expect).square()).unwrap();
+                        norm += num::cast::<_, f64>(expect.square()).unwrap();
+                    }
+                    assert!((diff / norm).sqrt() < 10.0 * $tol);
+                }
+            }
+        };
+    }
+
+    hmatrix_test!(f32, 0.0, 1E-4, test_hmatrix_f32);
+    hmatrix_test!(f64, 0.0, 1E-8, test_hmatrix_f64);
+    hmatrix_test!(c32, c32::new(0.0, 1.0), 1E-4, test_hmatrix_c32);
+    hmatrix_test!(c64, c64::new(0.0, 1.0), 1E-8, test_hmatrix_c64);
+
+    #[test]
+    fn test_rectangular_hmatrix() {
+        // Sources on a segment and targets on a parallel segment.
+        let sources: Vec<f64> = (0..150)
+            .flat_map(|index| vec![index as f64 / 150.0, 0.0])
+            .collect();
+        let targets: Vec<f64> = (0..90)
+            .flat_map(|index| vec![index as f64 / 90.0, 0.3])
+            .collect();
+        let (row_tree, col_tree) = (
+            ClusterTree::new(2, &targets, 10),
+            ClusterTree::new(2, &sources, 10),
+        );
+        let kernel = |row: IndexType, col: IndexType| {
+            let dx = targets[2 * row] - sources[2 * col];
+            1.0 / (dx * dx + 0.09).sqrt()
+        };
+        let mat = HMatrix::assemble(&row_tree, &col_tree, &WeakAdmissibility, kernel, 1E-10);
+        assert_eq!(mat.dim(), (90, 150));
+        // The segments are disjoint, so the whole matrix is a single low-rank block.
+        assert_eq!(mat.stats().low_rank_blocks, 1);
+        assert_eq!(mat.stats().dense_blocks, 0);
+
+        let mat = HMatrix::assemble(
+            &row_tree,
+            &col_tree,
+            &StrongAdmissibility { eta: 2.0 },
+            kernel,
+            1E-10,
+        );
+        assert!(mat.stats().low_rank_blocks > 0);
+        let dense = mat.to_dense();
+        for col in 0..150 {
+            for row in 0..90 {
+                assert!((dense.get(row, col) - kernel(row, col)).abs() < 1E-8);
+            }
+        }
+    }
+}
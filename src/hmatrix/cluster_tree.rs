@@ -0,0 +1,281 @@
+//! Cluster trees and admissibility conditions.
+
+use crate::types::IndexType;
+
+/// A node of a [ClusterTree].
+///
+/// A cluster describes the index set `permutation[start..end]` of its tree together with
+/// the axis-parallel bounding box of the associated points.
+#[derive(Clone, Debug)]
+pub struct Cluster {
+    start: IndexType,
+    end: IndexType,
+    bbox_min: Vec<f64>,
+    bbox_max: Vec<f64>,
+    children: Option<(IndexType, IndexType)>,
+    level: IndexType,
+}
+
+impl Cluster {
+    /// Return the range of the cluster in the permuted numbering of the tree.
+    pub fn range(&self) -> std::ops::Range<IndexType> {
+        self.start..self.end
+    }
+
+    /// Return the number of indices in the cluster.
+    pub fn size(&self) -> IndexType {
+        self.end - self.start
+    }
+
+    /// Return the indices of the two children, or `None` for a leaf.
+    pub fn children(&self) -> Option<(IndexType, IndexType)> {
+        self.children
+    }
+
+    /// Return `true` if the cluster has no children.
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_none()
+    }
+
+    /// Return the level of the cluster. The root has level 0.
+    pub fn level(&self) -> IndexType {
+        self.level
+    }
+
+    /// Return the Euclidian diameter of the bounding box.
+    pub fn diameter(&self) -> f64 {
+        self.bbox_min
+            .iter()
+            .zip(self.bbox_max.iter())
+            .map(|(min, max)| (max - min) * (max - min))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// Return the Euclidian distance between the bounding boxes of two clusters.
+    pub fn distance(&self, other: &Cluster) -> f64 {
+        (0..self.bbox_min.len())
+            .map(|axis| {
+                let gap = (other.bbox_min[axis] - self.bbox_max[axis])
+                    .max(self.bbox_min[axis] - other.bbox_max[axis])
+                    .max(0.0);
+                gap * gap
+            })
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+/// A binary cluster tree over the indices `0..n` of a set of points.
+///
+/// The tree is built by recursive bisection of the point set. Each cluster is split
+/// at the median of the coordinate in which its bounding box has the largest extent,
+/// until a cluster has at most `leaf_size` indices. The indices of each cluster are
+/// contiguous in the permuted numbering given by [permutation](ClusterTree::permutation).
+#[derive(Clone, Debug)]
+pub struct ClusterTree {
+    clusters: Vec<Cluster>,
+    permutation: Vec<IndexType>,
+}
+
+impl ClusterTree {
+    /// Build the tree for `n` points in `dim` dimensions. The coordinates of point `i`
+    /// are `coords[i * dim..(i + 1) * dim]`.
+    pub fn new(dim: IndexType, coords: &[f64], leaf_size: IndexType) -> Self {
+        assert!(dim > 0, "Points must have at least one coordinate.");
+        assert_eq!(
+            coords.len() % dim,
+            0,
+            "Number of coordinates {} is not a multiple of the dimension {}.",
+            coords.len(),
+            dim
+        );
+        assert!(leaf_size > 0, "Leaf size must be positive.");
+        let mut tree = Self {
+            clusters: Vec::new(),
+            permutation: (0..coords.len() / dim).collect(),
+        };
+        tree.build(dim, coords, leaf_size, 0, coords.len() / dim, 0);
+        tree
+    }
+
+    /// Create the cluster for the range `start..end` of the permutation and its
+    /// descendants. Return the index of the cluster.
+    fn build(
+        &mut self,
+        dim: IndexType,
+        coords: &[f64],
+        leaf_size: IndexType,
+        start: IndexType,
+        end: IndexType,
+        level: IndexType,
+    ) -> IndexType {
+        let mut bbox_min = vec![f64::INFINITY; dim];
+        let mut bbox_max = vec![f64::NEG_INFINITY; dim];
+        for &point in &self.permutation[start..end] {
+            for axis in 0..dim {
+                let value = coords[point * dim + axis];
+                bbox_min[axis] = bbox_min[axis].min(value);
+                bbox_max[axis] = bbox_max[axis].max(value);
+            }
+        }
+        let index = self.clusters.len();
+        self.clusters.push(Cluster {
+            start,
+            end,
+            bbox_min,
+            bbox_max,
+            children: None,
+            level,
+        });
+
+        if end - start > leaf_size {
+            let cluster = &self.clusters[index];
+            let axis = (0..dim)
+                .max_by(|&first, &second| {
+                    let extent = |axis: IndexType| cluster.bbox_max[axis] - cluster.bbox_min[axis];
+                    extent(first).partial_cmp(&extent(second)).unwrap()
+                })
+                .unwrap();
+            self.permutation[start..end].sort_by(|&first, &second| {
+                coords[first * dim + axis]
+                    .partial_cmp(&coords[second * dim + axis])
+                    .unwrap()
+            });
+            let middle = start + (end - start) / 2;
+            let first = self.build(dim, coords, leaf_size, start, middle, level + 1);
+            let second = self.build(dim, coords, leaf_size, middle, end, level + 1);
+            self.clusters[index].children = Some((first, second));
+        }
+        index
+    }
+
+    /// Return the index of the root cluster.
+    pub fn root(&self) -> IndexType {
+        0
+    }
+
+    /// Return the cluster with the given index.
+    pub fn cluster(&self, index: IndexType) -> &Cluster {
+        &self.clusters[index]
+    }
+
+    /// Return the number of clusters.
+    pub fn number_of_clusters(&self) -> IndexType {
+        self.clusters.len()
+    }
+
+    /// Return the number of indices.
+    pub fn number_of_indices(&self) -> IndexType {
+        self.permutation.len()
+    }
+
+    /// Return the number of levels.
+    pub fn depth(&self) -> IndexType {
+        self.clusters
+            .iter()
+            .map(|cluster| cluster.level + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Return the permutation. Position `i` of the permuted numbering corresponds to the
+    /// original index `permutation[i]`.
+    pub fn permutation(&self) -> &[IndexType] {
+        &self.permutation
+    }
+
+    /// Return the original indices of a cluster.
+    pub fn indices(&self, index: IndexType) -> &[IndexType] {
+        &self.permutation[self.clusters[index].range()]
+    }
+}
+
+/// An admissibility condition decides whether the interaction between two clusters
+/// can be approximated by a low-rank matrix.
+pub trait Admissibility {
+    /// Return `true` if the block `rows x cols` is admissible.
+    fn is_admissible(&self, rows: &Cluster, cols: &Cluster) -> bool;
+}
+
+/// The standard admissibility condition `min(diam(s), diam(t)) <= eta * dist(s, t)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StrongAdmissibility {
+    /// The admissibility parameter `eta > 0`.
+    pub eta: f64,
+}
+
+impl Default for StrongAdmissibility {
+    fn default() -> Self {
+        Self { eta: 1.0 }
+    }
+}
+
+impl Admissibility for StrongAdmissibility {
+    fn is_admissible(&self, rows: &Cluster, cols: &Cluster) -> bool {
+        rows.diameter().min(cols.diameter()) <= self.eta * rows.distance(cols)
+    }
+}
+
+/// The weak admissibility condition, under which all blocks of clusters with disjoint
+/// bounding boxes are admissible.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WeakAdmissibility;
+
+impl Admissibility for WeakAdmissibility {
+    fn is_admissible(&self, rows: &Cluster, cols: &Cluster) -> bool {
+        rows.distance(cols) > 0.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_cluster_tree() {
+        // Points on a 2d grid.
+        let coords: Vec<f64> = (0..100)
+            .flat_map(|index| vec![(index % 10) as f64, (index / 10) as f64 * 0.5])
+            .collect();
+        let tree = ClusterTree::new(2, &coords, 8);
+
+        let mut sorted = tree.permutation().to_vec();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+
+        let root = tree.cluster(tree.root());
+        assert_eq!(root.range(), 0..100);
+        assert!((root.diameter() - (81.0f64 + 4.5 * 4.5).sqrt()).abs() < 1E-14);
+        for index in 0..tree.number_of_clusters() {
+            let cluster = tree.cluster(index);
+            match cluster.children() {
+                Some((first, second)) => {
+                    let (first, second) = (tree.cluster(first), tree.cluster(second));
+                    assert_eq!(first.range().start, cluster.range().start);
+                    assert_eq!(first.range().end, second.range().start);
+                    assert_eq!(second.range().end, cluster.range().end);
+                    assert_eq!(first.level(), cluster.level() + 1);
+                }
+                None => assert!(cluster.size() <= 8),
+            }
+            for &point in tree.indices(index) {
+                let (x, y) = (coords[2 * point], coords[2 * point + 1]);
+                assert!((cluster.bbox_min[0] <= x) & (x <= cluster.bbox_max[0]));
+                assert!((cluster.bbox_min[1] <= y) & (y <= cluster.bbox_max[1]));
+            }
+        }
+        assert_eq!(tree.depth(), 5);
+
+        // The first split is along the x axis, which has the larger extent.
+        let (first, second) = root.children().unwrap();
+        assert_eq!(tree.cluster(first).distance(tree.cluster(second)), 1.0);
+        assert!(StrongAdmissibility { eta: 7.0 }
+            .is_admissible(tree.cluster(first), tree.cluster(second)));
+        assert!(!StrongAdmissibility::default()
+            .is_admissible(tree.cluster(first), tree.cluster(second)));
+        assert!(WeakAdmissibility.is_admissible(tree.cluster(first), tree.cluster(second)));
+        assert!(!WeakAdmissibility.is_admissible(root, tree.cluster(first)));
+    }
+}
@@ -0,0 +1,303 @@
+//! Interpolative decompositions of dense matrices.
+//!
+//! A column interpolative decomposition (ID) of an `m x n` matrix `A` selects `k` skeleton
+//! columns `J` and a `k x n` interpolation matrix `X` such that `A ~ A[:, J] * X`. The
+//! columns `J` of `X` form the identity matrix. Similarly a row ID selects `k` skeleton
+//! rows `I` and an `m x k` interpolation matrix `X` with `A ~ X * A[I, :]`.
+//!
+//! The column ID is computed from a truncated QR decomposition with column pivoting
+//! `A * P = Q * [R11, R12] + [0, E]` as described by Cheng, Gimbutas, Martinsson and
+//! Rokhlin (SIAM J. Sci. Comput. 26(4), 2005). The skeleton columns are the first `k`
+//! pivot columns and the remaining columns are interpolated with `R11^{-1} * R12`. The
+//! columns of the approximation error `A - A[:, J] * X` are the columns of `E`. The row ID
+//! is the column ID of `A^H`.
+//!
+//! The rank is either given or determined from a relative tolerance `tol`. In the latter
+//! case the pivoted QR decomposition stops as soon as all columns of `E` have a norm of at
+//! most `tol` times the largest column norm of `A`.
+//!
+//! ```
+//! # use householder::*;
+//! let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(30, 20);
+//! for col in 0..20 {
+//!     for row in 0..30 {
+//!         *mat.get_mut(row, col) = (1.0 + row as f64) * (2.0 + col as f64)
+//!             + (row as f64) * (col as f64).sqrt();
+//!     }
+//! }
+//! let id = mat.column_id(1E-10);
+//! assert_eq!(id.rank(), 2);
+//! ```
+
+use crate::data_container::DataContainer;
+use crate::dense_solvers::{adjoint, pivoted_qr_decomposition, PivotedQr};
+use crate::layouts::*;
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::*;
+
+/// A column interpolative decomposition `A ~ A[:, J] * X`.
+pub struct ColumnId<Item: HScalar> {
+    skeleton: Vec<IndexType>,
+    interpolation: MatrixD<Item, ColumnMajor>,
+}
+
+impl<Item: HScalar> ColumnId<Item> {
+    /// Return the indices `J` of the skeleton columns.
+    pub fn skeleton(&self) -> &[IndexType] {
+        &self.skeleton
+    }
+
+    /// Return the `k x n` interpolation matrix `X`.
+    pub fn interpolation(&self) -> &MatrixD<Item, ColumnMajor> {
+        &self.interpolation
+    }
+
+    /// Return the rank `k`.
+    pub fn rank(&self) -> IndexType {
+        self.skeleton.len()
+    }
+}
+
+/// A row interpolative decomposition `A ~ X * A[I, :]`.
+pub struct RowId<Item: HScalar> {
+    skeleton: Vec<IndexType>,
+    interpolation: MatrixD<Item, ColumnMajor>,
+}
+
+impl<Item: HScalar> RowId<Item> {
+    /// Return the indices `I` of the skeleton rows.
+    pub fn skeleton(&self) -> &[IndexType] {
+        &self.skeleton
+    }
+
+    /// Return the `m x k` interpolation matrix `X`.
+    pub fn interpolation(&self) -> &MatrixD<Item, ColumnMajor> {
+        &self.interpolation
+    }
+
+    /// Return the rank `k`.
+    pub fn rank(&self) -> IndexType {
+        self.skeleton.len()
+    }
+}
+
+/// Compute the column ID from a truncated pivoted QR decomposition.
+fn column_id_from_qr<Item: HScalar>(qr: &PivotedQr<Item>) -> ColumnId<Item> {
+    let n = qr.dim().1;
+    let r = qr.r();
+    let permutation = qr.permutation();
+
+    // Only use the leading part of R whose diagonal is nonzero.
+    let k = qr.rank(num::Zero::zero());
+    let mut interpolation = MatrixD::<Item, ColumnMajor>::zeros_from_dim(k, n);
+    unsafe {
+        for (col, &index) in permutation.iter().enumerate() {
+            if col < k {
+                *interpolation.get_unchecked_mut(col, index) = Item::one();
+                continue;
+            }
+            // Solve R11 * t = R12[:, col - k] by back substitution.
+            for row in (0..k).rev() {
+                let mut value = r.get_unchecked(row, col);
+                for other in row + 1..k {
+                    value -=
+                        r.get_unchecked(row, other) * interpolation.get_unchecked(other, index);
+                }
+                *interpolation.get_unchecked_mut(row, index) = value / r.get_unchecked(row, row);
+            }
+        }
+    }
+
+    ColumnId {
+        skeleton: permutation[..k].to_vec(),
+        interpolation,
+    }
+}
+
+/// Convert the column ID of `A^H` into the row ID of `A`.
+fn row_id_from_adjoint<Item: HScalar>(id: ColumnId<Item>) -> RowId<Item> {
+    let (k, m) = id.interpolation.layout().dim();
+    let mut interpolation = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, k);
+    for col in 0..k {
+        for row in 0..m {
+            *interpolation.get_mut(row, col) = id.interpolation.get(col, row).conj();
+        }
+    }
+    RowId {
+        skeleton: id.skeleton,
+        interpolation,
+    }
+}
+
+macro_rules! interpolative_impl {
+    ($Layout:ident) => {
+        impl<Item: HScalar, Data: DataContainer<Item = Item>>
+            GenericBaseMatrix<Item, $Layout, Data, Dynamic, Dynamic>
+        {
+            /// Compute a column ID whose error columns have a norm of at most `tol` times
+            /// the largest column norm.
+            pub fn column_id(&self, tol: <Item as Scalar>::Real) -> ColumnId<Item> {
+                let (m, n) = self.layout().dim();
+                column_id_from_qr(&pivoted_qr_decomposition(self, m.min(n), Some(tol)))
+            }
+
+            /// Compute a column ID with at most `rank` skeleton columns.
+            pub fn column_id_with_rank(&self, rank: IndexType) -> ColumnId<Item> {
+                column_id_from_qr(&pivoted_qr_decomposition(self, rank, None))
+            }
+
+            /// Compute a row ID whose error rows have a norm of at most `tol` times the
+            /// largest row norm.
+            pub fn row_id(&self, tol: <Item as Scalar>::Real) -> RowId<Item> {
+                row_id_from_adjoint(adjoint(self).column_id(tol))
+            }
+
+            /// Compute a row ID with at most `rank` skeleton rows.
+            pub fn row_id_with_rank(&self, rank: IndexType) -> RowId<Item> {
+                row_id_from_adjoint(adjoint(self).column_id_with_rank(rank))
+            }
+        }
+    };
+}
+
+interpolative_impl!(RowMajor);
+interpolative_impl!(ColumnMajor);
+interpolative_impl!(ArbitraryStrideRowMajor);
+interpolative_impl!(ArbitraryStrideColumnMajor);
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use rand::prelude::*;
+
+    macro_rules! interpolative_test {
+        ($HScalar:ty, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let (m, n, rank) = (40, 30, 6);
+                let mut rng = StdRng::seed_from_u64(0);
+                let mut left = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(m, rank);
+                let mut right = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(rank, n);
+                left.fill_from_rand_standard_normal(&mut rng);
+                right.fill_from_rand_standard_normal(&mut rng);
+                let mut mat = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(m, n);
+                for index in 0..rank {
+                    for col in 0..n {
+                        for row in 0..m {
+                            *mat.get_mut(row, col) += left.get(row, index) * right.get(index, col);
+                        }
+                    }
+                }
+                let max_error = |approx: &dyn Fn(IndexType, IndexType) -> $HScalar| {
+                    let mut error: f64 = 0.0;
+                    for col in 0..n {
+                        for row in 0..m {
+                            let diff = (mat.get(row, col) - approx(row, col)).abs();
+                            error = error.max(num::cast::<_, f64>(diff).unwrap());
+                        }
+                    }
+                    error
+                };
+
+                let qr = mat.pivoted_qr();
+                assert_eq!(qr.steps(), n);
+                assert_eq!(qr.rank($tol), rank);
+                let q = qr.q();
+                let diag: Vec<f64> = (0..n)
+                    .map(|index| num::cast::<_, f64>(qr.r().get(index, index).abs()).unwrap())
+                    .collect();
+                assert!(diag
+                    .windows(2)
+                    .all(|pair| pair[1] <= pair[0] * (1.0 + 1E-4)));
+                let error = max_error(&|row, col| {
+                    let col = qr
+                        .permutation()
+                        .iter()
+                        .position(|&index| index == col)
+                        .unwrap();
+                    (0..n).fold(<$HScalar as num::Zero>::zero(), |acc, index| {
+                        acc + q.get(row, index) * qr.r().get(index, col)
+                    })
+                });
+                assert!(error < $tol * 100.0);
+
+                for id in [mat.column_id($tol), mat.column_id_with_rank(rank)] {
+                    assert_eq!(id.rank(), rank);
+                    for (index, &col) in id.skeleton().iter().enumerate() {
+                        for other in 0..rank {
+                            let expect = if other == index { 1.0 } else { 0.0 };
+                            assert_eq!(
+                                id.interpolation().get(other, col),
+                                num::cast::<f64, $HScalar>(expect).unwrap()
+                            );
+                        }
+                    }
+                    let error = max_error(&|row, col| {
+                        id.skeleton().iter().enumerate().fold(
+                            <$HScalar as num::Zero>::zero(),
+                            |acc, (index, &skeleton)| {
+                                acc + mat.get(row, skeleton) * id.interpolation().get(index, col)
+                            },
+                        )
+                    });
+                    assert!(error < $tol * 100.0);
+                }
+
+                for id in [mat.row_id($tol), mat.row_id_with_rank(rank)] {
+                    assert_eq!(id.rank(), rank);
+                    assert_eq!(id.interpolation().layout().dim(), (m, rank));
+                    let error = max_error(&|row, col| {
+                        id.skeleton().iter().enumerate().fold(
+                            <$HScalar as num::Zero>::zero(),
+                            |acc, (index, &skeleton)| {
+                                acc + id.interpolation().get(row, index) * mat.get(skeleton, col)
+                            },
+                        )
+                    });
+                    assert!(error < $tol * 100.0);
+                }
+
+                // A lower rank gives the pivot columns of the full decomposition.
+                let id = mat.column_id_with_rank(2);
+                assert_eq!(id.skeleton(), &qr.permutation()[..2]);
+            }
+        };
+    }
+
+    interpolative_test!(f32, 1E-4, test_interpolative_f32);
+    interpolative_test!(f64, 1E-10, test_interpolative_f64);
+    interpolative_test!(c32, 1E-4, test_interpolative_c32);
+    interpolative_test!(c64, 1E-10, test_interpolative_c64);
+
+    #[test]
+    fn test_zero_and_wide_matrices() {
+        let mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(5, 8);
+        let id = mat.column_id(1E-8);
+        assert_eq!(id.rank(), 0);
+        assert_eq!(id.interpolation().layout().dim(), (0, 8));
+        assert_eq!(mat.pivoted_qr().rank(1E-8), 0);
+
+        // A wide matrix whose columns are multiples of two columns.
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(3, 6);
+        for col in 0..6 {
+            *mat.get_mut(0, col) = (col + 1) as f64;
+            *mat.get_mut(1, col) = if col % 2 == 0 { 1.0 } else { -1.0 };
+            *mat.get_mut(2, col) = 2.0 * (col + 1) as f64;
+        }
+        let qr = mat.pivoted_qr();
+        assert_eq!(qr.steps(), 3);
+        assert_eq!(qr.permutation()[0], 5);
+        assert_eq!(qr.rank(1E-12), 2);
+        let id = mat.row_id(1E-12);
+        assert_eq!(id.rank(), 2);
+        for col in 0..6 {
+            for row in 0..3 {
+                let value = id.interpolation().get(row, 0) * mat.get(id.skeleton()[0], col)
+                    + id.interpolation().get(row, 1) * mat.get(id.skeleton()[1], col);
+                assert!((value - mat.get(row, col)).abs() < 1E-12);
+            }
+        }
+    }
+}
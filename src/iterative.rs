@@ -0,0 +1,354 @@
+//! Iterative solvers for linear systems and eigenvalue problems.
+//!
+//! Iterative solvers only require the action of a matrix on a vector. This action is
+//! described by the [LinearOperator] trait, which is implemented for dense matrices,
+//! sparse matrices, diagonal matrices and, through [FnOperator], for closures.
+//!
+//! The following solvers are available.
+//! - [ConjugateGradient]: The preconditioned conjugate gradient method for Hermitian
+//!   positive definite systems.
+//! - [Gmres]: The restarted generalised minimal residual method GMRES(m) for general
+//!   nonsymmetric systems.
+//! - [BiCgStab]: The biconjugate gradient stabilised method for general nonsymmetric
+//!   systems. It requires only short recurrences but its convergence can be erratic.
+//!
+//! A [Preconditioner] applies an approximation of the inverse of the system matrix to a
+//! vector. The [Jacobi], [BlockJacobi], [Ssor] and [Ilu0] preconditioners are provided.
+//! Custom preconditioners can be defined through a [FnOperator]. The nonsymmetric solvers
+//! support left preconditioning, i.e. solving `M^{-1} * A * x = M^{-1} * b`, and right
+//! preconditioning, i.e. solving `A * M^{-1} * u = b` with `x = M^{-1} * u`. With left
+//! preconditioning the reported residuals are those of the preconditioned system.
+//!
+//! All solvers stop if the relative residual `|b - A * x| / |b|` drops below a given
+//! tolerance or if the maximum number of iterations is reached. The outcome is described
+//! by a [ConvergenceReport]. A callback can be registered that is called with the
+//! iteration number and the relative residual after each iteration.
+//!
+//! The following eigenvalue solvers compute a few eigenpairs of a large operator.
+//! - [Arnoldi]: The implicitly restarted Arnoldi method for general operators. The Ritz
+//!   values and vectors are computed in complex arithmetic.
+//! - [Lanczos]: The thick-restart Lanczos method for Hermitian operators.
+//!
+//! Both support a shift-invert mode to compute the eigenvalues closest to a shift `sigma`.
+//! The Krylov space is then built with a user supplied operator that applies
+//! `(A - sigma * I)^{-1}`, e.g. through a factorisation of `A - sigma * I`. The outcome is
+//! described by an [EigenReport].
+//!
+//! The following example solves a small diagonal system.
+//! ```
+//! # use householder::*;
+//! let mat = DiagonalMatrix::<f64>::from_diagonal(&[1.0, 2.0, 4.0]);
+//! let mut rhs = ColumnVectorD::<f64>::zeros_from_length(3);
+//! rhs.for_each(|elem| *elem = 1.0);
+//! let mut sol = ColumnVectorD::<f64>::zeros_from_length(3);
+//! let report = ConjugateGradient::new(&mat)
+//!     .with_tol(1E-10)
+//!     .solve(&rhs, &mut sol);
+//! assert!(report.converged);
+//! assert!((sol.get1d(2) - 0.25).abs() < 1E-10);
+//! ```
+
+pub mod arnoldi;
+pub mod bicgstab;
+pub mod cg;
+pub mod gmres;
+pub mod lanczos;
+pub mod linear_operator;
+pub mod preconditioner;
+mod projected;
+
+pub use arnoldi::*;
+pub use bicgstab::*;
+pub use cg::*;
+pub use gmres::*;
+pub use lanczos::*;
+pub use linear_operator::*;
+pub use preconditioner::*;
+
+use crate::matrix::ColumnVectorD;
+use crate::traits::*;
+use crate::types::{HScalar, IndexType};
+use cauchy::Scalar;
+use num::Float;
+use rand::prelude::*;
+use rand_distr::StandardNormal;
+
+/// A type alias for the real type associated with a scalar type.
+pub type RealType<Item> = <Item as Scalar>::Real;
+
+/// A callback that is called with the iteration number and the relative residual.
+pub type ResidualCallback<'a, Item> = Box<dyn FnMut(IndexType, RealType<Item>) + 'a>;
+
+/// The outcome of an iterative solver.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConvergenceReport<Real> {
+    /// `true` if the requested tolerance was reached.
+    pub converged: bool,
+    /// The number of iterations performed.
+    pub iterations: IndexType,
+    /// The final relative residual.
+    pub residual_norm: Real,
+    /// The relative residual before the first iteration and after each iteration.
+    pub residual_history: Vec<Real>,
+}
+
+/// The part of the spectrum that is computed by the eigenvalue solvers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Which {
+    /// The eigenvalues of largest magnitude.
+    LargestMagnitude,
+    /// The eigenvalues of smallest magnitude.
+    SmallestMagnitude,
+    /// The eigenvalues with the largest real part.
+    LargestReal,
+    /// The eigenvalues with the smallest real part.
+    SmallestReal,
+}
+
+/// The outcome of an eigenvalue solver.
+pub struct EigenReport<Value, Vector: HScalar> {
+    /// The Ritz values, ordered as requested by [Which].
+    pub values: Vec<Value>,
+    /// The normalised Ritz vectors.
+    pub vectors: Vec<ColumnVectorD<Vector>>,
+    /// The residual norms `|A * x - lambda * x|` of the Ritz pairs.
+    pub residual_norms: Vec<RealType<Vector>>,
+    /// `true` if all requested Ritz pairs reached the tolerance.
+    pub converged: bool,
+    /// The number of restarts performed.
+    pub restarts: IndexType,
+}
+
+/// Implement the builder methods shared by all solvers. The solver struct must
+/// have the fields `op`, `prec`, `tol`, `max_iter` and `callback`.
+macro_rules! solver_options {
+    ($Solver:ident) => {
+        impl<'a, Item: HScalar> $Solver<'a, Item> {
+            /// Set the preconditioner, which applies an approximation of `A^{-1}`.
+            pub fn with_preconditioner(mut self, prec: &'a dyn Preconditioner<Item>) -> Self {
+                assert_eq!(
+                    prec.dimension(),
+                    self.op.dim().0,
+                    "Preconditioner has dimension {} but operator has dimension {:#?}.",
+                    prec.dimension(),
+                    self.op.dim()
+                );
+                self.prec = Some(prec);
+                self
+            }
+
+            /// Set the tolerance for the relative residual.
+            pub fn with_tol(mut self, tol: RealType<Item>) -> Self {
+                self.tol = tol;
+                self
+            }
+
+            /// Set the maximum number of iterations.
+            pub fn with_max_iter(mut self, max_iter: IndexType) -> Self {
+                self.max_iter = max_iter;
+                self
+            }
+
+            /// Set a callback that is called with the iteration number and relative residual
+            /// after each iteration.
+            pub fn with_callback(
+                mut self,
+                callback: impl FnMut(IndexType, RealType<Item>) + 'a,
+            ) -> Self {
+                self.callback = Some(Box::new(callback));
+                self
+            }
+        }
+    };
+}
+
+pub(crate) use solver_options;
+
+/// Return the inner product `sum_i conj(x_i) * y_i`.
+pub(crate) fn inner<Item: HScalar>(x: &ColumnVectorD<Item>, y: &ColumnVectorD<Item>) -> Item {
+    let mut res = Item::zero();
+    for index in 0..x.length() {
+        res += unsafe { x.get1d_unchecked(index).conj() * y.get1d_unchecked(index) };
+    }
+    res
+}
+
+/// Return the Euclidian norm of `x`.
+pub(crate) fn norm2<Item: HScalar>(x: &ColumnVectorD<Item>) -> RealType<Item> {
+    let mut res = <RealType<Item> as num::Zero>::zero();
+    for index in 0..x.length() {
+        res += unsafe { x.get1d_unchecked(index).square() };
+    }
+    Float::sqrt(res)
+}
+
+/// Compute `y = alpha * x + y`.
+pub(crate) fn axpy<Item: HScalar>(
+    alpha: Item,
+    x: &ColumnVectorD<Item>,
+    y: &mut ColumnVectorD<Item>,
+) {
+    for index in 0..x.length() {
+        unsafe { *y.get1d_unchecked_mut(index) += alpha * x.get1d_unchecked(index) };
+    }
+}
+
+/// Compute `x = alpha * x`.
+pub(crate) fn scale<Item: HScalar>(alpha: Item, x: &mut ColumnVectorD<Item>) {
+    for index in 0..x.length() {
+        unsafe { *x.get1d_unchecked_mut(index) *= alpha };
+    }
+}
+
+/// Compute `y = x + beta * y`.
+pub(crate) fn xpby<Item: HScalar>(
+    x: &ColumnVectorD<Item>,
+    beta: Item,
+    y: &mut ColumnVectorD<Item>,
+) {
+    for index in 0..x.length() {
+        unsafe {
+            let elem = y.get1d_unchecked_mut(index);
+            *elem = x.get1d_unchecked(index) + beta * *elem;
+        }
+    }
+}
+
+/// Return a copy of `x`.
+pub(crate) fn copy<Item: HScalar>(x: &ColumnVectorD<Item>) -> ColumnVectorD<Item> {
+    let mut res = ColumnVectorD::<Item>::zeros_from_length(x.length());
+    for index in 0..x.length() {
+        unsafe { *res.get1d_unchecked_mut(index) = x.get1d_unchecked(index) };
+    }
+    res
+}
+
+/// Return the residual `b - A * x`.
+pub(crate) fn residual<Item: HScalar>(
+    op: &dyn LinearOperator<Item>,
+    rhs: &ColumnVectorD<Item>,
+    x: &ColumnVectorD<Item>,
+) -> ColumnVectorD<Item> {
+    let mut res = ColumnVectorD::<Item>::zeros_from_length(rhs.length());
+    op.apply(x, &mut res);
+    xpby(rhs, -Item::one(), &mut res);
+    res
+}
+
+/// Return `(c, s)` such that the rotation `[c, s; -conj(s), c]` maps `(a, b)` to `(r, 0)`.
+pub(crate) fn givens<Item: HScalar>(a: Item, b: Item) -> (Item, Item) {
+    let abs_a = a.abs();
+    let norm = num::Float::hypot(abs_a, b.abs());
+    if norm == <RealType<Item> as num::Zero>::zero() {
+        (Item::one(), Item::zero())
+    } else if abs_a == <RealType<Item> as num::Zero>::zero() {
+        (Item::zero(), Item::one())
+    } else {
+        let phase = a / Item::from_real(abs_a);
+        (
+            Item::from_real(abs_a / norm),
+            phase * b.conj() / Item::from_real(norm),
+        )
+    }
+}
+
+/// Return `[c, s; -conj(s), c] * [x; y]` for a rotation of [givens]. The product
+/// `[x, y] * [c, s; -conj(s), c]^H` from the right is `rotate(c, conj(s), x, y)`.
+pub(crate) fn rotate<Item: HScalar>(c: Item, s: Item, x: Item, y: Item) -> (Item, Item) {
+    (c * x + s * y, -s.conj() * x + c * y)
+}
+
+/// The number of random vectors that [random_orthogonal] tries.
+const RANDOM_ATTEMPTS: IndexType = 10;
+
+/// Return a random normalised vector that is orthogonal to `basis`, or `None` if no such
+/// vector is found in [RANDOM_ATTEMPTS] attempts. This is always the case if `basis`
+/// spans the whole space.
+pub(crate) fn random_orthogonal<Item: HScalar>(
+    n: IndexType,
+    basis: &[ColumnVectorD<Item>],
+    rng: &mut StdRng,
+) -> Option<ColumnVectorD<Item>> {
+    if basis.len() >= n {
+        return None;
+    }
+    let mut vec = ColumnVectorD::<Item>::zeros_from_length(n);
+    for _ in 0..RANDOM_ATTEMPTS {
+        for index in 0..n {
+            let value: f64 = rng.sample(StandardNormal);
+            *vec.get1d_mut(index) =
+                Item::from_real(num::cast::<f64, RealType<Item>>(value).unwrap());
+        }
+        for _ in 0..2 {
+            for v in basis {
+                let coeff = inner(v, &vec);
+                axpy(-coeff, v, &mut vec);
+            }
+        }
+        let norm = norm2(&vec);
+        if norm > num::cast::<f64, RealType<Item>>(1E-8).unwrap() {
+            scale(Item::from_real(norm.recip()), &mut vec);
+            return Some(vec);
+        }
+    }
+    None
+}
+
+/// Orthogonalise `w` against `basis` with two passes of Gram-Schmidt. The sum of the
+/// projection coefficients is added to `coeffs`.
+pub(crate) fn orthogonalise<Item: HScalar>(
+    basis: &[ColumnVectorD<Item>],
+    w: &mut ColumnVectorD<Item>,
+    coeffs: &mut [Item],
+) {
+    for _ in 0..2 {
+        for (i, v) in basis.iter().enumerate() {
+            let coeff = inner(v, w);
+            coeffs[i] += coeff;
+            axpy(-coeff, v, w);
+        }
+    }
+}
+
+/// Return the starting vector as a normalised vector.
+pub(crate) fn start_vector<Item: HScalar>(
+    n: IndexType,
+    start: Option<&ColumnVectorD<Item>>,
+    rng: &mut StdRng,
+) -> ColumnVectorD<Item> {
+    match start {
+        Some(start) => {
+            assert_eq!(
+                start.length(),
+                n,
+                "Start vector has length {} but operator has dimension {}.",
+                start.length(),
+                n
+            );
+            let mut vec = copy(start);
+            let norm = norm2(&vec);
+            assert!(
+                norm > <RealType<Item> as num::Zero>::zero(),
+                "Start vector must be nonzero."
+            );
+            scale(Item::from_real(norm.recip()), &mut vec);
+            vec
+        }
+        None => random_orthogonal(n, &[], rng).expect("Failed to generate a start vector."),
+    }
+}
+
+/// Return the threshold `tol * max(|theta|, eps^(2/3))` for the Ritz estimate of `theta`.
+pub(crate) fn ritz_threshold<Real: num::Float>(tol: Real, magnitude: Real) -> Real {
+    let eps23 = Real::epsilon().powf(Real::from(2.0 / 3.0).unwrap());
+    tol * magnitude.max(eps23)
+}
+
+/// Return `value / scale`, or `value` if `scale` is zero.
+pub(crate) fn relative<Real: Float>(value: Real, scale: Real) -> Real {
+    if scale == Real::zero() {
+        value
+    } else {
+        value / scale
+    }
+}
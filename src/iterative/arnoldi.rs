@@ -0,0 +1,496 @@
+//! The implicitly restarted Arnoldi method.
+
+use super::projected::{hessenberg_eigen, order_by, qr_step};
+use super::*;
+use crate::matrix::ColumnVectorD;
+use crate::types::{HScalar, IndexType};
+use num::{One, Zero};
+use rand::prelude::*;
+
+/// The complex type associated with a scalar type.
+type ComplexType<Item> = <Item as Scalar>::Complex;
+
+/// The implicitly restarted Arnoldi method (IRAM) for a few eigenpairs of a general
+/// square operator `A`.
+///
+/// The method builds an Arnoldi factorisation of length `ncv` and compresses it to
+/// length `nev` with implicitly shifted QR steps, using the unwanted Ritz values as
+/// exact shifts. This is repeated until the Ritz estimates of the `nev` wanted Ritz
+/// pairs drop below `tol * |theta|`.
+///
+/// Since the eigenvalues of a real nonsymmetric operator are complex in general, the
+/// Krylov basis and the Ritz pairs are computed in complex arithmetic. For real scalar
+/// types the operator is applied separately to the real and imaginary parts of complex
+/// basis vectors.
+pub struct Arnoldi<'a, Item: HScalar> {
+    op: &'a dyn LinearOperator<Item>,
+    shift_invert: Option<(Item, &'a dyn LinearOperator<Item>)>,
+    nev: IndexType,
+    ncv: IndexType,
+    which: Which,
+    tol: RealType<Item>,
+    max_restarts: IndexType,
+    start: Option<&'a ColumnVectorD<Item>>,
+}
+
+/// Compute `y = op * x` for a complex vector `x`.
+fn apply_complex<Item: HScalar>(
+    op: &dyn LinearOperator<Item>,
+    x: &ColumnVectorD<ComplexType<Item>>,
+    y: &mut ColumnVectorD<ComplexType<Item>>,
+) where
+    ComplexType<Item>: HScalar,
+{
+    let n = x.length();
+    let mut x_item = ColumnVectorD::<Item>::zeros_from_length(n);
+    let mut y_item = ColumnVectorD::<Item>::zeros_from_length(y.length());
+    let is_item = (0..n).all(|index| match Item::from_complex(x.get1d(index)) {
+        Some(value) => {
+            *x_item.get1d_mut(index) = value;
+            true
+        }
+        None => false,
+    });
+
+    if is_item {
+        op.apply(&x_item, &mut y_item);
+        for index in 0..y.length() {
+            *y.get1d_mut(index) = y_item.get1d(index).as_c();
+        }
+    } else {
+        // A real operator applied to a complex vector.
+        let imag_unit = Item::complex(0.0, 1.0);
+        for (part, factor) in [(false, ComplexType::<Item>::one()), (true, imag_unit)] {
+            for index in 0..n {
+                let value = x.get1d(index);
+                let value = if part { value.im() } else { value.re() };
+                *x_item.get1d_mut(index) = Item::from_real(value);
+            }
+            op.apply(&x_item, &mut y_item);
+            for index in 0..y.length() {
+                let value = factor * y_item.get1d(index).as_c();
+                let elem = y.get1d_mut(index);
+                *elem = if part { *elem + value } else { value };
+            }
+        }
+    }
+}
+
+impl<'a, Item: HScalar> Arnoldi<'a, Item>
+where
+    ComplexType<Item>: HScalar,
+{
+    /// Create a new solver for `nev` eigenpairs of the operator `op`.
+    ///
+    /// By default the eigenvalues of largest magnitude are computed with a Krylov space
+    /// of dimension `ncv = max(2 * nev + 1, 20)` (at most the dimension of `op`), a
+    /// tolerance of `1E-8` and at most 300 restarts.
+    pub fn new(op: &'a dyn LinearOperator<Item>, nev: IndexType) -> Self {
+        let dim = op.dim();
+        assert_eq!(
+            dim.0, dim.1,
+            "Arnoldi requires a square operator. dim = {:#?}",
+            dim
+        );
+        assert!(
+            (nev > 0) & (nev < dim.0),
+            "Number of eigenvalues must satisfy 0 < nev < n. nev = {}, n = {}",
+            nev,
+            dim.0
+        );
+        Self {
+            op,
+            shift_invert: None,
+            nev,
+            ncv: (2 * nev + 1).max(20).min(dim.0),
+            which: Which::LargestMagnitude,
+            tol: num::cast::<f64, RealType<Item>>(1E-8).unwrap(),
+            max_restarts: 300,
+            start: None,
+        }
+    }
+
+    /// Set the dimension `ncv` of the Krylov space, which must satisfy `nev < ncv <= n`.
+    pub fn with_ncv(mut self, ncv: IndexType) -> Self {
+        assert!(
+            (ncv > self.nev) & (ncv <= self.op.dim().0),
+            "Krylov dimension must satisfy nev < ncv <= n. ncv = {}",
+            ncv
+        );
+        self.ncv = ncv;
+        self
+    }
+
+    /// Set the part of the spectrum that is computed. It is ignored in shift-invert mode.
+    pub fn with_which(mut self, which: Which) -> Self {
+        self.which = which;
+        self
+    }
+
+    /// Set the relative tolerance for the Ritz estimates.
+    pub fn with_tol(mut self, tol: RealType<Item>) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// Set the maximum number of restarts.
+    pub fn with_max_restarts(mut self, max_restarts: IndexType) -> Self {
+        self.max_restarts = max_restarts;
+        self
+    }
+
+    /// Set the starting vector of the Arnoldi process. By default a random vector is used.
+    pub fn with_start_vector(mut self, start: &'a ColumnVectorD<Item>) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Compute the eigenvalues closest to `sigma`. The operator `solve` must apply
+    /// `(A - sigma * I)^{-1}`.
+    pub fn with_shift_invert(mut self, sigma: Item, solve: &'a dyn LinearOperator<Item>) -> Self {
+        assert_eq!(
+            solve.dim(),
+            self.op.dim(),
+            "Shift-invert operator has dimension {:#?} but operator has dimension {:#?}.",
+            solve.dim(),
+            self.op.dim()
+        );
+        self.shift_invert = Some((sigma, solve));
+        self
+    }
+
+    /// Extend the Arnoldi factorisation from length `start` to length `m`.
+    fn extend(
+        &self,
+        op: &dyn LinearOperator<Item>,
+        basis: &mut Vec<ColumnVectorD<ComplexType<Item>>>,
+        h: &mut [ComplexType<Item>],
+        start: IndexType,
+        rng: &mut StdRng,
+    ) {
+        let n = op.dim().0;
+        let m = self.ncv;
+        let ld = m + 1;
+        let eps = <RealType<Item> as num::Float>::epsilon();
+        for j in start..m {
+            let mut w = ColumnVectorD::<ComplexType<Item>>::zeros_from_length(n);
+            apply_complex(op, &basis[j], &mut w);
+            let w_norm = norm2(&w);
+            orthogonalise(basis, &mut w, &mut h[j * ld..j * ld + j + 1]);
+            let beta = norm2(&w);
+            if basis.len() == n {
+                // The basis spans the whole space and the factorisation is exact.
+                h[j + 1 + j * ld] = ComplexType::<Item>::zero();
+                break;
+            } else if beta <= eps * w_norm {
+                // The Krylov space is invariant. Continue with a new random direction.
+                h[j + 1 + j * ld] = ComplexType::<Item>::zero();
+                basis.push(
+                    random_orthogonal(n, basis, rng)
+                        .expect("Failed to extend an invariant Krylov space."),
+                );
+            } else {
+                h[j + 1 + j * ld] = ComplexType::<Item>::from_real(beta);
+                scale(ComplexType::<Item>::from_real(beta.recip()), &mut w);
+                basis.push(w);
+            }
+        }
+    }
+
+    /// Compute the eigenpairs.
+    pub fn solve(&self) -> EigenReport<ComplexType<Item>, ComplexType<Item>> {
+        type C<Item> = ComplexType<Item>;
+        let n = self.op.dim().0;
+        let (m, k) = (self.ncv, self.nev);
+        let ld = m + 1;
+        let (op, which) = match self.shift_invert {
+            Some((_, solve)) => (solve, Which::LargestMagnitude),
+            None => (self.op, self.which),
+        };
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let start = start_vector(n, self.start, &mut rng);
+        let mut basis: Vec<ColumnVectorD<C<Item>>> = Vec::with_capacity(m + 1);
+        let mut first = ColumnVectorD::<C<Item>>::zeros_from_length(n);
+        for index in 0..n {
+            *first.get1d_mut(index) = start.get1d(index).as_c();
+        }
+        basis.push(first);
+
+        // The (m + 1) x m Hessenberg matrix of the Arnoldi factorisation.
+        let mut h = vec![C::<Item>::zero(); ld * m];
+        let mut restarts = 0;
+        let mut start_col = 0;
+
+        let (theta, y, order, converged) = loop {
+            self.extend(op, &mut basis, &mut h, start_col, &mut rng);
+
+            let mut top = vec![C::<Item>::zero(); m * m];
+            for col in 0..m {
+                for row in 0..m {
+                    top[row + col * m] = h[row + col * ld];
+                }
+            }
+            let (theta, y) = hessenberg_eigen(m, &top);
+            let keys: Vec<_> = theta
+                .iter()
+                .map(|value| (value.abs(), value.re()))
+                .collect();
+            let order = order_by(&keys, which);
+            let beta = h[m + (m - 1) * ld].abs();
+            let converged = order[..k].iter().all(|&index| {
+                beta * y[m - 1 + index * m].abs() <= ritz_threshold(self.tol, theta[index].abs())
+            });
+            if converged | (restarts >= self.max_restarts) {
+                break (theta, y, order, converged);
+            }
+            restarts += 1;
+
+            // Apply the unwanted Ritz values as shifts.
+            let mut q = vec![C::<Item>::zero(); m * m];
+            for index in 0..m {
+                q[index + index * m] = C::<Item>::one();
+            }
+            for &index in order[k..].iter() {
+                qr_step(&mut h, ld, m, 0, m - 1, theta[index], &mut q, m, m);
+            }
+            // The last row of the extended Hessenberg matrix is beta * e_m^T * Q.
+            let beta = h[m + (m - 1) * ld];
+            for col in 0..m {
+                h[m + col * ld] = beta * q[m - 1 + col * m];
+            }
+
+            // Compress the factorisation to length k.
+            let mut compressed = Vec::with_capacity(m + 1);
+            for col in 0..=k {
+                let mut v = ColumnVectorD::<C<Item>>::zeros_from_length(n);
+                for (row, basis_vec) in basis[..m].iter().enumerate() {
+                    axpy(q[row + col * m], basis_vec, &mut v);
+                }
+                compressed.push(v);
+            }
+            let mut residual = compressed.pop().unwrap();
+            scale(h[k + (k - 1) * ld], &mut residual);
+            axpy(h[m + (k - 1) * ld], &basis[m], &mut residual);
+
+            for col in 0..m {
+                for row in 0..ld {
+                    if (col >= k) | (row > k) {
+                        h[row + col * ld] = C::<Item>::zero();
+                    }
+                }
+            }
+            let beta = norm2(&residual);
+            if beta == <RealType<Item> as num::Zero>::zero() {
+                h[k + (k - 1) * ld] = C::<Item>::zero();
+                residual = random_orthogonal(n, &compressed, &mut rng)
+                    .expect("Failed to extend an invariant Krylov space.");
+            } else {
+                h[k + (k - 1) * ld] = C::<Item>::from_real(beta);
+                scale(C::<Item>::from_real(beta.recip()), &mut residual);
+            }
+            compressed.push(residual);
+            basis = compressed;
+            start_col = k;
+        };
+
+        let mut values = Vec::with_capacity(k);
+        let mut vectors = Vec::with_capacity(k);
+        let mut residual_norms = Vec::with_capacity(k);
+        for &index in order[..k].iter() {
+            let mut x = ColumnVectorD::<C<Item>>::zeros_from_length(n);
+            for (row, basis_vec) in basis[..m].iter().enumerate() {
+                axpy(y[row + index * m], basis_vec, &mut x);
+            }
+            let norm = norm2(&x);
+            scale(C::<Item>::from_real(norm.recip()), &mut x);
+
+            let value = match self.shift_invert {
+                Some((sigma, _)) => sigma.as_c() + C::<Item>::one() / theta[index],
+                None => theta[index],
+            };
+            let mut res = ColumnVectorD::<C<Item>>::zeros_from_length(n);
+            apply_complex(self.op, &x, &mut res);
+            axpy(-value, &x, &mut res);
+
+            values.push(value);
+            residual_norms.push(norm2(&res));
+            vectors.push(x);
+        }
+
+        EigenReport {
+            values,
+            vectors,
+            residual_norms,
+            converged,
+            restarts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+    use crate::matrix::MatrixD;
+    use crate::sparse::CooMatrix;
+    use crate::types::*;
+
+    /// Return a real block diagonal matrix whose `2 x 2` blocks have the eigenvalues
+    /// `a +- ib`, followed by a similarity transform with a bidiagonal matrix.
+    fn rotation_blocks(blocks: &[(f64, f64)]) -> MatrixD<f64, ColumnMajor> {
+        let n = 2 * blocks.len();
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+        for (index, &(a, b)) in blocks.iter().enumerate() {
+            let i = 2 * index;
+            *mat.get_mut(i, i) = a;
+            *mat.get_mut(i + 1, i + 1) = a;
+            *mat.get_mut(i, i + 1) = b;
+            *mat.get_mut(i + 1, i) = -b;
+        }
+        // S * A * S^{-1} with S = I + 0.5 * superdiagonal shift.
+        let mut similar = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+        let mut s_inv = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+        for row in 0..n {
+            for col in row..n {
+                *s_inv.get_mut(row, col) = (-0.5f64).powi((col - row) as i32);
+            }
+        }
+        for row in 0..n {
+            for col in 0..n {
+                let mut value = 0.0;
+                for index in 0..n {
+                    let mut sa = mat.get(row, index);
+                    if row + 1 < n {
+                        sa += 0.5 * mat.get(row + 1, index);
+                    }
+                    value += sa * s_inv.get(index, col);
+                }
+                *similar.get_mut(row, col) = value;
+            }
+        }
+        similar
+    }
+
+    #[test]
+    fn test_arnoldi_complex_eigenvalues_of_real_matrix() {
+        let blocks: Vec<(f64, f64)> = (0..15)
+            .map(|index| (1.0 + index as f64, 0.5 * index as f64))
+            .collect();
+        let mat = rotation_blocks(&blocks);
+
+        let report = Arnoldi::new(&mat, 4).with_tol(1E-10).solve();
+        assert!(report.converged);
+        let expect = [
+            c64::new(15.0, 7.0),
+            c64::new(15.0, -7.0),
+            c64::new(14.0, 6.5),
+            c64::new(14.0, -6.5),
+        ];
+        for (value, residual_norm) in report.values.iter().zip(report.residual_norms.iter()) {
+            assert!(
+                expect.iter().any(|e| (value - e).norm() < 1E-8),
+                "{}",
+                value
+            );
+            assert!(*residual_norm < 1E-7);
+        }
+        let magnitudes: Vec<f64> = report.values.iter().map(|value| value.norm()).collect();
+        assert!(magnitudes.windows(2).all(|pair| pair[0] >= pair[1] - 1E-10));
+    }
+
+    macro_rules! arnoldi_test {
+        ($HScalar:ty, $imag:expr, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                // An upper bidiagonal matrix whose eigenvalues are its diagonal.
+                let n = 60;
+                let mut coo = CooMatrix::<$HScalar>::new((n, n));
+                for index in 0..n {
+                    let value = num::cast::<f64, $HScalar>(1.0 + index as f64).unwrap()
+                        + $imag * num::cast::<f64, $HScalar>((index % 3) as f64).unwrap();
+                    coo.push(index, index, value);
+                    if index + 1 < n {
+                        coo.push(index, index + 1, num::cast::<f64, $HScalar>(0.3).unwrap());
+                    }
+                }
+                let csr = coo.to_csr();
+                let report = Arnoldi::new(&csr, 3)
+                    .with_which(Which::SmallestReal)
+                    .with_ncv(30)
+                    .with_tol($tol)
+                    .solve();
+                assert!(report.converged);
+                for (index, value) in report.values.iter().enumerate() {
+                    assert!(
+                        (value.re() - (1.0 + index as f64) as RealType<$HScalar>).abs()
+                            < 100.0 * $tol
+                    );
+                }
+                for residual_norm in report.residual_norms.iter() {
+                    assert!(*residual_norm < 1000.0 * $tol);
+                }
+            }
+        };
+    }
+
+    arnoldi_test!(f32, 0.0, 1E-4, test_arnoldi_f32);
+    arnoldi_test!(f64, 0.0, 1E-10, test_arnoldi_f64);
+    arnoldi_test!(c32, c32::new(0.0, 1.0), 1E-4, test_arnoldi_c32);
+    arnoldi_test!(c64, c64::new(0.0, 1.0), 1E-10, test_arnoldi_c64);
+
+    #[test]
+    fn test_arnoldi_small_operator() {
+        // The default Krylov space spans the whole space for n <= 20.
+        for n in [3, 10, 20, 21] {
+            let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+            for index in 0..n {
+                *mat.get_mut(index, index) = (1 + index) as f64;
+            }
+            let report = Arnoldi::new(&mat, 2).solve();
+            assert!(report.converged);
+            assert!((report.values[0] - c64::new(n as f64, 0.0)).norm() < 1E-10);
+            assert!((report.values[1] - c64::new((n - 1) as f64, 0.0)).norm() < 1E-10);
+            for residual_norm in report.residual_norms.iter() {
+                assert!(*residual_norm < 1E-8);
+            }
+        }
+    }
+
+    #[test]
+    fn test_arnoldi_shift_invert() {
+        let blocks: Vec<(f64, f64)> = (0..20).map(|index| (1.0 + index as f64, 0.25)).collect();
+        let mat = rotation_blocks(&blocks);
+        let sigma = 7.2;
+
+        let mut shifted = MatrixD::<f64, ColumnMajor>::zeros_from_dim(40, 40);
+        for row in 0..40 {
+            for col in 0..40 {
+                *shifted.get_mut(row, col) = mat.get(row, col);
+            }
+            *shifted.get_mut(row, row) -= sigma;
+        }
+        let lu = shifted.lu().unwrap();
+        let solve = FnOperator::new(
+            (40, 40),
+            |x: &ColumnVectorD<f64>, y: &mut ColumnVectorD<f64>| {
+                for index in 0..40 {
+                    *y.get1d_mut(index) = x.get1d(index);
+                }
+                lu.solve(TransposeMode::NoTrans, y);
+            },
+        );
+
+        let report = Arnoldi::new(&mat, 2)
+            .with_shift_invert(sigma, &solve)
+            .with_tol(1E-12)
+            .solve();
+        assert!(report.converged);
+        for (value, residual_norm) in report.values.iter().zip(report.residual_norms.iter()) {
+            assert!((value.re - 7.0).abs() < 1E-10);
+            assert!((value.im.abs() - 0.25).abs() < 1E-10);
+            assert!(*residual_norm < 1E-9);
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! The biconjugate gradient stabilised method.
+
+use super::*;
+use crate::matrix::ColumnVectorD;
+use crate::types::{HScalar, IndexType, Side};
+
+/// The biconjugate gradient stabilised method (BiCGSTAB) for general linear
+/// systems `A * x = b`.
+///
+/// The method requires two applications of the operator and of the preconditioner
+/// in each iteration. It stops early if one of the recurrences breaks down, in which
+/// case the report is not marked as converged.
+pub struct BiCgStab<'a, Item: HScalar> {
+    op: &'a dyn LinearOperator<Item>,
+    prec: Option<&'a dyn Preconditioner<Item>>,
+    side: Side,
+    tol: RealType<Item>,
+    max_iter: IndexType,
+    callback: Option<ResidualCallback<'a, Item>>,
+}
+
+impl<'a, Item: HScalar> BiCgStab<'a, Item> {
+    /// Create a new solver for the operator `op`. The default tolerance is `1E-6`,
+    /// the default maximum number of iterations is the dimension of `op` and
+    /// preconditioning is applied from the right.
+    pub fn new(op: &'a dyn LinearOperator<Item>) -> Self {
+        let dim = op.dim();
+        assert_eq!(
+            dim.0, dim.1,
+            "BiCGSTAB requires a square operator. dim = {:#?}",
+            dim
+        );
+        Self {
+            op,
+            prec: None,
+            side: Side::Right,
+            tol: num::cast::<f64, RealType<Item>>(1E-6).unwrap(),
+            max_iter: dim.0.max(1),
+            callback: None,
+        }
+    }
+
+    /// Set whether the preconditioner is applied from the left or the right.
+    pub fn with_preconditioner_side(mut self, side: Side) -> Self {
+        self.side = side;
+        self
+    }
+
+    /// Solve `A * x = b`. On input `sol` contains the initial guess and
+    /// on output the approximate solution.
+    pub fn solve(
+        &mut self,
+        rhs: &ColumnVectorD<Item>,
+        sol: &mut ColumnVectorD<Item>,
+    ) -> ConvergenceReport<RealType<Item>> {
+        let n = self.op.dim().0;
+        assert!(
+            (rhs.length() == n) & (sol.length() == n),
+            "Incompatible dimensions: A = {:#?}, b = {}, x = {}",
+            self.op.dim(),
+            rhs.length(),
+            sol.length()
+        );
+        let identity = IdentityOperator::new(n);
+        match (self.prec, self.side) {
+            (Some(prec), Side::Left) => {
+                let op = PreconditionedOperator::new(prec, self.op);
+                let mut prec_rhs = ColumnVectorD::<Item>::zeros_from_length(n);
+                prec.apply_inverse(rhs, &mut prec_rhs);
+                self.iterate(&op, &identity, &prec_rhs, sol)
+            }
+            (prec, _) => self.iterate(self.op, prec.unwrap_or(&identity), rhs, sol),
+        }
+    }
+
+    /// Run BiCGSTAB on `op * x = rhs` with the right preconditioner `prec`.
+    fn iterate(
+        &mut self,
+        op: &dyn LinearOperator<Item>,
+        prec: &dyn Preconditioner<Item>,
+        rhs: &ColumnVectorD<Item>,
+        sol: &mut ColumnVectorD<Item>,
+    ) -> ConvergenceReport<RealType<Item>> {
+        let n = rhs.length();
+        let rhs_norm = norm2(rhs);
+        let mut res = residual(op, rhs, sol);
+        let mut rel_res = relative(norm2(&res), rhs_norm);
+        let mut history = vec![rel_res];
+        let mut iterations = 0;
+
+        let shadow = copy(&res);
+        let mut rho = Item::one();
+        let mut alpha = Item::one();
+        let mut omega = Item::one();
+        let mut direction = ColumnVectorD::<Item>::zeros_from_length(n);
+        let mut v = ColumnVectorD::<Item>::zeros_from_length(n);
+        let mut t = ColumnVectorD::<Item>::zeros_from_length(n);
+        let mut prec_direction = ColumnVectorD::<Item>::zeros_from_length(n);
+        let mut prec_res = ColumnVectorD::<Item>::zeros_from_length(n);
+
+        while (rel_res > self.tol) & (iterations < self.max_iter) {
+            let rho_new = inner(&shadow, &res);
+            if rho_new == Item::zero() {
+                break;
+            }
+            // p = r + beta * (p - omega * v)
+            let beta = (rho_new / rho) * (alpha / omega);
+            rho = rho_new;
+            axpy(-omega, &v, &mut direction);
+            xpby(&res, beta, &mut direction);
+
+            prec.apply_inverse(&direction, &mut prec_direction);
+            op.apply(&prec_direction, &mut v);
+            let shadow_v = inner(&shadow, &v);
+            if shadow_v == Item::zero() {
+                break;
+            }
+            alpha = rho / shadow_v;
+            axpy(alpha, &prec_direction, sol);
+            // The residual now holds the intermediate residual s = r - alpha * v.
+            axpy(-alpha, &v, &mut res);
+
+            iterations += 1;
+            rel_res = relative(norm2(&res), rhs_norm);
+            // Skip the stabilisation step if the intermediate residual is small enough.
+            let mut stop = rel_res <= self.tol;
+            if !stop {
+                prec.apply_inverse(&res, &mut prec_res);
+                op.apply(&prec_res, &mut t);
+                let t_norm = norm2(&t);
+                if t_norm == <RealType<Item> as num::Zero>::zero() {
+                    stop = true;
+                } else {
+                    omega = inner(&t, &res) / Item::from_real(t_norm * t_norm);
+                    axpy(omega, &prec_res, sol);
+                    axpy(-omega, &t, &mut res);
+                    rel_res = relative(norm2(&res), rhs_norm);
+                    stop = omega == Item::zero();
+                }
+            }
+
+            history.push(rel_res);
+            if let Some(callback) = self.callback.as_mut() {
+                callback(iterations, rel_res);
+            }
+            if stop {
+                break;
+            }
+        }
+
+        ConvergenceReport {
+            converged: rel_res <= self.tol,
+            iterations,
+            residual_norm: rel_res,
+            residual_history: history,
+        }
+    }
+}
+
+solver_options!(BiCgStab);
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::sparse::{CooMatrix, CsrMatrix};
+    use crate::types::*;
+
+    /// Return an upwind discretisation of the 1d convection-diffusion operator
+    /// with a variable reaction term on the diagonal.
+    fn convection_diffusion<Item: HScalar>(n: IndexType, convection: Item) -> CsrMatrix<Item> {
+        let mut coo = CooMatrix::<Item>::new((n, n));
+        for index in 0..n {
+            let reaction = num::cast::<f64, Item>(index as f64 / 4.0).unwrap();
+            coo.push(
+                index,
+                index,
+                num::cast::<f64, Item>(2.0).unwrap() + convection + reaction,
+            );
+            if index > 0 {
+                coo.push(index, index - 1, -Item::one() - convection);
+            }
+            if index + 1 < n {
+                coo.push(index, index + 1, -Item::one());
+            }
+        }
+        coo.to_csr()
+    }
+
+    macro_rules! bicgstab_test {
+        ($HScalar:ty, $convection:expr, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let n = 60;
+                let mat = convection_diffusion::<$HScalar>(n, $convection);
+                let mut rhs = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                rhs.for_each(|elem| *elem = num::cast::<f64, $HScalar>(1.0).unwrap());
+                let jacobi = Jacobi::new(&mat).unwrap();
+
+                for (prec, side) in [
+                    (false, Side::Right),
+                    (true, Side::Left),
+                    (true, Side::Right),
+                ] {
+                    let mut sol = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                    let mut callback_count = 0;
+                    let mut solver = BiCgStab::new(&mat)
+                        .with_tol($tol)
+                        .with_max_iter(500)
+                        .with_preconditioner_side(side)
+                        .with_callback(|_, _| callback_count += 1);
+                    if prec {
+                        solver = solver.with_preconditioner(&jacobi);
+                    }
+                    let report = solver.solve(&rhs, &mut sol);
+                    drop(solver);
+                    assert!(report.converged);
+                    assert_eq!(report.residual_history.len(), report.iterations + 1);
+                    assert_eq!(callback_count, report.iterations);
+
+                    let res = residual(&mat, &rhs, &sol);
+                    let scale = if side == Side::Left { 1000.0 } else { 10.0 };
+                    assert!(norm2(&res) < scale * $tol * norm2(&rhs));
+                }
+            }
+        };
+    }
+
+    bicgstab_test!(f32, 0.5, 1E-5, test_bicgstab_f32);
+    bicgstab_test!(f64, 0.5, 1E-10, test_bicgstab_f64);
+    bicgstab_test!(c32, c32::new(0.5, 0.5), 1E-5, test_bicgstab_c32);
+    bicgstab_test!(c64, c64::new(0.5, 0.5), 1E-10, test_bicgstab_c64);
+}
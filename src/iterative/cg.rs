@@ -0,0 +1,213 @@
+//! The preconditioned conjugate gradient method.
+
+use super::*;
+use crate::matrix::ColumnVectorD;
+use crate::types::{HScalar, IndexType};
+
+/// The preconditioned conjugate gradient (CG) method for Hermitian positive
+/// definite linear systems `A * x = b`.
+///
+/// The preconditioner applies an approximation of `A^{-1}`. It must itself be
+/// Hermitian positive definite.
+pub struct ConjugateGradient<'a, Item: HScalar> {
+    op: &'a dyn LinearOperator<Item>,
+    prec: Option<&'a dyn Preconditioner<Item>>,
+    tol: RealType<Item>,
+    max_iter: IndexType,
+    callback: Option<ResidualCallback<'a, Item>>,
+}
+
+impl<'a, Item: HScalar> ConjugateGradient<'a, Item> {
+    /// Create a new solver for the operator `op`. The default tolerance is `1E-6`
+    /// and the default maximum number of iterations is the dimension of `op`.
+    pub fn new(op: &'a dyn LinearOperator<Item>) -> Self {
+        let dim = op.dim();
+        assert_eq!(
+            dim.0, dim.1,
+            "CG requires a square operator. dim = {:#?}",
+            dim
+        );
+        Self {
+            op,
+            prec: None,
+            tol: num::cast::<f64, RealType<Item>>(1E-6).unwrap(),
+            max_iter: dim.0.max(1),
+            callback: None,
+        }
+    }
+
+    /// Solve `A * x = b`. On input `sol` contains the initial guess and
+    /// on output the approximate solution.
+    pub fn solve(
+        &mut self,
+        rhs: &ColumnVectorD<Item>,
+        sol: &mut ColumnVectorD<Item>,
+    ) -> ConvergenceReport<RealType<Item>> {
+        let n = self.op.dim().0;
+        assert!(
+            (rhs.length() == n) & (sol.length() == n),
+            "Incompatible dimensions: A = {:#?}, b = {}, x = {}",
+            self.op.dim(),
+            rhs.length(),
+            sol.length()
+        );
+        let identity = IdentityOperator::new(n);
+        let prec = self.prec.unwrap_or(&identity);
+
+        let rhs_norm = norm2(rhs);
+        let mut res = residual(self.op, rhs, sol);
+        let mut rel_res = relative(norm2(&res), rhs_norm);
+        let mut history = vec![rel_res];
+        let mut iterations = 0;
+
+        let mut prec_res = ColumnVectorD::<Item>::zeros_from_length(n);
+        prec.apply_inverse(&res, &mut prec_res);
+        let mut direction = copy(&prec_res);
+        let mut image = ColumnVectorD::<Item>::zeros_from_length(n);
+        let mut rho = inner(&res, &prec_res);
+
+        while (rel_res > self.tol) & (iterations < self.max_iter) {
+            self.op.apply(&direction, &mut image);
+            let curvature = inner(&direction, &image);
+            if curvature == Item::zero() {
+                break;
+            }
+            let alpha = rho / curvature;
+            axpy(alpha, &direction, sol);
+            axpy(-alpha, &image, &mut res);
+
+            iterations += 1;
+            rel_res = relative(norm2(&res), rhs_norm);
+            history.push(rel_res);
+            if let Some(callback) = self.callback.as_mut() {
+                callback(iterations, rel_res);
+            }
+            if rel_res <= self.tol {
+                break;
+            }
+
+            prec.apply_inverse(&res, &mut prec_res);
+            let rho_new = inner(&res, &prec_res);
+            let beta = rho_new / rho;
+            rho = rho_new;
+            xpby(&prec_res, beta, &mut direction);
+        }
+
+        ConvergenceReport {
+            converged: rel_res <= self.tol,
+            iterations,
+            residual_norm: rel_res,
+            residual_history: history,
+        }
+    }
+}
+
+solver_options!(ConjugateGradient);
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+    use crate::matrix::MatrixD;
+    use crate::sparse::CooMatrix;
+    use crate::types::*;
+
+    /// Return the 1d Laplacian with a complex shift on the off-diagonals
+    /// so that the matrix remains Hermitian positive definite.
+    fn laplace<Item: HScalar>(n: IndexType, off: Item) -> CooMatrix<Item> {
+        let mut coo = CooMatrix::<Item>::new((n, n));
+        for index in 0..n {
+            coo.push(index, index, num::cast::<f64, Item>(4.0).unwrap());
+            if index + 1 < n {
+                coo.push(index + 1, index, off);
+                coo.push(index, index + 1, off.conj());
+            }
+        }
+        coo
+    }
+
+    macro_rules! cg_test {
+        ($HScalar:ty, $off:expr, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let n = 50;
+                let csr = laplace::<$HScalar>(n, $off).to_csr();
+                let mut sol = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                let mut rhs = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                for index in 0..n {
+                    *sol.get1d_mut(index) = num::cast::<f64, $HScalar>(index as f64).unwrap();
+                }
+                csr.apply(&sol, &mut rhs);
+
+                let mut callback_count = 0;
+                let mut approx = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                let report = ConjugateGradient::new(&csr)
+                    .with_tol($tol)
+                    .with_callback(|_, _| callback_count += 1)
+                    .solve(&rhs, &mut approx);
+
+                assert!(report.converged);
+                assert_eq!(report.residual_history.len(), report.iterations + 1);
+                assert_eq!(callback_count, report.iterations);
+                for index in 0..n {
+                    let diff = (approx.get1d(index) - sol.get1d(index)).abs();
+                    assert!(diff < 100.0 * $tol * (n as RealType<$HScalar>));
+                }
+            }
+        };
+    }
+
+    cg_test!(f32, -1.0, 1E-5, test_cg_f32);
+    cg_test!(f64, -1.0, 1E-10, test_cg_f64);
+    cg_test!(c32, c32::new(-1.0, 0.5), 1E-5, test_cg_c32);
+    cg_test!(c64, c64::new(-1.0, 0.5), 1E-10, test_cg_c64);
+
+    #[test]
+    fn test_preconditioned_cg() {
+        let n = 40;
+        let mut dense = MatrixD::<f64, RowMajor>::zeros_from_dim(n, n);
+        let diag: Vec<f64> = (0..n).map(|index| 1.0 + 100.0 * index as f64).collect();
+        for (index, &value) in diag.iter().enumerate() {
+            *dense.get_mut(index, index) = value;
+            if index + 1 < n {
+                *dense.get_mut(index + 1, index) = 0.5;
+                *dense.get_mut(index, index + 1) = 0.5;
+            }
+        }
+        let jacobi = Jacobi::new(&dense).unwrap();
+
+        let mut rhs = ColumnVectorD::<f64>::zeros_from_length(n);
+        rhs.for_each(|elem| *elem = 1.0);
+
+        let mut sol = ColumnVectorD::<f64>::zeros_from_length(n);
+        let plain = ConjugateGradient::new(&dense)
+            .with_tol(1E-12)
+            .solve(&rhs, &mut sol);
+        let mut sol = ColumnVectorD::<f64>::zeros_from_length(n);
+        let preconditioned = ConjugateGradient::new(&dense)
+            .with_preconditioner(&jacobi)
+            .with_tol(1E-12)
+            .solve(&rhs, &mut sol);
+
+        assert!(plain.converged & preconditioned.converged);
+        assert!(preconditioned.iterations < plain.iterations);
+
+        let res = residual(&dense, &rhs, &sol);
+        assert!(norm2(&res) < 1E-10);
+    }
+
+    #[test]
+    fn test_cg_max_iter() {
+        let csr = laplace::<f64>(100, -1.0).to_csr();
+        let mut rhs = ColumnVectorD::<f64>::zeros_from_length(100);
+        rhs.for_each(|elem| *elem = 1.0);
+        let mut sol = ColumnVectorD::<f64>::zeros_from_length(100);
+        let report = ConjugateGradient::new(&csr)
+            .with_tol(1E-14)
+            .with_max_iter(3)
+            .solve(&rhs, &mut sol);
+        assert!(!report.converged);
+        assert_eq!(report.iterations, 3);
+    }
+}
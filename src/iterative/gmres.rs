@@ -0,0 +1,275 @@
+//! The restarted generalised minimal residual method.
+
+use super::*;
+use crate::matrix::ColumnVectorD;
+use crate::types::{HScalar, IndexType, Side};
+
+/// The restarted generalised minimal residual method GMRES(m) for general
+/// linear systems `A * x = b`.
+///
+/// Each cycle builds an orthonormal basis of a Krylov space of dimension at most `m`
+/// with the Arnoldi method. The basis is orthogonalised with modified Gram-Schmidt
+/// and a second reorthogonalisation pass to retain orthogonality. The least-squares
+/// problem is solved progressively with Givens rotations so that the residual is
+/// available in each iteration. After each cycle the true residual is recomputed.
+pub struct Gmres<'a, Item: HScalar> {
+    op: &'a dyn LinearOperator<Item>,
+    prec: Option<&'a dyn Preconditioner<Item>>,
+    side: Side,
+    restart: IndexType,
+    tol: RealType<Item>,
+    max_iter: IndexType,
+    callback: Option<ResidualCallback<'a, Item>>,
+}
+
+impl<'a, Item: HScalar> Gmres<'a, Item> {
+    /// Create a new solver for the operator `op`. By default the method restarts after
+    /// `m = 30` iterations, the tolerance is `1E-6`, the maximum number of iterations
+    /// is the dimension of `op` and preconditioning is applied from the right.
+    pub fn new(op: &'a dyn LinearOperator<Item>) -> Self {
+        let dim = op.dim();
+        assert_eq!(
+            dim.0, dim.1,
+            "GMRES requires a square operator. dim = {:#?}",
+            dim
+        );
+        Self {
+            op,
+            prec: None,
+            side: Side::Right,
+            restart: 30,
+            tol: num::cast::<f64, RealType<Item>>(1E-6).unwrap(),
+            max_iter: dim.0.max(1),
+            callback: None,
+        }
+    }
+
+    /// Set the dimension `m` of the Krylov space after which the method restarts.
+    pub fn with_restart(mut self, restart: IndexType) -> Self {
+        assert!(restart > 0, "Restart length must be positive.");
+        self.restart = restart;
+        self
+    }
+
+    /// Set whether the preconditioner is applied from the left or the right.
+    pub fn with_preconditioner_side(mut self, side: Side) -> Self {
+        self.side = side;
+        self
+    }
+
+    /// Solve `A * x = b`. On input `sol` contains the initial guess and
+    /// on output the approximate solution.
+    pub fn solve(
+        &mut self,
+        rhs: &ColumnVectorD<Item>,
+        sol: &mut ColumnVectorD<Item>,
+    ) -> ConvergenceReport<RealType<Item>> {
+        let n = self.op.dim().0;
+        assert!(
+            (rhs.length() == n) & (sol.length() == n),
+            "Incompatible dimensions: A = {:#?}, b = {}, x = {}",
+            self.op.dim(),
+            rhs.length(),
+            sol.length()
+        );
+        let identity = IdentityOperator::new(n);
+        match (self.prec, self.side) {
+            (Some(prec), Side::Left) => {
+                let op = PreconditionedOperator::new(prec, self.op);
+                let mut prec_rhs = ColumnVectorD::<Item>::zeros_from_length(n);
+                prec.apply_inverse(rhs, &mut prec_rhs);
+                self.iterate(&op, &identity, &prec_rhs, sol)
+            }
+            (prec, _) => self.iterate(self.op, prec.unwrap_or(&identity), rhs, sol),
+        }
+    }
+
+    /// Run GMRES(m) on `op * x = rhs` with the right preconditioner `prec`.
+    fn iterate(
+        &mut self,
+        op: &dyn LinearOperator<Item>,
+        prec: &dyn Preconditioner<Item>,
+        rhs: &ColumnVectorD<Item>,
+        sol: &mut ColumnVectorD<Item>,
+    ) -> ConvergenceReport<RealType<Item>> {
+        let n = rhs.length();
+        let rhs_norm = norm2(rhs);
+        let mut res = residual(op, rhs, sol);
+        let mut res_norm = norm2(&res);
+        let mut rel_res = relative(res_norm, rhs_norm);
+        let mut history = vec![rel_res];
+        let mut iterations = 0;
+
+        let mut tmp = ColumnVectorD::<Item>::zeros_from_length(n);
+        while (rel_res > self.tol) & (iterations < self.max_iter) {
+            let mut basis = Vec::<ColumnVectorD<Item>>::with_capacity(self.restart + 1);
+            // The columns of the rotated Hessenberg matrix, i.e. the triangular factor.
+            let mut hessenberg = Vec::<Vec<Item>>::with_capacity(self.restart);
+            let mut rotations = Vec::<(Item, Item)>::with_capacity(self.restart);
+            let mut g = vec![Item::zero(); self.restart + 1];
+            g[0] = Item::from_real(res_norm);
+
+            scale(Item::from_real(res_norm.recip()), &mut res);
+            basis.push(res);
+
+            for j in 0..self.restart {
+                let mut w = ColumnVectorD::<Item>::zeros_from_length(n);
+                prec.apply_inverse(&basis[j], &mut tmp);
+                op.apply(&tmp, &mut w);
+
+                // Modified Gram-Schmidt with one reorthogonalisation pass.
+                let mut h = vec![Item::zero(); j + 2];
+                for _ in 0..2 {
+                    for (i, v) in basis.iter().enumerate() {
+                        let coeff = inner(v, &w);
+                        h[i] += coeff;
+                        axpy(-coeff, v, &mut w);
+                    }
+                }
+                let w_norm = norm2(&w);
+                h[j + 1] = Item::from_real(w_norm);
+
+                for (i, &(c, s)) in rotations.iter().enumerate() {
+                    (h[i], h[i + 1]) = rotate(c, s, h[i], h[i + 1]);
+                }
+                let (c, s) = givens(h[j], h[j + 1]);
+                h[j] = rotate(c, s, h[j], h[j + 1]).0;
+                h[j + 1] = Item::zero();
+                (g[j], g[j + 1]) = rotate(c, s, g[j], Item::zero());
+                rotations.push((c, s));
+                hessenberg.push(h);
+
+                iterations += 1;
+                rel_res = relative(g[j + 1].abs(), rhs_norm);
+                history.push(rel_res);
+                if let Some(callback) = self.callback.as_mut() {
+                    callback(iterations, rel_res);
+                }
+
+                if (rel_res <= self.tol)
+                    | (iterations >= self.max_iter)
+                    | (w_norm == <RealType<Item> as num::Zero>::zero())
+                {
+                    break;
+                }
+                scale(Item::from_real(w_norm.recip()), &mut w);
+                basis.push(w);
+            }
+
+            // Solve the triangular least-squares system and update the solution.
+            let k = hessenberg.len();
+            let mut y = vec![Item::zero(); k];
+            for i in (0..k).rev() {
+                let mut value = g[i];
+                for l in i + 1..k {
+                    value -= hessenberg[l][i] * y[l];
+                }
+                y[i] = value / hessenberg[i][i];
+            }
+            let mut update = ColumnVectorD::<Item>::zeros_from_length(n);
+            for (coeff, v) in y.iter().zip(basis.iter()) {
+                axpy(*coeff, v, &mut update);
+            }
+            prec.apply_inverse(&update, &mut tmp);
+            axpy(Item::one(), &tmp, sol);
+
+            res = residual(op, rhs, sol);
+            res_norm = norm2(&res);
+            rel_res = relative(res_norm, rhs_norm);
+        }
+
+        ConvergenceReport {
+            converged: rel_res <= self.tol,
+            iterations,
+            residual_norm: rel_res,
+            residual_history: history,
+        }
+    }
+}
+
+solver_options!(Gmres);
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+    use crate::matrix::MatrixD;
+    use crate::types::*;
+    use rand::prelude::*;
+
+    /// Return a random nonsymmetric, diagonally dominant dense matrix.
+    fn nonsymmetric_matrix<Item: HScalar>(n: IndexType, imag: Item) -> MatrixD<Item, ColumnMajor> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut mat = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, n);
+        for row in 0..n {
+            for col in 0..n {
+                let re: f64 = rng.gen_range(-1.0..1.0);
+                let im: f64 = rng.gen_range(-1.0..1.0);
+                let mut value = (num::cast::<f64, Item>(re).unwrap()
+                    + imag * num::cast::<f64, Item>(im).unwrap())
+                    / num::cast::<f64, Item>(n as f64).unwrap();
+                if row == col {
+                    value += num::cast::<f64, Item>(2.0 + row as f64).unwrap();
+                }
+                *mat.get_mut(row, col) = value;
+            }
+        }
+        mat
+    }
+
+    macro_rules! gmres_test {
+        ($HScalar:ty, $imag:expr, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let n = 40;
+                let mat = nonsymmetric_matrix::<$HScalar>(n, $imag);
+                let mut rhs = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                rhs.for_each(|elem| *elem = num::cast::<f64, $HScalar>(1.0).unwrap());
+                let jacobi = Jacobi::new(&mat).unwrap();
+
+                for (prec, side) in [
+                    (false, Side::Right),
+                    (true, Side::Left),
+                    (true, Side::Right),
+                ] {
+                    let mut sol = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                    let mut solver = Gmres::new(&mat)
+                        .with_restart(5)
+                        .with_tol($tol)
+                        .with_max_iter(200)
+                        .with_preconditioner_side(side);
+                    if prec {
+                        solver = solver.with_preconditioner(&jacobi);
+                    }
+                    let report = solver.solve(&rhs, &mut sol);
+                    assert!(report.converged);
+
+                    let res = residual(&mat, &rhs, &sol);
+                    assert!(norm2(&res) < 100.0 * $tol * norm2(&rhs));
+                }
+            }
+        };
+    }
+
+    gmres_test!(f32, 0.0, 1E-5, test_gmres_f32);
+    gmres_test!(f64, 0.0, 1E-10, test_gmres_f64);
+    gmres_test!(c32, c32::new(0.0, 1.0), 1E-5, test_gmres_c32);
+    gmres_test!(c64, c64::new(0.0, 1.0), 1E-10, test_gmres_c64);
+
+    #[test]
+    fn test_full_gmres_terminates() {
+        // Without restarts GMRES terminates after at most n iterations.
+        let n = 12;
+        let mat = nonsymmetric_matrix::<f64>(n, 0.0);
+        let mut rhs = ColumnVectorD::<f64>::zeros_from_length(n);
+        *rhs.get1d_mut(0) = 1.0;
+        let mut sol = ColumnVectorD::<f64>::zeros_from_length(n);
+        let report = Gmres::new(&mat)
+            .with_restart(n)
+            .with_tol(1E-12)
+            .solve(&rhs, &mut sol);
+        assert!(report.converged);
+        assert!(report.iterations <= n);
+    }
+}
@@ -0,0 +1,358 @@
+//! The thick-restart Lanczos method.
+
+use super::projected::{order_by, symmetric_eigen};
+use super::*;
+use crate::matrix::ColumnVectorD;
+use crate::types::{HScalar, IndexType};
+use rand::prelude::*;
+
+/// The thick-restart Lanczos method for a few eigenpairs of a Hermitian operator `A`.
+///
+/// The method builds a Lanczos factorisation of length `ncv` with full
+/// reorthogonalisation. On restart the wanted Ritz vectors are kept and the projected
+/// matrix becomes a diagonal matrix of Ritz values bordered by the coupling to the last
+/// residual vector (Wu and Simon). This is repeated until the Ritz estimates of the
+/// `nev` wanted Ritz pairs drop below `tol * |theta|`.
+///
+/// The operator is assumed to be Hermitian. This is not checked.
+pub struct Lanczos<'a, Item: HScalar> {
+    op: &'a dyn LinearOperator<Item>,
+    shift_invert: Option<(RealType<Item>, &'a dyn LinearOperator<Item>)>,
+    nev: IndexType,
+    ncv: IndexType,
+    which: Which,
+    tol: RealType<Item>,
+    max_restarts: IndexType,
+    start: Option<&'a ColumnVectorD<Item>>,
+}
+
+impl<'a, Item: HScalar> Lanczos<'a, Item> {
+    /// Create a new solver for `nev` eigenpairs of the Hermitian operator `op`.
+    ///
+    /// By default the eigenvalues of largest magnitude are computed with a Krylov space
+    /// of dimension `ncv = max(2 * nev + 1, 20)` (at most the dimension of `op`), a
+    /// tolerance of `1E-8` and at most 300 restarts.
+    pub fn new(op: &'a dyn LinearOperator<Item>, nev: IndexType) -> Self {
+        let dim = op.dim();
+        assert_eq!(
+            dim.0, dim.1,
+            "Lanczos requires a square operator. dim = {:#?}",
+            dim
+        );
+        assert!(
+            (nev > 0) & (nev < dim.0),
+            "Number of eigenvalues must satisfy 0 < nev < n. nev = {}, n = {}",
+            nev,
+            dim.0
+        );
+        Self {
+            op,
+            shift_invert: None,
+            nev,
+            ncv: (2 * nev + 1).max(20).min(dim.0),
+            which: Which::LargestMagnitude,
+            tol: num::cast::<f64, RealType<Item>>(1E-8).unwrap(),
+            max_restarts: 300,
+            start: None,
+        }
+    }
+
+    /// Set the dimension `ncv` of the Krylov space, which must satisfy `nev < ncv <= n`.
+    pub fn with_ncv(mut self, ncv: IndexType) -> Self {
+        assert!(
+            (ncv > self.nev) & (ncv <= self.op.dim().0),
+            "Krylov dimension must satisfy nev < ncv <= n. ncv = {}",
+            ncv
+        );
+        self.ncv = ncv;
+        self
+    }
+
+    /// Set the part of the spectrum that is computed. It is ignored in shift-invert mode.
+    pub fn with_which(mut self, which: Which) -> Self {
+        self.which = which;
+        self
+    }
+
+    /// Set the relative tolerance for the Ritz estimates.
+    pub fn with_tol(mut self, tol: RealType<Item>) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// Set the maximum number of restarts.
+    pub fn with_max_restarts(mut self, max_restarts: IndexType) -> Self {
+        self.max_restarts = max_restarts;
+        self
+    }
+
+    /// Set the starting vector of the Lanczos process. By default a random vector is used.
+    pub fn with_start_vector(mut self, start: &'a ColumnVectorD<Item>) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Compute the eigenvalues closest to `sigma`. The operator `solve` must apply
+    /// `(A - sigma * I)^{-1}`.
+    pub fn with_shift_invert(
+        mut self,
+        sigma: RealType<Item>,
+        solve: &'a dyn LinearOperator<Item>,
+    ) -> Self {
+        assert_eq!(
+            solve.dim(),
+            self.op.dim(),
+            "Shift-invert operator has dimension {:#?} but operator has dimension {:#?}.",
+            solve.dim(),
+            self.op.dim()
+        );
+        self.shift_invert = Some((sigma, solve));
+        self
+    }
+
+    /// Compute the eigenpairs.
+    pub fn solve(&self) -> EigenReport<RealType<Item>, Item> {
+        let n = self.op.dim().0;
+        let (m, k) = (self.ncv, self.nev);
+        let keep = ((k + m) / 2).max(k).min(m - 1);
+        let zero = <RealType<Item> as num::Zero>::zero();
+        let eps = <RealType<Item> as Float>::epsilon();
+        let (op, which) = match self.shift_invert {
+            Some((_, solve)) => (solve, Which::LargestMagnitude),
+            None => (self.op, self.which),
+        };
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut basis = Vec::with_capacity(m + 1);
+        basis.push(start_vector(n, self.start, &mut rng));
+
+        // The real symmetric m x m projected matrix and the norm of the residual vector.
+        let mut t = vec![zero; m * m];
+        let mut beta = zero;
+        let mut coeffs = vec![Item::zero(); m];
+        let mut restarts = 0;
+        let mut start_col = 0;
+
+        let (theta, y, order, converged) = loop {
+            for j in start_col..m {
+                let mut w = ColumnVectorD::<Item>::zeros_from_length(n);
+                op.apply(&basis[j], &mut w);
+                let w_norm = norm2(&w);
+                coeffs.iter_mut().for_each(|elem| *elem = Item::zero());
+                orthogonalise(&basis, &mut w, &mut coeffs[..=j]);
+                t[j + j * m] = coeffs[j].re();
+
+                beta = norm2(&w);
+                if basis.len() == n {
+                    // The basis spans the whole space and the factorisation is exact.
+                    beta = zero;
+                    break;
+                }
+                let next = if beta <= eps * w_norm {
+                    // The Krylov space is invariant. Continue with a new random direction.
+                    beta = zero;
+                    random_orthogonal(n, &basis, &mut rng)
+                        .expect("Failed to extend an invariant Krylov space.")
+                } else {
+                    scale(Item::from_real(beta.recip()), &mut w);
+                    w
+                };
+                if j + 1 < m {
+                    t[j + 1 + j * m] = beta;
+                    t[j + (j + 1) * m] = beta;
+                }
+                basis.push(next);
+            }
+
+            let (theta, y) = symmetric_eigen(m, &t);
+            let keys: Vec<_> = theta
+                .iter()
+                .map(|&value| (Float::abs(value), value))
+                .collect();
+            let order = order_by(&keys, which);
+            let converged = order[..k].iter().all(|&index| {
+                beta * Float::abs(y[m - 1 + index * m])
+                    <= ritz_threshold(self.tol, Float::abs(theta[index]))
+            });
+            if converged | (restarts >= self.max_restarts) {
+                break (theta, y, order, converged);
+            }
+            restarts += 1;
+
+            // Keep the wanted Ritz vectors and the residual vector.
+            let residual = basis.pop().unwrap();
+            let mut kept = Vec::with_capacity(m + 1);
+            t.iter_mut().for_each(|elem| *elem = zero);
+            for (col, &index) in order[..keep].iter().enumerate() {
+                let mut v = ColumnVectorD::<Item>::zeros_from_length(n);
+                for (row, basis_vec) in basis.iter().enumerate() {
+                    axpy(Item::from_real(y[row + index * m]), basis_vec, &mut v);
+                }
+                kept.push(v);
+                t[col + col * m] = theta[index];
+                let coupling = beta * y[m - 1 + index * m];
+                t[keep + col * m] = coupling;
+                t[col + keep * m] = coupling;
+            }
+            kept.push(residual);
+            basis = kept;
+            start_col = keep;
+        };
+
+        let mut values = Vec::with_capacity(k);
+        let mut vectors = Vec::with_capacity(k);
+        let mut residual_norms = Vec::with_capacity(k);
+        for &index in order[..k].iter() {
+            let mut x = ColumnVectorD::<Item>::zeros_from_length(n);
+            for (row, basis_vec) in basis[..m].iter().enumerate() {
+                axpy(Item::from_real(y[row + index * m]), basis_vec, &mut x);
+            }
+            let norm = norm2(&x);
+            scale(Item::from_real(norm.recip()), &mut x);
+
+            let value = match self.shift_invert {
+                Some((sigma, _)) => sigma + theta[index].recip(),
+                None => theta[index],
+            };
+            let mut res = ColumnVectorD::<Item>::zeros_from_length(n);
+            self.op.apply(&x, &mut res);
+            axpy(-Item::from_real(value), &x, &mut res);
+
+            values.push(value);
+            residual_norms.push(norm2(&res));
+            vectors.push(x);
+        }
+
+        EigenReport {
+            values,
+            vectors,
+            residual_norms,
+            converged,
+            restarts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+    use crate::matrix::MatrixD;
+    use crate::sparse::{CooMatrix, CsrMatrix};
+    use crate::types::*;
+
+    /// Return the 1d Laplacian `tridiag(-1, 2, -1)` scaled by `factor`.
+    fn laplace<Item: HScalar>(n: IndexType, factor: Item) -> CsrMatrix<Item> {
+        let mut coo = CooMatrix::<Item>::new((n, n));
+        for index in 0..n {
+            coo.push(index, index, num::cast::<f64, Item>(2.0).unwrap());
+            if index > 0 {
+                coo.push(index, index - 1, -factor.conj());
+            }
+            if index + 1 < n {
+                coo.push(index, index + 1, -factor);
+            }
+        }
+        coo.to_csr()
+    }
+
+    /// Return the `k`-th smallest eigenvalue of the 1d Laplacian.
+    fn laplace_eigenvalue(n: IndexType, k: IndexType) -> f64 {
+        2.0 - 2.0 * ((k + 1) as f64 * std::f64::consts::PI / (n + 1) as f64).cos()
+    }
+
+    macro_rules! lanczos_test {
+        ($HScalar:ty, $factor:expr, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let n = 100;
+                let mat = laplace::<$HScalar>(n, $factor);
+
+                for which in [Which::LargestReal, Which::SmallestReal] {
+                    let report = Lanczos::new(&mat, 4)
+                        .with_which(which)
+                        .with_ncv(30)
+                        .with_tol($tol)
+                        .solve();
+                    assert!(report.converged);
+                    for (index, value) in report.values.iter().enumerate() {
+                        let k = if which == Which::SmallestReal {
+                            index
+                        } else {
+                            n - 1 - index
+                        };
+                        let expect = laplace_eigenvalue(n, k) as RealType<$HScalar>;
+                        assert!((value - expect).abs() < 100.0 * $tol * expect.max(1.0));
+                    }
+                    for residual_norm in report.residual_norms.iter() {
+                        assert!(*residual_norm < 1000.0 * $tol);
+                    }
+                }
+            }
+        };
+    }
+
+    lanczos_test!(f32, 1.0, 1E-5, test_lanczos_f32);
+    lanczos_test!(f64, 1.0, 1E-10, test_lanczos_f64);
+    lanczos_test!(c32, c32::new(0.6, 0.8), 1E-5, test_lanczos_c32);
+    lanczos_test!(c64, c64::new(0.6, 0.8), 1E-10, test_lanczos_c64);
+
+    #[test]
+    fn test_lanczos_small_operator() {
+        // The default Krylov space spans the whole space for n <= 20.
+        for n in [3, 10, 20, 21] {
+            let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+            for index in 0..n {
+                *mat.get_mut(index, index) = (1 + index) as f64;
+            }
+            let report = Lanczos::new(&mat, 2).solve();
+            assert!(report.converged);
+            assert!((report.values[0] - n as f64).abs() < 1E-10);
+            assert!((report.values[1] - (n - 1) as f64).abs() < 1E-10);
+            for residual_norm in report.residual_norms.iter() {
+                assert!(*residual_norm < 1E-8);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lanczos_shift_invert() {
+        let n = 50;
+        let mat = laplace::<f64>(n, 1.0);
+        let sigma = 1.01;
+
+        let mut shifted = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+        for row in 0..n {
+            for col in row.saturating_sub(1)..(row + 2).min(n) {
+                *shifted.get_mut(row, col) = mat.get(row, col);
+            }
+            *shifted.get_mut(row, row) -= sigma;
+        }
+        let lu = shifted.lu().unwrap();
+        let solve = FnOperator::new(
+            (n, n),
+            |x: &ColumnVectorD<f64>, y: &mut ColumnVectorD<f64>| {
+                for index in 0..n {
+                    *y.get1d_mut(index) = x.get1d(index);
+                }
+                lu.solve(TransposeMode::NoTrans, y);
+            },
+        );
+
+        let report = Lanczos::new(&mat, 3)
+            .with_shift_invert(sigma, &solve)
+            .with_tol(1E-12)
+            .solve();
+        assert!(report.converged);
+
+        let mut expect: Vec<f64> = (0..n).map(|k| laplace_eigenvalue(n, k)).collect();
+        expect.sort_by(|a, b| (a - sigma).abs().partial_cmp(&(b - sigma).abs()).unwrap());
+        for (value, expect) in report.values.iter().zip(expect.iter()) {
+            assert!((value - expect).abs() < 1E-10);
+        }
+        for residual_norm in report.residual_norms.iter() {
+            assert!(*residual_norm < 1E-9);
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Linear operators.
+
+use crate::data_container::DataContainer;
+use crate::diagonal_matrix::DiagonalMatrix;
+use crate::hmatrix::HMatrix;
+use crate::layouts::*;
+use crate::low_rank::LowRankMatrix;
+use crate::matrix::*;
+use crate::matrix_multiply::MatMul;
+use crate::sparse::{CscMatrix, CsrMatrix};
+use crate::traits::*;
+use crate::types::*;
+
+/// A linear map `A` that can be applied to a vector.
+pub trait LinearOperator<Item: HScalar> {
+    /// Return the dimension `(rows, cols)` of the operator.
+    fn dim(&self) -> (IndexType, IndexType);
+
+    /// Compute `y = A * x`.
+    fn apply(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>);
+}
+
+impl<Item: HScalar, Op: LinearOperator<Item> + ?Sized> LinearOperator<Item> for &Op {
+    fn dim(&self) -> (IndexType, IndexType) {
+        (**self).dim()
+    }
+
+    fn apply(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        (**self).apply(x, y)
+    }
+}
+
+/// Check the dimensions of `y = A * x` for a matrix `A` of dimension `dim`.
+fn check_apply_dims<Item: HScalar>(
+    dim: (IndexType, IndexType),
+    x: &ColumnVectorD<Item>,
+    y: &ColumnVectorD<Item>,
+) {
+    assert!(
+        (dim.1 == x.length()) & (dim.0 == y.length()),
+        "Incompatible dimensions for y = A * x: A = {:#?}, x = {}, y = {}",
+        dim,
+        x.length(),
+        y.length()
+    );
+}
+
+// Dense matrices with a strided layout are applied through [MatMul].
+macro_rules! strided_linear_operator {
+    ($HScalar:ty, $Layout:ident) => {
+        impl<Data: DataContainer<Item = $HScalar>> LinearOperator<$HScalar>
+            for GenericBaseMatrix<$HScalar, $Layout, Data, Dynamic, Dynamic>
+        {
+            fn dim(&self) -> (IndexType, IndexType) {
+                self.layout().dim()
+            }
+
+            fn apply(&self, x: &ColumnVectorD<$HScalar>, y: &mut ColumnVectorD<$HScalar>) {
+                check_apply_dims(self.layout().dim(), x, y);
+                <$HScalar>::matmul(
+                    <$HScalar as num::One>::one(),
+                    self,
+                    x,
+                    <$HScalar as num::Zero>::zero(),
+                    y,
+                );
+            }
+        }
+    };
+    ($HScalar:ty) => {
+        strided_linear_operator!($HScalar, RowMajor);
+        strided_linear_operator!($HScalar, ColumnMajor);
+        strided_linear_operator!($HScalar, ArbitraryStrideRowMajor);
+        strided_linear_operator!($HScalar, ArbitraryStrideColumnMajor);
+    };
+}
+
+strided_linear_operator!(f32);
+strided_linear_operator!(f64);
+strided_linear_operator!(c32);
+strided_linear_operator!(c64);
+
+// Matrices with a structured layout are applied element by element. Elements that are
+// not stored by the layout are zero.
+macro_rules! structured_linear_operator {
+    ($Layout:ident) => {
+        impl<Item: HScalar, Data: DataContainer<Item = Item>> LinearOperator<Item>
+            for GenericBaseMatrix<Item, $Layout, Data, Dynamic, Dynamic>
+        {
+            fn dim(&self) -> (IndexType, IndexType) {
+                self.layout().dim()
+            }
+
+            fn apply(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+                let dim = self.layout().dim();
+                check_apply_dims(dim, x, y);
+                for row in 0..dim.0 {
+                    let mut value = Item::zero();
+                    for col in 0..dim.1 {
+                        value += self.element(row, col) * unsafe { x.get1d_unchecked(col) };
+                    }
+                    unsafe { *y.get1d_unchecked_mut(row) = value };
+                }
+            }
+        }
+    };
+}
+
+structured_linear_operator!(UpperTriangular);
+structured_linear_operator!(LowerTriangular);
+structured_linear_operator!(SymmetricPacked);
+structured_linear_operator!(HermitianPacked);
+structured_linear_operator!(Banded);
+structured_linear_operator!(Tridiagonal);
+
+impl<Item: HScalar, B: BaseLayoutType> LinearOperator<Item> for DiagonalMatrix<Item, B> {
+    fn dim(&self) -> (IndexType, IndexType) {
+        self.layout().dim()
+    }
+
+    fn apply(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        check_apply_dims(self.layout().dim(), x, y);
+        for index in 0..x.length() {
+            unsafe {
+                *y.get1d_unchecked_mut(index) =
+                    self.get_unchecked(index, index) * x.get1d_unchecked(index)
+            };
+        }
+    }
+}
+
+macro_rules! sparse_linear_operator {
+    ($Sparse:ident) => {
+        impl<Item: HScalar> LinearOperator<Item> for $Sparse<Item> {
+            fn dim(&self) -> (IndexType, IndexType) {
+                $Sparse::dim(self)
+            }
+
+            fn apply(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+                self.matvec(TransposeMode::NoTrans, Item::one(), x, Item::zero(), y);
+            }
+        }
+    };
+}
+
+sparse_linear_operator!(CsrMatrix);
+sparse_linear_operator!(CscMatrix);
+sparse_linear_operator!(LowRankMatrix);
+sparse_linear_operator!(HMatrix);
+
+/// A linear operator defined by a closure `f(x, y)` that computes `y = A * x`.
+pub struct FnOperator<Item: HScalar, F: Fn(&ColumnVectorD<Item>, &mut ColumnVectorD<Item>)> {
+    dim: (IndexType, IndexType),
+    f: F,
+    _marker: std::marker::PhantomData<Item>,
+}
+
+impl<Item: HScalar, F: Fn(&ColumnVectorD<Item>, &mut ColumnVectorD<Item>)> FnOperator<Item, F> {
+    /// Create a new operator of dimension `dim` from a closure.
+    pub fn new(dim: (IndexType, IndexType), f: F) -> Self {
+        Self {
+            dim,
+            f,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<Item: HScalar, F: Fn(&ColumnVectorD<Item>, &mut ColumnVectorD<Item>)> LinearOperator<Item>
+    for FnOperator<Item, F>
+{
+    fn dim(&self) -> (IndexType, IndexType) {
+        self.dim
+    }
+
+    fn apply(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        (self.f)(x, y)
+    }
+}
+
+/// The identity operator of dimension `n x n`. It is used as the trivial preconditioner.
+pub struct IdentityOperator {
+    dim: IndexType,
+}
+
+impl IdentityOperator {
+    pub fn new(dim: IndexType) -> Self {
+        Self { dim }
+    }
+}
+
+impl<Item: HScalar> LinearOperator<Item> for IdentityOperator {
+    fn dim(&self) -> (IndexType, IndexType) {
+        (self.dim, self.dim)
+    }
+
+    fn apply(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        check_apply_dims((self.dim, self.dim), x, y);
+        for index in 0..x.length() {
+            unsafe { *y.get1d_unchecked_mut(index) = x.get1d_unchecked(index) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::sparse::CooMatrix;
+
+    #[test]
+    fn test_linear_operators() {
+        let mut dense = MatrixD::<f64, ColumnMajor>::zeros_from_dim(2, 3);
+        *dense.get_mut(0, 0) = 1.0;
+        *dense.get_mut(0, 2) = 2.0;
+        *dense.get_mut(1, 1) = 3.0;
+        let csr = CooMatrix::from_dense(&dense).to_csr();
+        let closure = FnOperator::new(
+            (2, 3),
+            |x: &ColumnVectorD<f64>, y: &mut ColumnVectorD<f64>| {
+                *y.get1d_mut(0) = x.get1d(0) + 2.0 * x.get1d(2);
+                *y.get1d_mut(1) = 3.0 * x.get1d(1);
+            },
+        );
+
+        let mut x = ColumnVectorD::<f64>::zeros_from_length(3);
+        for index in 0..3 {
+            *x.get1d_mut(index) = 1.0 + index as f64;
+        }
+
+        let operators: [&dyn LinearOperator<f64>; 3] = [&dense, &csr, &closure];
+        for op in operators {
+            assert_eq!(op.dim(), (2, 3));
+            let mut y = ColumnVectorD::<f64>::zeros_from_length(2);
+            op.apply(&x, &mut y);
+            assert_eq!(y.get1d(0), 7.0);
+            assert_eq!(y.get1d(1), 6.0);
+        }
+    }
+
+    #[test]
+    fn test_matrix_operators() {
+        // A block of a larger matrix is applied through its stride.
+        let mut mat = MatrixD::<c64, RowMajor>::zeros_from_dim(4, 5);
+        for row in 0..4 {
+            for col in 0..5 {
+                *mat.get_mut(row, col) = c64::new(row as f64, col as f64);
+            }
+        }
+        let block = mat.block((1, 1), (3, 3));
+        let mut x = ColumnVectorD::<c64>::zeros_from_length(3);
+        for index in 0..3 {
+            *x.get1d_mut(index) = c64::new(1.0, index as f64);
+        }
+        let mut y = ColumnVectorD::<c64>::zeros_from_length(3);
+        block.apply(&x, &mut y);
+        for row in 0..3 {
+            let expect = (0..3).fold(c64::new(0.0, 0.0), |acc, col| {
+                acc + block.get(row, col) * x.get1d(col)
+            });
+            assert_eq!(y.get1d(row), expect);
+        }
+
+        // Elements that are not stored by a structured layout do not contribute.
+        let mut upper = UpperTriangularMatrix::<f64>::zeros_from_dim(3, 3);
+        for col in 0..3 {
+            for row in 0..=col {
+                *upper.get_mut(row, col) = 1.0;
+            }
+        }
+        let mut x = ColumnVectorD::<f64>::zeros_from_length(3);
+        x.for_each(|elem| *elem = 1.0);
+        let mut y = ColumnVectorD::<f64>::zeros_from_length(3);
+        upper.apply(&x, &mut y);
+        assert_eq!([y.get1d(0), y.get1d(1), y.get1d(2)], [3.0, 2.0, 1.0]);
+    }
+}
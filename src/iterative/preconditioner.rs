@@ -0,0 +1,627 @@
+//! Preconditioners.
+
+use super::*;
+use crate::band_solvers::{BandedLu, TridiagonalLdlt};
+use crate::data_container::DataContainer;
+use crate::dense_solvers::{DenseCholesky, DenseLu};
+use crate::error::LinAlgError;
+use crate::layouts::*;
+use crate::matrix::*;
+use crate::sparse::CsrMatrix;
+use crate::types::*;
+
+/// A preconditioner `M` for a square `n x n` operator `A`.
+///
+/// A preconditioner applies `M^{-1}`, which should approximate `A^{-1}` and be much
+/// cheaper to apply than solving with `A`. Custom preconditioners can be defined through
+/// a [FnOperator], whose closure then computes `y = M^{-1} * x`.
+pub trait Preconditioner<Item: HScalar> {
+    /// Return the dimension `n` of the `n x n` preconditioner.
+    fn dimension(&self) -> IndexType;
+
+    /// Compute `y = M^{-1} * x`.
+    fn apply_inverse(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>);
+}
+
+impl<Item: HScalar> Preconditioner<Item> for IdentityOperator {
+    fn dimension(&self) -> IndexType {
+        LinearOperator::<Item>::dim(self).0
+    }
+
+    fn apply_inverse(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        self.apply(x, y)
+    }
+}
+
+impl<Item: HScalar, F: Fn(&ColumnVectorD<Item>, &mut ColumnVectorD<Item>)> Preconditioner<Item>
+    for FnOperator<Item, F>
+{
+    fn dimension(&self) -> IndexType {
+        self.dim().0
+    }
+
+    fn apply_inverse(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        self.apply(x, y)
+    }
+}
+
+// Factorisations of `A` apply `A^{-1}` exactly, which is e.g. required by iterative
+// refinement.
+
+impl<Item: HScalar> Preconditioner<Item> for DenseLu<Item> {
+    fn dimension(&self) -> IndexType {
+        self.dim()
+    }
+
+    fn apply_inverse(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        IdentityOperator::new(self.dim()).apply(x, y);
+        self.solve(TransposeMode::NoTrans, y);
+    }
+}
+
+impl<Item: HScalar> Preconditioner<Item> for DenseCholesky<Item> {
+    fn dimension(&self) -> IndexType {
+        self.dim()
+    }
+
+    fn apply_inverse(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        IdentityOperator::new(self.dim()).apply(x, y);
+        self.solve(y);
+    }
+}
+
+impl<Item: HScalar> Preconditioner<Item> for BandedLu<Item> {
+    fn dimension(&self) -> IndexType {
+        self.pivots().len()
+    }
+
+    fn apply_inverse(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        IdentityOperator::new(self.dimension()).apply(x, y);
+        self.solve(TransposeMode::NoTrans, y);
+    }
+}
+
+impl<Item: HScalar> Preconditioner<Item> for TridiagonalLdlt<Item> {
+    fn dimension(&self) -> IndexType {
+        self.d().len()
+    }
+
+    fn apply_inverse(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        IdentityOperator::new(self.dimension()).apply(x, y);
+        self.solve(y);
+    }
+}
+
+/// Check that `x` and `y` have the dimension `n` of a preconditioner.
+fn check_dimension<Item: HScalar>(n: IndexType, x: &ColumnVectorD<Item>, y: &ColumnVectorD<Item>) {
+    assert!(
+        (x.length() == n) & (y.length() == n),
+        "Incompatible dimensions for y = M^{{-1}} * x: M = {}, x = {}, y = {}",
+        n,
+        x.length(),
+        y.length()
+    );
+}
+
+/// The left preconditioned operator `M^{-1} * A`.
+pub(crate) struct PreconditionedOperator<'a, Item: HScalar> {
+    prec: &'a dyn Preconditioner<Item>,
+    op: &'a dyn LinearOperator<Item>,
+}
+
+impl<'a, Item: HScalar> PreconditionedOperator<'a, Item> {
+    pub(crate) fn new(
+        prec: &'a dyn Preconditioner<Item>,
+        op: &'a dyn LinearOperator<Item>,
+    ) -> Self {
+        Self { prec, op }
+    }
+}
+
+impl<'a, Item: HScalar> LinearOperator<Item> for PreconditionedOperator<'a, Item> {
+    fn dim(&self) -> (IndexType, IndexType) {
+        self.op.dim()
+    }
+
+    fn apply(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        let mut tmp = ColumnVectorD::<Item>::zeros_from_length(self.op.dim().0);
+        self.op.apply(x, &mut tmp);
+        self.prec.apply_inverse(&tmp, y);
+    }
+}
+
+/// The Jacobi preconditioner `M = D`, where `D` is the diagonal of `A`.
+pub struct Jacobi<Item: HScalar> {
+    inv_diag: Vec<Item>,
+}
+
+impl<Item: HScalar> Jacobi<Item> {
+    /// Create the Jacobi preconditioner from the diagonal of a dense or sparse matrix.
+    pub fn new<Mat: RandomAccess<Item = Item> + Layout>(mat: &Mat) -> Result<Self, LinAlgError> {
+        let dim = mat.layout().dim();
+        assert_eq!(
+            dim.0, dim.1,
+            "Jacobi preconditioner requires a square matrix. dim = {:#?}",
+            dim
+        );
+        let mut inv_diag = Vec::with_capacity(dim.0);
+        for index in 0..dim.0 {
+            let value = mat.get(index, index);
+            if value == Item::zero() {
+                return Err(LinAlgError::SingularMatrix(index));
+            }
+            inv_diag.push(Item::one() / value);
+        }
+        Ok(Self { inv_diag })
+    }
+}
+
+impl<Item: HScalar> Preconditioner<Item> for Jacobi<Item> {
+    fn dimension(&self) -> IndexType {
+        self.inv_diag.len()
+    }
+
+    fn apply_inverse(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        check_dimension(self.dimension(), x, y);
+        for (index, &value) in self.inv_diag.iter().enumerate() {
+            unsafe { *y.get1d_unchecked_mut(index) = value * x.get1d_unchecked(index) };
+        }
+    }
+}
+
+/// The block-Jacobi preconditioner `M = diag(A_11, ..., A_kk)`, where the `A_ii` are
+/// square diagonal blocks of `A`.
+///
+/// The diagonal blocks are factorised with a dense LU decomposition. The preconditioner
+/// is created with the `block_jacobi` method of a dense matrix.
+pub struct BlockJacobi<Item: HScalar> {
+    dim: IndexType,
+    // The first row of each block and its LU decomposition.
+    blocks: Vec<(IndexType, DenseLu<Item>)>,
+}
+
+impl<Item: HScalar> BlockJacobi<Item> {
+    /// Return the number of diagonal blocks.
+    pub fn number_of_blocks(&self) -> IndexType {
+        self.blocks.len()
+    }
+}
+
+impl<Item: HScalar> Preconditioner<Item> for BlockJacobi<Item> {
+    fn dimension(&self) -> IndexType {
+        self.dim
+    }
+
+    fn apply_inverse(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        check_dimension(self.dim, x, y);
+        for (start, lu) in self.blocks.iter() {
+            let mut rhs = ColumnVectorD::<Item>::zeros_from_length(lu.dim());
+            for index in 0..lu.dim() {
+                unsafe { *rhs.get1d_unchecked_mut(index) = x.get1d_unchecked(start + index) };
+            }
+            lu.solve(TransposeMode::NoTrans, &mut rhs);
+            for index in 0..lu.dim() {
+                unsafe { *y.get1d_unchecked_mut(start + index) = rhs.get1d_unchecked(index) };
+            }
+        }
+    }
+}
+
+macro_rules! block_jacobi {
+    ($Layout:ident) => {
+        impl<Item: HScalar, Data: DataContainer<Item = Item>>
+            GenericBaseMatrix<Item, $Layout, Data, Dynamic, Dynamic>
+        {
+            /// Create the block-Jacobi preconditioner with diagonal blocks of dimension
+            /// `block_size`. The last block is smaller if `block_size` does not divide
+            /// the dimension of the matrix.
+            pub fn block_jacobi(
+                &self,
+                block_size: IndexType,
+            ) -> Result<BlockJacobi<Item>, LinAlgError> {
+                let dim = self.layout().dim();
+                assert_eq!(
+                    dim.0, dim.1,
+                    "Block-Jacobi preconditioner requires a square matrix. dim = {:#?}",
+                    dim
+                );
+                assert!(block_size > 0, "Block size must be positive.");
+
+                let mut blocks = Vec::with_capacity(dim.0.div_ceil(block_size));
+                for start in (0..dim.0).step_by(block_size) {
+                    let size = block_size.min(dim.0 - start);
+                    let lu =
+                        self.block((start, start), (size, size))
+                            .lu()
+                            .map_err(|err| match err {
+                                LinAlgError::SingularMatrix(col) => {
+                                    LinAlgError::SingularMatrix(start + col)
+                                }
+                                err => err,
+                            })?;
+                    blocks.push((start, lu));
+                }
+                Ok(BlockJacobi { dim: dim.0, blocks })
+            }
+        }
+    };
+}
+
+block_jacobi!(RowMajor);
+block_jacobi!(ColumnMajor);
+
+/// Return the diagonal of a CSR matrix.
+fn csr_diagonal<Item: HScalar>(mat: &CsrMatrix<Item>) -> Result<Vec<Item>, LinAlgError> {
+    let dim = mat.dim();
+    assert_eq!(
+        dim.0, dim.1,
+        "Preconditioner requires a square matrix. dim = {:#?}",
+        dim
+    );
+    let mut diag = vec![Item::zero(); dim.0];
+    for (row, col, value) in mat.iter() {
+        if row == col {
+            diag[row] += value;
+        }
+    }
+    match diag.iter().position(|&value| value == Item::zero()) {
+        Some(index) => Err(LinAlgError::SingularMatrix(index)),
+        None => Ok(diag),
+    }
+}
+
+/// The symmetric successive over-relaxation (SSOR) preconditioner
+/// `M = (D / omega + L) * (D / omega)^{-1} * (D / omega + U) * omega / (2 - omega)`
+/// for a CSR matrix `A = L + D + U`.
+///
+/// For a Hermitian positive definite matrix and `0 < omega < 2` the preconditioner is
+/// Hermitian positive definite and can be used with [ConjugateGradient].
+pub struct Ssor<'a, Item: HScalar> {
+    mat: &'a CsrMatrix<Item>,
+    diag: Vec<Item>,
+    omega: RealType<Item>,
+}
+
+impl<'a, Item: HScalar> Ssor<'a, Item> {
+    /// Create the SSOR preconditioner with relaxation parameter `0 < omega < 2`.
+    pub fn new(mat: &'a CsrMatrix<Item>, omega: RealType<Item>) -> Result<Self, LinAlgError> {
+        let two = num::cast::<f64, RealType<Item>>(2.0).unwrap();
+        assert!(
+            (omega > <RealType<Item> as num::Zero>::zero()) & (omega < two),
+            "Relaxation parameter must satisfy 0 < omega < 2."
+        );
+        let diag = csr_diagonal(mat)?;
+        Ok(Self { mat, diag, omega })
+    }
+}
+
+impl<'a, Item: HScalar> Preconditioner<Item> for Ssor<'a, Item> {
+    fn dimension(&self) -> IndexType {
+        self.diag.len()
+    }
+
+    fn apply_inverse(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        let n = self.dimension();
+        check_dimension(n, x, y);
+        let (indptr, indices, data) = (self.mat.indptr(), self.mat.indices(), self.mat.data());
+        let omega = Item::from_real(self.omega);
+        let two = Item::from_real(num::cast::<f64, RealType<Item>>(2.0).unwrap());
+
+        unsafe {
+            // Solve (D + omega * L) * z = x.
+            for row in 0..n {
+                let mut value = x.get1d_unchecked(row);
+                for pos in indptr[row]..indptr[row + 1] {
+                    let col = indices[pos];
+                    if col < row {
+                        value -= omega * data[pos] * y.get1d_unchecked(col);
+                    }
+                }
+                *y.get1d_unchecked_mut(row) = value / self.diag[row];
+            }
+            // Multiply with D and solve (D + omega * U) * z = y.
+            for row in (0..n).rev() {
+                let mut value = self.diag[row] * y.get1d_unchecked(row);
+                for pos in indptr[row]..indptr[row + 1] {
+                    let col = indices[pos];
+                    if col > row {
+                        value -= omega * data[pos] * y.get1d_unchecked(col);
+                    }
+                }
+                *y.get1d_unchecked_mut(row) = value / self.diag[row];
+            }
+            for row in 0..n {
+                *y.get1d_unchecked_mut(row) *= omega * (two - omega);
+            }
+        }
+    }
+}
+
+/// The incomplete LU decomposition without fill-in ILU(0) of a CSR matrix.
+///
+/// The factors `L` and `U` have the same sparsity pattern as the lower and upper
+/// triangular parts of `A`, and `L * U` agrees with `A` on that pattern.
+pub struct Ilu0<Item: HScalar> {
+    factor: CsrMatrix<Item>,
+    // The position of the diagonal element of each row in the factor.
+    diag: Vec<IndexType>,
+}
+
+impl<Item: HScalar> Ilu0<Item> {
+    /// Compute the ILU(0) decomposition. Every diagonal element of `mat` must be
+    /// stored and the pivots must be nonzero.
+    pub fn new(mat: &CsrMatrix<Item>) -> Result<Self, LinAlgError> {
+        let dim = mat.dim();
+        assert_eq!(
+            dim.0, dim.1,
+            "ILU(0) requires a square matrix. dim = {:#?}",
+            dim
+        );
+        let n = dim.0;
+        let mut factor = CsrMatrix::new(
+            dim,
+            mat.indptr().to_vec(),
+            mat.indices().to_vec(),
+            mat.data().to_vec(),
+        );
+        factor.sum_duplicates();
+
+        let indptr = factor.indptr().to_vec();
+        let indices = factor.indices().to_vec();
+        let mut diag = Vec::with_capacity(n);
+        for row in 0..n {
+            match indices[indptr[row]..indptr[row + 1]].binary_search(&row) {
+                Ok(pos) => diag.push(indptr[row] + pos),
+                Err(_) => return Err(LinAlgError::SingularMatrix(row)),
+            }
+        }
+
+        // Position of the entries of the current row, or `usize::MAX` if not stored.
+        let mut position = vec![usize::MAX; n];
+        let data = factor.data_mut();
+        for row in 0..n {
+            for pos in indptr[row]..indptr[row + 1] {
+                position[indices[pos]] = pos;
+            }
+            for pos in indptr[row]..diag[row] {
+                let col = indices[pos];
+                let multiplier = data[pos] / data[diag[col]];
+                data[pos] = multiplier;
+                for upper in diag[col] + 1..indptr[col + 1] {
+                    let target = position[indices[upper]];
+                    if target != usize::MAX {
+                        data[target] -= multiplier * data[upper];
+                    }
+                }
+            }
+            if data[diag[row]] == Item::zero() {
+                return Err(LinAlgError::SingularMatrix(row));
+            }
+            for pos in indptr[row]..indptr[row + 1] {
+                position[indices[pos]] = usize::MAX;
+            }
+        }
+
+        Ok(Self { factor, diag })
+    }
+
+    /// Return the combined factors. The strict lower part holds the multipliers
+    /// of the unit lower triangular factor `L`.
+    pub fn factor(&self) -> &CsrMatrix<Item> {
+        &self.factor
+    }
+}
+
+impl<Item: HScalar> Preconditioner<Item> for Ilu0<Item> {
+    fn dimension(&self) -> IndexType {
+        self.diag.len()
+    }
+
+    fn apply_inverse(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        let n = self.dimension();
+        check_dimension(n, x, y);
+        let (indptr, indices, data) = (
+            self.factor.indptr(),
+            self.factor.indices(),
+            self.factor.data(),
+        );
+
+        unsafe {
+            for (row, &diag) in self.diag.iter().enumerate() {
+                let mut value = x.get1d_unchecked(row);
+                for pos in indptr[row]..diag {
+                    value -= data[pos] * y.get1d_unchecked(indices[pos]);
+                }
+                *y.get1d_unchecked_mut(row) = value;
+            }
+            for row in (0..n).rev() {
+                let mut value = y.get1d_unchecked(row);
+                for pos in self.diag[row] + 1..indptr[row + 1] {
+                    value -= data[pos] * y.get1d_unchecked(indices[pos]);
+                }
+                *y.get1d_unchecked_mut(row) = value / data[self.diag[row]];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::sparse::CooMatrix;
+
+    /// Return the 5-point finite difference discretisation of a convection-diffusion
+    /// operator on an `m x m` grid. The matrix is symmetric if `convection` is zero.
+    fn convection_diffusion_2d<Item: HScalar>(m: IndexType, convection: f64) -> CsrMatrix<Item> {
+        let n = m * m;
+        let cast = |value: f64| num::cast::<f64, Item>(value).unwrap();
+        let mut coo = CooMatrix::<Item>::new((n, n));
+        for i in 0..m {
+            for j in 0..m {
+                let row = i * m + j;
+                coo.push(row, row, cast(4.0 + (row % 7) as f64));
+                if j > 0 {
+                    coo.push(row, row - 1, cast(-1.0 - convection));
+                }
+                if j + 1 < m {
+                    coo.push(row, row + 1, cast(-1.0 + convection));
+                }
+                if i > 0 {
+                    coo.push(row, row - m, cast(-1.0));
+                }
+                if i + 1 < m {
+                    coo.push(row, row + m, cast(-1.0));
+                }
+            }
+        }
+        coo.to_csr()
+    }
+
+    fn ones<Item: HScalar>(n: IndexType) -> ColumnVectorD<Item> {
+        let mut vec = ColumnVectorD::<Item>::zeros_from_length(n);
+        vec.for_each(|elem| *elem = Item::one());
+        vec
+    }
+
+    /// Solve with GMRES and return the number of iterations after checking the residual.
+    fn gmres_iterations<Item: HScalar>(
+        op: &dyn LinearOperator<Item>,
+        prec: Option<&dyn Preconditioner<Item>>,
+    ) -> IndexType {
+        let n = op.dim().0;
+        let rhs = ones::<Item>(n);
+        let mut sol = ColumnVectorD::<Item>::zeros_from_length(n);
+        let tol = num::cast::<f64, RealType<Item>>(1E-10).unwrap();
+        let mut solver = Gmres::new(op).with_tol(tol).with_restart(n);
+        if let Some(prec) = prec {
+            solver = solver.with_preconditioner(prec);
+        }
+        let report = solver.solve(&rhs, &mut sol);
+        assert!(report.converged);
+        assert!(
+            norm2(&residual(op, &rhs, &sol))
+                < tol * num::cast::<f64, RealType<Item>>(100.0).unwrap()
+        );
+        report.iterations
+    }
+
+    #[test]
+    fn test_jacobi() {
+        let csr = convection_diffusion_2d::<f64>(5, 0.5);
+        let dense = csr.to_dense::<RowMajor>();
+        let from_csr = Jacobi::new(&csr).unwrap();
+        let from_dense = Jacobi::new(&dense).unwrap();
+
+        let x = ones::<f64>(25);
+        let mut y1 = ColumnVectorD::<f64>::zeros_from_length(25);
+        let mut y2 = ColumnVectorD::<f64>::zeros_from_length(25);
+        from_csr.apply_inverse(&x, &mut y1);
+        from_dense.apply_inverse(&x, &mut y2);
+        for index in 0..25 {
+            assert_eq!(y1.get1d(index), 1.0 / dense.get(index, index));
+            assert_eq!(y1.get1d(index), y2.get1d(index));
+        }
+
+        let singular = CsrMatrix::<f64>::from_triplets((2, 2), &[0], &[1], &[1.0]);
+        assert_eq!(
+            Jacobi::new(&singular).err(),
+            Some(LinAlgError::SingularMatrix(0))
+        );
+    }
+
+    #[test]
+    fn test_block_jacobi() {
+        let m = 6;
+        let csr = convection_diffusion_2d::<c64>(m, 0.5);
+        let dense = csr.to_dense::<ColumnMajor>();
+
+        // A single block is the exact inverse.
+        let exact = dense.block_jacobi(m * m).unwrap();
+        assert_eq!(exact.number_of_blocks(), 1);
+        assert_eq!(gmres_iterations(&csr, Some(&exact)), 1);
+
+        let prec = dense.block_jacobi(m).unwrap();
+        assert_eq!(prec.number_of_blocks(), m);
+        let uneven = dense.block_jacobi(5).unwrap();
+        assert_eq!(uneven.number_of_blocks(), 8);
+        let plain = gmres_iterations(&csr, None);
+        assert!(gmres_iterations(&csr, Some(&prec)) < plain);
+        assert!(gmres_iterations(&csr, Some(&uneven)) < plain);
+    }
+
+    #[test]
+    fn test_ssor() {
+        let csr = convection_diffusion_2d::<f64>(10, 0.0);
+        let ssor = Ssor::new(&csr, 1.2).unwrap();
+        let rhs = ones::<f64>(100);
+
+        let mut sol = ColumnVectorD::<f64>::zeros_from_length(100);
+        let plain = ConjugateGradient::new(&csr)
+            .with_tol(1E-10)
+            .solve(&rhs, &mut sol);
+        let mut sol = ColumnVectorD::<f64>::zeros_from_length(100);
+        let preconditioned = ConjugateGradient::new(&csr)
+            .with_preconditioner(&ssor)
+            .with_tol(1E-10)
+            .solve(&rhs, &mut sol);
+
+        assert!(plain.converged & preconditioned.converged);
+        assert!(preconditioned.iterations < plain.iterations);
+        assert!(norm2(&residual(&csr, &rhs, &sol)) < 1E-8);
+    }
+
+    macro_rules! ilu0_test {
+        ($HScalar:ty, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                // For a tridiagonal matrix ILU(0) is the exact LU decomposition.
+                let n = 20;
+                let mut coo = CooMatrix::<$HScalar>::new((n, n));
+                for index in 0..n {
+                    coo.push(index, index, num::cast::<f64, $HScalar>(3.0).unwrap());
+                    if index > 0 {
+                        coo.push(index, index - 1, num::cast::<f64, $HScalar>(-1.5).unwrap());
+                    }
+                    if index + 1 < n {
+                        coo.push(index, index + 1, num::cast::<f64, $HScalar>(-0.5).unwrap());
+                    }
+                }
+                let tridiagonal = coo.to_csr();
+                let ilu = Ilu0::new(&tridiagonal).unwrap();
+                assert_eq!(ilu.factor().nnz(), tridiagonal.nnz());
+                assert_eq!(gmres_iterations(&tridiagonal, Some(&ilu)), 1);
+
+                let csr = convection_diffusion_2d::<$HScalar>(10, 0.5);
+                let ilu = Ilu0::new(&csr).unwrap();
+                assert!(2 * gmres_iterations(&csr, Some(&ilu)) < gmres_iterations(&csr, None));
+            }
+        };
+    }
+
+    ilu0_test!(f64, test_ilu0_f64);
+    ilu0_test!(c64, test_ilu0_c64);
+
+    #[test]
+    fn test_ilu0_requires_diagonal() {
+        let csr = CsrMatrix::<f64>::from_triplets((2, 2), &[0, 1], &[1, 0], &[1.0, 1.0]);
+        assert_eq!(Ilu0::new(&csr).err(), Some(LinAlgError::SingularMatrix(0)));
+    }
+
+    #[test]
+    fn test_left_and_right_preconditioning() {
+        let csr = convection_diffusion_2d::<f64>(8, 0.5);
+        let ilu = Ilu0::new(&csr).unwrap();
+        let rhs = ones::<f64>(64);
+        for side in [Side::Left, Side::Right] {
+            let mut sol = ColumnVectorD::<f64>::zeros_from_length(64);
+            let report = BiCgStab::new(&csr)
+                .with_preconditioner(&ilu)
+                .with_preconditioner_side(side)
+                .with_tol(1E-10)
+                .solve(&rhs, &mut sol);
+            assert!(report.converged);
+            assert!(norm2(&residual(&csr, &rhs, &sol)) < 1E-8);
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//! Dense eigenvalue routines for the small projected matrices of the Krylov eigensolvers.
+//!
+//! The matrices are stored as column major slices, i.e. the element `(row, col)` of a
+//! matrix with leading dimension `ld` is stored at position `row + col * ld`. This is the
+//! storage of the Hessenberg matrices that the Arnoldi and Lanczos processes build up
+//! column by column. The QR steps use the Givens rotations `givens` and `rotate` that
+//! GMRES uses for its least squares problem.
+
+use super::{givens, rotate, RealType, Which};
+use crate::types::{HScalar, IndexType};
+use num::Float;
+
+/// Return the indices of `values` ordered according to `which`. The values are
+/// described by their magnitude and real part.
+pub(crate) fn order_by<Real: Float>(keys: &[(Real, Real)], which: Which) -> Vec<IndexType> {
+    let mut order: Vec<IndexType> = (0..keys.len()).collect();
+    let key = |index: IndexType| match which {
+        Which::LargestMagnitude => -keys[index].0,
+        Which::SmallestMagnitude => keys[index].0,
+        Which::LargestReal => -keys[index].1,
+        Which::SmallestReal => keys[index].1,
+    };
+    order.sort_by(|&first, &second| key(first).partial_cmp(&key(second)).unwrap());
+    order
+}
+
+/// Apply one implicitly shifted QR step to the rows and columns `lo..=hi` of the
+/// upper Hessenberg matrix `h` with leading dimension `ld` and `cols` columns.
+///
+/// The rotations are applied from the left to the columns `lo..cols` and from the right
+/// to the rows `0..row_end` (restricted to the Hessenberg structure). They are also
+/// accumulated into the columns of `z`, which has leading dimension `ldz`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn qr_step<C: HScalar>(
+    h: &mut [C],
+    ld: IndexType,
+    cols: IndexType,
+    lo: IndexType,
+    hi: IndexType,
+    shift: C,
+    z: &mut [C],
+    ldz: IndexType,
+    row_end: IndexType,
+) {
+    for k in lo..hi {
+        let (c, s) = if k == lo {
+            givens(h[lo + lo * ld] - shift, h[lo + 1 + lo * ld])
+        } else {
+            givens(h[k + (k - 1) * ld], h[k + 1 + (k - 1) * ld])
+        };
+
+        // Apply the rotation from the left to the rows k and k + 1.
+        let first_col = if k == lo { lo } else { k - 1 };
+        for col in first_col..cols {
+            (h[k + col * ld], h[k + 1 + col * ld]) =
+                rotate(c, s, h[k + col * ld], h[k + 1 + col * ld]);
+        }
+        if k > lo {
+            h[k + 1 + (k - 1) * ld] = C::zero();
+        }
+
+        // Apply the conjugate transposed rotation from the right to the columns k and k + 1.
+        for row in 0..row_end.min(k + 3) {
+            (h[row + k * ld], h[row + (k + 1) * ld]) =
+                rotate(c, s.conj(), h[row + k * ld], h[row + (k + 1) * ld]);
+        }
+        for row in 0..z.len() / ldz {
+            (z[row + k * ldz], z[row + (k + 1) * ldz]) =
+                rotate(c, s.conj(), z[row + k * ldz], z[row + (k + 1) * ldz]);
+        }
+    }
+}
+
+/// Return the eigenvalue of the `2 x 2` matrix `[a, b; c, d]` that is closer to `d`.
+fn wilkinson_shift<C: HScalar>(a: C, b: C, c: C, d: C) -> C {
+    let two = C::from_real(num::cast::<f64, RealType<C>>(2.0).unwrap());
+    let mean = (a + d) / two;
+    let disc = (((a - d) / two) * ((a - d) / two) + b * c).sqrt();
+    let (first, second) = (mean + disc, mean - disc);
+    if (first - d).abs() < (second - d).abs() {
+        first
+    } else {
+        second
+    }
+}
+
+/// Compute the eigenvalues and normalised eigenvectors of the `m x m` upper Hessenberg
+/// matrix `h` with the shifted QR algorithm. The eigenvectors are returned as the
+/// columns of an `m x m` matrix.
+pub(crate) fn hessenberg_eigen<C: HScalar>(m: IndexType, h: &[C]) -> (Vec<C>, Vec<C>) {
+    let eps = <RealType<C> as Float>::epsilon();
+    let mut t = h.to_vec();
+    let mut z = vec![C::zero(); m * m];
+    for index in 0..m {
+        z[index + index * m] = C::one();
+    }
+
+    // Reduce to upper triangular Schur form.
+    let mut hi = m.saturating_sub(1);
+    let mut iterations = 0;
+    while hi > 0 {
+        let mut lo = hi;
+        while lo > 0 {
+            let sub = t[lo + (lo - 1) * m].abs();
+            if sub <= eps * (t[lo - 1 + (lo - 1) * m].abs() + t[lo + lo * m].abs()) {
+                t[lo + (lo - 1) * m] = C::zero();
+                break;
+            }
+            lo -= 1;
+        }
+        if lo == hi {
+            hi -= 1;
+            iterations = 0;
+            continue;
+        }
+
+        iterations += 1;
+        if iterations > 100 {
+            // Give up on this eigenvalue. The Ritz estimates will reflect the inaccuracy.
+            hi -= 1;
+            iterations = 0;
+            continue;
+        }
+        let shift = if iterations % 10 == 0 {
+            // An exceptional shift to break cycles.
+            t[hi + hi * m] + C::from_real(t[hi + (hi - 1) * m].abs())
+        } else {
+            wilkinson_shift(
+                t[hi - 1 + (hi - 1) * m],
+                t[hi - 1 + hi * m],
+                t[hi + (hi - 1) * m],
+                t[hi + hi * m],
+            )
+        };
+        qr_step(&mut t, m, m, lo, hi, shift, &mut z, m, hi + 1);
+    }
+
+    let values: Vec<C> = (0..m).map(|index| t[index + index * m]).collect();
+
+    // Eigenvectors of the triangular factor by back substitution, then transform with z.
+    let norm = t
+        .iter()
+        .fold(<RealType<C> as num::Zero>::zero(), |acc, value| {
+            acc.max(value.abs())
+        });
+    let small = C::from_real(eps * norm.max(<RealType<C> as num::One>::one()));
+    let mut vectors = vec![C::zero(); m * m];
+    let mut x = vec![C::zero(); m];
+    for col in 0..m {
+        x.iter_mut().for_each(|elem| *elem = C::zero());
+        x[col] = C::one();
+        for row in (0..col).rev() {
+            let mut value = C::zero();
+            for index in row + 1..=col {
+                value += t[row + index * m] * x[index];
+            }
+            let mut denom = t[row + row * m] - values[col];
+            if denom.abs() < small.abs() {
+                denom = small;
+            }
+            x[row] = -value / denom;
+        }
+        let mut norm = <RealType<C> as num::Zero>::zero();
+        for row in 0..m {
+            let mut value = C::zero();
+            for index in 0..=col {
+                value += z[row + index * m] * x[index];
+            }
+            vectors[row + col * m] = value;
+            norm += value.square();
+        }
+        let norm = C::from_real(Float::sqrt(norm));
+        for row in 0..m {
+            vectors[row + col * m] /= norm;
+        }
+    }
+
+    (values, vectors)
+}
+
+/// Compute the eigenvalues and orthonormal eigenvectors of the real symmetric `m x m`
+/// matrix `a` with the cyclic Jacobi method. The eigenvectors are returned as the
+/// columns of an `m x m` matrix.
+pub(crate) fn symmetric_eigen<Real: Float>(m: IndexType, a: &[Real]) -> (Vec<Real>, Vec<Real>) {
+    let mut a = a.to_vec();
+    let mut vectors = vec![Real::zero(); m * m];
+    for index in 0..m {
+        vectors[index + index * m] = Real::one();
+    }
+
+    let frobenius = |a: &[Real], off: bool| {
+        let mut sum = Real::zero();
+        for col in 0..m {
+            for row in 0..m {
+                if !off || (row != col) {
+                    sum = sum + a[row + col * m] * a[row + col * m];
+                }
+            }
+        }
+        sum.sqrt()
+    };
+    let tol = Real::epsilon() * frobenius(&a, false);
+
+    for _ in 0..100 {
+        if frobenius(&a, true) <= tol {
+            break;
+        }
+        for p in 0..m {
+            for q in p + 1..m {
+                let apq = a[p + q * m];
+                if apq == Real::zero() {
+                    continue;
+                }
+                let two = Real::one() + Real::one();
+                let theta = (a[q + q * m] - a[p + p * m]) / (two * apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + Real::one()).sqrt());
+                let c = Real::one() / (t * t + Real::one()).sqrt();
+                let s = t * c;
+                for k in 0..m {
+                    let akp = a[k + p * m];
+                    let akq = a[k + q * m];
+                    a[k + p * m] = c * akp - s * akq;
+                    a[k + q * m] = s * akp + c * akq;
+                }
+                for k in 0..m {
+                    let apk = a[p + k * m];
+                    let aqk = a[q + k * m];
+                    a[p + k * m] = c * apk - s * aqk;
+                    a[q + k * m] = s * apk + c * aqk;
+                }
+                for k in 0..m {
+                    let vkp = vectors[k + p * m];
+                    let vkq = vectors[k + q * m];
+                    vectors[k + p * m] = c * vkp - s * vkq;
+                    vectors[k + q * m] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    let values = (0..m).map(|index| a[index + index * m]).collect();
+    (values, vectors)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::types::c64;
+
+    #[test]
+    fn test_hessenberg_eigen() {
+        // A real matrix with the eigenvalues 1 +- 2i and 3.
+        let m = 3;
+        let h: Vec<c64> = [1.0, -2.0, 0.0, 2.0, 1.0, 0.0, 4.0, 1.0, 3.0]
+            .iter()
+            .map(|&value| c64::new(value, 0.0))
+            .collect();
+        let (values, vectors) = hessenberg_eigen(m, &h);
+        for col in 0..m {
+            assert!(
+                [c64::new(1.0, 2.0), c64::new(1.0, -2.0), c64::new(3.0, 0.0)]
+                    .iter()
+                    .any(|expect| (values[col] - expect).norm() < 1E-12)
+            );
+            for row in 0..m {
+                let mut value = -values[col] * vectors[row + col * m];
+                for index in 0..m {
+                    value += h[row + index * m] * vectors[index + col * m];
+                }
+                assert!(value.norm() < 1E-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_symmetric_eigen() {
+        let m = 4;
+        let mut a = vec![0.0; m * m];
+        for index in 0..m {
+            a[index + index * m] = 2.0;
+            if index + 1 < m {
+                a[index + 1 + index * m] = -1.0;
+                a[index + (index + 1) * m] = -1.0;
+            }
+        }
+        let (values, vectors) = symmetric_eigen(m, &a);
+        let order = order_by(
+            &values
+                .iter()
+                .map(|&v: &f64| (v.abs(), v))
+                .collect::<Vec<_>>(),
+            Which::SmallestReal,
+        );
+        for (k, &index) in order.iter().enumerate() {
+            let expect = 2.0 - 2.0 * (((k + 1) as f64) * std::f64::consts::PI / 5.0).cos();
+            assert!((values[index] - expect).abs() < 1E-13);
+        }
+        for col in 0..m {
+            for other in 0..m {
+                let dot: f64 = (0..m)
+                    .map(|row| vectors[row + col * m] * vectors[row + other * m])
+                    .sum();
+                let expect = if col == other { 1.0 } else { 0.0 };
+                assert!((dot - expect).abs() < 1E-13);
+            }
+        }
+    }
+}
@@ -8,18 +8,30 @@ pub mod arbitrary_stride_column_major;
 pub mod arbitrary_stride_column_vector;
 pub mod arbitrary_stride_row_major;
 pub mod arbitrary_stride_row_vector;
+pub mod banded;
 pub mod column_major;
 pub mod column_vector;
+pub mod diagonal;
+pub mod hermitian_packed;
+pub mod lower_triangular;
 pub mod row_major;
 pub mod row_vector;
+pub mod symmetric_packed;
+pub mod tridiagonal;
 pub mod upper_triangular;
 
 pub use arbitrary_stride_column_major::*;
 pub use arbitrary_stride_column_vector::*;
 pub use arbitrary_stride_row_major::*;
 pub use arbitrary_stride_row_vector::*;
+pub use banded::*;
 pub use column_major::*;
 pub use column_vector::*;
+pub use diagonal::*;
+pub use hermitian_packed::*;
+pub use lower_triangular::*;
 pub use row_major::*;
 pub use row_vector::*;
+pub use symmetric_packed::*;
+pub use tridiagonal::*;
 pub use upper_triangular::*;
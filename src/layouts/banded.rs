@@ -0,0 +1,249 @@
+//! Banded layout.
+//!
+//! This layout describes a matrix with `kl` subdiagonals and `ku` superdiagonals in
+//! the band storage format of Lapack. The band is stored column by column in a
+//! column major array with `kl + ku + 1` rows. The element `(row, col)` of the matrix is
+//! stored in row `ku + row - col` of column `col` of this array. Hence, the diagonal is
+//! stored in row `ku`, the superdiagonals above and the subdiagonals below it. Elements
+//! outside the band are not stored and cannot be accessed.
+//!
+//! The positions in the top left and bottom right corners of the band storage array do
+//! not correspond to matrix elements. The one-dimensional index of this layout only
+//! enumerates the elements of the band that lie within the matrix, column by column and
+//! from top to bottom. A 1d traversal therefore visits every element of the band exactly
+//! once and never touches the padding. The [raw size](LayoutType::raw_size) of the
+//! layout is the size `ldab * cols` of the whole band storage array.
+//!
+//! A layout created from the dimension alone has the full band `kl = rows - 1` and
+//! `ku = cols - 1`.
+
+use crate::traits::*;
+use crate::types::IndexType;
+use std::ops::Range;
+
+/// A type that describes a banded matrix with `kl` subdiagonals and `ku` superdiagonals.
+pub struct Banded {
+    dim: (IndexType, IndexType),
+    kl: IndexType,
+    ku: IndexType,
+}
+
+impl Banded {
+    /// Create a new banded layout for a matrix of dimension `dim` with `kl`
+    /// subdiagonals and `ku` superdiagonals.
+    pub fn new(dim: (IndexType, IndexType), kl: IndexType, ku: IndexType) -> Self {
+        Self { dim, kl, ku }
+    }
+
+    /// Return the number of subdiagonals.
+    #[inline]
+    pub fn kl(&self) -> IndexType {
+        self.kl
+    }
+
+    /// Return the number of superdiagonals.
+    #[inline]
+    pub fn ku(&self) -> IndexType {
+        self.ku
+    }
+
+    /// Return the leading dimension `kl + ku + 1` of the band storage array.
+    #[inline]
+    pub fn ldab(&self) -> IndexType {
+        self.kl + self.ku + 1
+    }
+
+    /// Return true if the element `(row, col)` lies within the band.
+    #[inline]
+    pub fn in_band(&self, row: IndexType, col: IndexType) -> bool {
+        (col <= row + self.ku) & (row <= col + self.kl)
+    }
+
+    /// Return the rows of column `col` that lie within the band and the matrix.
+    #[inline]
+    fn band_rows(&self, col: IndexType) -> Range<IndexType> {
+        let start = col.saturating_sub(self.ku);
+        let end = self.dim.0.min(col + self.kl + 1);
+        start..end.max(start)
+    }
+
+    /// Return the number of band elements in the columns `0..col`.
+    #[inline]
+    fn column_offset(&self, col: IndexType) -> IndexType {
+        // Columns beyond `rows + ku` contain no band elements.
+        let col = col.min(self.dim.0 + self.ku);
+        // Sum of the ends `min(rows, c + kl + 1)` of the band rows of the columns.
+        let short = col.min(self.dim.0.saturating_sub(self.kl));
+        let ends = short * (self.kl + 1)
+            + short * short.saturating_sub(1) / 2
+            + (col - short) * self.dim.0;
+        // Sum of the starts `max(0, c - ku)` of the band rows of the columns.
+        let shifted = col.saturating_sub(self.ku + 1);
+        ends - shifted * (shifted + 1) / 2
+    }
+}
+
+impl LayoutType for Banded {
+    type IndexLayout = Self;
+
+    #[inline]
+    fn is_stored(&self, row: IndexType, col: IndexType) -> bool {
+        self.in_band(row, col)
+    }
+
+    #[inline]
+    fn convert_1d_2d(&self, index: IndexType) -> (IndexType, IndexType) {
+        // Find the column whose band elements contain the index.
+        let (mut first, mut last) = (0, self.dim.1);
+        while first < last {
+            let mid = (first + last) / 2;
+            if self.column_offset(mid + 1) <= index {
+                first = mid + 1;
+            } else {
+                last = mid;
+            }
+        }
+        let col = first;
+        (
+            self.band_rows(col).start + index - self.column_offset(col),
+            col,
+        )
+    }
+
+    #[inline]
+    fn convert_1d_raw(&self, index: IndexType) -> IndexType {
+        let (row, col) = self.convert_1d_2d(index);
+        self.convert_2d_raw(row, col)
+    }
+
+    #[inline]
+    fn convert_2d_1d(&self, row: IndexType, col: IndexType) -> IndexType {
+        assert!(
+            self.in_band(row, col),
+            "Element outside of band with kl={}, ku={}: row={}, col={}",
+            self.kl,
+            self.ku,
+            row,
+            col
+        );
+        self.column_offset(col) + row - self.band_rows(col).start
+    }
+
+    #[inline]
+    fn convert_2d_raw(&self, row: IndexType, col: IndexType) -> IndexType {
+        assert!(
+            self.in_band(row, col),
+            "Element outside of band with kl={}, ku={}: row={}, col={}",
+            self.kl,
+            self.ku,
+            row,
+            col
+        );
+        self.ku + row - col + col * self.ldab()
+    }
+
+    #[inline]
+    fn dim(&self) -> (IndexType, IndexType) {
+        self.dim
+    }
+
+    #[inline]
+    fn index_layout(&self) -> Self::IndexLayout {
+        Self::IndexLayout::new(self.dim, self.kl, self.ku)
+    }
+
+    #[inline]
+    fn number_of_elements(&self) -> IndexType {
+        self.column_offset(self.dim.1)
+    }
+
+    #[inline]
+    fn raw_size(&self) -> IndexType {
+        self.ldab() * self.dim.1
+    }
+
+    #[inline]
+    fn stride(&self) -> (IndexType, IndexType) {
+        std::unimplemented!("method 'stride' not implemented for Banded layout.")
+    }
+}
+
+impl BaseLayoutType for Banded {
+    fn from_dimension(dim: (IndexType, IndexType)) -> Self {
+        Self::new(dim, dim.0.saturating_sub(1), dim.1.saturating_sub(1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_banded_indexing() {
+        let layout = Banded::new((5, 5), 1, 2);
+
+        assert_eq!(layout.ldab(), 4);
+        assert_eq!(layout.raw_size(), 20);
+        assert_eq!(layout.number_of_elements(), 5 + 4 + 4 + 3);
+
+        // The 1d index enumerates the band column by column.
+        assert_eq!(layout.convert_2d_1d(0, 0), 0);
+        assert_eq!(layout.convert_2d_1d(1, 0), 1);
+        assert_eq!(layout.convert_2d_1d(0, 2), 5);
+        assert_eq!(layout.convert_1d_2d(5), (0, 2));
+
+        // Diagonal element (0, 0) is in row 'ku' of the first column.
+        assert_eq!(layout.convert_2d_raw(0, 0), 2);
+        assert_eq!(layout.convert_2d_raw(1, 0), 3);
+        assert_eq!(layout.convert_2d_raw(0, 2), 8);
+        assert_eq!(layout.convert_1d_raw(5), 8);
+
+        let full = Banded::from_dimension((3, 4));
+        assert_eq!((full.kl(), full.ku()), (2, 3));
+        assert_eq!(full.number_of_elements(), 12);
+
+        assert!(layout.in_band(3, 2));
+        assert!(!layout.in_band(3, 1));
+        assert!(!layout.in_band(0, 3));
+    }
+
+    #[test]
+    fn test_banded_1d_sweep() {
+        for (dim, kl, ku) in [
+            ((5, 5), 1, 2),
+            ((6, 3), 2, 0),
+            ((3, 7), 0, 1),
+            ((4, 9), 1, 1),
+            ((8, 2), 3, 4),
+            ((0, 3), 1, 1),
+        ] {
+            let layout = Banded::new(dim, kl, ku);
+            let mut visited = vec![0; layout.raw_size()];
+            for index in 0..layout.number_of_elements() {
+                let (row, col) = layout.convert_1d_2d(index);
+                assert!((row < dim.0) & (col < dim.1) & layout.in_band(row, col));
+                assert_eq!(layout.convert_2d_1d(row, col), index);
+                assert_eq!(
+                    layout.convert_1d_raw(index),
+                    layout.convert_2d_raw(row, col)
+                );
+                visited[layout.convert_1d_raw(index)] += 1;
+            }
+
+            // Every band element within the matrix is visited exactly once.
+            for col in 0..dim.1 {
+                for row in 0..dim.0 {
+                    if layout.in_band(row, col) {
+                        assert_eq!(visited[layout.convert_2d_raw(row, col)], 1);
+                    }
+                }
+            }
+            let band = (0..dim.0)
+                .flat_map(|row| (0..dim.1).map(move |col| (row, col)))
+                .filter(|&(row, col)| layout.in_band(row, col))
+                .count();
+            assert_eq!(visited.iter().sum::<usize>(), band);
+        }
+    }
+}
@@ -22,7 +22,7 @@ impl LayoutType for ColumnVector {
 
     #[inline]
     fn convert_1d_2d(&self, index: IndexType) -> (IndexType, IndexType) {
-        (index, 1)
+        (index, 0)
     }
 
     #[inline]
@@ -64,7 +64,7 @@ impl LayoutType for ColumnVector {
 impl BaseLayoutType for ColumnVector {
     fn from_dimension(dim: (IndexType, IndexType)) -> Self {
         assert_eq!(
-            dim.0, 1,
+            dim.1, 1,
             "Number of columns is {} but must be one for ColumnVector.",
             dim.1
         );
@@ -79,3 +79,36 @@ impl VectorBaseLayoutType for ColumnVector {
 }
 
 impl StridedLayoutType for ColumnVector {}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::matrix::*;
+
+    #[test]
+    fn test_column_vector_indexing() {
+        let layout = ColumnVector::from_dimension((4, 1));
+
+        assert_eq!(layout.dim(), (4, 1));
+        assert_eq!(layout.convert_1d_2d(0), (0, 0));
+        assert_eq!(layout.convert_1d_2d(3), (3, 0));
+        assert_eq!(layout.convert_2d_1d(3, 0), 3);
+
+        // The 2d index of every element addresses the element itself.
+        let mut vec = ColumnVectorD::<f64>::zeros_from_length(4);
+        for index in 0..4 {
+            *vec.get1d_mut(index) = index as f64;
+        }
+        for index in 0..4 {
+            let (row, col) = vec.layout().convert_1d_2d(index);
+            assert_eq!(vec.get(row, col), index as f64);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_column_vector_from_row_dimension() {
+        ColumnVector::from_dimension((1, 4));
+    }
+}
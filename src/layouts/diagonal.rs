@@ -0,0 +1,122 @@
+//! Diagonal layout.
+//!
+//! This layout describes a square diagonal matrix of which only the
+//! diagonal elements are stored consecutively in memory. The off-diagonal
+//! elements are not stored at all. Implementations backed by this layout
+//! (see [DiagonalMatrix](crate::diagonal_matrix::DiagonalMatrix)) return zero
+//! for off-diagonal reads.
+//!
+//! The logical indexing is inherited from the base layout `B`. A 1d traversal of a
+//! diagonal matrix therefore visits all `dim * dim` logical elements in the same order as
+//! a dense matrix with layout `B`. This makes it possible to combine diagonal and dense
+//! matrices in expressions such as additions.
+
+use crate::layouts::RowMajor;
+use crate::traits::*;
+use crate::types::IndexType;
+
+/// A type that describes a square diagonal matrix with logical indexing `B`.
+pub struct Diagonal<B: BaseLayoutType = RowMajor> {
+    dim: IndexType,
+    index_layout: B,
+}
+
+impl<B: BaseLayoutType> Diagonal<B> {
+    pub fn new(dim: IndexType) -> Self {
+        Self {
+            dim,
+            index_layout: B::from_dimension((dim, dim)),
+        }
+    }
+}
+
+impl<B: BaseLayoutType> LayoutType for Diagonal<B> {
+    type IndexLayout = B;
+
+    #[inline]
+    fn is_stored(&self, row: IndexType, col: IndexType) -> bool {
+        row == col
+    }
+
+    #[inline]
+    fn convert_1d_2d(&self, index: IndexType) -> (IndexType, IndexType) {
+        self.index_layout.convert_1d_2d(index)
+    }
+
+    #[inline]
+    fn convert_1d_raw(&self, index: IndexType) -> IndexType {
+        let (row, col) = self.convert_1d_2d(index);
+        self.convert_2d_raw(row, col)
+    }
+
+    #[inline]
+    fn convert_2d_1d(&self, row: IndexType, col: IndexType) -> IndexType {
+        self.index_layout.convert_2d_1d(row, col)
+    }
+
+    #[inline]
+    fn convert_2d_raw(&self, row: IndexType, col: IndexType) -> IndexType {
+        assert!(
+            self.is_stored(row, col),
+            "Off-diagonal element is not stored: row={}, col={}",
+            row,
+            col
+        );
+        row
+    }
+
+    #[inline]
+    fn dim(&self) -> (IndexType, IndexType) {
+        (self.dim, self.dim)
+    }
+
+    #[inline]
+    fn index_layout(&self) -> Self::IndexLayout {
+        B::from_dimension((self.dim, self.dim))
+    }
+
+    #[inline]
+    fn number_of_elements(&self) -> IndexType {
+        self.dim * self.dim
+    }
+
+    #[inline]
+    fn raw_size(&self) -> IndexType {
+        self.dim
+    }
+
+    #[inline]
+    fn stride(&self) -> (IndexType, IndexType) {
+        std::unimplemented!("method 'stride' not implemented for Diagonal layout.")
+    }
+}
+
+impl<B: BaseLayoutType> BaseLayoutType for Diagonal<B> {
+    fn from_dimension(dim: (IndexType, IndexType)) -> Self {
+        assert_eq!(
+            dim.0, dim.1,
+            "Only square diagonal matrices are supported. dim = {:#?}",
+            dim
+        );
+        Self::new(dim.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::ColumnMajor;
+
+    #[test]
+    fn test_diagonal_indexing() {
+        let layout = Diagonal::<ColumnMajor>::new(3);
+
+        assert_eq!(layout.number_of_elements(), 9);
+        assert_eq!(layout.raw_size(), 3);
+        assert_eq!(layout.convert_1d_2d(1), (1, 0));
+        assert_eq!(layout.convert_2d_1d(0, 2), 6);
+        assert_eq!(layout.convert_2d_raw(2, 2), 2);
+        assert_eq!(layout.convert_1d_raw(4), 1);
+    }
+}
@@ -0,0 +1,88 @@
+//! Hermitian packed layout.
+//!
+//! This layout stores only the lower triangular part of a Hermitian matrix
+//! in the same order as the [LowerTriangular](crate::layouts::LowerTriangular)
+//! layout. Accessing an element `(row, col)` in the strict upper triangular part
+//! returns the complex conjugate of the stored element `(col, row)`. The upper
+//! triangular part can only be read. Mutable access has to go through the
+//! stored lower triangular part.
+
+use crate::traits::*;
+use crate::types::IndexType;
+
+use super::lower_triangular::{lower_packed_1d_2d, lower_packed_2d_1d};
+
+/// A type that describes Hermitian matrices in packed storage.
+pub struct HermitianPacked {
+    dim: IndexType,
+}
+
+impl HermitianPacked {
+    pub fn new(dim: IndexType) -> Self {
+        Self { dim }
+    }
+}
+
+impl LayoutType for HermitianPacked {
+    type IndexLayout = Self;
+
+    #[inline]
+    fn convert_1d_2d(&self, index: IndexType) -> (IndexType, IndexType) {
+        lower_packed_1d_2d(index)
+    }
+
+    #[inline]
+    fn convert_1d_raw(&self, index: IndexType) -> IndexType {
+        index
+    }
+
+    #[inline]
+    fn convert_2d_1d(&self, row: IndexType, col: IndexType) -> IndexType {
+        if col <= row {
+            lower_packed_2d_1d(row, col)
+        } else {
+            lower_packed_2d_1d(col, row)
+        }
+    }
+
+    #[inline]
+    fn convert_2d_raw(&self, row: IndexType, col: IndexType) -> IndexType {
+        self.convert_1d_raw(self.convert_2d_1d(row, col))
+    }
+
+    #[inline]
+    fn dim(&self) -> (IndexType, IndexType) {
+        (self.dim, self.dim)
+    }
+
+    #[inline]
+    fn index_layout(&self) -> Self::IndexLayout {
+        Self::IndexLayout::new(self.dim)
+    }
+
+    #[inline]
+    fn number_of_elements(&self) -> IndexType {
+        (self.dim * (self.dim + 1)) / 2
+    }
+
+    #[inline]
+    fn stride(&self) -> (IndexType, IndexType) {
+        std::unimplemented!("method 'stride' not implemented for HermitianPacked layout.")
+    }
+
+    #[inline]
+    fn conjugate_2d(&self, row: IndexType, col: IndexType) -> bool {
+        col > row
+    }
+}
+
+impl BaseLayoutType for HermitianPacked {
+    fn from_dimension(dim: (IndexType, IndexType)) -> Self {
+        assert_eq!(
+            dim.0, dim.1,
+            "Only square Hermitian matrices are supported. dim = {:#?}",
+            dim
+        );
+        Self { dim: dim.0 }
+    }
+}
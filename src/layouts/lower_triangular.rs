@@ -0,0 +1,135 @@
+//! Lower triangular layout.
+//!
+//! This is a special layout for lower triangular matrices
+//! whose elements are stored row by row consecutively in memory
+//! without storing the zero upper triangular elements. Row `r`
+//! starts at position `r * (r + 1) / 2`.
+
+use crate::traits::*;
+use crate::types::IndexType;
+
+/// Convert a `(row, col)` index with `col <= row` into the position in
+/// row-wise packed lower triangular storage.
+#[inline]
+pub(crate) fn lower_packed_2d_1d(row: IndexType, col: IndexType) -> IndexType {
+    (row * (row + 1)) / 2 + col
+}
+
+/// Convert a position in row-wise packed lower triangular storage into
+/// the corresponding `(row, col)` index.
+#[inline]
+pub(crate) fn lower_packed_1d_2d(index: IndexType) -> (IndexType, IndexType) {
+    let mut row = ((f64::sqrt(8.0 * (index as f64) + 1.0) - 1.0) / 2.0) as IndexType;
+    // Correct possible rounding errors of the floating point estimate.
+    while (row * (row + 1)) / 2 > index {
+        row -= 1;
+    }
+    while ((row + 1) * (row + 2)) / 2 <= index {
+        row += 1;
+    }
+    (row, index - (row * (row + 1)) / 2)
+}
+
+/// A type that describes lower triangular matrices.
+pub struct LowerTriangular {
+    dim: IndexType,
+}
+
+impl LowerTriangular {
+    pub fn new(dim: IndexType) -> Self {
+        Self { dim }
+    }
+}
+
+impl LayoutType for LowerTriangular {
+    type IndexLayout = Self;
+
+    #[inline]
+    fn is_stored(&self, row: IndexType, col: IndexType) -> bool {
+        col <= row
+    }
+
+    #[inline]
+    fn convert_1d_2d(&self, index: IndexType) -> (IndexType, IndexType) {
+        lower_packed_1d_2d(index)
+    }
+
+    #[inline]
+    fn convert_1d_raw(&self, index: IndexType) -> IndexType {
+        index
+    }
+
+    #[inline]
+    fn convert_2d_1d(&self, row: IndexType, col: IndexType) -> IndexType {
+        assert!(
+            col <= row,
+            "For lower triangular require 'col' <= 'row': row={}, col={}",
+            row,
+            col
+        );
+        lower_packed_2d_1d(row, col)
+    }
+
+    #[inline]
+    fn convert_2d_raw(&self, row: IndexType, col: IndexType) -> IndexType {
+        self.convert_1d_raw(self.convert_2d_1d(row, col))
+    }
+
+    #[inline]
+    fn dim(&self) -> (IndexType, IndexType) {
+        (self.dim, self.dim)
+    }
+
+    #[inline]
+    fn index_layout(&self) -> Self::IndexLayout {
+        Self::IndexLayout::new(self.dim)
+    }
+
+    #[inline]
+    fn number_of_elements(&self) -> IndexType {
+        (self.dim * (self.dim + 1)) / 2
+    }
+
+    #[inline]
+    fn stride(&self) -> (IndexType, IndexType) {
+        std::unimplemented!("method 'stride' not implemented for LowerTriangular layout.")
+    }
+}
+
+impl BaseLayoutType for LowerTriangular {
+    fn from_dimension(dim: (IndexType, IndexType)) -> Self {
+        assert_eq!(
+            dim.0, dim.1,
+            "Only square triangular matrices are supported. dim = {:#?}",
+            dim
+        );
+        Self { dim: dim.0 }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_lower_triangular_indexing() {
+        let n = 5;
+
+        let layout = LowerTriangular::new(n);
+
+        assert_eq!(layout.convert_2d_1d(0, 0), 0);
+        assert_eq!(layout.convert_2d_1d(1, 0), 1);
+        assert_eq!(layout.convert_2d_1d(2, 1), 4);
+        assert_eq!(
+            layout.convert_2d_1d(n - 1, n - 1),
+            layout.number_of_elements() - 1
+        );
+
+        for index in 0..layout.number_of_elements() {
+            let (row, col) = layout.convert_1d_2d(index);
+            assert!(col <= row);
+            assert_eq!(layout.convert_2d_1d(row, col), index);
+        }
+    }
+}
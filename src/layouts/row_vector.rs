@@ -22,7 +22,7 @@ impl LayoutType for RowVector {
 
     #[inline]
     fn convert_1d_2d(&self, index: IndexType) -> (IndexType, IndexType) {
-        (1, index)
+        (0, index)
     }
 
     #[inline]
@@ -79,3 +79,36 @@ impl VectorBaseLayoutType for RowVector {
 }
 
 impl StridedLayoutType for RowVector {}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::matrix::*;
+
+    #[test]
+    fn test_row_vector_indexing() {
+        let layout = RowVector::from_dimension((1, 4));
+
+        assert_eq!(layout.dim(), (1, 4));
+        assert_eq!(layout.convert_1d_2d(0), (0, 0));
+        assert_eq!(layout.convert_1d_2d(3), (0, 3));
+        assert_eq!(layout.convert_2d_1d(0, 3), 3);
+
+        // The 2d index of every element addresses the element itself.
+        let mut vec = RowVectorD::<f64>::zeros_from_length(4);
+        for index in 0..4 {
+            *vec.get1d_mut(index) = index as f64;
+        }
+        for index in 0..4 {
+            let (row, col) = vec.layout().convert_1d_2d(index);
+            assert_eq!(vec.get(row, col), index as f64);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_row_vector_from_column_dimension() {
+        RowVector::from_dimension((4, 1));
+    }
+}
@@ -0,0 +1,104 @@
+//! Symmetric packed layout.
+//!
+//! This layout stores only the lower triangular part of a symmetric matrix
+//! in the same order as the [LowerTriangular](crate::layouts::LowerTriangular)
+//! layout. Accessing an element `(row, col)` in the strict upper triangular part
+//! maps to the stored element `(col, row)`. Hence, a matrix with this layout
+//! reads like a full symmetric matrix while requiring only half the memory.
+
+use crate::traits::*;
+use crate::types::IndexType;
+
+use super::lower_triangular::{lower_packed_1d_2d, lower_packed_2d_1d};
+
+/// A type that describes symmetric matrices in packed storage.
+pub struct SymmetricPacked {
+    dim: IndexType,
+}
+
+impl SymmetricPacked {
+    pub fn new(dim: IndexType) -> Self {
+        Self { dim }
+    }
+}
+
+impl LayoutType for SymmetricPacked {
+    type IndexLayout = Self;
+
+    #[inline]
+    fn convert_1d_2d(&self, index: IndexType) -> (IndexType, IndexType) {
+        lower_packed_1d_2d(index)
+    }
+
+    #[inline]
+    fn convert_1d_raw(&self, index: IndexType) -> IndexType {
+        index
+    }
+
+    #[inline]
+    fn convert_2d_1d(&self, row: IndexType, col: IndexType) -> IndexType {
+        if col <= row {
+            lower_packed_2d_1d(row, col)
+        } else {
+            lower_packed_2d_1d(col, row)
+        }
+    }
+
+    #[inline]
+    fn convert_2d_raw(&self, row: IndexType, col: IndexType) -> IndexType {
+        self.convert_1d_raw(self.convert_2d_1d(row, col))
+    }
+
+    #[inline]
+    fn dim(&self) -> (IndexType, IndexType) {
+        (self.dim, self.dim)
+    }
+
+    #[inline]
+    fn index_layout(&self) -> Self::IndexLayout {
+        Self::IndexLayout::new(self.dim)
+    }
+
+    #[inline]
+    fn number_of_elements(&self) -> IndexType {
+        (self.dim * (self.dim + 1)) / 2
+    }
+
+    #[inline]
+    fn stride(&self) -> (IndexType, IndexType) {
+        std::unimplemented!("method 'stride' not implemented for SymmetricPacked layout.")
+    }
+}
+
+impl BaseLayoutType for SymmetricPacked {
+    fn from_dimension(dim: (IndexType, IndexType)) -> Self {
+        assert_eq!(
+            dim.0, dim.1,
+            "Only square symmetric matrices are supported. dim = {:#?}",
+            dim
+        );
+        Self { dim: dim.0 }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_symmetric_packed_indexing() {
+        let n = 4;
+        let layout = SymmetricPacked::new(n);
+
+        assert_eq!(layout.number_of_elements(), 10);
+        for row in 0..n {
+            for col in 0..n {
+                assert_eq!(
+                    layout.convert_2d_raw(row, col),
+                    layout.convert_2d_raw(col, row)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Tridiagonal layout.
+//!
+//! This layout describes a square tridiagonal matrix in the band storage
+//! format of Lapack with one subdiagonal and one superdiagonal (see
+//! [Banded](crate::layouts::Banded)). The band is stored column by column in
+//! an array with three rows. The superdiagonal is stored in the first row,
+//! the diagonal in the second row and the subdiagonal in the third row.
+//!
+//! The first position of the first column and the last position of the last column do
+//! not correspond to matrix elements. The one-dimensional index skips these two padding
+//! positions and enumerates the `3 * dim - 2` elements of the band column by column.
+
+use crate::traits::*;
+use crate::types::IndexType;
+
+/// A type that describes a square tridiagonal matrix.
+pub struct Tridiagonal {
+    dim: IndexType,
+}
+
+impl Tridiagonal {
+    pub fn new(dim: IndexType) -> Self {
+        Self { dim }
+    }
+}
+
+impl LayoutType for Tridiagonal {
+    type IndexLayout = Self;
+
+    #[inline]
+    fn is_stored(&self, row: IndexType, col: IndexType) -> bool {
+        (col <= row + 1) & (row <= col + 1)
+    }
+
+    #[inline]
+    fn convert_1d_2d(&self, index: IndexType) -> (IndexType, IndexType) {
+        // The 1d index is the raw index shifted by the leading padding position.
+        let col = (index + 1) / 3;
+        (col + (index + 1) % 3 - 1, col)
+    }
+
+    #[inline]
+    fn convert_1d_raw(&self, index: IndexType) -> IndexType {
+        index + 1
+    }
+
+    #[inline]
+    fn convert_2d_1d(&self, row: IndexType, col: IndexType) -> IndexType {
+        assert!(
+            (col <= row + 1) & (row <= col + 1),
+            "Element outside of tridiagonal band: row={}, col={}",
+            row,
+            col
+        );
+        row + 2 * col
+    }
+
+    #[inline]
+    fn convert_2d_raw(&self, row: IndexType, col: IndexType) -> IndexType {
+        self.convert_1d_raw(self.convert_2d_1d(row, col))
+    }
+
+    #[inline]
+    fn dim(&self) -> (IndexType, IndexType) {
+        (self.dim, self.dim)
+    }
+
+    #[inline]
+    fn index_layout(&self) -> Self::IndexLayout {
+        Self::IndexLayout::new(self.dim)
+    }
+
+    #[inline]
+    fn number_of_elements(&self) -> IndexType {
+        (3 * self.dim).saturating_sub(2)
+    }
+
+    #[inline]
+    fn raw_size(&self) -> IndexType {
+        3 * self.dim
+    }
+
+    #[inline]
+    fn stride(&self) -> (IndexType, IndexType) {
+        std::unimplemented!("method 'stride' not implemented for Tridiagonal layout.")
+    }
+}
+
+impl BaseLayoutType for Tridiagonal {
+    fn from_dimension(dim: (IndexType, IndexType)) -> Self {
+        assert_eq!(
+            dim.0, dim.1,
+            "Only square tridiagonal matrices are supported. dim = {:#?}",
+            dim
+        );
+        Self { dim: dim.0 }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_tridiagonal_indexing() {
+        let layout = Tridiagonal::new(4);
+
+        assert_eq!(layout.number_of_elements(), 10);
+        assert_eq!(layout.raw_size(), 12);
+        assert_eq!(layout.convert_2d_1d(0, 0), 0);
+        assert_eq!(layout.convert_2d_1d(1, 0), 1);
+        assert_eq!(layout.convert_2d_1d(2, 3), 8);
+        assert_eq!(layout.convert_2d_raw(0, 0), 1);
+        assert_eq!(layout.convert_2d_raw(2, 3), 9);
+        assert_eq!(Tridiagonal::new(0).number_of_elements(), 0);
+    }
+
+    #[test]
+    fn test_tridiagonal_1d_sweep() {
+        for dim in 1..6 {
+            let layout = Tridiagonal::new(dim);
+            let mut visited = vec![0; layout.raw_size()];
+            for index in 0..layout.number_of_elements() {
+                let (row, col) = layout.convert_1d_2d(index);
+                assert!((row < dim) & (col < dim) & layout.is_stored(row, col));
+                assert_eq!(layout.convert_2d_1d(row, col), index);
+                assert_eq!(
+                    layout.convert_1d_raw(index),
+                    layout.convert_2d_raw(row, col)
+                );
+                visited[layout.convert_1d_raw(index)] += 1;
+            }
+
+            // Every element of the band is visited exactly once and the padding never.
+            assert_eq!(visited[0], 0);
+            assert_eq!(visited[3 * dim - 1], 0);
+            for col in 0..dim {
+                for row in col.saturating_sub(1)..dim.min(col + 2) {
+                    assert_eq!(visited[layout.convert_2d_raw(row, col)], 1);
+                }
+            }
+        }
+    }
+}
@@ -21,6 +21,11 @@ impl UpperTriangular {
 impl LayoutType for UpperTriangular {
     type IndexLayout = Self;
 
+    #[inline]
+    fn is_stored(&self, row: IndexType, col: IndexType) -> bool {
+        col >= row
+    }
+
     #[inline]
     fn convert_1d_2d(&self, index: IndexType) -> (IndexType, IndexType) {
         let p = -0.5 + f64::sqrt(0.25 + 2.0 * (index as f64));
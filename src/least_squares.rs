@@ -0,0 +1,381 @@
+//! Least-squares and minimum-norm solutions of dense linear systems.
+//!
+//! The `lstsq` method of dynamic dense matrices solves `min ||A * X - B||_2` for an
+//! `m x n` matrix `A` and an `m x p` right-hand side `B`. Among all minimisers the solution
+//! with the smallest norm is returned. The method is chosen from the shape and the
+//! numerical rank of `A`, which is estimated with a QR decomposition with column pivoting.
+//!
+//! - If `m >= n` and `A` has full column rank, `X = P * R^{-1} * Q^H * B` is computed from
+//!   the pivoted QR decomposition `A * P = Q * R`.
+//! - If `m < n` and `A` has full row rank, the pivoted QR decomposition of `A^H` gives a
+//!   row pivoted LQ decomposition `P^T * A = R^H * Q^H` of `A`. The minimum-norm solution
+//!   is `X = Q * R^{-H} * P^T * B`.
+//! - Otherwise the singular value decomposition of `A` is computed and singular values
+//!   that are at most `tol` times the largest singular value are treated as zero.
+//!
+//! A diagonal entry of the triangular factor that is at most `tol` times the first diagonal
+//! entry marks a rank-deficient matrix. The default tolerance is `eps * max(m, n)`.
+//!
+//! ```
+//! # use householder::*;
+//! // Fit a line y = a + b * t through four points.
+//! let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(4, 2);
+//! let mut rhs = ColumnVectorD::<f64>::zeros_from_length(4);
+//! for (index, (t, y)) in [(0.0, 1.0), (1.0, 3.0), (2.0, 5.0), (3.0, 7.0)].iter().enumerate() {
+//!     *mat.get_mut(index, 0) = 1.0;
+//!     *mat.get_mut(index, 1) = *t;
+//!     *rhs.get1d_mut(index) = *y;
+//! }
+//! let lstsq = mat.lstsq(&rhs);
+//! assert_eq!(lstsq.method, LstsqMethod::Qr);
+//! assert!((lstsq.solution.get(0, 0) - 1.0).abs() < 1E-12);
+//! assert!((lstsq.solution.get(1, 0) - 2.0).abs() < 1E-12);
+//! assert!(lstsq.residuals[0] < 1E-12);
+//! ```
+
+use crate::data_container::DataContainer;
+use crate::dense_solvers::{adjoint, column_major_copy, jacobi_svd, pivoted_qr_decomposition};
+use crate::layouts::*;
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::*;
+
+/// The method that was used to compute a least-squares solution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LstsqMethod {
+    /// Pivoted QR decomposition of a matrix with full column rank.
+    Qr,
+    /// Row pivoted LQ decomposition of a matrix with full row rank.
+    Lq,
+    /// Singular value decomposition of a rank-deficient matrix.
+    Svd,
+}
+
+/// The result of a least-squares solve.
+pub struct LstsqSolution<Item: HScalar> {
+    /// The `n x p` minimum-norm least-squares solution `X`.
+    pub solution: MatrixD<Item, ColumnMajor>,
+    /// The 2-norms of the columns of the residual `B - A * X`.
+    pub residuals: Vec<<Item as Scalar>::Real>,
+    /// The effective rank of `A`.
+    pub rank: IndexType,
+    /// The singular values of `A` in descending order if they were computed.
+    pub singular_values: Option<Vec<<Item as Scalar>::Real>>,
+    /// The method that was used.
+    pub method: LstsqMethod,
+}
+
+/// Compute the minimum-norm least-squares solution of `A * X = B`.
+fn least_squares<Item: HScalar>(
+    mat: &MatrixD<Item, ColumnMajor>,
+    rhs: &MatrixD<Item, ColumnMajor>,
+    tol: <Item as Scalar>::Real,
+) -> LstsqSolution<Item> {
+    let (m, n) = mat.layout().dim();
+    let nrhs = rhs.layout().dim().1;
+    let mut solution = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, nrhs);
+
+    let (rank, singular_values, method) = if m >= n {
+        let qr = pivoted_qr_decomposition(mat, n, None);
+        if qr.rank(tol) == n {
+            // Solve R * Y = Q^H * B and undo the column pivoting.
+            let mut qhb = column_major_copy(rhs);
+            qr.apply_adjoint_q(&mut qhb);
+            let (r, permutation) = (qr.r(), qr.permutation());
+            for col in 0..nrhs {
+                for row in (0..n).rev() {
+                    let mut value = qhb.get(row, col);
+                    for other in row + 1..n {
+                        value -= r.get(row, other) * qhb.get(other, col);
+                    }
+                    *qhb.get_mut(row, col) = value / r.get(row, row);
+                    *solution.get_mut(permutation[row], col) = qhb.get(row, col);
+                }
+            }
+            (n, None, LstsqMethod::Qr)
+        } else {
+            svd_solve(mat, rhs, tol, &mut solution)
+        }
+    } else {
+        let qr = pivoted_qr_decomposition(&adjoint(mat), m, None);
+        if qr.rank(tol) == m {
+            // Solve R^H * Y = P^T * B and multiply with Q.
+            let (r, permutation) = (qr.r(), qr.permutation());
+            let mut y = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, nrhs);
+            for col in 0..nrhs {
+                for (row, &pivot) in permutation[..m].iter().enumerate() {
+                    let mut value = rhs.get(pivot, col);
+                    for other in 0..row {
+                        value -= r.get(other, row).conj() * y.get(other, col);
+                    }
+                    *y.get_mut(row, col) = value / r.get(row, row).conj();
+                }
+            }
+            let q = qr.q();
+            for col in 0..nrhs {
+                for index in 0..m {
+                    let value = y.get(index, col);
+                    for row in 0..n {
+                        *solution.get_mut(row, col) += q.get(row, index) * value;
+                    }
+                }
+            }
+            (m, None, LstsqMethod::Lq)
+        } else {
+            svd_solve(mat, rhs, tol, &mut solution)
+        }
+    };
+
+    let residuals = (0..nrhs)
+        .map(|col| {
+            let mut norm = <<Item as Scalar>::Real as num::Zero>::zero();
+            for row in 0..m {
+                let mut value = rhs.get(row, col);
+                for index in 0..n {
+                    value -= mat.get(row, index) * solution.get(index, col);
+                }
+                norm += value.square();
+            }
+            num::Float::sqrt(norm)
+        })
+        .collect();
+
+    LstsqSolution {
+        solution,
+        residuals,
+        rank,
+        singular_values,
+        method,
+    }
+}
+
+/// The rank, the singular values and the method of a least-squares solve.
+type SolveInfo<Item> = (IndexType, Option<Vec<<Item as Scalar>::Real>>, LstsqMethod);
+
+/// Compute `X = A^+ * B` from the truncated singular value decomposition of `A`.
+fn svd_solve<Item: HScalar>(
+    mat: &MatrixD<Item, ColumnMajor>,
+    rhs: &MatrixD<Item, ColumnMajor>,
+    tol: <Item as Scalar>::Real,
+    solution: &mut MatrixD<Item, ColumnMajor>,
+) -> SolveInfo<Item> {
+    let (m, n) = mat.layout().dim();
+    let nrhs = rhs.layout().dim().1;
+
+    // A = right * S * left^H with left of dimension n x k and right of dimension m x k.
+    let (left, values, right) = if m >= n {
+        let (u, values, v) = jacobi_svd(mat);
+        (v, values, u)
+    } else {
+        jacobi_svd(&adjoint(mat))
+    };
+    let zero = <<Item as Scalar>::Real as num::Zero>::zero();
+    let rank = match values.first() {
+        Some(&first) => values
+            .iter()
+            .take_while(|&&value| (value > tol * first) & (value > zero))
+            .count(),
+        None => 0,
+    };
+
+    for col in 0..nrhs {
+        for (index, &singular_value) in values[..rank].iter().enumerate() {
+            let mut value = Item::zero();
+            for row in 0..m {
+                value += right.get(row, index).conj() * rhs.get(row, col);
+            }
+            let value = value / Item::from_real(singular_value);
+            for row in 0..n {
+                *solution.get_mut(row, col) += left.get(row, index) * value;
+            }
+        }
+    }
+    (rank, Some(values), LstsqMethod::Svd)
+}
+
+macro_rules! least_squares_impl {
+    ($Layout:ident) => {
+        impl<Item: HScalar, Data: DataContainer<Item = Item>>
+            GenericBaseMatrix<Item, $Layout, Data, Dynamic, Dynamic>
+        {
+            /// Compute the minimum-norm least-squares solution of `A * X = B` with the
+            /// default rank tolerance `eps * max(m, n)`.
+            pub fn lstsq<
+                MatImpl: MatrixTrait<Item, L, RS, CS>,
+                L: LayoutType,
+                RS: SizeIdentifier,
+                CS: SizeIdentifier,
+            >(
+                &self,
+                rhs: &Matrix<Item, MatImpl, L, RS, CS>,
+            ) -> LstsqSolution<Item> {
+                let (m, n) = self.layout().dim();
+                let tol = <<Item as Scalar>::Real as num::Float>::epsilon()
+                    * num::cast::<IndexType, <Item as Scalar>::Real>(m.max(n)).unwrap();
+                self.lstsq_with_tol(rhs, tol)
+            }
+
+            /// Compute the minimum-norm least-squares solution of `A * X = B`. Singular
+            /// values that are at most `tol` times the largest singular value are treated
+            /// as zero.
+            pub fn lstsq_with_tol<
+                MatImpl: MatrixTrait<Item, L, RS, CS>,
+                L: LayoutType,
+                RS: SizeIdentifier,
+                CS: SizeIdentifier,
+            >(
+                &self,
+                rhs: &Matrix<Item, MatImpl, L, RS, CS>,
+                tol: <Item as Scalar>::Real,
+            ) -> LstsqSolution<Item> {
+                let m = self.layout().dim().0;
+                let rows = rhs.layout().dim().0;
+                assert_eq!(
+                    m, rows,
+                    "Right-hand side has {} rows but matrix has {} rows.",
+                    rows, m
+                );
+                least_squares(&column_major_copy(self), &column_major_copy(rhs), tol)
+            }
+        }
+    };
+}
+
+least_squares_impl!(RowMajor);
+least_squares_impl!(ColumnMajor);
+least_squares_impl!(ArbitraryStrideRowMajor);
+least_squares_impl!(ArbitraryStrideColumnMajor);
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use rand::prelude::*;
+
+    /// Return the largest entry of `|A^H * (B - A * X)|`, which vanishes for a
+    /// least-squares solution.
+    fn normal_equation_error<Item: HScalar>(
+        mat: &MatrixD<Item, ColumnMajor>,
+        rhs: &MatrixD<Item, ColumnMajor>,
+        solution: &MatrixD<Item, ColumnMajor>,
+    ) -> f64 {
+        let (m, n) = mat.layout().dim();
+        let nrhs = rhs.layout().dim().1;
+        let mut error: f64 = 0.0;
+        for col in 0..nrhs {
+            let residual: Vec<Item> = (0..m)
+                .map(|row| {
+                    (0..n).fold(rhs.get(row, col), |acc, index| {
+                        acc - mat.get(row, index) * solution.get(index, col)
+                    })
+                })
+                .collect();
+            for index in 0..n {
+                let value = (0..m).fold(Item::zero(), |acc, row| {
+                    acc + mat.get(row, index).conj() * residual[row]
+                });
+                error = error.max(num::cast::<_, f64>(value.abs()).unwrap());
+            }
+        }
+        error
+    }
+
+    /// Return the residual of the least-squares solution of `A^H * Z = X`, which vanishes
+    /// if the columns of `X` are in the range of `A^H`.
+    fn range_error<Item: HScalar>(
+        mat: &MatrixD<Item, ColumnMajor>,
+        solution: &MatrixD<Item, ColumnMajor>,
+        tol: f64,
+    ) -> f64 {
+        adjoint(mat)
+            .lstsq_with_tol(
+                solution,
+                num::cast::<f64, <Item as Scalar>::Real>(tol).unwrap(),
+            )
+            .residuals
+            .iter()
+            .fold(0.0, |acc, &value| {
+                acc.max(num::cast::<_, f64>(value).unwrap())
+            })
+    }
+
+    macro_rules! least_squares_test {
+        ($HScalar:ty, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let mut rng = StdRng::seed_from_u64(0);
+
+                // Overdetermined system with full column rank.
+                let mut mat = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(20, 5);
+                let mut rhs = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(20, 2);
+                mat.fill_from_rand_standard_normal(&mut rng);
+                rhs.fill_from_rand_standard_normal(&mut rng);
+                let lstsq = mat.lstsq(&rhs);
+                assert_eq!(lstsq.method, LstsqMethod::Qr);
+                assert_eq!(lstsq.rank, 5);
+                assert!(lstsq.singular_values.is_none());
+                assert_eq!(lstsq.solution.layout().dim(), (5, 2));
+                assert!(lstsq.residuals.iter().all(|&value| value > 1.0));
+                assert!(normal_equation_error(&mat, &rhs, &lstsq.solution) < $tol);
+
+                // Underdetermined system with full row rank.
+                let mut mat = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(5, 20);
+                let mut rhs = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(5, 2);
+                mat.fill_from_rand_standard_normal(&mut rng);
+                rhs.fill_from_rand_standard_normal(&mut rng);
+                let lstsq = mat.lstsq(&rhs);
+                assert_eq!(lstsq.method, LstsqMethod::Lq);
+                assert_eq!(lstsq.rank, 5);
+                assert!(lstsq
+                    .residuals
+                    .iter()
+                    .all(|&value| num::cast::<_, f64>(value).unwrap() < $tol));
+                assert!(range_error(&mat, &lstsq.solution, $tol) < $tol);
+
+                // Rank-deficient systems.
+                for (m, n) in [(12, 8), (8, 12)] {
+                    let rank = 3;
+                    let mut left = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(m, rank);
+                    let mut right = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(rank, n);
+                    left.fill_from_rand_standard_normal(&mut rng);
+                    right.fill_from_rand_standard_normal(&mut rng);
+                    let mut mat = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(m, n);
+                    for index in 0..rank {
+                        for col in 0..n {
+                            for row in 0..m {
+                                *mat.get_mut(row, col) +=
+                                    left.get(row, index) * right.get(index, col);
+                            }
+                        }
+                    }
+                    let mut rhs = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(m, 1);
+                    rhs.fill_from_rand_standard_normal(&mut rng);
+                    let lstsq = mat.lstsq_with_tol(&rhs, num::cast::<f64, _>($tol).unwrap());
+                    assert_eq!(lstsq.method, LstsqMethod::Svd);
+                    assert_eq!(lstsq.rank, rank);
+                    assert_eq!(lstsq.singular_values.as_ref().unwrap().len(), m.min(n));
+                    assert!(normal_equation_error(&mat, &rhs, &lstsq.solution) < 10.0 * $tol);
+                    assert!(range_error(&mat, &lstsq.solution, $tol) < 10.0 * $tol);
+                }
+            }
+        };
+    }
+
+    least_squares_test!(f32, 1E-3, test_least_squares_f32);
+    least_squares_test!(f64, 1E-10, test_least_squares_f64);
+    least_squares_test!(c32, 1E-3, test_least_squares_c32);
+    least_squares_test!(c64, 1E-10, test_least_squares_c64);
+
+    #[test]
+    fn test_least_squares_of_zero_matrix() {
+        let mat = MatrixD::<f64, RowMajor>::zeros_from_dim(3, 2);
+        let mut rhs = ColumnVectorD::<f64>::zeros_from_length(3);
+        *rhs.get1d_mut(1) = 2.0;
+        let lstsq = mat.lstsq(&rhs);
+        assert_eq!(lstsq.method, LstsqMethod::Svd);
+        assert_eq!(lstsq.rank, 0);
+        assert_eq!(lstsq.solution.get(0, 0), 0.0);
+        assert_eq!(lstsq.solution.get(1, 0), 0.0);
+        assert_eq!(lstsq.residuals, vec![2.0]);
+    }
+}
@@ -19,11 +19,16 @@
 //! about the operation. Only when the user asks for the evaluation, all operations
 //! are executed in a single pass without creating temporaries.
 //!
-//! Matrix-matrix products are implemented through the [matrixmultiply](matrixmultiply)
-//! crate. We are in the process of implementing more advanced linear algebra routines
-//! (e.g. LU, QR, etc.). But these are not yet available. The focus is on implementing
+//! Matrix-matrix products are implemented through the [matrixmultiply] crate. The LU,
+//! Cholesky and column pivoted QR decompositions of dense matrices are available in
+//! [dense_solvers], banded and tridiagonal solvers in [band_solvers], and Krylov
+//! solvers, eigensolvers and preconditioners in [iterative]. The focus is on implementing
 //! modern blocked multi-threaded routines whose performance is competitive with Lapack.
 //!
+//! Multi-threading is enabled with the `threading` feature. It turns on the threaded
+//! matrix-matrix products of [matrixmultiply] and parallel sparse
+//! matrix-vector products (see [sparse::products]).
+//!
 //! To learn more about `householder` we recommend the user to read the following bits
 //! of information.
 //!
@@ -33,6 +38,8 @@
 //! - [Examples](crate::examples)
 
 pub mod data_container;
+pub mod dense_solvers;
+pub mod error;
 pub mod examples;
 pub mod layouts;
 pub mod macros;
@@ -42,10 +49,33 @@ pub mod traits;
 pub mod types;
 
 pub mod addition;
+pub mod band_solvers;
 pub mod base_matrix;
+pub mod comparison;
+pub mod condensed;
+pub mod conversion;
+pub mod diagonal_matrix;
 pub mod global;
+pub mod hmatrix;
+pub mod interpolative;
+pub mod iterative;
+pub mod least_squares;
+pub mod low_rank;
+pub mod map;
 pub mod matrix_multiply;
 pub mod matrix_ref;
+pub mod norms;
+pub mod permutation;
+pub mod pseudo_inverse;
+pub mod randomized;
+pub mod reductions;
+pub mod refinement;
 pub mod scalar_mult;
+pub mod sparse;
+pub mod symmetric_multiply;
+pub mod triangular_multiply;
+
+#[cfg(test)]
+mod test_utils;
 
 pub use global::*;
@@ -0,0 +1,702 @@
+//! Low-rank matrices and the adaptive cross approximation.
+//!
+//! A [LowRankMatrix] stores an `m x n` matrix in the factorised form `A = U * V^H` with
+//! `U` of dimension `m x k` and `V` of dimension `n x k`. This requires `(m + n) * k`
+//! instead of `m * n` elements and a matrix-vector product costs `O((m + n) * k)`
+//! operations. The rank `k` of a given factorisation can be reduced with
+//! [recompress](LowRankMatrix::recompress), which computes a truncated SVD from QR
+//! decompositions of the two factors.
+//!
+//! Low-rank approximations of matrices with smooth generating kernels, as they occur in
+//! boundary element methods, are computed by the adaptive cross approximation [Aca].
+//! It only requires the evaluation of a few rows and columns of the matrix, which are
+//! provided either by a [Matrix](crate::matrix::Matrix) or by a closure that evaluates
+//! individual entries. Two pivoting strategies are supported.
+//! - [AcaVariant::PartialPivoting]: The next pivot row is the row in which the last column
+//!   of `U` has its largest entry (Bebendorf, Numer. Math. 86, 2000).
+//! - [AcaVariant::Plus]: The pivots are chosen with the help of a reference row and a
+//!   reference column of the residual (Grasedyck, Computing 74, 2005). This is more
+//!   robust for matrices whose nonzero entries are not spread over all rows.
+//!
+//! The iteration stops once the Frobenius norm of the last rank-one update is smaller
+//! than the tolerance times the Frobenius norm of the current approximation.
+//!
+//! ```
+//! # use householder::*;
+//! // The kernel 1 / |x - y| for two well separated sets of points.
+//! let kernel = |row: usize, col: usize| 1.0 / (2.0 + row as f64 / 100.0 + col as f64 / 100.0);
+//! let approx = Aca::new((100, 100), kernel).with_tol(1E-8).compute();
+//! assert!(approx.rank() < 15);
+//! ```
+
+use crate::layouts::*;
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::*;
+
+use crate::dense_solvers::{column_major_copy, jacobi_svd, thin_qr};
+
+/// A type alias for the real type associated with a scalar type.
+type RealType<Item> = <Item as Scalar>::Real;
+
+/// An `m x n` matrix in the factorised form `U * V^H`.
+pub struct LowRankMatrix<Item: HScalar> {
+    u: MatrixD<Item, ColumnMajor>,
+    v: MatrixD<Item, ColumnMajor>,
+}
+
+impl<Item: HScalar> LowRankMatrix<Item> {
+    /// Create the matrix `U * V^H` from the `m x k` matrix `U` and the `n x k` matrix `V`.
+    /// The rank `k` must not exceed `min(m, n)`.
+    pub fn new(u: MatrixD<Item, ColumnMajor>, v: MatrixD<Item, ColumnMajor>) -> Self {
+        let (dim_u, dim_v) = (u.layout().dim(), v.layout().dim());
+        assert_eq!(
+            dim_u.1, dim_v.1,
+            "Factors must have the same number of columns. U = {:#?}, V = {:#?}",
+            dim_u, dim_v
+        );
+        assert!(
+            dim_u.1 <= dim_u.0.min(dim_v.0),
+            "The rank must not exceed min(m, n). U = {:#?}, V = {:#?}",
+            dim_u,
+            dim_v
+        );
+        Self { u, v }
+    }
+
+    /// Return the dimension `(m, n)`.
+    pub fn dim(&self) -> (IndexType, IndexType) {
+        (self.u.layout().dim().0, self.v.layout().dim().0)
+    }
+
+    /// Return the rank `k`, i.e. the number of columns of the factors.
+    pub fn rank(&self) -> IndexType {
+        self.u.layout().dim().1
+    }
+
+    /// Return the `m x k` factor `U`.
+    pub fn u(&self) -> &MatrixD<Item, ColumnMajor> {
+        &self.u
+    }
+
+    /// Return the `n x k` factor `V`.
+    pub fn v(&self) -> &MatrixD<Item, ColumnMajor> {
+        &self.v
+    }
+
+    /// Compute `y = alpha * op(A) * x + beta * y`.
+    pub fn matvec<
+        MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+        MatImpl2: MatrixTraitMut<Item, L2, RS2, CS2>,
+        L1: LayoutType,
+        L2: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    >(
+        &self,
+        trans: TransposeMode,
+        alpha: Item,
+        vec_x: &Matrix<Item, MatImpl1, L1, RS1, CS1>,
+        beta: Item,
+        vec_y: &mut Matrix<Item, MatImpl2, L2, RS2, CS2>,
+    ) {
+        // op(U * V^H) = left * right^T with the factors below.
+        let (left, right, conj_left, conj_right) = match trans {
+            TransposeMode::NoTrans => (&self.u, &self.v, false, true),
+            TransposeMode::Trans => (&self.v, &self.u, true, false),
+            TransposeMode::ConjTrans => (&self.v, &self.u, false, true),
+        };
+        let conj = |value: Item, flag: bool| if flag { value.conj() } else { value };
+        let (rows, cols) = (left.layout().dim().0, right.layout().dim().0);
+        let (len_x, len_y) = (
+            vec_x.layout().number_of_elements(),
+            vec_y.layout().number_of_elements(),
+        );
+        assert!(
+            (cols == len_x) & (rows == len_y),
+            "Incompatible dimensions for y = op(A) * x: op(A) = {:#?}, x = {}, y = {}",
+            (rows, cols),
+            len_x,
+            len_y
+        );
+
+        let coeffs: Vec<Item> = (0..self.rank())
+            .map(|index| {
+                let mut value = Item::zero();
+                for row in 0..cols {
+                    value += unsafe {
+                        conj(right.get_unchecked(row, index), conj_right)
+                            * vec_x.get1d_unchecked(row)
+                    };
+                }
+                alpha * value
+            })
+            .collect();
+        for row in 0..rows {
+            let mut value = Item::zero();
+            for (index, &coeff) in coeffs.iter().enumerate() {
+                value += unsafe { conj(left.get_unchecked(row, index), conj_left) } * coeff;
+            }
+            let elem = unsafe { vec_y.get1d_unchecked_mut(row) };
+            *elem = if beta == Item::zero() {
+                value
+            } else {
+                value + beta * *elem
+            };
+        }
+    }
+
+    /// Return a factorisation of smaller rank whose relative distance to this matrix in
+    /// the Frobenius norm is at most `tol`.
+    ///
+    /// The factors are decomposed as `U = Q_U * R_U` and `V = Q_V * R_V`. The truncated
+    /// SVD `W * S * Z^H` of the small matrix `R_U * R_V^H` gives the new factors
+    /// `Q_U * W * S` and `Q_V * Z`.
+    pub fn recompress(&self, tol: RealType<Item>) -> Self {
+        let (m, n) = self.dim();
+        let k = self.rank();
+        let mut q_u = column_major_copy(&self.u);
+        let r_u = thin_qr(&mut q_u);
+        let mut q_v = column_major_copy(&self.v);
+        let r_v = thin_qr(&mut q_v);
+
+        let mut core = MatrixD::<Item, ColumnMajor>::zeros_from_dim(k, k);
+        for col in 0..k {
+            for row in 0..k {
+                let mut value = Item::zero();
+                for index in row.max(col)..k {
+                    value += r_u.get(row, index) * r_v.get(col, index).conj();
+                }
+                *core.get_mut(row, col) = value;
+            }
+        }
+        let (w, singular_values, z) = jacobi_svd(&core);
+
+        // The smallest rank whose discarded singular values satisfy the tolerance.
+        let total: RealType<Item> = singular_values.iter().map(|&value| value * value).sum();
+        let mut tail = <RealType<Item> as num::Zero>::zero();
+        let mut rank = k;
+        while rank > 0 {
+            let value = singular_values[rank - 1];
+            if tail + value * value > tol * tol * total {
+                break;
+            }
+            tail += value * value;
+            rank -= 1;
+        }
+
+        let mut u = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, rank);
+        let mut v = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, rank);
+        for (col, &sigma) in singular_values[..rank].iter().enumerate() {
+            let sigma = Item::from_real(sigma);
+            for index in 0..k {
+                let coeff_u = w.get(index, col) * sigma;
+                for row in 0..m {
+                    *u.get_mut(row, col) += q_u.get(row, index) * coeff_u;
+                }
+                let coeff_v = z.get(index, col);
+                for row in 0..n {
+                    *v.get_mut(row, col) += q_v.get(row, index) * coeff_v;
+                }
+            }
+        }
+        Self { u, v }
+    }
+
+    /// Return the dense `m x n` matrix `U * V^H`.
+    pub fn to_dense(&self) -> MatrixD<Item, ColumnMajor> {
+        let (m, n) = self.dim();
+        let mut res = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, n);
+        for col in 0..n {
+            for index in 0..self.rank() {
+                let value = self.v.get(col, index).conj();
+                for row in 0..m {
+                    *res.get_mut(row, col) += self.u.get(row, index) * value;
+                }
+            }
+        }
+        res
+    }
+}
+
+/// The pivoting strategy of the adaptive cross approximation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AcaVariant {
+    /// ACA with partial pivoting.
+    PartialPivoting,
+    /// ACA+ with a reference row and a reference column.
+    Plus,
+}
+
+/// The adaptive cross approximation (ACA) of an `m x n` matrix.
+///
+/// The default tolerance is `1E-6`, the default maximum rank is `min(m, n)` and ACA+ is
+/// used by default.
+pub struct Aca<'a, Item: HScalar> {
+    dim: (IndexType, IndexType),
+    entry: Box<dyn Fn(IndexType, IndexType) -> Item + 'a>,
+    tol: RealType<Item>,
+    max_rank: IndexType,
+    variant: AcaVariant,
+}
+
+impl<'a, Item: HScalar> Aca<'a, Item> {
+    /// Approximate the `m x n` matrix whose entry `(row, col)` is `entry(row, col)`.
+    pub fn new(
+        dim: (IndexType, IndexType),
+        entry: impl Fn(IndexType, IndexType) -> Item + 'a,
+    ) -> Self {
+        Self {
+            dim,
+            entry: Box::new(entry),
+            tol: num::cast::<f64, RealType<Item>>(1E-6).unwrap(),
+            max_rank: dim.0.min(dim.1),
+            variant: AcaVariant::Plus,
+        }
+    }
+
+    /// Approximate a dense or sparse matrix.
+    pub fn from_matrix<Mat: RandomAccess<Item = Item> + Layout>(mat: &'a Mat) -> Self {
+        Self::new(mat.layout().dim(), move |row, col| mat.get(row, col))
+    }
+
+    /// Set the relative tolerance in the Frobenius norm.
+    pub fn with_tol(mut self, tol: RealType<Item>) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// Set the maximum rank of the approximation.
+    pub fn with_max_rank(mut self, max_rank: IndexType) -> Self {
+        self.max_rank = max_rank.min(self.dim.0).min(self.dim.1);
+        self
+    }
+
+    /// Set the pivoting strategy.
+    pub fn with_variant(mut self, variant: AcaVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Compute the low-rank approximation.
+    pub fn compute(&self) -> LowRankMatrix<Item> {
+        let mut state = AcaState::new(self);
+        match self.variant {
+            AcaVariant::PartialPivoting => state.partial_pivoting(),
+            AcaVariant::Plus => state.plus(),
+        }
+        state.into_low_rank()
+    }
+}
+
+/// The cross approximation `sum_l u_l * v_l^H` built so far.
+struct AcaState<'b, 'a, Item: HScalar> {
+    aca: &'b Aca<'a, Item>,
+    u: Vec<Vec<Item>>,
+    v: Vec<Vec<Item>>,
+    used_rows: Vec<bool>,
+    used_cols: Vec<bool>,
+    /// The squared Frobenius norm of the approximation.
+    norm2: RealType<Item>,
+}
+
+impl<'b, 'a, Item: HScalar> AcaState<'b, 'a, Item> {
+    fn new(aca: &'b Aca<'a, Item>) -> Self {
+        Self {
+            aca,
+            u: Vec::new(),
+            v: Vec::new(),
+            used_rows: vec![false; aca.dim.0],
+            used_cols: vec![false; aca.dim.1],
+            norm2: <RealType<Item> as num::Zero>::zero(),
+        }
+    }
+
+    /// Return the row `row` of the residual.
+    fn residual_row(&self, row: IndexType) -> Vec<Item> {
+        (0..self.aca.dim.1)
+            .map(|col| {
+                let mut value = (self.aca.entry)(row, col);
+                for (u, v) in self.u.iter().zip(self.v.iter()) {
+                    value -= u[row] * v[col].conj();
+                }
+                value
+            })
+            .collect()
+    }
+
+    /// Return the column `col` of the residual.
+    fn residual_col(&self, col: IndexType) -> Vec<Item> {
+        (0..self.aca.dim.0)
+            .map(|row| {
+                let mut value = (self.aca.entry)(row, col);
+                for (u, v) in self.u.iter().zip(self.v.iter()) {
+                    value -= u[row] * v[col].conj();
+                }
+                value
+            })
+            .collect()
+    }
+
+    /// Add the cross through the pivot `(row, col)` with the residual row `res_row` and
+    /// the residual column `res_col`. Return `true` if the stopping criterion is satisfied.
+    fn add_cross(
+        &mut self,
+        row: IndexType,
+        col: IndexType,
+        res_row: &[Item],
+        res_col: &[Item],
+    ) -> bool {
+        let pivot = res_row[col];
+        let u: Vec<Item> = res_col.iter().map(|&value| value / pivot).collect();
+        let v: Vec<Item> = res_row.iter().map(|&value| value.conj()).collect();
+
+        let u_norm2: RealType<Item> = u.iter().map(|value| value.square()).sum();
+        let v_norm2: RealType<Item> = v.iter().map(|value| value.square()).sum();
+        let mut cross = Item::zero();
+        for (u_old, v_old) in self.u.iter().zip(self.v.iter()) {
+            cross += dot(u_old, &u) * dot(&v, v_old);
+        }
+        let two = num::cast::<f64, RealType<Item>>(2.0).unwrap();
+        self.norm2 += u_norm2 * v_norm2 + two * cross.re();
+
+        self.u.push(u);
+        self.v.push(v);
+        self.used_rows[row] = true;
+        self.used_cols[col] = true;
+        u_norm2 * v_norm2 <= self.aca.tol * self.aca.tol * self.norm2
+    }
+
+    fn partial_pivoting(&mut self) {
+        let mut row = 0;
+        while self.u.len() < self.aca.max_rank {
+            let res_row = self.residual_row(row);
+            self.used_rows[row] = true;
+            match argmax(&res_row, &self.used_cols) {
+                Some(col) if res_row[col] != Item::zero() => {
+                    let res_col = self.residual_col(col);
+                    if self.add_cross(row, col, &res_row, &res_col) {
+                        break;
+                    }
+                    match argmax(self.u.last().unwrap(), &self.used_rows) {
+                        Some(next) => row = next,
+                        None => break,
+                    }
+                }
+                _ => {
+                    // The residual row vanishes. Continue with the next unused row.
+                    match self.used_rows.iter().position(|&used| !used) {
+                        Some(next) => row = next,
+                        None => break,
+                    }
+                }
+            }
+        }
+    }
+
+    fn plus(&mut self) {
+        let zero = <RealType<Item> as num::Zero>::zero();
+        let (mut ref_row, mut ref_col) = (0, 0);
+        let mut res_ref_row = self.residual_row(ref_row);
+        let mut res_ref_col = self.residual_col(ref_col);
+        // The number of consecutive attempts to find nonzero references.
+        let mut attempts = 0;
+
+        while self.u.len() < self.aca.max_rank {
+            let row_max = argmax(&res_ref_col, &self.used_rows);
+            let col_max = argmax(&res_ref_row, &self.used_cols);
+            let value =
+                |res: &[Item], index: Option<IndexType>| index.map_or(zero, |i| res[i].abs());
+            let (row_value, col_value) =
+                (value(&res_ref_col, row_max), value(&res_ref_row, col_max));
+
+            let pivot = if (row_value == zero) & (col_value == zero) {
+                None
+            } else if row_value >= col_value {
+                let row = row_max.unwrap();
+                let res_row = self.residual_row(row);
+                argmax(&res_row, &self.used_cols).map(|col| {
+                    let res_col = self.residual_col(col);
+                    (row, col, res_row, res_col)
+                })
+            } else {
+                let col = col_max.unwrap();
+                let res_col = self.residual_col(col);
+                argmax(&res_col, &self.used_rows).map(|row| {
+                    let res_row = self.residual_row(row);
+                    (row, col, res_row, res_col)
+                })
+            };
+
+            match pivot {
+                Some((row, col, res_row, res_col)) if res_row[col] != Item::zero() => {
+                    attempts = 0;
+                    if self.add_cross(row, col, &res_row, &res_col) {
+                        break;
+                    }
+                    let (u, v) = (self.u.last().unwrap(), self.v.last().unwrap());
+                    for (index, elem) in res_ref_row.iter_mut().enumerate() {
+                        *elem -= u[ref_row] * v[index].conj();
+                    }
+                    for (index, elem) in res_ref_col.iter_mut().enumerate() {
+                        *elem -= u[index] * v[ref_col].conj();
+                    }
+                    if self.used_rows[ref_row] {
+                        ref_row = self.next_unused(&self.used_rows, ref_row);
+                        res_ref_row = self.residual_row(ref_row);
+                    }
+                    if self.used_cols[ref_col] {
+                        ref_col = self.next_unused(&self.used_cols, ref_col);
+                        res_ref_col = self.residual_col(ref_col);
+                    }
+                }
+                _ => {
+                    // Both references are represented exactly. Try new references.
+                    attempts += 1;
+                    if attempts > self.aca.dim.0.max(self.aca.dim.1) {
+                        break;
+                    }
+                    ref_row = self.next_unused(&self.used_rows, ref_row);
+                    ref_col = self.next_unused(&self.used_cols, ref_col);
+                    res_ref_row = self.residual_row(ref_row);
+                    res_ref_col = self.residual_col(ref_col);
+                }
+            }
+        }
+    }
+
+    /// Return the next unused index after `current` in cyclic order, or `current` if all
+    /// other indices are used.
+    fn next_unused(&self, used: &[bool], current: IndexType) -> IndexType {
+        (1..=used.len())
+            .map(|offset| (current + offset) % used.len())
+            .find(|&index| !used[index])
+            .unwrap_or(current)
+    }
+
+    fn into_low_rank(self) -> LowRankMatrix<Item> {
+        let (m, n) = self.aca.dim;
+        let k = self.u.len();
+        let mut u = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, k);
+        let mut v = MatrixD::<Item, ColumnMajor>::zeros_from_dim(n, k);
+        for (col, (u_col, v_col)) in self.u.iter().zip(self.v.iter()).enumerate() {
+            for (row, &value) in u_col.iter().enumerate() {
+                *u.get_mut(row, col) = value;
+            }
+            for (row, &value) in v_col.iter().enumerate() {
+                *v.get_mut(row, col) = value;
+            }
+        }
+        LowRankMatrix::new(u, v)
+    }
+}
+
+/// Return the index of the entry of largest magnitude among the entries that are not used.
+fn argmax<Item: HScalar>(values: &[Item], used: &[bool]) -> Option<IndexType> {
+    let mut res: Option<(IndexType, RealType<Item>)> = None;
+    for (index, value) in values.iter().enumerate() {
+        if used[index] {
+            continue;
+        }
+        let abs = value.abs();
+        if res.is_none_or(|(_, max)| abs > max) {
+            res = Some((index, abs));
+        }
+    }
+    res.map(|(index, _)| index)
+}
+
+/// Return the inner product `sum_i conj(x_i) * y_i`.
+fn dot<Item: HScalar>(x: &[Item], y: &[Item]) -> Item {
+    x.iter()
+        .zip(y.iter())
+        .fold(Item::zero(), |acc, (&a, &b)| acc + a.conj() * b)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    /// Return the relative Frobenius norm distance between `approx` and `expect`.
+    fn relative_error<Item: HScalar, Mat: RandomAccess<Item = Item> + Layout>(
+        approx: &LowRankMatrix<Item>,
+        expect: &Mat,
+    ) -> f64 {
+        let dense = approx.to_dense();
+        let (m, n) = expect.layout().dim();
+        let (mut diff, mut norm) = (0.0, 0.0);
+        for col in 0..n {
+            for row in 0..m {
+                let value = expect.get(row, col);
+                diff += num::cast::<RealType<Item>, f64>((dense.get(row, col) - value).square())
+                    .unwrap();
+                norm += num::cast::<RealType<Item>, f64>(value.square()).unwrap();
+            }
+        }
+        (diff / norm).sqrt()
+    }
+
+    macro_rules! aca_test {
+        ($HScalar:ty, $imag:expr, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                // A Helmholtz-like kernel between points on two separated segments.
+                let (m, n) = (60, 40);
+                let kernel = |row: IndexType, col: IndexType| {
+                    let dist = 3.0 + row as f64 / m as f64 - col as f64 / n as f64;
+                    let value = num::cast::<f64, $HScalar>(1.0 / dist).unwrap();
+                    value
+                        * (num::cast::<f64, $HScalar>(1.0).unwrap()
+                            + $imag * num::cast::<f64, $HScalar>(dist).unwrap())
+                };
+                let mut mat = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(m, n);
+                for row in 0..m {
+                    for col in 0..n {
+                        *mat.get_mut(row, col) = kernel(row, col);
+                    }
+                }
+
+                for variant in [AcaVariant::PartialPivoting, AcaVariant::Plus] {
+                    let approx = Aca::new((m, n), kernel)
+                        .with_tol($tol)
+                        .with_variant(variant)
+                        .compute();
+                    assert!(approx.rank() < 20);
+                    assert!(relative_error(&approx, &mat) < 10.0 * $tol);
+
+                    let approx = Aca::from_matrix(&mat)
+                        .with_tol($tol)
+                        .with_variant(variant)
+                        .compute();
+                    assert!(relative_error(&approx, &mat) < 10.0 * $tol);
+                }
+            }
+        };
+    }
+
+    aca_test!(f32, 0.0, 1E-3, test_aca_f32);
+    aca_test!(f64, 0.0, 1E-8, test_aca_f64);
+    aca_test!(c32, c32::new(0.0, 1.0), 1E-3, test_aca_c32);
+    aca_test!(c64, c64::new(0.0, 1.0), 1E-8, test_aca_c64);
+
+    #[test]
+    fn test_aca_with_zero_rows() {
+        // A rank-2 matrix whose first rows and columns vanish.
+        let (m, n) = (30, 20);
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(m, n);
+        for row in 10..m {
+            for col in 5..n {
+                *mat.get_mut(row, col) = (row * col) as f64 + 1.0;
+            }
+        }
+        for variant in [AcaVariant::PartialPivoting, AcaVariant::Plus] {
+            let approx = Aca::from_matrix(&mat)
+                .with_tol(1E-12)
+                .with_variant(variant)
+                .compute();
+            // The stopping criterion requires one more cross than the exact rank.
+            assert!(approx.rank() <= 3);
+            assert!(relative_error(&approx, &mat) < 1E-12);
+        }
+
+        let zero = MatrixD::<f64, ColumnMajor>::zeros_from_dim(m, n);
+        for variant in [AcaVariant::PartialPivoting, AcaVariant::Plus] {
+            let approx = Aca::from_matrix(&zero).with_variant(variant).compute();
+            assert_eq!(approx.rank(), 0);
+            assert_eq!(approx.to_dense().get(3, 4), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_aca_max_rank() {
+        let kernel = |row: IndexType, col: IndexType| 1.0 / (1.0 + (row as f64 - col as f64).abs());
+        let approx = Aca::new((50, 50), kernel)
+            .with_tol(1E-14)
+            .with_max_rank(5)
+            .compute();
+        assert_eq!(approx.rank(), 5);
+    }
+
+    #[test]
+    fn test_low_rank_matvec() {
+        let (m, n, k) = (7, 5, 3);
+        let mut u = MatrixD::<c64, ColumnMajor>::zeros_from_dim(m, k);
+        let mut v = MatrixD::<c64, ColumnMajor>::zeros_from_dim(n, k);
+        for col in 0..k {
+            for row in 0..m {
+                *u.get_mut(row, col) = c64::new(row as f64, (col + 1) as f64);
+            }
+            for row in 0..n {
+                *v.get_mut(row, col) = c64::new(1.0, (row * col) as f64);
+            }
+        }
+        let mat = LowRankMatrix::new(u, v);
+        let dense = mat.to_dense();
+
+        for trans in [
+            TransposeMode::NoTrans,
+            TransposeMode::Trans,
+            TransposeMode::ConjTrans,
+        ] {
+            let (rows, cols) = if trans == TransposeMode::NoTrans {
+                (m, n)
+            } else {
+                (n, m)
+            };
+            let mut x = ColumnVectorD::<c64>::zeros_from_length(cols);
+            for index in 0..cols {
+                *x.get1d_mut(index) = c64::new(1.0, index as f64);
+            }
+            let mut y = ColumnVectorD::<c64>::zeros_from_length(rows);
+            y.for_each(|elem| *elem = c64::new(1.0, 1.0));
+            let (alpha, beta) = (c64::new(0.5, 1.0), c64::new(2.0, 0.0));
+            mat.matvec(trans, alpha, &x, beta, &mut y);
+
+            for row in 0..rows {
+                let mut expect = beta * c64::new(1.0, 1.0);
+                for col in 0..cols {
+                    let elem = match trans {
+                        TransposeMode::NoTrans => dense.get(row, col),
+                        TransposeMode::Trans => dense.get(col, row),
+                        TransposeMode::ConjTrans => dense.get(col, row).conj(),
+                    };
+                    expect += alpha * elem * x.get1d(col);
+                }
+                assert!((y.get1d(row) - expect).norm() < 1E-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_recompress() {
+        // A matrix of rank three with a small third singular value stored with four columns.
+        let (m, n) = (12, 9);
+        let mut u = MatrixD::<f64, ColumnMajor>::zeros_from_dim(m, 4);
+        let mut v = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, 4);
+        for row in 0..m {
+            let x = row as f64;
+            for (col, value) in [1.0, x, 2.0, x * x].iter().enumerate() {
+                *u.get_mut(row, col) = *value;
+            }
+        }
+        for row in 0..n {
+            let y = row as f64;
+            for (col, value) in [y, 1.0, -y, 1E-9 * y * y].iter().enumerate() {
+                *v.get_mut(row, col) = *value;
+            }
+        }
+        let mat = LowRankMatrix::new(u, v);
+        let dense = mat.to_dense();
+
+        let exact = mat.recompress(1E-14);
+        assert_eq!(exact.rank(), 3);
+        assert!(relative_error(&exact, &dense) < 1E-14);
+
+        let truncated = mat.recompress(1E-6);
+        assert_eq!(truncated.rank(), 2);
+        assert!(relative_error(&truncated, &dense) < 1E-6);
+    }
+}
@@ -65,6 +65,52 @@ macro_rules! rand_vector {
     }};
 }
 
+/// Assert that two matrices are equal up to an absolute difference. Optional arguments
+/// such as `epsilon = 1E-10` are passed on to [approx::AbsDiff]. On failure the first
+/// offending index and the two values are reported.
+#[macro_export]
+macro_rules! assert_matrix_abs_diff_eq {
+    ($left:expr, $right:expr $(, $opt:ident = $val:expr)* $(,)?) => {
+        $crate::comparison::assert_elementwise(
+            &$left,
+            &$right,
+            |x, y| $crate::comparison::AbsDiff::default()$(.$opt($val))*.eq(&x, &y),
+            stringify!(assert_matrix_abs_diff_eq!($left, $right $(, $opt = $val)*)),
+        )
+    };
+}
+
+/// Assert that two matrices are equal up to a relative difference. Optional arguments
+/// such as `epsilon = 1E-10` and `max_relative = 1E-8` are passed on to
+/// [approx::Relative]. On failure the first offending index and the two values are
+/// reported.
+#[macro_export]
+macro_rules! assert_matrix_relative_eq {
+    ($left:expr, $right:expr $(, $opt:ident = $val:expr)* $(,)?) => {
+        $crate::comparison::assert_elementwise(
+            &$left,
+            &$right,
+            |x, y| $crate::comparison::Relative::default()$(.$opt($val))*.eq(&x, &y),
+            stringify!(assert_matrix_relative_eq!($left, $right $(, $opt = $val)*)),
+        )
+    };
+}
+
+/// Assert that two matrices are equal up to a number of units in the last place.
+/// Optional arguments such as `max_ulps = 4` are passed on to [approx::Ulps]. On failure
+/// the first offending index and the two values are reported.
+#[macro_export]
+macro_rules! assert_matrix_ulps_eq {
+    ($left:expr, $right:expr $(, $opt:ident = $val:expr)* $(,)?) => {
+        $crate::comparison::assert_elementwise(
+            &$left,
+            &$right,
+            |x, y| $crate::comparison::Ulps::default()$(.$opt($val))*.eq(&x, &y),
+            stringify!(assert_matrix_ulps_eq!($left, $right $(, $opt = $val)*)),
+        )
+    };
+}
+
 #[cfg(test)]
 mod test {
 
@@ -0,0 +1,348 @@
+//! Elementwise maps of one or two matrices.
+//!
+//! This module defines the types [MapMat] and [ZipMapMat]. They represent the result of
+//! applying a closure to every element of a matrix, respectively to every pair of
+//! corresponding elements of two matrices. As for [Addition](crate::addition::Addition)
+//! the closure is only evaluated on element access, so that an expression such as
+//! `(a.map(f) + b) * 2.0` is evaluated in a single pass by `eval()` or `eval_into()`.
+//!
+//! The element type of the result is the return type of the closure. The two matrices of
+//! a zip map must have the same dimension and the same index layout.
+//!
+//! ```
+//! # use householder::*;
+//! let mut a = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 2);
+//! let mut b = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 2);
+//! *a.get_mut(0, 1) = 1.0;
+//! *b.get_mut(0, 1) = 3.0;
+//! let res = (Matrix::from_ref(&a).map(|x| x.exp()).zip_map(&b, |x, y| x * y) + &b).eval();
+//! assert_eq!(res.get(0, 1), 3.0 * 1f64.exp() + 3.0);
+//! assert_eq!(res.get(1, 0), 0.0);
+//! ```
+
+use crate::matrix::*;
+use crate::matrix_ref::MatrixRef;
+use crate::traits::*;
+use crate::types::*;
+use std::marker::PhantomData;
+
+/// A type that represents the elementwise map of a matrix.
+pub type MapMat<Item, Target, MatImpl, L, RS, CS, F> =
+    Matrix<Target, Map<Item, Target, MatImpl, L, RS, CS, F>, L, RS, CS>;
+
+/// A structure holding a matrix and a closure that is applied to its elements on access.
+pub struct Map<Item, Target, MatImpl, L, RS, CS, F>(
+    Matrix<Item, MatImpl, L, RS, CS>,
+    F,
+    PhantomData<Target>,
+)
+where
+    Item: HScalar,
+    Target: HScalar,
+    L: LayoutType,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+    MatImpl: MatrixTrait<Item, L, RS, CS>,
+    F: Fn(Item) -> Target;
+
+impl<
+        Item: HScalar,
+        Target: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+        F: Fn(Item) -> Target,
+    > Map<Item, Target, MatImpl, L, RS, CS, F>
+{
+    pub fn new(mat: Matrix<Item, MatImpl, L, RS, CS>, f: F) -> Self {
+        Self(mat, f, PhantomData)
+    }
+}
+
+impl<
+        Item: HScalar,
+        Target: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+        F: Fn(Item) -> Target,
+    > SizeType for Map<Item, Target, MatImpl, L, RS, CS, F>
+{
+    type R = RS;
+    type C = CS;
+}
+
+impl<
+        Item: HScalar,
+        Target: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+        F: Fn(Item) -> Target,
+    > Layout for Map<Item, Target, MatImpl, L, RS, CS, F>
+{
+    type Impl = L;
+
+    #[inline]
+    fn layout(&self) -> &Self::Impl {
+        self.0.layout()
+    }
+}
+
+impl<
+        Item: HScalar,
+        Target: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+        F: Fn(Item) -> Target,
+    > UnsafeRandomAccess for Map<Item, Target, MatImpl, L, RS, CS, F>
+{
+    type Item = Target;
+
+    #[inline]
+    unsafe fn get_unchecked(&self, row: IndexType, col: IndexType) -> Self::Item {
+        (self.1)(self.0.get_unchecked(row, col))
+    }
+
+    #[inline]
+    unsafe fn get1d_unchecked(&self, index: IndexType) -> Self::Item {
+        (self.1)(self.0.get1d_unchecked(index))
+    }
+}
+
+/// A type that represents the elementwise map of two matrices.
+pub type ZipMapMat<Item1, Item2, Target, MatImpl1, MatImpl2, B, L1, L2, RS, CS, F> = Matrix<
+    Target,
+    ZipMap<Item1, Item2, Target, MatImpl1, MatImpl2, B, L1, L2, RS, CS, F>,
+    B,
+    RS,
+    CS,
+>;
+
+/// A type that represents the elementwise map of a matrix and a reference to another matrix.
+pub type ZipMapRefMat<'a, Item1, Item2, Target, MatImpl1, MatImpl2, B, L1, L2, RS, CS, F> =
+    ZipMapMat<
+        Item1,
+        Item2,
+        Target,
+        MatImpl1,
+        MatrixRef<'a, Item2, MatImpl2, L2, RS, CS>,
+        B,
+        L1,
+        L2,
+        RS,
+        CS,
+        F,
+    >;
+
+/// A structure holding two matrices and a closure that is applied to pairs of their
+/// elements on access.
+pub struct ZipMap<Item1, Item2, Target, MatImpl1, MatImpl2, B, L1, L2, RS, CS, F>(
+    Matrix<Item1, MatImpl1, L1, RS, CS>,
+    Matrix<Item2, MatImpl2, L2, RS, CS>,
+    B,
+    F,
+    PhantomData<Target>,
+)
+where
+    Item1: HScalar,
+    Item2: HScalar,
+    Target: HScalar,
+    B: BaseLayoutType,
+    L1: LayoutType<IndexLayout = B>,
+    L2: LayoutType<IndexLayout = B>,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+    MatImpl1: MatrixTrait<Item1, L1, RS, CS>,
+    MatImpl2: MatrixTrait<Item2, L2, RS, CS>,
+    F: Fn(Item1, Item2) -> Target;
+
+impl<
+        Item1: HScalar,
+        Item2: HScalar,
+        Target: HScalar,
+        B: BaseLayoutType,
+        MatImpl1: MatrixTrait<Item1, L1, RS, CS>,
+        MatImpl2: MatrixTrait<Item2, L2, RS, CS>,
+        L1: LayoutType<IndexLayout = B>,
+        L2: LayoutType<IndexLayout = B>,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+        F: Fn(Item1, Item2) -> Target,
+    > ZipMap<Item1, Item2, Target, MatImpl1, MatImpl2, B, L1, L2, RS, CS, F>
+{
+    pub fn new(
+        mat1: Matrix<Item1, MatImpl1, L1, RS, CS>,
+        mat2: Matrix<Item2, MatImpl2, L2, RS, CS>,
+        f: F,
+    ) -> Self {
+        assert_eq!(
+            mat1.layout().dim(),
+            mat2.layout().dim(),
+            "Dimensions not identical in zip_map with a.dim() = {:#?}, b.dim() = {:#?}",
+            mat1.layout().dim(),
+            mat2.layout().dim()
+        );
+
+        let layout = mat1.layout().index_layout();
+        Self(mat1, mat2, layout, f, PhantomData)
+    }
+}
+
+impl<
+        Item1: HScalar,
+        Item2: HScalar,
+        Target: HScalar,
+        B: BaseLayoutType,
+        MatImpl1: MatrixTrait<Item1, L1, RS, CS>,
+        MatImpl2: MatrixTrait<Item2, L2, RS, CS>,
+        L1: LayoutType<IndexLayout = B>,
+        L2: LayoutType<IndexLayout = B>,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+        F: Fn(Item1, Item2) -> Target,
+    > Layout for ZipMap<Item1, Item2, Target, MatImpl1, MatImpl2, B, L1, L2, RS, CS, F>
+{
+    type Impl = B;
+
+    fn layout(&self) -> &Self::Impl {
+        &self.2
+    }
+}
+
+impl<
+        Item1: HScalar,
+        Item2: HScalar,
+        Target: HScalar,
+        B: BaseLayoutType,
+        MatImpl1: MatrixTrait<Item1, L1, RS, CS>,
+        MatImpl2: MatrixTrait<Item2, L2, RS, CS>,
+        L1: LayoutType<IndexLayout = B>,
+        L2: LayoutType<IndexLayout = B>,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+        F: Fn(Item1, Item2) -> Target,
+    > SizeType for ZipMap<Item1, Item2, Target, MatImpl1, MatImpl2, B, L1, L2, RS, CS, F>
+{
+    type R = RS;
+    type C = CS;
+}
+
+impl<
+        Item1: HScalar,
+        Item2: HScalar,
+        Target: HScalar,
+        B: BaseLayoutType,
+        MatImpl1: MatrixTrait<Item1, L1, RS, CS>,
+        MatImpl2: MatrixTrait<Item2, L2, RS, CS>,
+        L1: LayoutType<IndexLayout = B>,
+        L2: LayoutType<IndexLayout = B>,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+        F: Fn(Item1, Item2) -> Target,
+    > UnsafeRandomAccess
+    for ZipMap<Item1, Item2, Target, MatImpl1, MatImpl2, B, L1, L2, RS, CS, F>
+{
+    type Item = Target;
+
+    #[inline]
+    unsafe fn get_unchecked(&self, row: IndexType, col: IndexType) -> Self::Item {
+        (self.3)(
+            self.0.get_unchecked(row, col),
+            self.1.get_unchecked(row, col),
+        )
+    }
+
+    #[inline]
+    unsafe fn get1d_unchecked(&self, index: IndexType) -> Self::Item {
+        (self.3)(self.0.get1d_unchecked(index), self.1.get1d_unchecked(index))
+    }
+}
+
+impl<
+        Item: HScalar,
+        B: BaseLayoutType,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType<IndexLayout = B>,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > Matrix<Item, MatImpl, L, RS, CS>
+{
+    /// Apply `f` lazily to every element.
+    pub fn map<Target: HScalar, F: Fn(Item) -> Target>(
+        self,
+        f: F,
+    ) -> MapMat<Item, Target, MatImpl, L, RS, CS, F> {
+        Matrix::new(Map::new(self, f))
+    }
+
+    /// Apply `f` lazily to every pair of corresponding elements of `self` and `other`.
+    pub fn zip_map<
+        'a,
+        Item2: HScalar,
+        Target: HScalar,
+        MatImpl2: MatrixTrait<Item2, L2, RS, CS>,
+        L2: LayoutType<IndexLayout = B>,
+        F: Fn(Item, Item2) -> Target,
+    >(
+        self,
+        other: &'a Matrix<Item2, MatImpl2, L2, RS, CS>,
+        f: F,
+    ) -> ZipMapRefMat<'a, Item, Item2, Target, MatImpl, MatImpl2, B, L, L2, RS, CS, F> {
+        Matrix::new(ZipMap::new(self, Matrix::from_ref(other), f))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+
+    #[test]
+    fn test_map() {
+        let mut mat = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 3);
+        *mat.get_mut(1, 2) = 2.0;
+        let res = (2.0 * Matrix::from_ref(&mat).map(|x| x * x + 1.0)).eval();
+        assert_eq!(res.get(1, 2), 10.0);
+        assert_eq!(res.get(0, 0), 2.0);
+
+        // The element type may change and closures may capture their environment.
+        let shift = c32::new(0.0, 1.0);
+        let res = mat.map(|x| c32::new(x as f32, 0.0) + shift).eval();
+        assert_eq!(res.get(1, 2), c32::new(2.0, 1.0));
+    }
+
+    #[test]
+    fn test_zip_map() {
+        let mut a = MatrixD::<c64, ColumnMajor>::zeros_from_dim(3, 2);
+        let mut b = MatrixD::<f64, ColumnMajor>::zeros_from_dim(3, 2);
+        *a.get_mut(2, 1) = c64::new(1.0, 2.0);
+        *b.get_mut(2, 1) = 3.0;
+        *b.get_mut(0, 0) = -1.0;
+
+        let expr = Matrix::from_ref(&a).zip_map(&b, |x, y| x.mul_real(y)) + &a;
+        let mut res = MatrixD::<c64, ColumnMajor>::zeros_from_dim(3, 2);
+        expr.eval_into(&mut res);
+        assert_eq!(res.get(2, 1), c64::new(4.0, 8.0));
+        assert_eq!(res.get(0, 0), c64::new(0.0, 0.0));
+
+        // Zip maps of lazy expressions.
+        let res = (&b + &b).zip_map(&b, |x, y| x.max(y)).eval();
+        assert_eq!(res.get(0, 0), -1.0);
+        assert_eq!(res.get(2, 1), 6.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zip_map_dimension_mismatch() {
+        let a = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 3);
+        let b = MatrixD::<f64, RowMajor>::zeros_from_dim(3, 2);
+        let _ = a.zip_map(&b, |x, y| x + y);
+    }
+}
@@ -8,21 +8,23 @@
 //! implements [MatrixTrait] or [MatrixTraitMut].
 //! A matrix is generic over the following parameters:
 //! - `Item`. Implements the [HScalar] trait and represents the underlying scalar type
-//!           of the matrix.
+//!   of the matrix.
 //! - `MatImpl`. The actual implementation of the matrix. It must itself implement the
-//!              trait [MatrixTrait] or [MatrixTraitMut] depending on whether mutable access
-//!              is required.
+//!   trait [MatrixTrait] or [MatrixTraitMut] depending on whether mutable access
+//!   is required.
 //! - `L`. A given type that implements the [LayoutType] trait and specifies the memory layout
-//!        of the matrix.
+//!   of the matrix.
 //! - `RS`. A type that implements [SizeType] and specifies whether the row dimension is known
-//!         at compile time or dynamically at runtime.
+//!   at compile time or dynamically at runtime.
 //! - `CS`. A type that implements [SizeType]  and specifies whether the column dimension is
-//!         known at compile time or dynamically at runtime.
+//!   known at compile time or dynamically at runtime.
 
+pub mod band_storage;
 pub mod base_methods;
 pub mod common_impl;
 pub mod constructors;
 pub mod matrix_slices;
+pub mod packed;
 pub mod random;
 
 use crate::base_matrix::BaseMatrix;
@@ -70,6 +72,51 @@ pub type UpperTriangularMatrix<Item> = Matrix<
     Dynamic,
 >;
 
+/// A dynamic lower triangular matrix.
+pub type LowerTriangularMatrix<Item> = Matrix<
+    Item,
+    BaseMatrix<Item, VectorContainer<Item>, LowerTriangular, Dynamic, Dynamic>,
+    LowerTriangular,
+    Dynamic,
+    Dynamic,
+>;
+
+/// A dynamic symmetric matrix in packed storage.
+pub type SymmetricMatrix<Item> = Matrix<
+    Item,
+    BaseMatrix<Item, VectorContainer<Item>, SymmetricPacked, Dynamic, Dynamic>,
+    SymmetricPacked,
+    Dynamic,
+    Dynamic,
+>;
+
+/// A dynamic Hermitian matrix in packed storage.
+pub type HermitianMatrix<Item> = Matrix<
+    Item,
+    BaseMatrix<Item, VectorContainer<Item>, HermitianPacked, Dynamic, Dynamic>,
+    HermitianPacked,
+    Dynamic,
+    Dynamic,
+>;
+
+/// A dynamic banded matrix in Lapack band storage.
+pub type BandedMatrix<Item> = Matrix<
+    Item,
+    BaseMatrix<Item, VectorContainer<Item>, Banded, Dynamic, Dynamic>,
+    Banded,
+    Dynamic,
+    Dynamic,
+>;
+
+/// A dynamic tridiagonal matrix in Lapack band storage.
+pub type TridiagonalMatrix<Item> = Matrix<
+    Item,
+    BaseMatrix<Item, VectorContainer<Item>, Tridiagonal, Dynamic, Dynamic>,
+    Tridiagonal,
+    Dynamic,
+    Dynamic,
+>;
+
 /// A dynamic column vector. This means that the row dimension is dynamic and the column
 /// dimension is [Fixed1].
 pub type ColumnVectorD<Item> =
@@ -0,0 +1,94 @@
+//! Construction of banded and tridiagonal matrices and conversions to and from dense matrices.
+
+use crate::data_container::VectorContainer;
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::{HScalar, IndexType};
+
+impl<Item: HScalar> BandedMatrix<Item> {
+    /// Create a new zero banded matrix with `kl` subdiagonals and `ku` superdiagonals.
+    pub fn zeros_from_band(rows: IndexType, cols: IndexType, kl: IndexType, ku: IndexType) -> Self {
+        let layout = Banded::new((rows, cols), kl, ku);
+        Self::from_data(VectorContainer::<Item>::new(layout.raw_size()), layout)
+    }
+
+    /// Create a new banded matrix from the band of a given matrix. Elements
+    /// outside the band are ignored.
+    pub fn from_dense<MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>, L: LayoutType>(
+        mat: &Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+        kl: IndexType,
+        ku: IndexType,
+    ) -> Self {
+        let dim = mat.layout().dim();
+        let mut result = Self::zeros_from_band(dim.0, dim.1, kl, ku);
+        for col in 0..dim.1 {
+            for row in col.saturating_sub(ku)..dim.0.min(col + kl + 1) {
+                unsafe { *result.get_unchecked_mut(row, col) = mat.get_unchecked(row, col) };
+            }
+        }
+        result
+    }
+
+    /// Convert into a new dense matrix with layout `L`.
+    pub fn to_dense<L: BaseLayoutType>(&self) -> MatrixD<Item, L> {
+        let dim = self.layout().dim();
+        let (kl, ku) = (self.layout().kl(), self.layout().ku());
+        let mut result = MatrixD::<Item, L>::zeros_from_dim(dim.0, dim.1);
+        for col in 0..dim.1 {
+            for row in col.saturating_sub(ku)..dim.0.min(col + kl + 1) {
+                unsafe { *result.get_unchecked_mut(row, col) = self.get_unchecked(row, col) };
+            }
+        }
+        result
+    }
+}
+
+impl<Item: HScalar> TridiagonalMatrix<Item> {
+    /// Create a new tridiagonal matrix from its subdiagonal `sub`, diagonal `diag`
+    /// and superdiagonal `sup`.
+    pub fn from_diagonals(sub: &[Item], diag: &[Item], sup: &[Item]) -> Self {
+        let n = diag.len();
+        assert!(
+            (sub.len() + 1 == n) & (sup.len() + 1 == n),
+            "Off-diagonals must have one element less than the diagonal: {}, {}, {}",
+            sub.len(),
+            n,
+            sup.len()
+        );
+        let mut result = Self::zeros_from_dim(n, n);
+        for (index, &value) in diag.iter().enumerate() {
+            *result.get_mut(index, index) = value;
+        }
+        for index in 0..n.saturating_sub(1) {
+            *result.get_mut(index + 1, index) = sub[index];
+            *result.get_mut(index, index + 1) = sup[index];
+        }
+        result
+    }
+
+    /// Create a new tridiagonal matrix from the tridiagonal part of a given square matrix.
+    pub fn from_dense<MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>, L: LayoutType>(
+        mat: &Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+    ) -> Self {
+        let dim = mat.layout().dim();
+        let mut result = Self::zeros_from_dim(dim.0, dim.1);
+        for col in 0..dim.1 {
+            for row in col.saturating_sub(1)..dim.0.min(col + 2) {
+                unsafe { *result.get_unchecked_mut(row, col) = mat.get_unchecked(row, col) };
+            }
+        }
+        result
+    }
+
+    /// Convert into a new dense matrix with layout `L`.
+    pub fn to_dense<L: BaseLayoutType>(&self) -> MatrixD<Item, L> {
+        let n = self.layout().dim().0;
+        let mut result = MatrixD::<Item, L>::zeros_from_dim(n, n);
+        for col in 0..n {
+            for row in col.saturating_sub(1)..n.min(col + 2) {
+                unsafe { *result.get_unchecked_mut(row, col) = self.get_unchecked(row, col) };
+            }
+        }
+        result
+    }
+}
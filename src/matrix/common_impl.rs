@@ -77,9 +77,9 @@ impl<Item: HScalar, L: LayoutType, MatImpl: MatrixTrait<Item, L, Dynamic, Dynami
 {
     /// Evaluate into a new matrix.
     pub fn eval(&self) -> MatrixD<Item, <L as LayoutType>::IndexLayout> {
-        let dim = self.layout().dim();
-        let mut result =
-            MatrixD::<Item, <L as LayoutType>::IndexLayout>::zeros_from_dim(dim.0, dim.1);
+        let mut result = MatrixD::<Item, <L as LayoutType>::IndexLayout>::zeros_from_layout(
+            self.layout().index_layout(),
+        );
         unsafe {
             for index in 0..self.layout().number_of_elements() {
                 *result.get1d_unchecked_mut(index) = self.get1d_unchecked(index);
@@ -89,6 +89,37 @@ impl<Item: HScalar, L: LayoutType, MatImpl: MatrixTrait<Item, L, Dynamic, Dynami
     }
 }
 
+impl<
+        Item: HScalar,
+        L: LayoutType,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > Matrix<Item, MatImpl, L, RS, CS>
+{
+    /// Evaluate into an existing matrix with the same dimension and index layout.
+    pub fn eval_into<
+        MatImpl2: MatrixTraitMut<Item, L2, RS, CS>,
+        L2: LayoutType<IndexLayout = <L as LayoutType>::IndexLayout>,
+    >(
+        &self,
+        target: &mut Matrix<Item, MatImpl2, L2, RS, CS>,
+    ) {
+        assert_eq!(
+            self.layout().dim(),
+            target.layout().dim(),
+            "Dimensions not identical in eval_into with self.dim() = {:#?}, target.dim() = {:#?}",
+            self.layout().dim(),
+            target.layout().dim()
+        );
+        unsafe {
+            for index in 0..self.layout().number_of_elements() {
+                *target.get1d_unchecked_mut(index) = self.get1d_unchecked(index);
+            }
+        }
+    }
+}
+
 impl<
         Item: HScalar,
         L: LayoutType,
@@ -72,11 +72,12 @@ impl<Item: HScalar, L: BaseLayoutType>
 {
     /// Create a new zero matrix with given number of rows and columns.
     pub fn zeros_from_dim(rows: IndexType, cols: IndexType) -> Self {
-        let layout = L::from_dimension((rows, cols));
-        Self::from_data(
-            VectorContainer::<Item>::new(layout.number_of_elements()),
-            L::from_dimension((rows, cols)),
-        )
+        Self::zeros_from_layout(L::from_dimension((rows, cols)))
+    }
+
+    /// Create a new zero matrix with a given layout.
+    pub fn zeros_from_layout(layout: L) -> Self {
+        Self::from_data(VectorContainer::<Item>::new(layout.raw_size()), layout)
     }
 }
 
@@ -132,6 +133,10 @@ macro_rules! from_pointer_strided {
     ($RS:ident, $CS:ident, $L:ident) => {
         impl<'a, Item: HScalar> SliceMatrixMut<'a, Item, $L, $RS, $CS> {
             /// Create a new mutable matrix by specifying a pointer, dimension and stride tuple.
+            ///
+            /// # Safety
+            /// `ptr` must point to a memory region that is valid for all elements
+            /// addressed by the given dimension (and stride) for the lifetime `'a`.
             pub unsafe fn from_pointer(
                 ptr: *mut Item,
                 dim: (IndexType, IndexType),
@@ -148,6 +153,10 @@ macro_rules! from_pointer_strided {
 
         impl<'a, Item: HScalar> SliceMatrix<'a, Item, $L, $RS, $CS> {
             /// Create a new matrix by specifying a pointer, dimension and stride tuple.
+            ///
+            /// # Safety
+            /// `ptr` must point to a memory region that is valid for all elements
+            /// addressed by the given dimension (and stride) for the lifetime `'a`.
             pub unsafe fn from_pointer(
                 ptr: *const Item,
                 dim: (IndexType, IndexType),
@@ -168,6 +177,10 @@ macro_rules! from_pointer {
     ($RS:ident, $CS:ident, $L:ident) => {
         impl<'a, Item: HScalar> SliceMatrixMut<'a, Item, $L, $RS, $CS> {
             /// Create a new mutable matrix from a given pointer and dimension.
+            ///
+            /// # Safety
+            /// `ptr` must point to a memory region that is valid for all elements
+            /// addressed by the given dimension (and stride) for the lifetime `'a`.
             pub unsafe fn from_pointer(ptr: *mut Item, dim: (IndexType, IndexType)) -> Self {
                 let new_layout = $L::new(dim);
                 let nindices = dim.0 * dim.1;
@@ -180,6 +193,10 @@ macro_rules! from_pointer {
 
         impl<'a, Item: HScalar> SliceMatrix<'a, Item, $L, $RS, $CS> {
             /// Create a new matrix from a given pointer and dimension.
+            ///
+            /// # Safety
+            /// `ptr` must point to a memory region that is valid for all elements
+            /// addressed by the given dimension (and stride) for the lifetime `'a`.
             pub unsafe fn from_pointer(ptr: *const Item, dim: (IndexType, IndexType)) -> Self {
                 let new_layout = $L::new(dim);
                 let nindices = dim.0 * dim.1;
@@ -165,6 +165,7 @@ mod test {
 
     use super::*;
     use crate::matrix::*;
+    use crate::matrix_multiply::Dot;
     use crate::rand_mat;
 
     #[test]
@@ -203,4 +204,38 @@ mod test {
         assert_eq!(mat.get(7, 1), 4.0);
         assert_eq!(mat.get(9, 7), 5.0);
     }
+
+    #[test]
+    fn test_block_stride() {
+        // The stride of a block is the stride of the parent and not derived from the
+        // dimension of the block.
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(4, 5);
+        let mut row_mat = MatrixD::<f64, RowMajor>::zeros_from_dim(4, 5);
+        for col in 0..5 {
+            for row in 0..4 {
+                *mat.get_mut(row, col) = (row + 10 * col) as f64;
+                *row_mat.get_mut(row, col) = (row + 10 * col) as f64;
+            }
+        }
+
+        let block = mat.block((1, 1), (2, 3));
+        assert_eq!(block.layout().stride(), (1, 4));
+        let inner = block.block((1, 1), (1, 2));
+        assert_eq!(inner.layout().stride(), (1, 4));
+        assert_eq!(inner.get(0, 1), 32.0);
+
+        let row_block = row_mat.block((1, 1), (2, 3));
+        assert_eq!(row_block.layout().stride(), (5, 1));
+        let inner = row_block.block((1, 1), (1, 2));
+        assert_eq!(inner.get(0, 1), 32.0);
+
+        // The product reads the block through its stride.
+        let mut ones = MatrixD::<f64, ColumnMajor>::zeros_from_dim(3, 1);
+        ones.for_each(|elem| *elem = 1.0);
+        let product = block.dot(&ones);
+        assert_eq!(product.get(0, 0), 3.0 + 60.0);
+        assert_eq!(product.get(1, 0), 6.0 + 60.0);
+        let product = row_block.dot(&ones);
+        assert_eq!(product.get(1, 0), 6.0 + 60.0);
+    }
 }
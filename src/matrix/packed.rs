@@ -0,0 +1,151 @@
+//! Conversions between packed structured matrices and dense matrices.
+//!
+//! Triangular, symmetric and Hermitian matrices can be stored in packed layouts that
+//! only keep one triangular part in memory. This module provides conversions of these
+//! matrices from and to dense [MatrixD] types. A conversion from a dense matrix only
+//! reads the triangular part that is stored in the packed layout. A conversion to a
+//! dense matrix fills in the zeros of triangular matrices and the mirrored elements of
+//! symmetric and Hermitian matrices.
+
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::{HScalar, IndexType};
+
+macro_rules! packed_conversions {
+    ($MatrixType:ident, $stored:expr, $filled:expr) => {
+        impl<Item: HScalar> $MatrixType<Item> {
+            /// Create a new packed matrix from the stored triangular part of a square matrix.
+            pub fn from_dense<MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>, L: LayoutType>(
+                mat: &Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+            ) -> Self {
+                let dim = mat.layout().dim();
+                assert_eq!(
+                    dim.0, dim.1,
+                    "Only square matrices can be converted to packed storage. dim = {:#?}",
+                    dim
+                );
+                let mut result = Self::zeros_from_dim(dim.0, dim.1);
+                let stored: fn(IndexType, IndexType) -> bool = $stored;
+                for row in 0..dim.0 {
+                    for col in 0..dim.1 {
+                        if stored(row, col) {
+                            unsafe {
+                                *result.get_unchecked_mut(row, col) = mat.get_unchecked(row, col)
+                            };
+                        }
+                    }
+                }
+                result
+            }
+
+            /// Convert into a new dense matrix with layout `L`.
+            pub fn to_dense<L: BaseLayoutType>(&self) -> MatrixD<Item, L> {
+                let dim = self.layout().dim();
+                let mut result = MatrixD::<Item, L>::zeros_from_dim(dim.0, dim.1);
+                let filled: fn(IndexType, IndexType) -> bool = $filled;
+                for row in 0..dim.0 {
+                    for col in 0..dim.1 {
+                        if filled(row, col) {
+                            unsafe {
+                                *result.get_unchecked_mut(row, col) = self.get_unchecked(row, col)
+                            };
+                        }
+                    }
+                }
+                result
+            }
+        }
+    };
+}
+
+// The first closure marks the elements that are stored in the packed layout, the second
+// closure the elements of the dense matrix that are filled from the packed matrix.
+packed_conversions!(UpperTriangularMatrix, |row, col| row <= col, |row, col| row
+    <= col);
+packed_conversions!(LowerTriangularMatrix, |row, col| row >= col, |row, col| row
+    >= col);
+packed_conversions!(SymmetricMatrix, |row, col| row >= col, |_row, _col| true);
+packed_conversions!(HermitianMatrix, |row, col| row >= col, |_row, _col| true);
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::types::c64;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_triangular_conversion() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(4, 4);
+        mat.fill_from_rand_standard_normal(&mut rng);
+
+        let lower = LowerTriangularMatrix::<f64>::from_dense(&mat);
+        let upper = UpperTriangularMatrix::<f64>::from_dense(&mat);
+        assert_eq!(lower.layout().number_of_elements(), 10);
+
+        let lower_dense = lower.to_dense::<RowMajor>();
+        let upper_dense = upper.to_dense::<RowMajor>();
+
+        for row in 0..4 {
+            for col in 0..4 {
+                let (expect_lower, expect_upper) = if row == col {
+                    (mat.get(row, col), mat.get(row, col))
+                } else if row > col {
+                    (mat.get(row, col), 0.0)
+                } else {
+                    (0.0, mat.get(row, col))
+                };
+                assert_eq!(lower_dense.get(row, col), expect_lower);
+                assert_eq!(upper_dense.get(row, col), expect_upper);
+            }
+        }
+    }
+
+    #[test]
+    fn test_symmetric_mirrors_elements() {
+        let mut sym = SymmetricMatrix::<f64>::zeros_from_dim(3, 3);
+        *sym.get_mut(2, 0) = 5.0;
+        *sym.get_mut(0, 2) = 4.0;
+
+        assert_eq!(sym.get(2, 0), 4.0);
+        assert_eq!(sym.get(0, 2), 4.0);
+
+        let dense = sym.to_dense::<ColumnMajor>();
+        let roundtrip = SymmetricMatrix::<f64>::from_dense(&dense);
+        for index in 0..sym.layout().number_of_elements() {
+            assert_eq!(roundtrip.get1d(index), sym.get1d(index));
+        }
+    }
+
+    #[test]
+    fn test_hermitian_conjugates_elements() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut mat = MatrixD::<c64, RowMajor>::zeros_from_dim(4, 4);
+        mat.fill_from_rand_standard_normal(&mut rng);
+
+        let herm = HermitianMatrix::<c64>::from_dense(&mat);
+        for row in 0..4 {
+            assert_eq!(herm.get(row, row), mat.get(row, row));
+            for col in 0..row {
+                assert_eq!(herm.get(row, col), mat.get(row, col));
+                assert_eq!(herm.get(col, row), mat.get(row, col).conj());
+            }
+        }
+
+        // Evaluating the lazy expression keeps the Hermitian structure.
+        let scaled = (c64::new(2.0, 0.0) * &herm).eval();
+        assert_eq!(scaled.get(0, 3), c64::new(2.0, 0.0) * mat.get(3, 0).conj());
+
+        let dense = herm.to_dense::<RowMajor>();
+        assert_eq!(dense.get(1, 3), mat.get(3, 1).conj());
+    }
+
+    #[test]
+    #[should_panic(expected = "Element (0, 1) is the conjugate of a stored element")]
+    fn test_hermitian_rejects_conjugated_write() {
+        let mut herm = HermitianMatrix::<c64>::zeros_from_dim(2, 2);
+        *herm.get_mut(1, 0) = c64::new(1.0, -1.0);
+        *herm.get_mut(0, 1) = c64::new(1.0, 1.0);
+    }
+}
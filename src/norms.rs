@@ -0,0 +1,434 @@
+//! Matrix norms and condition number estimation.
+//!
+//! The following norms are defined for every [Matrix], including lazy expressions such
+//! as sums and scalar multiples of matrices and matrices with band or triangular layouts.
+//!
+//! - `norm_1`: the maximum absolute column sum.
+//! - `norm_inf`: the maximum absolute row sum.
+//! - `norm_fro`: the Frobenius norm.
+//! - `norm_max`: the largest absolute value of an element. This is not a submultiplicative
+//!   matrix norm.
+//! - `norm_2`: the spectral norm, i.e. the largest singular value. It is computed exactly
+//!   from a singular value decomposition and therefore considerably more expensive.
+//!
+//! The reciprocal condition number `1 / (||A||_1 * ||A^{-1}||_1)` of a factorised matrix
+//! is estimated with the method of Hager and Higham (Lapack `lacn2`) by the `rcond`
+//! methods of [DenseLu](crate::dense_solvers::DenseLu),
+//! [DenseCholesky](crate::dense_solvers::DenseCholesky),
+//! [BandedLu](crate::band_solvers::BandedLu) and
+//! [TridiagonalLdlt](crate::band_solvers::TridiagonalLdlt). The estimator requires only
+//! a few solves with the existing factorisation and `||A||_1` must be supplied by the
+//! caller, e.g. through `norm_1`. The estimate of `||A^{-1}||_1` is a lower bound and
+//! almost always correct within a factor of 3.
+//!
+//! The `solve_checked` methods of the factorisations return a
+//! [LinAlgError::IllConditioned] instead of solving if the estimate is below a given
+//! threshold. Otherwise they solve and return the estimate, so that a caller who
+//! only wants to warn can pass a threshold of zero and inspect the result.
+//!
+//! ```
+//! # use householder::*;
+//! let mut mat = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 2);
+//! *mat.get_mut(0, 0) = 1.0;
+//! *mat.get_mut(0, 1) = 1.0;
+//! *mat.get_mut(1, 1) = 1E-10;
+//! assert_eq!(mat.norm_inf(), 2.0);
+//! let lu = mat.lu().unwrap();
+//! let rcond = lu.rcond(mat.norm_1());
+//! assert!(rcond < 1E-9);
+//! let mut rhs = ColumnVectorD::<f64>::zeros_from_length(2);
+//! assert!(lu.solve_checked(TransposeMode::NoTrans, &mut rhs, mat.norm_1(), 1E-8).is_err());
+//! ```
+
+use crate::dense_solvers::{adjoint, jacobi_svd};
+use crate::error::LinAlgError;
+use crate::layouts::*;
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::*;
+use num::Float;
+use std::cmp::Ordering;
+
+impl<
+        Item: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > Matrix<Item, MatImpl, L, RS, CS>
+{
+    /// Return the element `(row, col)`, which is zero if it is not stored by the layout.
+    pub(crate) fn element(&self, row: IndexType, col: IndexType) -> Item {
+        if self.layout().is_stored(row, col) {
+            unsafe { self.get_unchecked(row, col) }
+        } else {
+            Item::zero()
+        }
+    }
+
+    /// Return the maximum absolute column sum.
+    pub fn norm_1(&self) -> <Item as Scalar>::Real {
+        let (m, n) = self.layout().dim();
+        (0..n)
+            .map(|col| {
+                (0..m).fold(num::Zero::zero(), |acc, row| {
+                    acc + self.element(row, col).abs()
+                })
+            })
+            .fold(num::Zero::zero(), Float::max)
+    }
+
+    /// Return the maximum absolute row sum.
+    pub fn norm_inf(&self) -> <Item as Scalar>::Real {
+        let (m, n) = self.layout().dim();
+        (0..m)
+            .map(|row| {
+                (0..n).fold(num::Zero::zero(), |acc, col| {
+                    acc + self.element(row, col).abs()
+                })
+            })
+            .fold(num::Zero::zero(), Float::max)
+    }
+
+    /// Return the Frobenius norm.
+    pub fn norm_fro(&self) -> <Item as Scalar>::Real {
+        // Scale by the largest absolute value to avoid overflow and underflow.
+        let scale = self.norm_max();
+        if scale == num::Zero::zero() {
+            return scale;
+        }
+        let (m, n) = self.layout().dim();
+        let mut sum = <<Item as Scalar>::Real as num::Zero>::zero();
+        for col in 0..n {
+            for row in 0..m {
+                let value = self.element(row, col).abs() / scale;
+                sum += value * value;
+            }
+        }
+        scale * Float::sqrt(sum)
+    }
+
+    /// Return the largest absolute value of an element.
+    pub fn norm_max(&self) -> <Item as Scalar>::Real {
+        let (m, n) = self.layout().dim();
+        let mut res = <<Item as Scalar>::Real as num::Zero>::zero();
+        for col in 0..n {
+            for row in 0..m {
+                res = res.max(self.element(row, col).abs());
+            }
+        }
+        res
+    }
+
+    /// Return the spectral norm, i.e. the largest singular value.
+    pub fn norm_2(&self) -> <Item as Scalar>::Real {
+        let (m, n) = self.layout().dim();
+        let mut mat = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, n);
+        for col in 0..n {
+            for row in 0..m {
+                unsafe { *mat.get_unchecked_mut(row, col) = self.element(row, col) };
+            }
+        }
+        let (_, values, _) = if m >= n {
+            jacobi_svd(&mat)
+        } else {
+            jacobi_svd(&adjoint(&mat))
+        };
+        values.first().copied().unwrap_or(num::Zero::zero())
+    }
+}
+
+/// Estimate `||A^{-1}||_1` for an `n x n` matrix `A` from a callable that overwrites a
+/// vector `x` with `op(A)^{-1} * x`.
+pub(crate) fn inverse_norm_1_estimate<Item: HScalar>(
+    n: IndexType,
+    mut solve: impl FnMut(TransposeMode, &mut ColumnVectorD<Item>),
+) -> <Item as Scalar>::Real {
+    type Real<Item> = <Item as Scalar>::Real;
+    let zero = <Real<Item> as num::Zero>::zero();
+    let one = <Real<Item> as num::One>::one();
+    if n == 0 {
+        return zero;
+    }
+    let norm_1 =
+        |x: &ColumnVectorD<Item>| (0..n).fold(zero, |acc, index| acc + x.get1d(index).abs());
+    let n_real = num::cast::<IndexType, Real<Item>>(n).unwrap();
+
+    let mut x = ColumnVectorD::<Item>::zeros_from_length(n);
+    x.for_each(|elem| *elem = Item::from_real(one / n_real));
+    solve(TransposeMode::NoTrans, &mut x);
+    let mut estimate = norm_1(&x);
+    if n == 1 {
+        return estimate;
+    }
+
+    let mut last: Option<IndexType> = None;
+    for _ in 0..5 {
+        // Solve with the sign vector of x and move to the largest entry of the result.
+        x.for_each(|elem| {
+            let value = elem.abs();
+            *elem = if value == zero {
+                Item::one()
+            } else {
+                *elem / Item::from_real(value)
+            }
+        });
+        solve(TransposeMode::ConjTrans, &mut x);
+        let index = (0..n)
+            .max_by(|&first, &second| nan_first_cmp(x.get1d(first).abs(), x.get1d(second).abs()))
+            .unwrap();
+        if last.is_some_and(|last| x.get1d(index).abs() <= x.get1d(last).abs()) {
+            break;
+        }
+        last = Some(index);
+
+        x.for_each(|elem| *elem = Item::zero());
+        *x.get1d_mut(index) = Item::one();
+        solve(TransposeMode::NoTrans, &mut x);
+        let new_estimate = norm_1(&x);
+        if new_estimate <= estimate {
+            break;
+        }
+        estimate = new_estimate;
+    }
+
+    // Higham's alternative estimate guards against special matrices that defeat the
+    // iteration above.
+    for index in 0..n {
+        let value = one + num::cast::<IndexType, Real<Item>>(index).unwrap() / (n_real - one);
+        *x.get1d_mut(index) = Item::from_real(if index % 2 == 0 { value } else { -value });
+    }
+    solve(TransposeMode::NoTrans, &mut x);
+    let two = one + one;
+    estimate.max(two * norm_1(&x) / (two * n_real + n_real))
+}
+
+/// Return the reciprocal condition number from `||A||_1` and the estimate of `||A^{-1}||_1`.
+pub(crate) fn reciprocal_condition<Real: Float>(anorm: Real, inverse_norm: Real) -> Real {
+    if anorm == Real::zero() || inverse_norm == Real::zero() {
+        Real::zero()
+    } else {
+        Real::one() / anorm / inverse_norm
+    }
+}
+
+/// Compare two real numbers such that `NaN` is smaller than every other value. A
+/// maximum with respect to this order is only `NaN` if all values are `NaN`.
+pub(crate) fn nan_first_cmp<Real: Float>(first: Real, second: Real) -> Ordering {
+    first
+        .partial_cmp(&second)
+        .unwrap_or_else(|| second.is_nan().cmp(&first.is_nan()))
+}
+
+/// Return `Ok(rcond)` if `rcond` is at least `threshold` and an error otherwise.
+pub(crate) fn check_condition<Real: Float>(
+    rcond: Real,
+    threshold: Real,
+) -> Result<Real, LinAlgError> {
+    if rcond < threshold || rcond.is_nan() {
+        Err(LinAlgError::IllConditioned(rcond.to_f64().unwrap()))
+    } else {
+        Ok(rcond)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use rand::prelude::*;
+
+    /// Return `||A^{-1}||_1` computed column by column from a solver with `A`.
+    fn exact_inverse_norm<Item: HScalar>(
+        n: IndexType,
+        solve: impl Fn(&mut ColumnVectorD<Item>),
+    ) -> f64 {
+        (0..n)
+            .map(|col| {
+                let mut x = ColumnVectorD::<Item>::zeros_from_length(n);
+                *x.get1d_mut(col) = Item::one();
+                solve(&mut x);
+                (0..n).fold(0.0, |acc, row| {
+                    acc + num::cast::<_, f64>(x.get1d(row).abs()).unwrap()
+                })
+            })
+            .fold(0.0, f64::max)
+    }
+
+    macro_rules! rcond_test {
+        ($HScalar:ty, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let n = 12;
+                let mut rng = StdRng::seed_from_u64(0);
+                let mut mat = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, n);
+                mat.fill_from_rand_standard_normal(&mut rng);
+                let anorm = mat.norm_1();
+                let lu = mat.lu().unwrap();
+                let exact = exact_inverse_norm(n, |x| lu.solve(TransposeMode::NoTrans, x));
+                let estimate = 1.0 / num::cast::<_, f64>(lu.rcond(anorm) * anorm).unwrap();
+                assert!(estimate <= exact * (1.0 + 1E-4));
+                assert!(estimate >= exact / 3.0);
+
+                let mut rhs = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                rhs.fill_from_rand_standard_normal(&mut rng);
+                let rcond = lu
+                    .solve_checked(TransposeMode::NoTrans, &mut rhs, anorm, num::Zero::zero())
+                    .unwrap();
+                assert_eq!(rcond, lu.rcond(anorm));
+            }
+        };
+    }
+
+    rcond_test!(f32, test_rcond_f32);
+    rcond_test!(f64, test_rcond_f64);
+    rcond_test!(c32, test_rcond_c32);
+    rcond_test!(c64, test_rcond_c64);
+
+    #[test]
+    fn test_ill_conditioned_hilbert_matrix() {
+        let n = 10;
+        let mut mat = MatrixD::<f64, RowMajor>::zeros_from_dim(n, n);
+        for col in 0..n {
+            for row in 0..n {
+                *mat.get_mut(row, col) = 1.0 / (1.0 + row as f64 + col as f64);
+            }
+        }
+        let lu = mat.lu().unwrap();
+        let rcond = lu.rcond(mat.norm_1());
+        // The 1-norm condition number of the Hilbert matrix of order 10 is about 3.5E13.
+        assert!((1E-14..1E-13).contains(&rcond));
+
+        let mut rhs = ColumnVectorD::<f64>::zeros_from_length(n);
+        *rhs.get1d_mut(0) = 1.0;
+        assert_eq!(
+            lu.solve_checked(TransposeMode::NoTrans, &mut rhs, mat.norm_1(), 1E-8),
+            Err(LinAlgError::IllConditioned(rcond))
+        );
+        assert_eq!(rhs.get1d(0), 1.0);
+    }
+
+    #[test]
+    fn test_rcond_of_band_factorisations() {
+        // The second difference matrix with ||A^{-1}||_1 growing like n^2 / 8.
+        let n = 40;
+        let sub = vec![-1.0; n - 1];
+        let diag = vec![2.0; n];
+        let mat = TridiagonalMatrix::<f64>::from_diagonals(&sub, &diag, &sub);
+        let anorm = mat.norm_1();
+        assert_eq!(anorm, 4.0);
+
+        let ldlt = mat.ldlt().unwrap();
+        let exact = exact_inverse_norm(n, |x| ldlt.solve(x));
+        assert!((1.0 / (ldlt.rcond(anorm) * anorm) - exact).abs() < 1E-10 * exact);
+
+        let mut dense = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+        for col in 0..n {
+            for row in 0..n {
+                *dense.get_mut(row, col) = if mat.layout().is_stored(row, col) {
+                    mat.get(row, col)
+                } else {
+                    0.0
+                };
+            }
+        }
+        let lu = BandedMatrix::<f64>::from_dense(&dense, 1, 1).lu().unwrap();
+        assert!((1.0 / (lu.rcond(anorm) * anorm) - exact).abs() < 1E-10 * exact);
+
+        let mut rhs = ColumnVectorD::<f64>::zeros_from_length(n);
+        assert!(ldlt.solve_checked(&mut rhs, anorm, 1E-2).is_err());
+        assert!(lu
+            .solve_checked(TransposeMode::NoTrans, &mut rhs, anorm, 1E-4)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_rcond_of_cholesky() {
+        // The second difference matrix with ||A^{-1}||_1 growing like n^2 / 8.
+        let n = 40;
+        let mut mat = MatrixD::<c64, ColumnMajor>::zeros_from_dim(n, n);
+        for index in 0..n {
+            *mat.get_mut(index, index) = c64::new(2.0, 0.0);
+            if index > 0 {
+                *mat.get_mut(index, index - 1) = c64::new(0.0, -1.0);
+                *mat.get_mut(index - 1, index) = c64::new(0.0, 1.0);
+            }
+        }
+        let anorm = mat.norm_1();
+        assert_eq!(anorm, 4.0);
+
+        let chol = mat.cholesky().unwrap();
+        let exact = exact_inverse_norm(n, |x| chol.solve(x));
+        assert!((1.0 / (chol.rcond(anorm) * anorm) - exact).abs() < 1E-10 * exact);
+
+        let mut rhs = ColumnVectorD::<c64>::zeros_from_length(n);
+        *rhs.get1d_mut(0) = c64::new(1.0, 0.0);
+        assert!(chol.solve_checked(&mut rhs, anorm, 1E-2).is_err());
+        assert_eq!(rhs.get1d(0), c64::new(1.0, 0.0));
+        assert!(chol.solve_checked(&mut rhs, anorm, 1E-4).is_ok());
+    }
+
+    #[test]
+    fn test_nan_input() {
+        assert_eq!(nan_first_cmp(f64::NAN, 1.0), Ordering::Less);
+        assert_eq!(nan_first_cmp(2.0, f64::NAN), Ordering::Greater);
+        assert_eq!(nan_first_cmp(f64::NAN, f64::NAN), Ordering::Equal);
+
+        let mut mat = MatrixD::<f64, RowMajor>::zeros_from_dim(3, 3);
+        for index in 0..3 {
+            *mat.get_mut(index, index) = 1.0 + index as f64;
+        }
+        *mat.get_mut(0, 2) = f64::NAN;
+
+        // The estimators and the singular value decomposition must not panic.
+        let lu = mat.lu().unwrap();
+        assert!(lu.rcond(mat.norm_1()).is_nan());
+        let mut rhs = ColumnVectorD::<f64>::zeros_from_length(3);
+        assert!(lu
+            .solve_checked(TransposeMode::NoTrans, &mut rhs, mat.norm_1(), 0.0)
+            .is_err());
+        let _ = mat.norm_2();
+        let _ = mat.pivoted_qr();
+    }
+
+    #[test]
+    fn test_norms() {
+        let mut mat = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 3);
+        *mat.get_mut(0, 0) = 1.0;
+        *mat.get_mut(0, 1) = -2.0;
+        *mat.get_mut(0, 2) = 3.0;
+        *mat.get_mut(1, 0) = -4.0;
+        *mat.get_mut(1, 2) = 2.0;
+        assert_eq!(mat.norm_1(), 5.0);
+        assert_eq!(mat.norm_inf(), 6.0);
+        assert_eq!(mat.norm_max(), 4.0);
+        assert!((mat.norm_fro() - 34f64.sqrt()).abs() < 1E-14);
+
+        // The norms of a lazy expression.
+        let mut other = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 3);
+        *other.get_mut(1, 1) = 1.0;
+        let expr = 2.0 * mat + other;
+        assert_eq!(expr.norm_1(), 10.0);
+        assert_eq!(expr.norm_inf(), 13.0);
+        assert_eq!(expr.norm_max(), 8.0);
+
+        let mut mat = MatrixD::<c64, ColumnMajor>::zeros_from_dim(2, 2);
+        *mat.get_mut(0, 0) = c64::new(3.0, 4.0);
+        *mat.get_mut(1, 1) = c64::new(0.0, -2.0);
+        assert_eq!(mat.norm_1(), 5.0);
+        assert!((mat.norm_2() - 5.0).abs() < 1E-14);
+        assert!((mat.norm_fro() - 29f64.sqrt()).abs() < 1E-14);
+
+        // The spectral norm of a wide rank-one matrix u * v^T is |u| * |v|.
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(2, 4);
+        for col in 0..4 {
+            *mat.get_mut(0, col) = col as f64;
+            *mat.get_mut(1, col) = 2.0 * col as f64;
+        }
+        assert!((mat.norm_2() - 5f64.sqrt() * 14f64.sqrt()).abs() < 1E-13);
+
+        let zero = MatrixD::<f64, ColumnMajor>::zeros_from_dim(3, 3);
+        assert_eq!(zero.norm_fro(), 0.0);
+        assert_eq!(zero.norm_2(), 0.0);
+    }
+}
@@ -0,0 +1,538 @@
+//! Permutations of rows and columns.
+//!
+//! A [Permutation] `p` of `0..n` represents the `n x n` permutation matrix `P` whose
+//! column `j` is the unit vector `e_{p[j]}`. Consequently
+//! - row `i` of `P^T * A` is row `p[i]` of `A`, which is computed in place by
+//!   `permute_rows` and lazily by `permuted_rows`,
+//! - column `j` of `A * P` is column `p[j]` of `A`, which is computed in place by
+//!   `permute_cols` and lazily by `permuted_cols`.
+//!
+//! This matches the pivoted factorisations of this crate. The permutation returned by
+//! [DenseLu::permutation](crate::dense_solvers::DenseLu::permutation) satisfies
+//! `A = P * L * U`, so that `permute_rows` turns `A` into `L * U`, and the column
+//! permutation of a [PivotedQr](crate::dense_solvers::PivotedQr) converted by
+//! [Permutation::from_indices] satisfies `A * P = Q * R`.
+//!
+//! The in-place methods are defined for dense matrices and vectors. They decompose the
+//! permutation into cycles and apply each cycle by interchanges, so that no copy of the
+//! matrix is needed. The lazy view is defined for every [Matrix]. Its layout is the
+//! index layout of the underlying matrix, since a permutation does not preserve e.g. a
+//! band structure.
+//!
+//! Permutations are also the result of reorderings of sparse matrices such as
+//! [CsrMatrix::reverse_cuthill_mckee](crate::sparse::CsrMatrix::reverse_cuthill_mckee).
+//!
+//! ```
+//! # use householder::*;
+//! let mut mat = MatrixD::<f64, RowMajor>::zeros_from_dim(3, 2);
+//! *mat.get_mut(0, 0) = 1.0;
+//! *mat.get_mut(1, 0) = 2.0;
+//! *mat.get_mut(2, 1) = 3.0;
+//! let perm = Permutation::from_indices(vec![2, 0, 1]);
+//! assert_eq!(perm.sign(), 1);
+//! assert_eq!(perm.compose(&perm.inverse()), Permutation::identity(3));
+//!
+//! let view = Matrix::from_ref(&mat).permuted_rows(&perm).eval();
+//! mat.permute_rows(&perm);
+//! assert!(view == mat);
+//! assert_eq!(mat.get(0, 1), 3.0);
+//! assert_eq!(mat.get(1, 0), 1.0);
+//! ```
+
+use crate::data_container::DataContainerMut;
+use crate::layouts::*;
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::*;
+
+/// A permutation of the indices `0..n`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Permutation {
+    indices: Vec<IndexType>,
+}
+
+impl Permutation {
+    /// Create the identity permutation of `0..n`.
+    pub fn identity(n: IndexType) -> Self {
+        Self {
+            indices: (0..n).collect(),
+        }
+    }
+
+    /// Create a permutation from its index array, which must contain each of
+    /// `0..indices.len()` exactly once.
+    pub fn from_indices(indices: Vec<IndexType>) -> Self {
+        let n = indices.len();
+        let mut seen = vec![false; n];
+        for &index in &indices {
+            assert!(index < n, "Index {} out of bounds. Length is {}.", index, n);
+            assert!(!seen[index], "Index {} appears more than once.", index);
+            seen[index] = true;
+        }
+        Self { indices }
+    }
+
+    /// Create the permutation of a sequence of interchanges as returned by Lapack `getrf`,
+    /// where index `i` was interchanged with index `pivots[i]` in the order `i = 0, 1, ...`.
+    pub fn from_pivots(pivots: &[IndexType]) -> Self {
+        let n = pivots.len();
+        let mut indices: Vec<IndexType> = (0..n).collect();
+        for (index, &pivot) in pivots.iter().enumerate() {
+            assert!(pivot < n, "Pivot {} out of bounds. Length is {}.", pivot, n);
+            indices.swap(index, pivot);
+        }
+        Self { indices }
+    }
+
+    /// Return the number `n` of permuted indices.
+    pub fn len(&self) -> IndexType {
+        self.indices.len()
+    }
+
+    /// Return true if the permutation has no indices.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Return the index array `p`.
+    pub fn indices(&self) -> &[IndexType] {
+        &self.indices
+    }
+
+    /// Return true if this is the identity permutation.
+    pub fn is_identity(&self) -> bool {
+        self.indices
+            .iter()
+            .enumerate()
+            .all(|(index, &value)| index == value)
+    }
+
+    /// Return the inverse permutation, whose matrix is `P^T`.
+    pub fn inverse(&self) -> Self {
+        let mut indices = vec![0; self.len()];
+        for (index, &value) in self.indices.iter().enumerate() {
+            indices[value] = index;
+        }
+        Self { indices }
+    }
+
+    /// Return the permutation of the matrix product `P * Q`, where `Q` is `other`.
+    /// Its index array is `j -> p[q[j]]`.
+    pub fn compose(&self, other: &Permutation) -> Self {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "Permutations have different lengths {} and {}.",
+            self.len(),
+            other.len()
+        );
+        Self {
+            indices: other
+                .indices
+                .iter()
+                .map(|&index| self.indices[index])
+                .collect(),
+        }
+    }
+
+    /// Return the sign of the permutation, i.e. the determinant of `P`.
+    pub fn sign(&self) -> i32 {
+        if self.transpositions().len().is_multiple_of(2) {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// Return the cycles of the permutation. Each cycle `[i, p[i], p[p[i]], ...]`
+    /// starts with its smallest index. Fixed points are cycles of length one.
+    pub fn cycles(&self) -> Vec<Vec<IndexType>> {
+        let mut visited = vec![false; self.len()];
+        let mut cycles = Vec::new();
+        for start in 0..self.len() {
+            if visited[start] {
+                continue;
+            }
+            let mut cycle = vec![start];
+            visited[start] = true;
+            let mut index = self.indices[start];
+            while index != start {
+                cycle.push(index);
+                visited[index] = true;
+                index = self.indices[index];
+            }
+            cycles.push(cycle);
+        }
+        cycles
+    }
+
+    /// Return the interchanges that turn a sequence `x` into `i -> x[p[i]]` if they are
+    /// applied in order. Each cycle of length `k` contributes `k - 1` interchanges.
+    fn transpositions(&self) -> Vec<(IndexType, IndexType)> {
+        let mut res = Vec::new();
+        for cycle in self.cycles() {
+            for pair in cycle.windows(2) {
+                res.push((pair[0], pair[1]));
+            }
+        }
+        res
+    }
+
+    /// Permute `values` in place such that position `i` afterwards holds the
+    /// value at position `p[i]` before.
+    pub fn permute_slice<T>(&self, values: &mut [T]) {
+        assert_eq!(
+            values.len(),
+            self.len(),
+            "Slice has length {} but permutation has length {}.",
+            values.len(),
+            self.len()
+        );
+        for (first, second) in self.transpositions() {
+            values.swap(first, second);
+        }
+    }
+}
+
+impl std::ops::Index<IndexType> for Permutation {
+    type Output = IndexType;
+
+    fn index(&self, index: IndexType) -> &Self::Output {
+        &self.indices[index]
+    }
+}
+
+macro_rules! permute_in_place {
+    ($Layout:ident) => {
+        impl<
+                Item: HScalar,
+                Data: DataContainerMut<Item = Item>,
+                RS: SizeIdentifier,
+                CS: SizeIdentifier,
+            > GenericBaseMatrixMut<Item, $Layout, Data, RS, CS>
+        {
+            /// Replace the matrix `A` by `P^T * A`, i.e. row `i` by row `perm[i]`.
+            pub fn permute_rows(&mut self, perm: &Permutation) {
+                let (m, n) = self.layout().dim();
+                assert_eq!(
+                    m,
+                    perm.len(),
+                    "Matrix has {} rows but permutation has length {}.",
+                    m,
+                    perm.len()
+                );
+                let transpositions = perm.transpositions();
+                for col in 0..n {
+                    for &(first, second) in &transpositions {
+                        unsafe {
+                            let tmp = self.get_unchecked(first, col);
+                            *self.get_unchecked_mut(first, col) = self.get_unchecked(second, col);
+                            *self.get_unchecked_mut(second, col) = tmp;
+                        }
+                    }
+                }
+            }
+
+            /// Replace the matrix `A` by `A * P`, i.e. column `j` by column `perm[j]`.
+            pub fn permute_cols(&mut self, perm: &Permutation) {
+                let (m, n) = self.layout().dim();
+                assert_eq!(
+                    n,
+                    perm.len(),
+                    "Matrix has {} columns but permutation has length {}.",
+                    n,
+                    perm.len()
+                );
+                for (first, second) in perm.transpositions() {
+                    for row in 0..m {
+                        unsafe {
+                            let tmp = self.get_unchecked(row, first);
+                            *self.get_unchecked_mut(row, first) = self.get_unchecked(row, second);
+                            *self.get_unchecked_mut(row, second) = tmp;
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+permute_in_place!(RowMajor);
+permute_in_place!(ColumnMajor);
+permute_in_place!(ArbitraryStrideRowMajor);
+permute_in_place!(ArbitraryStrideColumnMajor);
+permute_in_place!(RowVector);
+permute_in_place!(ColumnVector);
+permute_in_place!(ArbitraryStrideRowVector);
+permute_in_place!(ArbitraryStrideColumnVector);
+
+/// A type that represents a matrix with permuted rows and columns.
+pub type PermutedMat<'a, Item, MatImpl, L, RS, CS> =
+    Matrix<Item, Permuted<'a, Item, MatImpl, L, RS, CS>, <L as LayoutType>::IndexLayout, RS, CS>;
+
+/// A structure holding a matrix `A` and permutations `P_r` and `P_c` that represents
+/// `P_r^T * A * P_c`. A missing permutation is the identity.
+pub struct Permuted<'a, Item, MatImpl, L, RS, CS>
+where
+    Item: HScalar,
+    L: LayoutType,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+    MatImpl: MatrixTrait<Item, L, RS, CS>,
+{
+    mat: Matrix<Item, MatImpl, L, RS, CS>,
+    rows: Option<&'a Permutation>,
+    cols: Option<&'a Permutation>,
+    layout: L::IndexLayout,
+}
+
+impl<
+        'a,
+        Item: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > Permuted<'a, Item, MatImpl, L, RS, CS>
+{
+    pub fn new(
+        mat: Matrix<Item, MatImpl, L, RS, CS>,
+        rows: Option<&'a Permutation>,
+        cols: Option<&'a Permutation>,
+    ) -> Self {
+        let dim = mat.layout().dim();
+        if let Some(rows) = rows {
+            assert_eq!(
+                dim.0,
+                rows.len(),
+                "Matrix has {} rows but permutation has length {}.",
+                dim.0,
+                rows.len()
+            );
+        }
+        if let Some(cols) = cols {
+            assert_eq!(
+                dim.1,
+                cols.len(),
+                "Matrix has {} columns but permutation has length {}.",
+                dim.1,
+                cols.len()
+            );
+        }
+        Self {
+            mat,
+            rows,
+            cols,
+            layout: L::IndexLayout::from_dimension(dim),
+        }
+    }
+}
+
+impl<
+        'a,
+        Item: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > SizeType for Permuted<'a, Item, MatImpl, L, RS, CS>
+{
+    type R = RS;
+    type C = CS;
+}
+
+impl<
+        'a,
+        Item: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > Layout for Permuted<'a, Item, MatImpl, L, RS, CS>
+{
+    type Impl = L::IndexLayout;
+
+    #[inline]
+    fn layout(&self) -> &Self::Impl {
+        &self.layout
+    }
+}
+
+impl<
+        'a,
+        Item: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > UnsafeRandomAccess for Permuted<'a, Item, MatImpl, L, RS, CS>
+{
+    type Item = Item;
+
+    #[inline]
+    unsafe fn get_unchecked(&self, row: IndexType, col: IndexType) -> Self::Item {
+        let row = self.rows.map_or(row, |perm| perm.indices[row]);
+        let col = self.cols.map_or(col, |perm| perm.indices[col]);
+        self.mat.element(row, col)
+    }
+
+    #[inline]
+    unsafe fn get1d_unchecked(&self, index: IndexType) -> Self::Item {
+        let (row, col) = self.layout.convert_1d_2d(index);
+        self.get_unchecked(row, col)
+    }
+}
+
+impl<
+        Item: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > Matrix<Item, MatImpl, L, RS, CS>
+{
+    /// Return a lazy view of `P^T * A`, whose row `i` is row `perm[i]` of `A`.
+    pub fn permuted_rows(self, perm: &Permutation) -> PermutedMat<'_, Item, MatImpl, L, RS, CS> {
+        Matrix::new(Permuted::new(self, Some(perm), None))
+    }
+
+    /// Return a lazy view of `A * P`, whose column `j` is column `perm[j]` of `A`.
+    pub fn permuted_cols(self, perm: &Permutation) -> PermutedMat<'_, Item, MatImpl, L, RS, CS> {
+        Matrix::new(Permuted::new(self, None, Some(perm)))
+    }
+
+    /// Return a lazy view of `P_r^T * A * P_c`, whose element `(i, j)` is the element
+    /// `(rows[i], cols[j])` of `A`.
+    pub fn permuted<'a>(
+        self,
+        rows: &'a Permutation,
+        cols: &'a Permutation,
+    ) -> PermutedMat<'a, Item, MatImpl, L, RS, CS> {
+        Matrix::new(Permuted::new(self, Some(rows), Some(cols)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::assert_matrix_relative_eq;
+
+    #[test]
+    fn test_permutation_algebra() {
+        let perm = Permutation::from_indices(vec![1, 2, 0, 4, 3]);
+        assert_eq!(perm.cycles(), vec![vec![0, 1, 2], vec![3, 4]]);
+        assert_eq!(perm.sign(), -1);
+        assert_eq!(perm.inverse().indices(), &[2, 0, 1, 4, 3]);
+        assert!(perm.compose(&perm.inverse()).is_identity());
+        assert!(perm.inverse().compose(&perm).is_identity());
+
+        let other = Permutation::from_indices(vec![4, 3, 2, 1, 0]);
+        let product = perm.compose(&other);
+        assert_eq!(product.indices(), &[3, 4, 0, 2, 1]);
+        assert_eq!(product.sign(), perm.sign() * other.sign());
+
+        let mut values = vec!['a', 'b', 'c', 'd', 'e'];
+        perm.permute_slice(&mut values);
+        assert_eq!(values, vec!['b', 'c', 'a', 'e', 'd']);
+        other.permute_slice(&mut values);
+        let mut expect = vec!['a', 'b', 'c', 'd', 'e'];
+        product.permute_slice(&mut expect);
+        assert_eq!(values, expect);
+    }
+
+    #[test]
+    fn test_from_pivots() {
+        let perm = Permutation::from_pivots(&[2, 2, 2]);
+        assert_eq!(perm.indices(), &[2, 0, 1]);
+        assert_eq!(perm.sign(), 1);
+        assert!(Permutation::from_pivots(&[0, 1, 2]).is_identity());
+        assert_eq!(Permutation::from_pivots(&[1, 1]).sign(), -1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index 1 appears more than once.")]
+    fn test_invalid_indices() {
+        Permutation::from_indices(vec![1, 0, 1]);
+    }
+
+    #[test]
+    fn test_permute_in_place() {
+        let perm = Permutation::from_indices(vec![3, 0, 2, 1]);
+        let mut mat = MatrixD::<c64, ColumnMajor>::zeros_from_dim(4, 4);
+        for row in 0..4 {
+            for col in 0..4 {
+                *mat.get_mut(row, col) = c64::new(row as f64, col as f64);
+            }
+        }
+        let rows = Matrix::from_ref(&mat).permuted_rows(&perm).eval();
+        let cols = Matrix::from_ref(&mat).permuted_cols(&perm).eval();
+        let both = Matrix::from_ref(&mat)
+            .permuted(&perm, &perm.inverse())
+            .eval();
+
+        mat.permute_rows(&perm);
+        assert!(mat == rows);
+        assert_eq!(mat.get(0, 2), c64::new(3.0, 2.0));
+        mat.permute_rows(&perm.inverse());
+        mat.permute_cols(&perm);
+        assert!(mat == cols);
+        assert_eq!(mat.get(2, 1), c64::new(2.0, 0.0));
+        mat.permute_cols(&perm.inverse());
+        mat.permute_rows(&perm);
+        mat.permute_cols(&perm.inverse());
+        assert!(mat == both);
+
+        let mut vec = ColumnVectorD::<f64>::zeros_from_length(4);
+        vec.for_each(|elem| *elem = 1.0);
+        *vec.get_mut(1, 0) = 5.0;
+        vec.permute_rows(&perm);
+        assert_eq!(vec.get1d(3), 5.0);
+    }
+
+    #[test]
+    fn test_lu_permutation() {
+        let n = 4;
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+        for row in 0..n {
+            for col in 0..n {
+                *mat.get_mut(row, col) = ((row * 7 + col * 3) % 5) as f64 + 0.5 * col as f64;
+            }
+        }
+        let lu = mat.lu().unwrap();
+        let factor = lu.factor();
+        let mut product = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+        for row in 0..n {
+            for col in 0..n {
+                let mut value = 0.0;
+                for k in 0..=row.min(col) {
+                    let lower = if k == row { 1.0 } else { factor.get(row, k) };
+                    value += lower * factor.get(k, col);
+                }
+                *product.get_mut(row, col) = value;
+            }
+        }
+        mat.permute_rows(&lu.permutation());
+        assert_matrix_relative_eq!(mat, product, epsilon = 1E-14);
+    }
+
+    #[test]
+    fn test_permuted_band_view() {
+        let band = TridiagonalMatrix::<f64>::from_diagonals(&[1.0; 2], &[2.0; 3], &[3.0; 2]);
+        let perm = Permutation::from_indices(vec![2, 1, 0]);
+        let view = Matrix::from_ref(&band).permuted(&perm, &perm);
+        assert_eq!(view.layout().dim(), (3, 3));
+        assert_eq!(view.get(0, 2), 0.0);
+        assert_eq!(view.get(0, 1), 1.0);
+        assert_eq!(view.get(1, 0), 3.0);
+        assert_eq!(view.get1d(3), 2.0);
+
+        // A 1d traversal of the view only visits the band elements.
+        let swap = Permutation::from_indices(vec![1, 0, 2]);
+        let rows = Matrix::from_ref(&band).permuted_rows(&swap).eval();
+        assert_eq!(rows.get(0, 0), 1.0);
+        assert_eq!(rows.get(1, 0), 2.0);
+        assert_eq!(rows.get(1, 2), 0.0);
+        assert_eq!(rows.get(2, 2), 2.0);
+    }
+}
@@ -0,0 +1,271 @@
+//! Pseudoinverse, numerical rank and bases of the fundamental subspaces.
+//!
+//! The methods of this module are based on the singular value decomposition
+//! `A = U * S * V^H` of an `m x n` matrix `A` with singular values
+//! `s_0 >= s_1 >= ... >= 0`. A singular value `s_i` is treated as zero if
+//! `s_i <= tol * s_0`, and the numerical rank `r` is the number of the remaining
+//! singular values.
+//!
+//! - `rank(tol)` returns `r`.
+//! - `pinv(rcond)` returns the `n x m` Moore-Penrose pseudoinverse
+//!   `A^+ = V[:, :r] * S[:r, :r]^{-1} * U[:, :r]^H` with `tol = rcond`.
+//! - `orth()` returns the `m x r` orthonormal basis `U[:, :r]` of the range of `A`, where
+//!   `tol = eps * max(m, n)`.
+//! - `null_space(tol)` returns the `n x (n - r)` orthonormal basis `V[:, r:]` of the null
+//!   space of `A`.
+//!
+//! The results are dynamic matrices with the same index layout as `A`. For subblocks with
+//! arbitrary strides the corresponding row major or column major layout is used.
+//!
+//! ```
+//! # use householder::*;
+//! let mut mat = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 3);
+//! *mat.get_mut(0, 0) = 1.0;
+//! *mat.get_mut(0, 1) = 1.0;
+//! *mat.get_mut(1, 0) = 2.0;
+//! *mat.get_mut(1, 1) = 2.0;
+//! assert_eq!(mat.rank(1E-12), 1);
+//! let null_space: MatrixD<f64, RowMajor> = mat.null_space(1E-12);
+//! assert_eq!(null_space.layout().dim(), (3, 2));
+//! ```
+
+use crate::data_container::DataContainer;
+use crate::dense_solvers::{jacobi_svd, SvdFactors};
+use crate::layouts::*;
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::*;
+
+/// Compute the singular value decomposition of an `m x n` matrix with square `n x n`
+/// factor `V`. If `m < n` the matrix is padded with zero rows, so that `U` is `n x n`
+/// and only its first `m` rows are meaningful.
+fn full_svd<Item: HScalar, MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>, L: LayoutType>(
+    mat: &Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+) -> SvdFactors<Item> {
+    let (m, n) = mat.layout().dim();
+    let mut padded = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m.max(n), n);
+    for col in 0..n {
+        for row in 0..m {
+            unsafe { *padded.get_unchecked_mut(row, col) = mat.get_unchecked(row, col) };
+        }
+    }
+    jacobi_svd(&padded)
+}
+
+/// Return the number of singular values that are larger than `tol` times the largest one.
+fn numerical_rank<Real: num::Float>(values: &[Real], tol: Real) -> IndexType {
+    match values.first() {
+        Some(&first) => values
+            .iter()
+            .take_while(|&&value| (value > tol * first) & (value > Real::zero()))
+            .count(),
+        None => 0,
+    }
+}
+
+macro_rules! pseudo_inverse_impl {
+    ($Layout:ident, $ResultLayout:ident) => {
+        impl<Item: HScalar, Data: DataContainer<Item = Item>>
+            GenericBaseMatrix<Item, $Layout, Data, Dynamic, Dynamic>
+        {
+            /// Return the number of singular values that are larger than `tol` times the
+            /// largest singular value.
+            pub fn rank(&self, tol: <Item as Scalar>::Real) -> IndexType {
+                let (_, values, _) = full_svd(self);
+                numerical_rank(&values, tol)
+            }
+
+            /// Return the Moore-Penrose pseudoinverse. Singular values that are at most
+            /// `rcond` times the largest singular value are treated as zero.
+            pub fn pinv(&self, rcond: <Item as Scalar>::Real) -> MatrixD<Item, $ResultLayout> {
+                let (m, n) = self.layout().dim();
+                let (u, values, v) = full_svd(self);
+                let rank = numerical_rank(&values, rcond);
+                let mut res = MatrixD::<Item, $ResultLayout>::zeros_from_dim(n, m);
+                for (index, &value) in values[..rank].iter().enumerate() {
+                    let inverse = Item::one() / Item::from_real(value);
+                    for col in 0..m {
+                        let factor = inverse * u.get(col, index).conj();
+                        for row in 0..n {
+                            *res.get_mut(row, col) += v.get(row, index) * factor;
+                        }
+                    }
+                }
+                res
+            }
+
+            /// Return an orthonormal basis of the range. Singular values that are at most
+            /// `eps * max(m, n)` times the largest singular value are treated as zero.
+            pub fn orth(&self) -> MatrixD<Item, $ResultLayout> {
+                let (m, n) = self.layout().dim();
+                let tol = <<Item as Scalar>::Real as num::Float>::epsilon()
+                    * num::cast::<IndexType, <Item as Scalar>::Real>(m.max(n)).unwrap();
+                let (u, values, _) = full_svd(self);
+                let rank = numerical_rank(&values, tol);
+                let mut res = MatrixD::<Item, $ResultLayout>::zeros_from_dim(m, rank);
+                for col in 0..rank {
+                    for row in 0..m {
+                        *res.get_mut(row, col) = u.get(row, col);
+                    }
+                }
+                res
+            }
+
+            /// Return an orthonormal basis of the null space. Singular values that are at
+            /// most `tol` times the largest singular value are treated as zero.
+            pub fn null_space(&self, tol: <Item as Scalar>::Real) -> MatrixD<Item, $ResultLayout> {
+                let n = self.layout().dim().1;
+                let (_, values, v) = full_svd(self);
+                let rank = numerical_rank(&values, tol);
+                let mut res = MatrixD::<Item, $ResultLayout>::zeros_from_dim(n, n - rank);
+                for col in rank..n {
+                    for row in 0..n {
+                        *res.get_mut(row, col - rank) = v.get(row, col);
+                    }
+                }
+                res
+            }
+        }
+    };
+}
+
+pseudo_inverse_impl!(RowMajor, RowMajor);
+pseudo_inverse_impl!(ColumnMajor, ColumnMajor);
+pseudo_inverse_impl!(ArbitraryStrideRowMajor, RowMajor);
+pseudo_inverse_impl!(ArbitraryStrideColumnMajor, ColumnMajor);
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use rand::prelude::*;
+
+    /// Return the largest absolute entry of `A * B - C`, where `A` is `m x k` and `B` is
+    /// `k x n`.
+    fn product_error<Item: HScalar>(
+        dim: (IndexType, IndexType, IndexType),
+        a: impl Fn(IndexType, IndexType) -> Item,
+        b: impl Fn(IndexType, IndexType) -> Item,
+        c: impl Fn(IndexType, IndexType) -> Item,
+    ) -> f64 {
+        let (m, k, n) = dim;
+        let mut error: f64 = 0.0;
+        for col in 0..n {
+            for row in 0..m {
+                let value = (0..k).fold(c(row, col), |acc, index| {
+                    acc - a(row, index) * b(index, col)
+                });
+                error = error.max(num::cast::<_, f64>(value.abs()).unwrap());
+            }
+        }
+        error
+    }
+
+    /// Return the `n x n` identity as a closure.
+    fn identity<Item: HScalar>(row: IndexType, col: IndexType) -> Item {
+        if row == col {
+            Item::one()
+        } else {
+            Item::zero()
+        }
+    }
+
+    macro_rules! pseudo_inverse_test {
+        ($HScalar:ty, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let mut rng = StdRng::seed_from_u64(0);
+                let zero = |_, _| <$HScalar as num::Zero>::zero();
+                for (m, n) in [(9, 6), (6, 9)] {
+                    let rank = 4;
+                    let mut left = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(m, rank);
+                    let mut right = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(rank, n);
+                    left.fill_from_rand_standard_normal(&mut rng);
+                    right.fill_from_rand_standard_normal(&mut rng);
+                    let mut mat = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(m, n);
+                    for index in 0..rank {
+                        for col in 0..n {
+                            for row in 0..m {
+                                *mat.get_mut(row, col) +=
+                                    left.get(row, index) * right.get(index, col);
+                            }
+                        }
+                    }
+                    let a = |row, col| mat.get(row, col);
+                    let tol = num::cast::<f64, _>($tol).unwrap();
+                    assert_eq!(mat.rank(tol), rank);
+
+                    // The Penrose conditions A * X * A = A and X * A * X = X.
+                    let pinv: MatrixD<$HScalar, RowMajor> = mat.pinv(tol);
+                    assert_eq!(pinv.layout().dim(), (n, m));
+                    let x = |row, col| pinv.get(row, col);
+                    let ax = |row, col| {
+                        (0..n).fold(<$HScalar as num::Zero>::zero(), |acc, index| {
+                            acc + a(row, index) * x(index, col)
+                        })
+                    };
+                    let xa = |row, col| {
+                        (0..m).fold(<$HScalar as num::Zero>::zero(), |acc, index| {
+                            acc + x(row, index) * a(index, col)
+                        })
+                    };
+                    assert!(product_error((m, m, n), ax, a, a) < $tol);
+                    assert!(product_error((n, n, m), xa, x, x) < $tol);
+
+                    let orth: MatrixD<$HScalar, RowMajor> = mat.orth();
+                    assert_eq!(orth.layout().dim(), (m, rank));
+                    let q = |row, col| orth.get(row, col);
+                    let qh = |row, col| orth.get(col, row).conj();
+                    assert!(product_error((rank, m, rank), qh, q, identity) < $tol);
+                    let qqh_a = |row, col| {
+                        (0..rank).fold(<$HScalar as num::Zero>::zero(), |acc, index| {
+                            acc + q(row, index)
+                                * (0..m).fold(<$HScalar as num::Zero>::zero(), |acc, other| {
+                                    acc + qh(index, other) * a(other, col)
+                                })
+                        })
+                    };
+                    assert!(
+                        product_error((m, 0, n), zero, zero, |row, col| a(row, col)
+                            - qqh_a(row, col))
+                            < $tol
+                    );
+
+                    let null_space: MatrixD<$HScalar, RowMajor> = mat.null_space(tol);
+                    assert_eq!(null_space.layout().dim(), (n, n - rank));
+                    let z = |row, col| null_space.get(row, col);
+                    let zh = |row, col| null_space.get(col, row).conj();
+                    assert!(product_error((n - rank, n, n - rank), zh, z, identity) < $tol);
+                    assert!(product_error((m, n, n - rank), a, z, zero) < $tol);
+                }
+            }
+        };
+    }
+
+    pseudo_inverse_test!(f32, 1E-4, test_pseudo_inverse_f32);
+    pseudo_inverse_test!(f64, 1E-10, test_pseudo_inverse_f64);
+    pseudo_inverse_test!(c32, 1E-4, test_pseudo_inverse_c32);
+    pseudo_inverse_test!(c64, 1E-10, test_pseudo_inverse_c64);
+
+    #[test]
+    fn test_pseudo_inverse_of_block() {
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(4, 4);
+        *mat.get_mut(1, 1) = 2.0;
+        *mat.get_mut(2, 1) = 1.0;
+        let block = mat.block((1, 1), (2, 2));
+        assert_eq!(block.rank(1E-12), 1);
+        let pinv: MatrixD<f64, ColumnMajor> = block.pinv(1E-12);
+        assert!((pinv.get(0, 0) - 0.4).abs() < 1E-14);
+        assert!((pinv.get(0, 1) - 0.2).abs() < 1E-14);
+        assert_eq!(pinv.get(1, 0), 0.0);
+        assert_eq!(pinv.get(1, 1), 0.0);
+        let null_space = block.null_space(1E-12);
+        assert_eq!(null_space.layout().dim(), (2, 1));
+        assert!((null_space.get(1, 0).abs() - 1.0).abs() < 1E-14);
+
+        let zero = MatrixD::<f64, ColumnMajor>::zeros_from_dim(3, 2);
+        assert_eq!(zero.rank(1E-12), 0);
+        assert_eq!(zero.orth().layout().dim(), (3, 0));
+        assert_eq!(zero.null_space(1E-12).layout().dim(), (2, 2));
+    }
+}
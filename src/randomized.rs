@@ -0,0 +1,368 @@
+//! Randomized low-rank approximations of dense matrices.
+//!
+//! This module implements the randomized range finder and the randomized singular value
+//! decomposition of Halko, Martinsson and Tropp (SIAM Review 53(2), 2011). For an `m x n`
+//! matrix `A` and a target rank `k` the range finder multiplies `A` with an `n x (k + p)`
+//! Gaussian random matrix, where `p` is the oversampling parameter, and orthonormalises the
+//! result. This gives an `m x (k + p)` matrix `Q` with orthonormal columns such that
+//! `A ~ Q * Q^H * A`. If the singular values of `A` decay slowly, a number of power
+//! iterations with `A * A^H` improve the approximation. The iterates are reorthonormalised
+//! after each multiplication with `A` or `A^H` to avoid the loss of small singular values
+//! in floating point arithmetic.
+//!
+//! The randomized SVD computes the SVD of the small matrix `Q^H * A` and returns the
+//! leading `k` singular triplets of `A` as a [RandomizedSvd].
+//!
+//! The products with `A` and `A^H` are the dominant cost. They are computed with
+//! [MatMul](crate::matrix_multiply::MatMul) and are therefore multi-threaded if the
+//! `threading` feature is enabled.
+//!
+//! The random numbers are drawn from a user supplied [Rng](rand::Rng). Hence, the results
+//! are reproducible by seeding the generator.
+//!
+//! ```
+//! # use householder::*;
+//! # use rand::prelude::*;
+//! let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(50, 40);
+//! for col in 0..40 {
+//!     for row in 0..50 {
+//!         *mat.get_mut(row, col) = 1.0 / (1.0 + row as f64 + col as f64);
+//!     }
+//! }
+//! let mut rng = StdRng::seed_from_u64(0);
+//! let svd = mat.randomized_svd(5, &RandomizedOptions::default(), &mut rng);
+//! assert_eq!(svd.rank(), 5);
+//! assert!(svd.singular_values()[4] < 1E-2 * svd.singular_values()[0]);
+//! ```
+
+use crate::data_container::DataContainer;
+use crate::dense_solvers::{jacobi_svd, thin_qr};
+use crate::layouts::*;
+use crate::matrix::*;
+use crate::matrix_multiply::MatMul;
+use crate::traits::*;
+use crate::types::*;
+use rand::prelude::*;
+
+/// Parameters of the randomized range finder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RandomizedOptions {
+    /// The number `p` of additional random samples. The default is 10.
+    pub oversampling: IndexType,
+    /// The number of power iterations with `A * A^H`. The default is 2.
+    pub power_iterations: IndexType,
+}
+
+impl Default for RandomizedOptions {
+    fn default() -> Self {
+        Self {
+            oversampling: 10,
+            power_iterations: 2,
+        }
+    }
+}
+
+/// A truncated singular value decomposition `A ~ U * S * V^H` of rank `k`.
+pub struct RandomizedSvd<Item: HScalar> {
+    u: MatrixD<Item, ColumnMajor>,
+    singular_values: Vec<<Item as Scalar>::Real>,
+    vh: MatrixD<Item, ColumnMajor>,
+}
+
+impl<Item: HScalar> RandomizedSvd<Item> {
+    /// Return the `m x k` matrix `U` of left singular vectors.
+    pub fn u(&self) -> &MatrixD<Item, ColumnMajor> {
+        &self.u
+    }
+
+    /// Return the `k` singular values in descending order.
+    pub fn singular_values(&self) -> &[<Item as Scalar>::Real] {
+        &self.singular_values
+    }
+
+    /// Return the `k x n` matrix `V^H` of conjugate transposed right singular vectors.
+    pub fn vh(&self) -> &MatrixD<Item, ColumnMajor> {
+        &self.vh
+    }
+
+    /// Return the rank `k` of the decomposition.
+    pub fn rank(&self) -> IndexType {
+        self.singular_values.len()
+    }
+
+    /// Return the dense `m x n` matrix `U * S * V^H`.
+    pub fn to_dense(&self) -> MatrixD<Item, ColumnMajor> {
+        let m = self.u.layout().dim().0;
+        let n = self.vh.layout().dim().1;
+        let mut res = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, n);
+        for col in 0..n {
+            for (index, &sigma) in self.singular_values.iter().enumerate() {
+                let value = Item::from_real(sigma) * self.vh.get(index, col);
+                for row in 0..m {
+                    *res.get_mut(row, col) += self.u.get(row, index) * value;
+                }
+            }
+        }
+        res
+    }
+}
+
+/// Return the elementwise complex conjugate of `mat`.
+fn conjugate<Item: HScalar>(mat: &MatrixD<Item, ColumnMajor>) -> MatrixD<Item, ColumnMajor> {
+    let (m, n) = mat.layout().dim();
+    let mut res = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, n);
+    for index in 0..m * n {
+        unsafe { *res.get1d_unchecked_mut(index) = mat.get1d_unchecked(index).conj() };
+    }
+    res
+}
+
+macro_rules! randomized_impl {
+    ($HScalar:ty) => {
+        impl<L: StridedLayoutType, Data: DataContainer<Item = $HScalar>>
+            GenericBaseMatrix<$HScalar, L, Data, Dynamic, Dynamic>
+        {
+            /// Compute `A * X`.
+            fn multiply(&self, x: &MatrixD<$HScalar, ColumnMajor>) -> MatrixD<$HScalar, ColumnMajor> {
+                let mut res = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(
+                    self.layout().dim().0,
+                    x.layout().dim().1,
+                );
+                <$HScalar>::matmul(
+                    <$HScalar as num::One>::one(),
+                    self,
+                    x,
+                    <$HScalar as num::Zero>::zero(),
+                    &mut res,
+                );
+                res
+            }
+
+            /// Compute `A^H * X` as `conj(A^T * conj(X))`, where `A^T` is a strided view of `A`.
+            fn multiply_adjoint(
+                &self,
+                x: &MatrixD<$HScalar, ColumnMajor>,
+            ) -> MatrixD<$HScalar, ColumnMajor> {
+                let (m, n) = self.layout().dim();
+                let stride = self.layout().stride();
+                let transpose = unsafe {
+                    SliceMatrix::<'_, $HScalar, ArbitraryStrideColumnMajor, Dynamic, Dynamic>::from_pointer(
+                        self.get_pointer(),
+                        (n, m),
+                        (stride.1, stride.0),
+                    )
+                };
+                let mut res =
+                    MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, x.layout().dim().1);
+                <$HScalar>::matmul(
+                    <$HScalar as num::One>::one(),
+                    &transpose,
+                    &conjugate(x),
+                    <$HScalar as num::Zero>::zero(),
+                    &mut res,
+                );
+                conjugate(&res)
+            }
+
+            /// Return an `m x l` matrix `Q` with orthonormal columns whose range approximates
+            /// the range of `A`, where `l = min(rank + oversampling, m, n)`.
+            pub fn range_finder<R: Rng>(
+                &self,
+                rank: IndexType,
+                options: &RandomizedOptions,
+                rng: &mut R,
+            ) -> MatrixD<$HScalar, ColumnMajor> {
+                let (m, n) = self.layout().dim();
+                assert!(
+                    (rank > 0) & (rank <= m.min(n)),
+                    "Rank must satisfy 0 < rank <= min(m, n). rank = {}, dim = {:#?}",
+                    rank,
+                    (m, n)
+                );
+                let samples = (rank + options.oversampling).min(m).min(n);
+
+                let mut omega = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, samples);
+                omega.fill_from_rand_standard_normal(rng);
+                let mut q = self.multiply(&omega);
+                thin_qr(&mut q);
+                for _ in 0..options.power_iterations {
+                    let mut z = self.multiply_adjoint(&q);
+                    thin_qr(&mut z);
+                    q = self.multiply(&z);
+                    thin_qr(&mut q);
+                }
+                q
+            }
+
+            /// Compute a randomized singular value decomposition of rank `rank`.
+            pub fn randomized_svd<R: Rng>(
+                &self,
+                rank: IndexType,
+                options: &RandomizedOptions,
+                rng: &mut R,
+            ) -> RandomizedSvd<$HScalar> {
+                let (m, n) = self.layout().dim();
+                let q = self.range_finder(rank, options, rng);
+                let samples = q.layout().dim().1;
+
+                // B^H = A^H * Q = W * R with R = U_R * S * V_R^H, hence
+                // A ~ Q * B = (Q * V_R) * S * (W * U_R)^H.
+                let mut w = self.multiply_adjoint(&q);
+                let r = thin_qr(&mut w);
+                let (u_r, singular_values, v_r) = jacobi_svd(&r);
+
+                let mut u = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(m, rank);
+                <$HScalar>::matmul(
+                    <$HScalar as num::One>::one(),
+                    &q,
+                    &v_r.block((0, 0), (samples, rank)),
+                    <$HScalar as num::Zero>::zero(),
+                    &mut u,
+                );
+                let mut v = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, rank);
+                <$HScalar>::matmul(
+                    <$HScalar as num::One>::one(),
+                    &w,
+                    &u_r.block((0, 0), (samples, rank)),
+                    <$HScalar as num::Zero>::zero(),
+                    &mut v,
+                );
+                let mut vh = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(rank, n);
+                for col in 0..n {
+                    for row in 0..rank {
+                        *vh.get_mut(row, col) = v.get(col, row).conj();
+                    }
+                }
+
+                RandomizedSvd {
+                    u,
+                    singular_values: singular_values[..rank].to_vec(),
+                    vh,
+                }
+            }
+        }
+    };
+}
+
+randomized_impl!(f32);
+randomized_impl!(f64);
+randomized_impl!(c32);
+randomized_impl!(c64);
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    macro_rules! randomized_svd_test {
+        ($HScalar:ty, $tol:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let (m, n, k) = (80, 60, 6);
+                let mut rng = StdRng::seed_from_u64(0);
+
+                // A = X * Y of exact rank k.
+                let mut x = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(m, k);
+                let mut y = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(k, n);
+                x.fill_from_rand_standard_normal(&mut rng);
+                y.fill_from_rand_standard_normal(&mut rng);
+                let mut mat = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(m, n);
+                <$HScalar>::matmul(
+                    <$HScalar as num::One>::one(),
+                    &x,
+                    &y,
+                    <$HScalar as num::Zero>::zero(),
+                    &mut mat,
+                );
+
+                let options = RandomizedOptions {
+                    oversampling: 4,
+                    power_iterations: 1,
+                };
+                let q = mat.range_finder(k, &options, &mut rng);
+                assert_eq!(q.layout().dim(), (m, k + 4));
+                for first in 0..k + 4 {
+                    for second in 0..k + 4 {
+                        let mut value = <$HScalar as num::Zero>::zero();
+                        for row in 0..m {
+                            value += q.get(row, first).conj() * q.get(row, second);
+                        }
+                        let expect = if first == second { 1.0 } else { 0.0 };
+                        assert!((value - expect).abs() < $tol);
+                    }
+                }
+
+                let svd = mat.randomized_svd(k, &options, &mut rng);
+                assert_eq!(svd.rank(), k);
+                assert_eq!(svd.u().layout().dim(), (m, k));
+                assert_eq!(svd.vh().layout().dim(), (k, n));
+                assert!(svd
+                    .singular_values()
+                    .windows(2)
+                    .all(|pair| pair[0] >= pair[1]));
+
+                let approx = svd.to_dense();
+                let scale = svd.singular_values()[0];
+                for col in 0..n {
+                    for row in 0..m {
+                        assert!((approx.get(row, col) - mat.get(row, col)).abs() < $tol * scale);
+                    }
+                }
+            }
+        };
+    }
+
+    randomized_svd_test!(f32, 1E-4, test_randomized_svd_f32);
+    randomized_svd_test!(f64, 1E-12, test_randomized_svd_f64);
+    randomized_svd_test!(c32, 1E-4, test_randomized_svd_c32);
+    randomized_svd_test!(c64, 1E-12, test_randomized_svd_c64);
+
+    /// Return the `n x n` matrix `U * diag(s) * U^T` with a random orthogonal `U` and
+    /// singular values `s_i = 0.7^i`.
+    fn decaying_matrix(n: IndexType, rng: &mut StdRng) -> MatrixD<f64, ColumnMajor> {
+        let mut u = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+        u.fill_from_rand_standard_normal(rng);
+        thin_qr(&mut u);
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+        for index in 0..n {
+            let sigma = 0.7f64.powi(index as i32);
+            for col in 0..n {
+                for row in 0..n {
+                    *mat.get_mut(row, col) += sigma * u.get(row, index) * u.get(col, index);
+                }
+            }
+        }
+        mat
+    }
+
+    #[test]
+    fn test_power_iterations_and_reproducibility() {
+        let n = 100;
+        let k = 10;
+        let mat = decaying_matrix(n, &mut StdRng::seed_from_u64(1));
+
+        // The optimal rank-k error in the spectral norm is 0.7^k.
+        let mut errors = Vec::new();
+        for power_iterations in [0, 3] {
+            let options = RandomizedOptions {
+                oversampling: 2,
+                power_iterations,
+            };
+            let svd = mat.randomized_svd(k, &options, &mut StdRng::seed_from_u64(2));
+            for (index, sigma) in svd.singular_values().iter().enumerate() {
+                assert!(*sigma <= 0.7f64.powi(index as i32) * (1.0 + 1E-12));
+            }
+            let error = (0..k)
+                .map(|index| (svd.singular_values()[index] - 0.7f64.powi(index as i32)).abs())
+                .fold(0.0, f64::max);
+            errors.push(error);
+        }
+        assert!(errors[1] < errors[0]);
+        assert!(errors[1] < 1E-4);
+
+        let options = RandomizedOptions::default();
+        let first = mat.randomized_svd(k, &options, &mut StdRng::seed_from_u64(3));
+        let second = mat.randomized_svd(k, &options, &mut StdRng::seed_from_u64(3));
+        assert_eq!(first.singular_values(), second.singular_values());
+    }
+}
@@ -0,0 +1,270 @@
+//! Reductions of matrices.
+//!
+//! The following reductions are defined for every [Matrix], including lazy expressions
+//! and matrices with band or triangular layouts, whose elements outside the stored
+//! structure are treated as zero.
+//!
+//! - `sum`, `prod` and `mean` of all elements.
+//! - `trace`, the sum of the diagonal elements.
+//! - `sum_rows`, the sum of all rows as a row vector, and `sum_cols`, the sum of all
+//!   columns as a column vector.
+//! - `max`, `min`, `argmax` and `argmin` for real types. The position is the first one
+//!   in column major order and `NaN` elements are ignored. Complex matrices can be
+//!   reduced e.g. through `abs().argmax()`.
+//!
+//! Sums of all elements and of the diagonal use pairwise summation, whose error grows
+//! only with the logarithm of the number of elements. The sums along an axis traverse
+//! the matrix column by column and accumulate with Kahan's compensated summation.
+//!
+//! ```
+//! # use householder::*;
+//! let mut mat = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 3);
+//! *mat.get_mut(0, 0) = 1.0;
+//! *mat.get_mut(1, 2) = 5.0;
+//! *mat.get_mut(1, 1) = -2.0;
+//! assert_eq!(mat.sum(), 4.0);
+//! assert_eq!(mat.trace(), -1.0);
+//! assert_eq!(mat.argmax(), Some((1, 2)));
+//! assert_eq!(mat.sum_rows().get1d(2), 5.0);
+//! assert_eq!(mat.sum_cols().get1d(1), 3.0);
+//! ```
+
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::*;
+use num::Float;
+
+/// The number of terms that are summed directly by [pairwise_sum].
+const PAIRWISE_BLOCK_SIZE: IndexType = 32;
+
+/// Return the sum of `term(index)` for `index` in `start..end` by pairwise summation.
+pub(crate) fn pairwise_sum<Item: HScalar, F: Fn(IndexType) -> Item>(
+    start: IndexType,
+    end: IndexType,
+    term: &F,
+) -> Item {
+    if end - start <= PAIRWISE_BLOCK_SIZE {
+        (start..end).fold(Item::zero(), |acc, index| acc + term(index))
+    } else {
+        let mid = start + (end - start) / 2;
+        pairwise_sum(start, mid, term) + pairwise_sum(mid, end, term)
+    }
+}
+
+/// A sum with Kahan's compensated summation.
+#[derive(Clone, Copy)]
+pub(crate) struct KahanSum<Item: HScalar> {
+    sum: Item,
+    compensation: Item,
+}
+
+impl<Item: HScalar> KahanSum<Item> {
+    pub(crate) fn new() -> Self {
+        Self {
+            sum: Item::zero(),
+            compensation: Item::zero(),
+        }
+    }
+
+    pub(crate) fn add(&mut self, value: Item) {
+        let value = value - self.compensation;
+        let sum = self.sum + value;
+        self.compensation = (sum - self.sum) - value;
+        self.sum = sum;
+    }
+
+    pub(crate) fn value(&self) -> Item {
+        self.sum
+    }
+}
+
+impl<
+        Item: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > Matrix<Item, MatImpl, L, RS, CS>
+{
+    /// Return the sum of all elements.
+    pub fn sum(&self) -> Item {
+        let (m, n) = self.layout().dim();
+        pairwise_sum(0, m * n, &|index| self.element(index % m, index / m))
+    }
+
+    /// Return the product of all elements.
+    pub fn prod(&self) -> Item {
+        let (m, n) = self.layout().dim();
+        let mut res = Item::one();
+        for col in 0..n {
+            for row in 0..m {
+                res *= self.element(row, col);
+            }
+        }
+        res
+    }
+
+    /// Return the mean of all elements, which is `NaN` for an empty matrix.
+    pub fn mean(&self) -> Item {
+        let (m, n) = self.layout().dim();
+        self.sum() / num::cast::<IndexType, Item>(m * n).unwrap()
+    }
+
+    /// Return the sum of the diagonal elements.
+    pub fn trace(&self) -> Item {
+        let (m, n) = self.layout().dim();
+        pairwise_sum(0, m.min(n), &|index| self.element(index, index))
+    }
+
+    /// Return the sum of all rows.
+    pub fn sum_rows(&self) -> RowVectorD<Item> {
+        let (m, n) = self.layout().dim();
+        let mut res = RowVectorD::<Item>::zeros_from_length(n);
+        for col in 0..n {
+            let mut sum = KahanSum::new();
+            for row in 0..m {
+                sum.add(self.element(row, col));
+            }
+            unsafe { *res.get1d_unchecked_mut(col) = sum.value() };
+        }
+        res
+    }
+
+    /// Return the sum of all columns.
+    pub fn sum_cols(&self) -> ColumnVectorD<Item> {
+        let (m, n) = self.layout().dim();
+        let mut sums = vec![KahanSum::<Item>::new(); m];
+        for col in 0..n {
+            for (row, sum) in sums.iter_mut().enumerate() {
+                sum.add(self.element(row, col));
+            }
+        }
+        let mut res = ColumnVectorD::<Item>::zeros_from_length(m);
+        for (row, sum) in sums.iter().enumerate() {
+            unsafe { *res.get1d_unchecked_mut(row) = sum.value() };
+        }
+        res
+    }
+}
+
+impl<
+        Item: HScalar + Float,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > Matrix<Item, MatImpl, L, RS, CS>
+{
+    /// Return the position of the first element in column major order for which
+    /// `better(value, current)` holds against all previous candidates.
+    fn arg_extremum(&self, better: impl Fn(Item, Item) -> bool) -> Option<(IndexType, IndexType)> {
+        let (m, n) = self.layout().dim();
+        let mut res: Option<((IndexType, IndexType), Item)> = None;
+        for col in 0..n {
+            for row in 0..m {
+                let value = self.element(row, col);
+                if value.is_nan() {
+                    continue;
+                }
+                match res {
+                    Some((_, current)) if !better(value, current) => (),
+                    _ => res = Some(((row, col), value)),
+                }
+            }
+        }
+        res.map(|(position, _)| position)
+    }
+
+    /// Return the position of the largest element, or `None` if there is no element
+    /// that is not `NaN`.
+    pub fn argmax(&self) -> Option<(IndexType, IndexType)> {
+        self.arg_extremum(|value, current| value > current)
+    }
+
+    /// Return the position of the smallest element, or `None` if there is no element
+    /// that is not `NaN`.
+    pub fn argmin(&self) -> Option<(IndexType, IndexType)> {
+        self.arg_extremum(|value, current| value < current)
+    }
+
+    /// Return the largest element, or `None` if there is no element that is not `NaN`.
+    pub fn max(&self) -> Option<Item> {
+        self.argmax().map(|(row, col)| self.element(row, col))
+    }
+
+    /// Return the smallest element, or `None` if there is no element that is not `NaN`.
+    pub fn min(&self) -> Option<Item> {
+        self.argmin().map(|(row, col)| self.element(row, col))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+
+    #[test]
+    fn test_accurate_sums() {
+        // Naive summation of 10000 times 0.1 in single precision is off by about 0.1.
+        let mut mat = MatrixD::<f32, ColumnMajor>::zeros_from_dim(100, 100);
+        mat.for_each(|elem| *elem = 0.1);
+        assert!((mat.sum() - 1000.0).abs() < 1E-3);
+        assert!((mat.mean() - 0.1).abs() < 1E-7);
+
+        let mut row = MatrixD::<f32, RowMajor>::zeros_from_dim(1, 10000);
+        row.for_each(|elem| *elem = 0.1);
+        assert!((row.sum_cols().get1d(0) - 1000.0).abs() < 1E-3);
+        let col = row.sum_rows();
+        assert_eq!(col.length(), 10000);
+        assert_eq!(col.get1d(17), 0.1);
+    }
+
+    #[test]
+    fn test_complex_reductions() {
+        let mut mat = MatrixD::<c64, RowMajor>::zeros_from_dim(2, 2);
+        *mat.get_mut(0, 0) = c64::new(1.0, 1.0);
+        *mat.get_mut(0, 1) = c64::new(0.0, 2.0);
+        *mat.get_mut(1, 0) = c64::new(3.0, 0.0);
+        *mat.get_mut(1, 1) = c64::new(1.0, -1.0);
+        assert_eq!(mat.sum(), c64::new(5.0, 2.0));
+        assert_eq!(mat.trace(), c64::new(2.0, 0.0));
+        assert_eq!(mat.prod(), c64::new(0.0, 2.0) * c64::new(6.0, 0.0));
+        assert_eq!(mat.mean(), c64::new(1.25, 0.5));
+        assert_eq!(mat.sum_rows().get1d(1), c64::new(1.0, 1.0));
+        assert_eq!(mat.sum_cols().get1d(0), c64::new(1.0, 3.0));
+        assert_eq!(Matrix::from_ref(&mat).abs().argmax(), Some((1, 0)));
+        assert_eq!(mat.re().argmin(), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_extrema() {
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(3, 2);
+        *mat.get_mut(0, 0) = f64::NAN;
+        *mat.get_mut(2, 0) = 4.0;
+        *mat.get_mut(1, 1) = 4.0;
+        *mat.get_mut(0, 1) = -1.0;
+        assert_eq!(mat.argmax(), Some((2, 0)));
+        assert_eq!(mat.max(), Some(4.0));
+        assert_eq!(mat.argmin(), Some((0, 1)));
+        assert_eq!(mat.min(), Some(-1.0));
+        assert_eq!((2.0 * &mat).max(), Some(8.0));
+
+        let empty = MatrixD::<f64, ColumnMajor>::zeros_from_dim(0, 3);
+        assert_eq!(empty.max(), None);
+        assert_eq!(empty.sum(), 0.0);
+        assert!(empty.mean().is_nan());
+        assert_eq!(empty.sum_rows().length(), 3);
+    }
+
+    #[test]
+    fn test_band_reductions() {
+        let mat = TridiagonalMatrix::<f64>::from_diagonals(&[1.0; 3], &[2.0; 4], &[-3.0; 3]);
+        assert_eq!(mat.sum(), 2.0);
+        assert_eq!(mat.trace(), 8.0);
+        assert_eq!(mat.prod(), 0.0);
+        assert_eq!(mat.min(), Some(-3.0));
+        assert_eq!(mat.sum_rows().get1d(0), 3.0);
+        assert_eq!(mat.sum_cols().get1d(3), 3.0);
+    }
+}
@@ -0,0 +1,577 @@
+//! Iterative refinement and mixed precision solvers.
+//!
+//! Iterative refinement improves an approximate solution `x` of `A * x = b` by repeating
+//! the steps
+//!
+//! 1. compute the residual `r = b - A * x`,
+//! 2. solve `A * d = r` with an existing factorisation of `A`,
+//! 3. update `x = x + d`.
+//!
+//! The residual is computed with compensated dot products (Ogita, Rump and Oishi,
+//! SIAM J. Sci. Comput. 26(6), 2005) as if in twice the working precision. This removes
+//! the influence of the rounding errors of the factorisation and usually gives a solution
+//! that is accurate to working precision with one or two refinement steps.
+//! [IterativeRefinement] works with every factorisation that implements
+//! [Preconditioner], i.e. [DenseLu], [DenseCholesky](crate::dense_solvers::DenseCholesky),
+//! [BandedLu](crate::band_solvers::BandedLu) and
+//! [TridiagonalLdlt](crate::band_solvers::TridiagonalLdlt).
+//!
+//! In mixed precision mode a double precision matrix is factorised in single precision
+//! through `mixed_precision_lu`, which is about twice as fast as the double precision
+//! factorisation. The resulting [MixedPrecisionLu] solves the correction equations in
+//! single precision, while the residuals and the solution are kept in double precision
+//! (Lapack `dsgesv`). This converges to double precision accuracy as long as the
+//! condition number of `A` is well below `1 / eps` of single precision, i.e. about `1E7`.
+//! Otherwise the refinement stagnates and reports that it has not converged. The caller
+//! can then fall back to a double precision factorisation.
+//!
+//! The iteration stops if the normwise backward error
+//! `|b - A * x|_inf / (|A|_inf * |x|_inf + |b|_inf)` drops below a tolerance, if the
+//! corrections stop decreasing by at least a factor of two, or if the maximum number of
+//! iterations is reached. The outcome is described by a [ConvergenceReport] whose
+//! residuals are the backward errors.
+//!
+//! The scalar types of both precisions are connected through the [MixedPrecision] trait.
+//! Dynamic matrices are converted with `to_double`, which is exact, and `to_single`, which
+//! rounds and returns a [LinAlgError::PrecisionOverflow] if a finite element is too large
+//! for single precision. Both are built on [ScalarCast], so they follow the overflow
+//! policy of [conversion](crate::conversion): the lazy `cast` overflows to infinity, the
+//! checked conversions here report the overflow.
+//!
+//! ```
+//! # use householder::*;
+//! let n = 20;
+//! let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+//! let mut rhs = ColumnVectorD::<f64>::zeros_from_length(n);
+//! for row in 0..n {
+//!     for col in 0..n {
+//!         *mat.get_mut(row, col) = 1.0 / (1.0 + row as f64 + 2.0 * col as f64);
+//!     }
+//!     *mat.get_mut(row, row) += 2.0;
+//!     *rhs.get1d_mut(row) = 1.0;
+//! }
+//! let lu = mat.mixed_precision_lu().unwrap();
+//! let mut sol = ColumnVectorD::<f64>::zeros_from_length(n);
+//! let report = IterativeRefinement::new(&mat, &lu).solve(&rhs, &mut sol);
+//! assert!(report.converged);
+//! assert!(report.residual_norm < 1E-15);
+//! ```
+
+use crate::conversion::ScalarCast;
+use crate::data_container::{DataContainer, VectorContainer};
+use crate::dense_solvers::DenseLu;
+use crate::error::LinAlgError;
+use crate::iterative::{ConvergenceReport, Preconditioner, RealType};
+use crate::layouts::*;
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::*;
+use num::Float;
+
+/// Double precision scalar types with a single precision counterpart.
+///
+/// Both directions are conversions through [ScalarCast]. Rounding to single precision
+/// uses [checked_cast](ScalarCast::checked_cast) to detect overflow, the conversion back
+/// to double precision is exact.
+pub trait MixedPrecision: HScalar + ScalarCast<Self::Single> {
+    /// The single precision type, i.e. [f32] for [f64] and [c32] for [c64].
+    type Single: HScalar + ScalarCast<Self>;
+}
+
+impl MixedPrecision for f64 {
+    type Single = f32;
+}
+
+impl MixedPrecision for c64 {
+    type Single = c32;
+}
+
+macro_rules! precision_cast {
+    ($Single:ty, $Double:ty) => {
+        impl<L: BaseLayoutType<IndexLayout = L>, RS: SizeIdentifier, CS: SizeIdentifier>
+            GenericBaseMatrix<$Single, L, VectorContainer<$Single>, RS, CS>
+        {
+            /// Convert to a double precision matrix with the same layout.
+            pub fn to_double(&self) -> GenericBaseMatrix<$Double, L, VectorContainer<$Double>, RS, CS> {
+                let layout = self.layout();
+                let mut res = GenericBaseMatrix::<$Double, L, VectorContainer<$Double>, RS, CS>::from_data(
+                    VectorContainer::new(layout.raw_size()),
+                    layout.index_layout(),
+                );
+                for index in 0..layout.number_of_elements() {
+                    unsafe {
+                        *res.get1d_unchecked_mut(index) =
+                            ScalarCast::<$Double>::cast(self.get1d_unchecked(index))
+                    };
+                }
+                res
+            }
+        }
+
+        impl<L: BaseLayoutType<IndexLayout = L>, RS: SizeIdentifier, CS: SizeIdentifier>
+            GenericBaseMatrix<$Double, L, VectorContainer<$Double>, RS, CS>
+        {
+            /// Round to a single precision matrix with the same layout. A
+            /// [LinAlgError::PrecisionOverflow] is returned if a finite element is too
+            /// large for single precision.
+            pub fn to_single(
+                &self,
+            ) -> Result<GenericBaseMatrix<$Single, L, VectorContainer<$Single>, RS, CS>, LinAlgError>
+            {
+                let layout = self.layout();
+                let mut res = GenericBaseMatrix::<$Single, L, VectorContainer<$Single>, RS, CS>::from_data(
+                    VectorContainer::new(layout.raw_size()),
+                    layout.index_layout(),
+                );
+                for index in 0..layout.number_of_elements() {
+                    let value = unsafe { self.get1d_unchecked(index) };
+                    match ScalarCast::<$Single>::checked_cast(value) {
+                        Some(value) => unsafe { *res.get1d_unchecked_mut(index) = value },
+                        None => {
+                            let (row, col) = layout.convert_1d_2d(index);
+                            return Err(LinAlgError::PrecisionOverflow(row, col));
+                        }
+                    }
+                }
+                Ok(res)
+            }
+        }
+    };
+}
+
+precision_cast!(f32, f64);
+precision_cast!(c32, c64);
+
+/// An LU decomposition with partial pivoting of a double precision matrix that is
+/// computed in single precision.
+///
+/// As a [Preconditioner] it solves `A * x = b` with the single precision factors. The
+/// right-hand side is scaled by its largest absolute value before it is rounded, so that
+/// residuals of any magnitude can be passed without overflow or underflow.
+pub struct MixedPrecisionLu<Item: MixedPrecision> {
+    lu: DenseLu<Item::Single>,
+}
+
+impl<Item: MixedPrecision> MixedPrecisionLu<Item> {
+    /// Return the single precision LU decomposition.
+    pub fn lu(&self) -> &DenseLu<Item::Single> {
+        &self.lu
+    }
+
+    /// Return the dimension `n` of the factorised `n x n` matrix.
+    pub fn dim(&self) -> IndexType {
+        self.lu.dim()
+    }
+}
+
+impl<Item: MixedPrecision> Preconditioner<Item> for MixedPrecisionLu<Item> {
+    fn dimension(&self) -> IndexType {
+        self.dim()
+    }
+
+    fn apply_inverse(&self, x: &ColumnVectorD<Item>, y: &mut ColumnVectorD<Item>) {
+        let n = self.dim();
+        assert!(
+            (x.length() == n) & (y.length() == n),
+            "Incompatible dimensions for y = A^{{-1}} * x: A = {}, x = {}, y = {}",
+            n,
+            x.length(),
+            y.length()
+        );
+        let scale = max_abs(x);
+        if scale == num::Zero::zero() {
+            y.for_each(|elem| *elem = Item::zero());
+            return;
+        }
+
+        let inverse_scale = Item::from_real(<RealType<Item> as num::One>::one() / scale);
+        let mut rhs = ColumnVectorD::<Item::Single>::zeros_from_length(n);
+        for index in 0..n {
+            let value = unsafe { x.get1d_unchecked(index) } * inverse_scale;
+            unsafe { *rhs.get1d_unchecked_mut(index) = value.checked_cast().unwrap() };
+        }
+        self.lu.solve(TransposeMode::NoTrans, &mut rhs);
+        let scale = Item::from_real(scale);
+        for index in 0..n {
+            unsafe { *y.get1d_unchecked_mut(index) = rhs.get1d_unchecked(index).cast() * scale };
+        }
+    }
+}
+
+/// Compute the LU decomposition in single precision of a double precision matrix.
+fn mixed_precision_lu<
+    Item: MixedPrecision,
+    MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>,
+    L: LayoutType,
+>(
+    mat: &Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+) -> Result<MixedPrecisionLu<Item>, LinAlgError> {
+    let (m, n) = mat.layout().dim();
+    let mut single = MatrixD::<Item::Single, ColumnMajor>::zeros_from_dim(m, n);
+    for col in 0..n {
+        for row in 0..m {
+            match unsafe { mat.get_unchecked(row, col) }.checked_cast() {
+                Some(value) => unsafe { *single.get_unchecked_mut(row, col) = value },
+                None => return Err(LinAlgError::PrecisionOverflow(row, col)),
+            }
+        }
+    }
+    Ok(MixedPrecisionLu { lu: single.lu()? })
+}
+
+macro_rules! mixed_precision_impl {
+    ($Layout:ident) => {
+        impl<Item: MixedPrecision, Data: DataContainer<Item = Item>>
+            GenericBaseMatrix<Item, $Layout, Data, Dynamic, Dynamic>
+        {
+            /// Compute the LU decomposition with partial pivoting of a square matrix in
+            /// single precision. A [LinAlgError::PrecisionOverflow] is returned if an
+            /// element is too large for single precision.
+            pub fn mixed_precision_lu(&self) -> Result<MixedPrecisionLu<Item>, LinAlgError> {
+                mixed_precision_lu(self)
+            }
+        }
+    };
+}
+
+mixed_precision_impl!(RowMajor);
+mixed_precision_impl!(ColumnMajor);
+mixed_precision_impl!(ArbitraryStrideRowMajor);
+mixed_precision_impl!(ArbitraryStrideColumnMajor);
+
+/// A sum of real numbers that is accumulated as if in twice the working precision
+/// (algorithm `Sum2` of Ogita, Rump and Oishi).
+struct CompensatedSum<Real: Float> {
+    sum: Real,
+    error: Real,
+}
+
+impl<Real: Float> CompensatedSum<Real> {
+    fn new(value: Real) -> Self {
+        Self {
+            sum: value,
+            error: Real::zero(),
+        }
+    }
+
+    /// Add `value` and accumulate the rounding error with `TwoSum`.
+    fn add(&mut self, value: Real) {
+        let sum = self.sum + value;
+        let correction = sum - self.sum;
+        self.error = self.error + ((self.sum - (sum - correction)) + (value - correction));
+        self.sum = sum;
+    }
+
+    /// Add `a * b`, whose rounding error is computed exactly with a fused multiply-add.
+    fn add_product(&mut self, a: Real, b: Real) {
+        let product = a * b;
+        self.error = self.error + a.mul_add(b, -product);
+        self.add(product);
+    }
+
+    fn value(&self) -> Real {
+        self.sum + self.error
+    }
+}
+
+/// Return the largest absolute value of a vector.
+fn max_abs<Item: HScalar>(x: &ColumnVectorD<Item>) -> RealType<Item> {
+    (0..x.length()).fold(num::Zero::zero(), |acc, index| {
+        Float::max(acc, unsafe { x.get1d_unchecked(index) }.abs())
+    })
+}
+
+/// Iterative refinement of the solution of `A * x = b` with residuals in twice the
+/// working precision.
+///
+/// The corrections are computed with a [Preconditioner] that solves with a
+/// factorisation of `A`, e.g. a [DenseLu] or a [MixedPrecisionLu].
+pub struct IterativeRefinement<
+    'a,
+    Item: HScalar,
+    MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>,
+    L: LayoutType,
+> {
+    mat: &'a Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+    solver: &'a dyn Preconditioner<Item>,
+    tol: RealType<Item>,
+    max_iter: IndexType,
+}
+
+impl<'a, Item: HScalar, MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>, L: LayoutType>
+    IterativeRefinement<'a, Item, MatImpl, L>
+{
+    /// Create a new refinement for the matrix `mat`, whose corrections are computed by
+    /// `solver`. The default tolerance for the backward error is `sqrt(n) * eps` and the
+    /// default maximum number of iterations is 30.
+    pub fn new(
+        mat: &'a Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+        solver: &'a dyn Preconditioner<Item>,
+    ) -> Self {
+        let dim = mat.layout().dim();
+        assert!(
+            (dim.0 == dim.1) & (solver.dimension() == dim.0),
+            "Iterative refinement requires a square matrix of the solver dimension. dim = {:#?}, solver = {}",
+            dim,
+            solver.dimension()
+        );
+        let n = num::cast::<IndexType, RealType<Item>>(dim.0.max(1)).unwrap();
+        Self {
+            mat,
+            solver,
+            tol: Float::sqrt(n) * <RealType<Item> as Float>::epsilon(),
+            max_iter: 30,
+        }
+    }
+
+    /// Set the tolerance for the normwise backward error.
+    pub fn with_tol(mut self, tol: RealType<Item>) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// Set the maximum number of refinement steps.
+    pub fn with_max_iterations(mut self, max_iter: IndexType) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    /// Compute `res = b - A * x` with compensated dot products.
+    fn residual(
+        &self,
+        rhs: &ColumnVectorD<Item>,
+        sol: &ColumnVectorD<Item>,
+        res: &mut ColumnVectorD<Item>,
+    ) {
+        let n = rhs.length();
+        for row in 0..n {
+            let value = unsafe { rhs.get1d_unchecked(row) };
+            let mut re = CompensatedSum::new(value.re());
+            let mut im = CompensatedSum::new(value.im());
+            for col in 0..n {
+                let a = self.mat.element(row, col);
+                let x = unsafe { sol.get1d_unchecked(col) };
+                re.add_product(-a.re(), x.re());
+                re.add_product(a.im(), x.im());
+                im.add_product(-a.re(), x.im());
+                im.add_product(-a.im(), x.re());
+            }
+            unsafe {
+                *res.get1d_unchecked_mut(row) =
+                    Item::from_complex(Item::complex(re.value(), im.value())).unwrap()
+            };
+        }
+    }
+
+    /// Solve `A * x = b`. On input `sol` contains the initial guess, which is usually
+    /// zero, and on output the refined solution.
+    pub fn solve(
+        &self,
+        rhs: &ColumnVectorD<Item>,
+        sol: &mut ColumnVectorD<Item>,
+    ) -> ConvergenceReport<RealType<Item>> {
+        let n = self.mat.layout().dim().0;
+        assert!(
+            (rhs.length() == n) & (sol.length() == n),
+            "Incompatible dimensions: A = {:#?}, b = {}, x = {}",
+            self.mat.layout().dim(),
+            rhs.length(),
+            sol.length()
+        );
+
+        let anorm = self.mat.norm_inf();
+        let bnorm = max_abs(rhs);
+        let backward_error = |res: &ColumnVectorD<Item>, sol: &ColumnVectorD<Item>| {
+            let scale = anorm * max_abs(sol) + bnorm;
+            if scale == num::Zero::zero() {
+                scale
+            } else {
+                max_abs(res) / scale
+            }
+        };
+
+        let mut res = ColumnVectorD::<Item>::zeros_from_length(n);
+        let mut update = ColumnVectorD::<Item>::zeros_from_length(n);
+        self.residual(rhs, sol, &mut res);
+        let mut error = backward_error(&res, sol);
+        let mut history = vec![error];
+        let mut iterations = 0;
+        let mut previous = <RealType<Item> as Float>::infinity();
+
+        while (error > self.tol) & (iterations < self.max_iter) {
+            self.solver.apply_inverse(&res, &mut update);
+            let update_norm = max_abs(&update);
+            // Stop if the corrections do not decrease sufficiently or break down.
+            if (update_norm + update_norm > previous) | update_norm.is_nan() {
+                break;
+            }
+            previous = update_norm;
+            for index in 0..n {
+                unsafe { *sol.get1d_unchecked_mut(index) += update.get1d_unchecked(index) };
+            }
+
+            iterations += 1;
+            self.residual(rhs, sol, &mut res);
+            error = backward_error(&res, sol);
+            history.push(error);
+        }
+
+        ConvergenceReport {
+            converged: error <= self.tol,
+            iterations,
+            residual_norm: error,
+            residual_history: history,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use rand::prelude::*;
+
+    macro_rules! refinement_test {
+        ($HScalar:ty, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let n = 60;
+                let mut rng = StdRng::seed_from_u64(0);
+                let mut mat = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                mat.fill_from_rand_standard_normal(&mut rng);
+                let mut expect = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                expect.fill_from_rand_standard_normal(&mut rng);
+                let mut rhs = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                for row in 0..n {
+                    for col in 0..n {
+                        *rhs.get1d_mut(row) += mat.get(row, col) * expect.get1d(col);
+                    }
+                }
+                let error = |sol: &ColumnVectorD<$HScalar>| {
+                    (0..n).fold(0.0, |acc: f64, index| {
+                        acc.max((sol.get1d(index) - expect.get1d(index)).abs())
+                    })
+                };
+
+                // Refinement with a single precision factorisation.
+                let lu = mat.mixed_precision_lu().unwrap();
+                let mut sol = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                let report = IterativeRefinement::new(&mat, &lu).solve(&rhs, &mut sol);
+                assert!(report.converged);
+                assert!(report.iterations > 1);
+                assert_eq!(report.residual_history.len(), report.iterations + 1);
+                assert!(report.residual_norm < 1E-15);
+                assert!(error(&sol) < 1E-11);
+
+                // Refinement with a double precision factorisation of the same matrix.
+                let lu = mat.lu().unwrap();
+                let mut sol = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                let report = IterativeRefinement::new(&mat, &lu)
+                    .with_tol(0.0)
+                    .with_max_iterations(3)
+                    .solve(&rhs, &mut sol);
+                assert!(!report.converged);
+                assert!(report.residual_norm < 1E-15);
+                assert!(error(&sol) < 1E-11);
+            }
+        };
+    }
+
+    refinement_test!(f64, test_refinement_f64);
+    refinement_test!(c64, test_refinement_c64);
+
+    #[test]
+    fn test_ill_conditioned_mixed_precision() {
+        // The Hilbert matrix of dimension 10 has a condition number of about 1E13.
+        let n = 10;
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+        for col in 0..n {
+            for row in 0..n {
+                *mat.get_mut(row, col) = 1.0 / (1 + row + col) as f64;
+            }
+        }
+        let mut rhs = ColumnVectorD::<f64>::zeros_from_length(n);
+        rhs.for_each(|elem| *elem = 1.0);
+        let lu = mat.mixed_precision_lu().unwrap();
+        let mut sol = ColumnVectorD::<f64>::zeros_from_length(n);
+        let report = IterativeRefinement::new(&mat, &lu).solve(&rhs, &mut sol);
+        assert!(!report.converged);
+        assert!(report.iterations < 30);
+    }
+
+    #[test]
+    fn test_cholesky_refinement() {
+        // A symmetric positive definite matrix with a condition number of about 1E6.
+        let n = 30;
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(n, n);
+        for col in 0..n {
+            for row in 0..n {
+                *mat.get_mut(row, col) = 1.0 / (1 + row + col) as f64;
+            }
+            *mat.get_mut(col, col) += 1E-6;
+        }
+        let mut expect = ColumnVectorD::<f64>::zeros_from_length(n);
+        let mut rhs = ColumnVectorD::<f64>::zeros_from_length(n);
+        for index in 0..n {
+            *expect.get1d_mut(index) = (index as f64).cos();
+        }
+        for row in 0..n {
+            for col in 0..n {
+                *rhs.get1d_mut(row) += mat.get(row, col) * expect.get1d(col);
+            }
+        }
+
+        let chol = mat.cholesky().unwrap();
+        let mut sol = ColumnVectorD::<f64>::zeros_from_length(n);
+        let report = IterativeRefinement::new(&mat, &chol).solve(&rhs, &mut sol);
+        assert!(report.converged);
+        assert!(report.iterations <= 3);
+        assert!(report.residual_norm < 1E-15);
+        for index in 0..n {
+            assert!((sol.get1d(index) - expect.get1d(index)).abs() < 1E-8);
+        }
+    }
+
+    #[test]
+    fn test_tridiagonal_refinement() {
+        let n = 50;
+        let diag = vec![2.0; n];
+        let off = vec![-1.0; n - 1];
+        let mat = TridiagonalMatrix::<f64>::from_diagonals(&off, &diag, &off);
+        let mut rhs = ColumnVectorD::<f64>::zeros_from_length(n);
+        for index in 0..n {
+            *rhs.get1d_mut(index) = (index as f64).sin();
+        }
+        let ldlt = mat.ldlt().unwrap();
+        let mut sol = ColumnVectorD::<f64>::zeros_from_length(n);
+        let report = IterativeRefinement::new(&mat, &ldlt).solve(&rhs, &mut sol);
+        assert!(report.converged);
+        assert!(report.iterations <= 2);
+    }
+
+    #[test]
+    fn test_precision_casts() {
+        let mut mat = MatrixD::<c32, RowMajor>::zeros_from_dim(2, 3);
+        *mat.get_mut(1, 2) = c32::new(0.1, -2.0);
+        let double = mat.to_double();
+        assert_eq!(double.layout().dim(), (2, 3));
+        assert_eq!(double.get(1, 2), c64::new(0.1f32 as f64, -2.0));
+        assert_eq!(double.to_single().unwrap().get(1, 2), mat.get(1, 2));
+
+        let mut vec = ColumnVectorD::<f64>::zeros_from_length(3);
+        *vec.get1d_mut(1) = 1E300;
+        assert_eq!(
+            vec.to_single().err(),
+            Some(LinAlgError::PrecisionOverflow(1, 0))
+        );
+        *vec.get1d_mut(1) = f64::INFINITY;
+        assert_eq!(vec.to_single().unwrap().get1d(1), f32::INFINITY);
+
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(2, 2);
+        *mat.get_mut(0, 1) = -1E40;
+        assert_eq!(
+            mat.mixed_precision_lu().err(),
+            Some(LinAlgError::PrecisionOverflow(0, 1))
+        );
+    }
+}
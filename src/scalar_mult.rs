@@ -173,7 +173,6 @@ scalar_mult_impl!(c32);
 scalar_mult_impl!(c64);
 
 #[cfg(test)]
-
 mod test {
 
     use crate::layouts::RowMajor;
@@ -191,4 +190,17 @@ mod test {
 
         assert_eq!(res.get(1, 2), 10.0);
     }
+
+    #[test]
+    fn scalar_mult_banded() {
+        let mut band = BandedMatrix::<f64>::zeros_from_band(4, 3, 2, 0);
+        *band.get_mut(2, 0) = 1.0;
+        *band.get_mut(3, 2) = 3.0;
+
+        let res = (2.0 * Matrix::from_ref(&band)).eval();
+
+        assert_eq!((res.layout().kl(), res.layout().ku()), (2, 0));
+        assert_eq!(res.get(2, 0), 2.0);
+        assert_eq!(res.get(3, 2), 6.0);
+    }
 }
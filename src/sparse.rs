@@ -0,0 +1,345 @@
+//! Sparse matrix formats.
+//!
+//! This module provides three storage formats for sparse matrices.
+//! - [CooMatrix]: The coordinate format stores a list of `(row, col, value)` triplets.
+//!   It is meant for assembly. Triplets can be pushed in any order and duplicate
+//!   entries are allowed. They are summed up on conversion to a compressed format.
+//! - [CsrMatrix]: The compressed sparse row format stores the column indices and values
+//!   of each row consecutively. It is the format of choice for matrix-vector products.
+//! - [CscMatrix]: The compressed sparse column format is the column oriented counterpart
+//!   of [CsrMatrix].
+//!
+//! All formats provide the number of stored entries through `nnz`, an iterator over
+//! the stored triplets and duplicate summation through `sum_duplicates`. Conversions
+//! to and from dynamic dense matrices are provided by `from_dense` and `to_dense`.
+//! The compressed formats can be permuted and reordered by reverse Cuthill–McKee, see
+//! [reordering].
+//!
+//! The compressed formats implement [UnsafeRandomAccess](crate::traits::UnsafeRandomAccess)
+//! and [Layout](crate::traits::Layout) with a [RowMajor](crate::layouts::RowMajor) (CSR)
+//! or [ColumnMajor](crate::layouts::ColumnMajor) (CSC) index layout. Wrapped into a
+//! [Matrix](crate::matrix::Matrix) they can therefore be read by the existing expression
+//! machinery, e.g. `(Matrix::new(csr) + dense).eval()`. Random access requires a search
+//! within a row (column) and is not meant for performance critical code.
+
+pub mod coo;
+pub mod csc;
+pub mod csr;
+pub mod products;
+pub mod reordering;
+
+pub use coo::*;
+pub use csc::*;
+pub use csr::*;
+
+use crate::types::{HScalar, IndexType};
+
+/// Compressed storage shared by the CSR and CSC formats.
+///
+/// The storage is described in terms of a major dimension (rows for CSR,
+/// columns for CSC) and a minor dimension. The entries of major index `i`
+/// are stored at the positions `indptr[i]..indptr[i + 1]` of `indices` and `data`.
+pub(crate) struct CompressedStorage<Item: HScalar> {
+    n_major: IndexType,
+    n_minor: IndexType,
+    indptr: Vec<IndexType>,
+    indices: Vec<IndexType>,
+    data: Vec<Item>,
+    // True if the minor indices within each major index are strictly increasing.
+    canonical: bool,
+}
+
+impl<Item: HScalar> CompressedStorage<Item> {
+    /// Create new storage from compressed arrays after checking their consistency.
+    pub(crate) fn new(
+        n_major: IndexType,
+        n_minor: IndexType,
+        indptr: Vec<IndexType>,
+        indices: Vec<IndexType>,
+        data: Vec<Item>,
+    ) -> Self {
+        assert_eq!(
+            indptr.len(),
+            n_major + 1,
+            "Index pointer has length {} but expected length {}.",
+            indptr.len(),
+            n_major + 1
+        );
+        assert_eq!(
+            indices.len(),
+            data.len(),
+            "Number of indices {} differs from number of values {}.",
+            indices.len(),
+            data.len()
+        );
+        assert_eq!(indptr[0], 0, "Index pointer must start with 0.");
+        assert_eq!(
+            indptr[n_major],
+            data.len(),
+            "Last index pointer entry {} differs from number of values {}.",
+            indptr[n_major],
+            data.len()
+        );
+        assert!(
+            indptr.windows(2).all(|w| w[0] <= w[1]),
+            "Index pointer must be nondecreasing."
+        );
+        assert!(
+            indices.iter().all(|&index| index < n_minor),
+            "Index out of bounds. Minor dimension is {}.",
+            n_minor
+        );
+
+        let canonical = (0..n_major).all(|major| {
+            indices[indptr[major]..indptr[major + 1]]
+                .windows(2)
+                .all(|w| w[0] < w[1])
+        });
+
+        Self {
+            n_major,
+            n_minor,
+            indptr,
+            indices,
+            data,
+            canonical,
+        }
+    }
+
+    /// Create canonical storage from triplets given as major and minor indices.
+    /// Duplicate entries are summed.
+    pub(crate) fn from_triplets(
+        n_major: IndexType,
+        n_minor: IndexType,
+        major: &[IndexType],
+        minor: &[IndexType],
+        values: &[Item],
+    ) -> Self {
+        let nnz = values.len();
+        assert!(
+            (major.len() == nnz) & (minor.len() == nnz),
+            "Triplet arrays have different lengths: {}, {}, {}",
+            major.len(),
+            minor.len(),
+            nnz
+        );
+        assert!(
+            major.iter().all(|&index| index < n_major) & minor.iter().all(|&index| index < n_minor),
+            "Triplet index out of bounds."
+        );
+
+        // Counting sort by the major index.
+        let mut indptr = vec![0; n_major + 1];
+        for &index in major {
+            indptr[index + 1] += 1;
+        }
+        for index in 0..n_major {
+            indptr[index + 1] += indptr[index];
+        }
+
+        let mut next = indptr.clone();
+        let mut indices = vec![0; nnz];
+        let mut data = vec![Item::zero(); nnz];
+        for ((&major_index, &minor_index), &value) in major.iter().zip(minor).zip(values) {
+            let pos = next[major_index];
+            indices[pos] = minor_index;
+            data[pos] = value;
+            next[major_index] += 1;
+        }
+
+        let mut storage = Self {
+            n_major,
+            n_minor,
+            indptr,
+            indices,
+            data,
+            canonical: false,
+        };
+        storage.sum_duplicates();
+        storage
+    }
+
+    /// Sort the minor indices within each major index and sum duplicate entries.
+    pub(crate) fn sum_duplicates(&mut self) {
+        if self.canonical {
+            return;
+        }
+
+        let mut buffer = Vec::<(IndexType, Item)>::new();
+        let mut count = 0;
+        let mut start = 0;
+        for major in 0..self.n_major {
+            let end = self.indptr[major + 1];
+            buffer.clear();
+            buffer.extend((start..end).map(|pos| (self.indices[pos], self.data[pos])));
+            buffer.sort_by_key(|&(index, _)| index);
+
+            let first = count;
+            for &(index, value) in buffer.iter() {
+                if (count > first) && (self.indices[count - 1] == index) {
+                    self.data[count - 1] += value;
+                } else {
+                    self.indices[count] = index;
+                    self.data[count] = value;
+                    count += 1;
+                }
+            }
+            start = end;
+            self.indptr[major + 1] = count;
+        }
+        self.indices.truncate(count);
+        self.data.truncate(count);
+        self.canonical = true;
+    }
+
+    /// Return the value at `(major, minor)`. Duplicates are summed.
+    pub(crate) fn get(&self, major: IndexType, minor: IndexType) -> Item {
+        let range = self.indptr[major]..self.indptr[major + 1];
+        if self.canonical {
+            match self.indices[range.clone()].binary_search(&minor) {
+                Ok(pos) => self.data[range.start + pos],
+                Err(_) => Item::zero(),
+            }
+        } else {
+            let mut value = Item::zero();
+            for pos in range {
+                if self.indices[pos] == minor {
+                    value += self.data[pos];
+                }
+            }
+            value
+        }
+    }
+
+    /// Return an iterator over `(major, minor, value)` triplets.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (IndexType, IndexType, Item)> + '_ {
+        (0..self.n_major).flat_map(move |major| {
+            (self.indptr[major]..self.indptr[major + 1])
+                .map(move |pos| (major, self.indices[pos], self.data[pos]))
+        })
+    }
+
+    /// Return the storage of the transposed matrix, i.e. the same matrix in
+    /// the other compressed format.
+    pub(crate) fn transpose(&self) -> Self {
+        let (major, (minor, values)): (Vec<_>, (Vec<_>, Vec<_>)) = self
+            .iter()
+            .map(|(major, minor, value)| (minor, (major, value)))
+            .unzip();
+        Self::from_triplets(self.n_minor, self.n_major, &major, &minor, &values)
+    }
+
+    /// Call `y(i, sum_k op(a[i, k]) * x(k))` for each major index `i`, where `op`
+    /// is the identity or the complex conjugation.
+    pub(crate) fn gather(
+        &self,
+        conj: bool,
+        x: impl Fn(IndexType) -> Item,
+        mut y: impl FnMut(IndexType, Item),
+    ) {
+        for major in 0..self.n_major {
+            y(major, self.gather_major(major, conj, &x));
+        }
+    }
+
+    /// Like [CompressedStorage::gather] but with the major indices partitioned
+    /// into contiguous chunks of similar numbers of entries that are processed in parallel.
+    /// The output `y` has one element per major index, and `update(y[i], value)` is called
+    /// with the gathered value of each major index `i`.
+    #[cfg(feature = "threading")]
+    pub(crate) fn par_gather(
+        &self,
+        x: &(impl Fn(IndexType) -> Item + Sync),
+        y: &mut [Item],
+        update: &(impl Fn(&mut Item, Item) + Sync),
+    ) where
+        Item: Send + Sync,
+    {
+        assert_eq!(
+            y.len(),
+            self.n_major,
+            "Output has length {} but the storage has {} major indices.",
+            y.len(),
+            self.n_major
+        );
+
+        let nchunks = 4 * rayon::current_num_threads();
+        let chunk_nnz = 1 + self.nnz() / nchunks;
+        let mut bounds = vec![0];
+        while *bounds.last().unwrap() < self.n_major {
+            let start = *bounds.last().unwrap();
+            let target = self.indptr[start] + chunk_nnz;
+            let end = self.indptr.partition_point(|&pos| pos < target);
+            bounds.push(end.clamp(start + 1, self.n_major));
+        }
+
+        rayon::scope(|scope| {
+            let mut rest = y;
+            for w in bounds.windows(2) {
+                let (chunk, tail) = std::mem::take(&mut rest).split_at_mut(w[1] - w[0]);
+                rest = tail;
+                let start = w[0];
+                scope.spawn(move |_| {
+                    for (offset, elem) in chunk.iter_mut().enumerate() {
+                        update(elem, self.gather_major(start + offset, false, x));
+                    }
+                });
+            }
+        });
+    }
+
+    fn gather_major(&self, major: IndexType, conj: bool, x: &impl Fn(IndexType) -> Item) -> Item {
+        let mut value = Item::zero();
+        for pos in self.indptr[major]..self.indptr[major + 1] {
+            let elem = if conj {
+                self.data[pos].conj()
+            } else {
+                self.data[pos]
+            };
+            value += elem * x(self.indices[pos]);
+        }
+        value
+    }
+
+    /// Call `y(k, op(a[i, k]) * x(i))` for each stored entry `a[i, k]`, where `op`
+    /// is the identity or the complex conjugation. The sum of these contributions
+    /// for a minor index `k` is `sum_i op(a[i, k]) * x(i)`.
+    pub(crate) fn scatter(
+        &self,
+        conj: bool,
+        x: impl Fn(IndexType) -> Item,
+        mut y: impl FnMut(IndexType, Item),
+    ) {
+        for major in 0..self.n_major {
+            let x_major = x(major);
+            for pos in self.indptr[major]..self.indptr[major + 1] {
+                let value = if conj {
+                    self.data[pos].conj()
+                } else {
+                    self.data[pos]
+                };
+                y(self.indices[pos], value * x_major);
+            }
+        }
+    }
+
+    pub(crate) fn nnz(&self) -> IndexType {
+        self.data.len()
+    }
+
+    pub(crate) fn indptr(&self) -> &[IndexType] {
+        &self.indptr
+    }
+
+    pub(crate) fn indices(&self) -> &[IndexType] {
+        &self.indices
+    }
+
+    pub(crate) fn data(&self) -> &[Item] {
+        &self.data
+    }
+
+    pub(crate) fn data_mut(&mut self) -> &mut [Item] {
+        &mut self.data
+    }
+}
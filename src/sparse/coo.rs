@@ -0,0 +1,196 @@
+//! Coordinate format.
+
+use super::{CompressedStorage, CscMatrix, CsrMatrix};
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::{HScalar, IndexType};
+
+/// A sparse matrix in coordinate (COO) format.
+///
+/// The matrix is stored as a list of `(row, col, value)` triplets in the
+/// order in which they were pushed. Duplicate entries are allowed and represent
+/// the sum of their values. This is the natural format for the assembly of e.g.
+/// finite element matrices. For computations convert to [CsrMatrix] or [CscMatrix].
+pub struct CooMatrix<Item: HScalar> {
+    dim: (IndexType, IndexType),
+    rows: Vec<IndexType>,
+    cols: Vec<IndexType>,
+    values: Vec<Item>,
+}
+
+impl<Item: HScalar> CooMatrix<Item> {
+    /// Create a new empty matrix of dimension `dim`.
+    pub fn new(dim: (IndexType, IndexType)) -> Self {
+        Self::with_capacity(dim, 0)
+    }
+
+    /// Create a new empty matrix with space for `capacity` triplets.
+    pub fn with_capacity(dim: (IndexType, IndexType), capacity: IndexType) -> Self {
+        Self {
+            dim,
+            rows: Vec::with_capacity(capacity),
+            cols: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Create a new matrix from triplet arrays.
+    pub fn from_triplets(
+        dim: (IndexType, IndexType),
+        rows: Vec<IndexType>,
+        cols: Vec<IndexType>,
+        values: Vec<Item>,
+    ) -> Self {
+        assert!(
+            (rows.len() == values.len()) & (cols.len() == values.len()),
+            "Triplet arrays have different lengths: {}, {}, {}",
+            rows.len(),
+            cols.len(),
+            values.len()
+        );
+        assert!(
+            rows.iter().all(|&row| row < dim.0) & cols.iter().all(|&col| col < dim.1),
+            "Triplet index out of bounds for matrix of dimension {:#?}",
+            dim
+        );
+        Self {
+            dim,
+            rows,
+            cols,
+            values,
+        }
+    }
+
+    /// Create a COO matrix from the nonzero elements of a dense matrix.
+    pub fn from_dense<MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>, L: LayoutType>(
+        mat: &Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+    ) -> Self {
+        let dim = mat.layout().dim();
+        let mut coo = Self::new(dim);
+        for row in 0..dim.0 {
+            for col in 0..dim.1 {
+                let value = unsafe { mat.get_unchecked(row, col) };
+                if value != Item::zero() {
+                    coo.push(row, col, value);
+                }
+            }
+        }
+        coo
+    }
+
+    /// Append the triplet `(row, col, value)`.
+    pub fn push(&mut self, row: IndexType, col: IndexType, value: Item) {
+        assert!(
+            (row < self.dim.0) & (col < self.dim.1),
+            "Index ({}, {}) out of bounds for matrix of dimension {:#?}",
+            row,
+            col,
+            self.dim
+        );
+        self.rows.push(row);
+        self.cols.push(col);
+        self.values.push(value);
+    }
+
+    /// Return the dimension `(rows, cols)`.
+    pub fn dim(&self) -> (IndexType, IndexType) {
+        self.dim
+    }
+
+    /// Return the number of stored triplets, including duplicates.
+    pub fn nnz(&self) -> IndexType {
+        self.values.len()
+    }
+
+    /// Return the row indices of the stored triplets.
+    pub fn rows(&self) -> &[IndexType] {
+        &self.rows
+    }
+
+    /// Return the column indices of the stored triplets.
+    pub fn cols(&self) -> &[IndexType] {
+        &self.cols
+    }
+
+    /// Return the values of the stored triplets.
+    pub fn values(&self) -> &[Item] {
+        &self.values
+    }
+
+    /// Return an iterator over the stored `(row, col, value)` triplets.
+    pub fn iter(&self) -> impl Iterator<Item = (IndexType, IndexType, Item)> + '_ {
+        self.rows
+            .iter()
+            .zip(self.cols.iter())
+            .zip(self.values.iter())
+            .map(|((&row, &col), &value)| (row, col, value))
+    }
+
+    /// Sum duplicate entries. Afterwards the triplets are sorted in row-major order.
+    pub fn sum_duplicates(&mut self) {
+        let csr = self.to_csr();
+        *self = csr.to_coo();
+    }
+
+    /// Convert to compressed sparse row format. Duplicates are summed.
+    pub fn to_csr(&self) -> CsrMatrix<Item> {
+        CsrMatrix::from_storage(CompressedStorage::from_triplets(
+            self.dim.0,
+            self.dim.1,
+            &self.rows,
+            &self.cols,
+            &self.values,
+        ))
+    }
+
+    /// Convert to compressed sparse column format. Duplicates are summed.
+    pub fn to_csc(&self) -> CscMatrix<Item> {
+        CscMatrix::from_storage(CompressedStorage::from_triplets(
+            self.dim.1,
+            self.dim.0,
+            &self.cols,
+            &self.rows,
+            &self.values,
+        ))
+    }
+
+    /// Convert to a dense matrix. Duplicates are summed.
+    pub fn to_dense<L: BaseLayoutType>(&self) -> MatrixD<Item, L> {
+        let mut res = MatrixD::<Item, L>::zeros_from_dim(self.dim.0, self.dim.1);
+        for (row, col, value) in self.iter() {
+            *res.get_mut(row, col) += value;
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+    use crate::types::c64;
+
+    #[test]
+    fn test_coo_assembly() {
+        let mut coo = CooMatrix::<c64>::new((3, 2));
+        coo.push(2, 1, c64::new(1.0, 1.0));
+        coo.push(0, 0, c64::new(2.0, 0.0));
+        coo.push(2, 1, c64::new(3.0, -1.0));
+        assert_eq!(coo.nnz(), 3);
+
+        let dense = coo.to_dense::<ColumnMajor>();
+        assert_eq!(dense.get(2, 1), c64::new(4.0, 0.0));
+        assert_eq!(dense.get(1, 1), c64::new(0.0, 0.0));
+
+        coo.sum_duplicates();
+        assert_eq!(
+            coo.iter().collect::<Vec<_>>(),
+            vec![(0, 0, c64::new(2.0, 0.0)), (2, 1, c64::new(4.0, 0.0))]
+        );
+
+        let back = CooMatrix::from_dense(&dense);
+        assert_eq!(back.nnz(), 2);
+        assert_eq!(back.to_csc().data(), coo.values());
+    }
+}
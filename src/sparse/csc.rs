@@ -0,0 +1,188 @@
+//! Compressed sparse column format.
+
+use super::{CompressedStorage, CooMatrix, CsrMatrix};
+use crate::layouts::ColumnMajor;
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::{HScalar, IndexType};
+
+/// A sparse matrix in compressed sparse column (CSC) format.
+///
+/// The row indices and values of column `j` are stored at the positions
+/// `indptr[j]..indptr[j + 1]` of `indices` and `data`.
+pub struct CscMatrix<Item: HScalar> {
+    storage: CompressedStorage<Item>,
+    layout: ColumnMajor,
+}
+
+impl<Item: HScalar> CscMatrix<Item> {
+    /// Create a new CSC matrix from its compressed arrays.
+    ///
+    /// The row indices within a column need not be sorted and may contain duplicates.
+    /// Use [CscMatrix::sum_duplicates] to bring the matrix into canonical form.
+    pub fn new(
+        dim: (IndexType, IndexType),
+        indptr: Vec<IndexType>,
+        indices: Vec<IndexType>,
+        data: Vec<Item>,
+    ) -> Self {
+        Self::from_storage(CompressedStorage::new(dim.1, dim.0, indptr, indices, data))
+    }
+
+    /// Create a new CSC matrix from `(row, col, value)` triplets. Duplicates are summed.
+    pub fn from_triplets(
+        dim: (IndexType, IndexType),
+        rows: &[IndexType],
+        cols: &[IndexType],
+        values: &[Item],
+    ) -> Self {
+        Self::from_storage(CompressedStorage::from_triplets(
+            dim.1, dim.0, cols, rows, values,
+        ))
+    }
+
+    /// Create a CSC matrix from the nonzero elements of a dense matrix.
+    pub fn from_dense<MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>, L: LayoutType>(
+        mat: &Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+    ) -> Self {
+        CooMatrix::from_dense(mat).to_csc()
+    }
+
+    pub(crate) fn from_storage(storage: CompressedStorage<Item>) -> Self {
+        let layout = ColumnMajor::new((storage.n_minor, storage.n_major));
+        Self { storage, layout }
+    }
+
+    pub(crate) fn storage(&self) -> &CompressedStorage<Item> {
+        &self.storage
+    }
+
+    /// Return the dimension `(rows, cols)`.
+    pub fn dim(&self) -> (IndexType, IndexType) {
+        self.layout.dim()
+    }
+
+    /// Return the number of stored entries.
+    pub fn nnz(&self) -> IndexType {
+        self.storage.nnz()
+    }
+
+    /// Return the column pointer array of length `cols + 1`.
+    pub fn indptr(&self) -> &[IndexType] {
+        self.storage.indptr()
+    }
+
+    /// Return the row indices of the stored entries.
+    pub fn indices(&self) -> &[IndexType] {
+        self.storage.indices()
+    }
+
+    /// Return the values of the stored entries.
+    pub fn data(&self) -> &[Item] {
+        self.storage.data()
+    }
+
+    /// Return the values of the stored entries as mutable slice.
+    pub fn data_mut(&mut self) -> &mut [Item] {
+        self.storage.data_mut()
+    }
+
+    /// Sort the row indices within each column and sum duplicate entries.
+    pub fn sum_duplicates(&mut self) {
+        self.storage.sum_duplicates();
+    }
+
+    /// Return an iterator over the stored `(row, col, value)` triplets in column order.
+    pub fn iter(&self) -> impl Iterator<Item = (IndexType, IndexType, Item)> + '_ {
+        self.storage
+            .iter()
+            .map(|(col, row, value)| (row, col, value))
+    }
+
+    /// Convert to coordinate format.
+    pub fn to_coo(&self) -> CooMatrix<Item> {
+        let mut coo = CooMatrix::with_capacity(self.dim(), self.nnz());
+        for (row, col, value) in self.iter() {
+            coo.push(row, col, value);
+        }
+        coo
+    }
+
+    /// Convert to compressed sparse row format.
+    pub fn to_csr(&self) -> CsrMatrix<Item> {
+        CsrMatrix::from_storage(self.storage.transpose())
+    }
+
+    /// Convert to a dense matrix.
+    pub fn to_dense<L: BaseLayoutType>(&self) -> MatrixD<Item, L> {
+        let mut res = MatrixD::<Item, L>::zeros_from_dim(self.dim().0, self.dim().1);
+        for (row, col, value) in self.iter() {
+            *res.get_mut(row, col) += value;
+        }
+        res
+    }
+}
+
+impl<Item: HScalar> Layout for CscMatrix<Item> {
+    type Impl = ColumnMajor;
+
+    fn layout(&self) -> &Self::Impl {
+        &self.layout
+    }
+}
+
+impl<Item: HScalar> SizeType for CscMatrix<Item> {
+    type R = Dynamic;
+    type C = Dynamic;
+}
+
+impl<Item: HScalar> UnsafeRandomAccess for CscMatrix<Item> {
+    type Item = Item;
+
+    #[inline]
+    unsafe fn get_unchecked(&self, row: IndexType, col: IndexType) -> Self::Item {
+        self.storage.get(col, row)
+    }
+
+    #[inline]
+    unsafe fn get1d_unchecked(&self, index: IndexType) -> Self::Item {
+        let (row, col) = self.layout.convert_1d_2d(index);
+        self.storage.get(col, row)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::RowMajor;
+
+    #[test]
+    fn test_csc_from_triplets() {
+        let rows = [2, 0, 1, 0, 2];
+        let cols = [1, 2, 0, 2, 1];
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let csc = CscMatrix::<f64>::from_triplets((3, 4), &rows, &cols, &values);
+
+        assert_eq!(csc.dim(), (3, 4));
+        assert_eq!(csc.nnz(), 3);
+        assert_eq!(csc.indptr(), &[0, 1, 2, 3, 3]);
+        assert_eq!(csc.indices(), &[1, 2, 0]);
+        assert_eq!(csc.data(), &[3.0, 6.0, 6.0]);
+        assert_eq!(
+            csc.iter().collect::<Vec<_>>(),
+            vec![(1, 0, 3.0), (2, 1, 6.0), (0, 2, 6.0)]
+        );
+        assert_eq!(csc.get(0, 2), 6.0);
+        assert_eq!(csc.get(0, 3), 0.0);
+
+        let dense = csc.to_dense::<RowMajor>();
+        let csr = csc.to_csr();
+        assert_eq!(csr.dim(), (3, 4));
+        for row in 0..3 {
+            for col in 0..4 {
+                assert_eq!(csr.get(row, col), dense.get(row, col));
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! Compressed sparse row format.
+
+use super::{CompressedStorage, CooMatrix, CscMatrix};
+use crate::layouts::RowMajor;
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::{HScalar, IndexType};
+
+/// A sparse matrix in compressed sparse row (CSR) format.
+///
+/// The column indices and values of row `i` are stored at the positions
+/// `indptr[i]..indptr[i + 1]` of `indices` and `data`.
+pub struct CsrMatrix<Item: HScalar> {
+    storage: CompressedStorage<Item>,
+    layout: RowMajor,
+}
+
+impl<Item: HScalar> CsrMatrix<Item> {
+    /// Create a new CSR matrix from its compressed arrays.
+    ///
+    /// The column indices within a row need not be sorted and may contain duplicates.
+    /// Use [CsrMatrix::sum_duplicates] to bring the matrix into canonical form.
+    pub fn new(
+        dim: (IndexType, IndexType),
+        indptr: Vec<IndexType>,
+        indices: Vec<IndexType>,
+        data: Vec<Item>,
+    ) -> Self {
+        Self::from_storage(CompressedStorage::new(dim.0, dim.1, indptr, indices, data))
+    }
+
+    /// Create a new CSR matrix from `(row, col, value)` triplets. Duplicates are summed.
+    pub fn from_triplets(
+        dim: (IndexType, IndexType),
+        rows: &[IndexType],
+        cols: &[IndexType],
+        values: &[Item],
+    ) -> Self {
+        Self::from_storage(CompressedStorage::from_triplets(
+            dim.0, dim.1, rows, cols, values,
+        ))
+    }
+
+    /// Create a CSR matrix from the nonzero elements of a dense matrix.
+    pub fn from_dense<MatImpl: MatrixTrait<Item, L, Dynamic, Dynamic>, L: LayoutType>(
+        mat: &Matrix<Item, MatImpl, L, Dynamic, Dynamic>,
+    ) -> Self {
+        CooMatrix::from_dense(mat).to_csr()
+    }
+
+    pub(crate) fn from_storage(storage: CompressedStorage<Item>) -> Self {
+        let layout = RowMajor::new((storage.n_major, storage.n_minor));
+        Self { storage, layout }
+    }
+
+    pub(crate) fn storage(&self) -> &CompressedStorage<Item> {
+        &self.storage
+    }
+
+    /// Return the dimension `(rows, cols)`.
+    pub fn dim(&self) -> (IndexType, IndexType) {
+        self.layout.dim()
+    }
+
+    /// Return the number of stored entries.
+    pub fn nnz(&self) -> IndexType {
+        self.storage.nnz()
+    }
+
+    /// Return the row pointer array of length `rows + 1`.
+    pub fn indptr(&self) -> &[IndexType] {
+        self.storage.indptr()
+    }
+
+    /// Return the column indices of the stored entries.
+    pub fn indices(&self) -> &[IndexType] {
+        self.storage.indices()
+    }
+
+    /// Return the values of the stored entries.
+    pub fn data(&self) -> &[Item] {
+        self.storage.data()
+    }
+
+    /// Return the values of the stored entries as mutable slice.
+    pub fn data_mut(&mut self) -> &mut [Item] {
+        self.storage.data_mut()
+    }
+
+    /// Sort the column indices within each row and sum duplicate entries.
+    pub fn sum_duplicates(&mut self) {
+        self.storage.sum_duplicates();
+    }
+
+    /// Return an iterator over the stored `(row, col, value)` triplets in row order.
+    pub fn iter(&self) -> impl Iterator<Item = (IndexType, IndexType, Item)> + '_ {
+        self.storage.iter()
+    }
+
+    /// Convert to coordinate format.
+    pub fn to_coo(&self) -> CooMatrix<Item> {
+        let mut coo = CooMatrix::with_capacity(self.dim(), self.nnz());
+        for (row, col, value) in self.iter() {
+            coo.push(row, col, value);
+        }
+        coo
+    }
+
+    /// Convert to compressed sparse column format.
+    pub fn to_csc(&self) -> CscMatrix<Item> {
+        CscMatrix::from_storage(self.storage.transpose())
+    }
+
+    /// Convert to a dense matrix.
+    pub fn to_dense<L: BaseLayoutType>(&self) -> MatrixD<Item, L> {
+        let mut res = MatrixD::<Item, L>::zeros_from_dim(self.dim().0, self.dim().1);
+        for (row, col, value) in self.iter() {
+            *res.get_mut(row, col) += value;
+        }
+        res
+    }
+}
+
+impl<Item: HScalar> Layout for CsrMatrix<Item> {
+    type Impl = RowMajor;
+
+    fn layout(&self) -> &Self::Impl {
+        &self.layout
+    }
+}
+
+impl<Item: HScalar> SizeType for CsrMatrix<Item> {
+    type R = Dynamic;
+    type C = Dynamic;
+}
+
+impl<Item: HScalar> UnsafeRandomAccess for CsrMatrix<Item> {
+    type Item = Item;
+
+    #[inline]
+    unsafe fn get_unchecked(&self, row: IndexType, col: IndexType) -> Self::Item {
+        self.storage.get(row, col)
+    }
+
+    #[inline]
+    unsafe fn get1d_unchecked(&self, index: IndexType) -> Self::Item {
+        let (row, col) = self.layout.convert_1d_2d(index);
+        self.storage.get(row, col)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::ColumnMajor;
+
+    #[test]
+    fn test_csr_from_triplets() {
+        let rows = [2, 0, 1, 0, 2];
+        let cols = [1, 2, 0, 2, 1];
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let csr = CsrMatrix::<f64>::from_triplets((3, 3), &rows, &cols, &values);
+
+        assert_eq!(csr.nnz(), 3);
+        assert_eq!(csr.indptr(), &[0, 1, 2, 3]);
+        assert_eq!(csr.indices(), &[2, 0, 1]);
+        assert_eq!(csr.data(), &[6.0, 3.0, 6.0]);
+        assert_eq!(
+            csr.iter().collect::<Vec<_>>(),
+            vec![(0, 2, 6.0), (1, 0, 3.0), (2, 1, 6.0)]
+        );
+    }
+
+    #[test]
+    fn test_csr_unsorted_duplicates() {
+        let mut csr = CsrMatrix::<f64>::new(
+            (2, 3),
+            vec![0, 3, 4],
+            vec![2, 0, 2, 1],
+            vec![1.0, 2.0, 3.0, 4.0],
+        );
+        assert_eq!(csr.get(0, 2), 4.0);
+        csr.sum_duplicates();
+        assert_eq!(csr.nnz(), 3);
+        assert_eq!(csr.indices(), &[0, 2, 1]);
+        assert_eq!(csr.get(0, 2), 4.0);
+        assert_eq!(csr.get(1, 0), 0.0);
+    }
+
+    #[test]
+    fn test_csr_in_expression() {
+        let mut dense = MatrixD::<f64, RowMajor>::zeros_from_dim(2, 2);
+        *dense.get_mut(0, 0) = 1.0;
+        *dense.get_mut(1, 0) = 2.0;
+        let csr = CsrMatrix::<f64>::from_dense(&dense);
+        assert_eq!(csr.nnz(), 2);
+
+        let res = (Matrix::new(csr) + &dense).eval();
+        assert_eq!(res.get(0, 0), 2.0);
+        assert_eq!(res.get(1, 0), 4.0);
+        assert_eq!(res.get(1, 1), 0.0);
+
+        let csr = CsrMatrix::<f64>::from_dense(&dense);
+        let back = csr.to_csc().to_csr().to_dense::<ColumnMajor>();
+        for index in 0..4 {
+            let (row, col) = back.layout().convert_1d_2d(index);
+            assert_eq!(back.get(row, col), dense.get(row, col));
+        }
+    }
+}
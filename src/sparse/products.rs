@@ -0,0 +1,407 @@
+//! Products of sparse matrices with dense vectors and matrices.
+//!
+//! The compressed formats provide the sparse matrix-vector product (SpMV)
+//! `y = alpha * op(A) * x + beta * y` through `matvec` and the sparse matrix-matrix
+//! product (SpMM) `C = alpha * op(A) * B + beta * C` through `matmul`, where `op` is
+//! the identity, the transpose or the conjugate transpose. Vectors are accessed through
+//! their one-dimensional indexing. Both products accumulate directly into the result and
+//! do not allocate.
+//!
+//! For a [CsrMatrix] the non-transposed product is computed row by row, while the
+//! transposed product scatters the rows into the result. For a [CscMatrix] it is the
+//! other way round. With the `threading` feature enabled, `CsrMatrix::par_matvec`
+//! computes the non-transposed product in parallel, partitioning the rows into contiguous
+//! chunks with similar numbers of nonzero entries.
+//!
+//! The [Dot] trait is implemented for products with dynamic dense matrices and column
+//! vectors.
+
+use super::{CompressedStorage, CscMatrix, CsrMatrix};
+use crate::data_container::DataContainer;
+use crate::matrix::*;
+use crate::matrix_multiply::Dot;
+use crate::traits::*;
+use crate::types::*;
+
+/// Compute `y = alpha * op(A) * x + beta * y` for a matrix `A` in compressed storage.
+/// The flag `row_major` indicates whether the major dimension of the storage are rows.
+/// The vector `x` is read through `x(index)`, and `y(index, value, scale)` has to set
+/// `y[index] = value + scale * y[index]`.
+fn apply<Item: HScalar>(
+    storage: &CompressedStorage<Item>,
+    row_major: bool,
+    trans: TransposeMode,
+    alpha: Item,
+    x: impl Fn(IndexType) -> Item,
+    beta: Item,
+    mut y: impl FnMut(IndexType, Item, Item),
+) {
+    let conj = trans == TransposeMode::ConjTrans;
+    if row_major == (trans == TransposeMode::NoTrans) {
+        storage.gather(conj, x, |index, value| y(index, alpha * value, beta));
+    } else {
+        for index in 0..storage.n_minor {
+            y(index, Item::zero(), beta);
+        }
+        storage.scatter(conj, x, |index, value| y(index, alpha * value, Item::one()));
+    }
+}
+
+/// Return the dimension of `op(A)`.
+fn op_dim(dim: (IndexType, IndexType), trans: TransposeMode) -> (IndexType, IndexType) {
+    match trans {
+        TransposeMode::NoTrans => dim,
+        _ => (dim.1, dim.0),
+    }
+}
+
+/// Return `value + beta * current`. The value `current` is not read if `beta` is zero.
+#[inline]
+fn scale_add<Item: HScalar>(value: Item, beta: Item, current: Item) -> Item {
+    if beta == Item::zero() {
+        value
+    } else {
+        value + beta * current
+    }
+}
+
+/// Check the dimensions of `x` and `y` in `y = op(A) * x`.
+fn check_matvec_dims(
+    dim: (IndexType, IndexType),
+    len_x: IndexType,
+    len_y: IndexType,
+    trans: TransposeMode,
+) {
+    let op_dim = op_dim(dim, trans);
+    assert!(
+        (op_dim.1 == len_x) & (op_dim.0 == len_y),
+        "Incompatible dimensions for y = op(A) * x: op(A) = {:#?}, x = {}, y = {}",
+        op_dim,
+        len_x,
+        len_y
+    );
+}
+
+macro_rules! sparse_products {
+    ($Sparse:ident, $row_major:expr) => {
+        impl<Item: HScalar> $Sparse<Item> {
+            /// Compute `y = alpha * op(A) * x + beta * y`.
+            pub fn matvec<
+                MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+                MatImpl2: MatrixTraitMut<Item, L2, RS2, CS2>,
+                L1: LayoutType,
+                L2: LayoutType,
+                RS1: SizeIdentifier,
+                CS1: SizeIdentifier,
+                RS2: SizeIdentifier,
+                CS2: SizeIdentifier,
+            >(
+                &self,
+                trans: TransposeMode,
+                alpha: Item,
+                vec_x: &Matrix<Item, MatImpl1, L1, RS1, CS1>,
+                beta: Item,
+                vec_y: &mut Matrix<Item, MatImpl2, L2, RS2, CS2>,
+            ) {
+                check_matvec_dims(
+                    self.dim(),
+                    vec_x.layout().number_of_elements(),
+                    vec_y.layout().number_of_elements(),
+                    trans,
+                );
+
+                apply(
+                    self.storage(),
+                    $row_major,
+                    trans,
+                    alpha,
+                    |index| unsafe { vec_x.get1d_unchecked(index) },
+                    beta,
+                    |index, value, scale| {
+                        let elem = unsafe { vec_y.get1d_unchecked_mut(index) };
+                        *elem = scale_add(value, scale, *elem);
+                    },
+                );
+            }
+
+            /// Compute `mat_c = alpha * op(A) * mat_b + beta * mat_c`.
+            pub fn matmul<
+                MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+                MatImpl2: MatrixTraitMut<Item, L2, RS2, CS2>,
+                L1: LayoutType,
+                L2: LayoutType,
+                RS1: SizeIdentifier,
+                CS1: SizeIdentifier,
+                RS2: SizeIdentifier,
+                CS2: SizeIdentifier,
+            >(
+                &self,
+                trans: TransposeMode,
+                alpha: Item,
+                mat_b: &Matrix<Item, MatImpl1, L1, RS1, CS1>,
+                beta: Item,
+                mat_c: &mut Matrix<Item, MatImpl2, L2, RS2, CS2>,
+            ) {
+                let op_dim = op_dim(self.dim(), trans);
+                let dim_b = mat_b.layout().dim();
+                let dim_c = mat_c.layout().dim();
+                assert!(
+                    (op_dim.1 == dim_b.0) & (op_dim.0 == dim_c.0) & (dim_b.1 == dim_c.1),
+                    "Incompatible dimensions for C = op(A) * B: op(A) = {:#?}, B = {:#?}, C = {:#?}",
+                    op_dim,
+                    dim_b,
+                    dim_c
+                );
+
+                for col in 0..dim_b.1 {
+                    apply(
+                        self.storage(),
+                        $row_major,
+                        trans,
+                        alpha,
+                        |row| unsafe { mat_b.get_unchecked(row, col) },
+                        beta,
+                        |row, value, scale| {
+                            let elem = unsafe { mat_c.get_unchecked_mut(row, col) };
+                            *elem = scale_add(value, scale, *elem);
+                        },
+                    );
+                }
+            }
+        }
+
+        // Sparse x ColumnVector = ColumnVector
+        impl<Item: HScalar, L: LayoutType, Data: DataContainer<Item = Item>>
+            Dot<GenericBaseMatrix<Item, L, Data, Dynamic, Fixed1>> for $Sparse<Item>
+        {
+            type Output = ColumnVectorD<Item>;
+
+            fn dot(&self, rhs: &GenericBaseMatrix<Item, L, Data, Dynamic, Fixed1>) -> Self::Output {
+                let mut res = Self::Output::zeros_from_length(self.dim().0);
+                self.matvec(
+                    TransposeMode::NoTrans,
+                    Item::one(),
+                    rhs,
+                    Item::zero(),
+                    &mut res,
+                );
+                res
+            }
+        }
+
+        // Sparse x Matrix = Matrix
+        impl<Item: HScalar, L: LayoutType, Data: DataContainer<Item = Item>>
+            Dot<GenericBaseMatrix<Item, L, Data, Dynamic, Dynamic>> for $Sparse<Item>
+        {
+            type Output = MatrixD<Item, L::IndexLayout>;
+
+            fn dot(
+                &self,
+                rhs: &GenericBaseMatrix<Item, L, Data, Dynamic, Dynamic>,
+            ) -> Self::Output {
+                let mut res = Self::Output::zeros_from_dim(self.dim().0, rhs.layout().dim().1);
+                self.matmul(
+                    TransposeMode::NoTrans,
+                    Item::one(),
+                    rhs,
+                    Item::zero(),
+                    &mut res,
+                );
+                res
+            }
+        }
+    };
+}
+
+sparse_products!(CsrMatrix, true);
+sparse_products!(CscMatrix, false);
+
+#[cfg(feature = "threading")]
+impl<Item: HScalar + Send + Sync> CsrMatrix<Item> {
+    /// Compute `y = alpha * A * x + beta * y` in parallel. The result is written directly
+    /// into the memory of the contiguous vector `y`.
+    pub fn par_matvec<
+        MatImpl: MatrixTrait<Item, L1, RS1, CS1>,
+        Data: crate::data_container::DataContainerMut<Item = Item>,
+        L1: LayoutType,
+        L2: VectorBaseLayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    >(
+        &self,
+        alpha: Item,
+        vec_x: &Matrix<Item, MatImpl, L1, RS1, CS1>,
+        beta: Item,
+        vec_y: &mut GenericBaseMatrixMut<Item, L2, Data, RS2, CS2>,
+    ) where
+        Matrix<Item, MatImpl, L1, RS1, CS1>: Sync,
+    {
+        let len_y = vec_y.layout().number_of_elements();
+        check_matvec_dims(
+            self.dim(),
+            vec_x.layout().number_of_elements(),
+            len_y,
+            TransposeMode::NoTrans,
+        );
+
+        self.storage().par_gather(
+            &|index| unsafe { vec_x.get1d_unchecked(index) },
+            vec_y.get_slice_mut(0, len_y),
+            &|elem, value| *elem = scale_add(alpha * value, beta, *elem),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+    use crate::sparse::CooMatrix;
+    use crate::test_utils::{assert_close, op_element};
+
+    /// Return a sparse 4 x 3 test matrix with a duplicate entry. The imaginary
+    /// parts of the entries are multiplied with `imag`.
+    fn test_coo<Item: HScalar>(imag: Item) -> CooMatrix<Item> {
+        let mut coo = CooMatrix::<Item>::new((4, 3));
+        for (row, col, re, im) in [
+            (0, 0, 1.0, 1.0),
+            (0, 2, 2.0, 0.0),
+            (1, 1, -1.0, 3.0),
+            (3, 0, 4.0, -2.0),
+            (3, 2, 0.5, 0.5),
+            (0, 2, 1.0, 0.0),
+        ] {
+            let value =
+                num::cast::<f64, Item>(re).unwrap() + imag * num::cast::<f64, Item>(im).unwrap();
+            coo.push(row, col, value);
+        }
+        coo
+    }
+
+    macro_rules! spmv_test {
+        ($HScalar:ty, $imag:expr, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let coo = test_coo::<$HScalar>($imag);
+                let dense = coo.to_dense::<RowMajor>();
+                let csr = coo.to_csr();
+                let csc = coo.to_csc();
+                let alpha = num::cast::<f64, $HScalar>(2.0).unwrap();
+                let beta = num::cast::<f64, $HScalar>(0.5).unwrap();
+                let ncols = 3;
+
+                for trans in [
+                    TransposeMode::NoTrans,
+                    TransposeMode::Trans,
+                    TransposeMode::ConjTrans,
+                ] {
+                    let (m, n) = op_dim((4, 3), trans);
+                    let mut vec_x = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                    let mut mat_b = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, ncols);
+                    for row in 0..n {
+                        *vec_x.get1d_mut(row) =
+                            num::cast::<f64, $HScalar>(1.0 + row as f64).unwrap();
+                        for col in 0..ncols {
+                            *mat_b.get_mut(row, col) =
+                                num::cast::<f64, $HScalar>(1.0 + (row * col) as f64 - col as f64)
+                                    .unwrap();
+                        }
+                    }
+
+                    let mut y_csr = ColumnVectorD::<$HScalar>::zeros_from_length(m);
+                    let mut y_csc = ColumnVectorD::<$HScalar>::zeros_from_length(m);
+                    let mut c_csr = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(m, ncols);
+                    let mut c_csc = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(m, ncols);
+                    for row in 0..m {
+                        *y_csr.get1d_mut(row) = num::cast::<f64, $HScalar>(1.0).unwrap();
+                        *y_csc.get1d_mut(row) = num::cast::<f64, $HScalar>(1.0).unwrap();
+                        for col in 0..ncols {
+                            let value = num::cast::<f64, $HScalar>(1.0 + col as f64).unwrap();
+                            *c_csr.get_mut(row, col) = value;
+                            *c_csc.get_mut(row, col) = value;
+                        }
+                    }
+
+                    csr.matvec(trans, alpha, &vec_x, beta, &mut y_csr);
+                    csc.matvec(trans, alpha, &vec_x, beta, &mut y_csc);
+                    csr.matmul(trans, alpha, &mat_b, beta, &mut c_csr);
+                    csc.matmul(trans, alpha, &mat_b, beta, &mut c_csc);
+
+                    for row in 0..m {
+                        let mut expect = <$HScalar as num::Zero>::zero();
+                        for index in 0..n {
+                            expect += op_element(&dense, trans, row, index) * vec_x.get1d(index);
+                        }
+                        let expect = alpha * expect + beta;
+                        assert_close(y_csr.get1d(row), expect, 1E-5);
+                        assert_close(y_csc.get1d(row), expect, 1E-5);
+
+                        for col in 0..ncols {
+                            let mut expect = <$HScalar as num::Zero>::zero();
+                            for index in 0..n {
+                                expect +=
+                                    op_element(&dense, trans, row, index) * mat_b.get(index, col);
+                            }
+                            let expect = alpha * expect
+                                + beta * num::cast::<f64, $HScalar>(1.0 + col as f64).unwrap();
+                            assert_close(c_csr.get(row, col), expect, 1E-5);
+                            assert_close(c_csc.get(row, col), expect, 1E-5);
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    spmv_test!(f32, 0.0, test_spmv_f32);
+    spmv_test!(f64, 0.0, test_spmv_f64);
+    spmv_test!(c32, c32::new(0.0, 1.0), test_spmv_c32);
+    spmv_test!(c64, c64::new(0.0, 1.0), test_spmv_c64);
+
+    #[test]
+    fn test_sparse_dot() {
+        let csr = test_coo::<f64>(0.0).to_csr();
+        let dense = csr.to_dense::<RowMajor>();
+        let mut mat = MatrixD::<f64, ColumnMajor>::zeros_from_dim(3, 2);
+        *mat.get_mut(0, 0) = 1.0;
+        *mat.get_mut(2, 1) = 2.0;
+
+        let res = csr.dot(&mat);
+        for row in 0..4 {
+            assert_eq!(res.get(row, 0), dense.get(row, 0));
+            assert_eq!(res.get(row, 1), 2.0 * dense.get(row, 2));
+        }
+    }
+
+    #[cfg(feature = "threading")]
+    #[test]
+    fn test_par_spmv() {
+        let n = 1000;
+        let mut coo = CooMatrix::<f64>::new((n, n));
+        for row in 0..n {
+            for col in row.saturating_sub(2)..(row + 3).min(n) {
+                coo.push(row, col, (row + 2 * col) as f64);
+            }
+        }
+        let csr = coo.to_csr();
+
+        let mut vec_x = ColumnVectorD::<f64>::zeros_from_length(n);
+        for index in 0..n {
+            *vec_x.get1d_mut(index) = 1.0 / (1.0 + index as f64);
+        }
+        let mut expect = ColumnVectorD::<f64>::zeros_from_length(n);
+        let mut actual = ColumnVectorD::<f64>::zeros_from_length(n);
+        for index in 0..n {
+            *expect.get1d_mut(index) = index as f64;
+            *actual.get1d_mut(index) = index as f64;
+        }
+        csr.matvec(TransposeMode::NoTrans, 1.5, &vec_x, 0.5, &mut expect);
+        csr.par_matvec(1.5, &vec_x, 0.5, &mut actual);
+        for index in 0..n {
+            assert_eq!(actual.get1d(index), expect.get1d(index));
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! Permutation and reordering of sparse matrices.
+//!
+//! The compressed formats can be permuted explicitly with `permute`, which returns
+//! `P_r^T * A * P_c` in canonical form, i.e. row `i` of the result is row `rows[i]`
+//! of `A` and column `j` is column `cols[j]`.
+//!
+//! The reverse Cuthill–McKee ordering of a square matrix reduces its bandwidth and
+//! profile. It traverses the graph of the symmetrised sparsity pattern of `A + A^T`
+//! breadth first, starting each connected component from a pseudo-peripheral node found
+//! with the algorithm of George and Liu, and visits the neighbours of a node in the order
+//! of increasing degree. The returned permutation is meant to be applied symmetrically.
+//!
+//! ```
+//! # use householder::*;
+//! // The path 0 - 3 - 1 - 4 - 2 has bandwidth 3 in its natural numbering.
+//! let rows = [0, 3, 3, 1, 1, 4, 4, 2];
+//! let cols = [3, 0, 1, 3, 4, 1, 2, 4];
+//! let csr = CsrMatrix::<f64>::from_triplets((5, 5), &rows, &cols, &[1.0; 8]);
+//! let perm = csr.reverse_cuthill_mckee();
+//! let reordered = csr.permute(&perm, &perm);
+//! assert!(reordered.iter().all(|(row, col, _)| row.abs_diff(col) <= 1));
+//! ```
+
+use super::{CompressedStorage, CscMatrix, CsrMatrix};
+use crate::permutation::Permutation;
+use crate::types::{HScalar, IndexType};
+use std::collections::VecDeque;
+
+impl<Item: HScalar> CompressedStorage<Item> {
+    /// Return the storage in which major index `i` is major index `major[i]` of this
+    /// storage and minor index `j` is minor index `minor[j]`.
+    pub(crate) fn permute(&self, major: &Permutation, minor: &Permutation) -> Self {
+        assert_eq!(
+            (major.len(), minor.len()),
+            (self.n_major, self.n_minor),
+            "Permutations of lengths {:?} do not match the dimension {:?}.",
+            (major.len(), minor.len()),
+            (self.n_major, self.n_minor)
+        );
+        let major_inverse = major.inverse();
+        let minor_inverse = minor.inverse();
+        let (major_indices, (minor_indices, values)): (Vec<_>, (Vec<_>, Vec<_>)) = self
+            .iter()
+            .map(|(major, minor, value)| (major_inverse[major], (minor_inverse[minor], value)))
+            .unzip();
+        Self::from_triplets(
+            self.n_major,
+            self.n_minor,
+            &major_indices,
+            &minor_indices,
+            &values,
+        )
+    }
+
+    /// Return the sorted adjacency lists of the symmetrised pattern without self loops.
+    fn adjacency(&self) -> Vec<Vec<IndexType>> {
+        assert_eq!(
+            self.n_major, self.n_minor,
+            "Reordering requires a square matrix but dimension is ({}, {}).",
+            self.n_major, self.n_minor
+        );
+        let mut adjacency = vec![Vec::new(); self.n_major];
+        for (major, minor, _) in self.iter() {
+            if major != minor {
+                adjacency[major].push(minor);
+                adjacency[minor].push(major);
+            }
+        }
+        for neighbours in adjacency.iter_mut() {
+            neighbours.sort_unstable();
+            neighbours.dedup();
+        }
+        adjacency
+    }
+
+    /// Return the reverse Cuthill–McKee ordering of the symmetrised pattern.
+    pub(crate) fn reverse_cuthill_mckee(&self) -> Permutation {
+        let adjacency = self.adjacency();
+        let n = adjacency.len();
+        let degree = |node: IndexType| adjacency[node].len();
+
+        let mut by_degree: Vec<IndexType> = (0..n).collect();
+        by_degree.sort_by_key(|&node| degree(node));
+
+        let mut visited = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        for &candidate in &by_degree {
+            if visited[candidate] {
+                continue;
+            }
+            let start = pseudo_peripheral_node(&adjacency, candidate);
+            visited[start] = true;
+            let mut head = order.len();
+            order.push(start);
+            while head < order.len() {
+                let node = order[head];
+                head += 1;
+                let mut neighbours: Vec<IndexType> = adjacency[node]
+                    .iter()
+                    .copied()
+                    .filter(|&other| !visited[other])
+                    .collect();
+                neighbours.sort_by_key(|&other| degree(other));
+                for other in neighbours {
+                    visited[other] = true;
+                    order.push(other);
+                }
+            }
+        }
+        order.reverse();
+        Permutation::from_indices(order)
+    }
+}
+
+/// Return the breadth first level structure rooted at `root`.
+fn level_structure(adjacency: &[Vec<IndexType>], root: IndexType) -> Vec<Vec<IndexType>> {
+    let mut level = vec![None; adjacency.len()];
+    let mut levels: Vec<Vec<IndexType>> = Vec::new();
+    let mut queue = VecDeque::from([root]);
+    level[root] = Some(0);
+    while let Some(node) = queue.pop_front() {
+        let depth = level[node].unwrap();
+        if levels.len() == depth {
+            levels.push(Vec::new());
+        }
+        levels[depth].push(node);
+        for &other in &adjacency[node] {
+            if level[other].is_none() {
+                level[other] = Some(depth + 1);
+                queue.push_back(other);
+            }
+        }
+    }
+    levels
+}
+
+/// Return a node of large eccentricity in the connected component of `start` by the
+/// algorithm of George and Liu.
+fn pseudo_peripheral_node(adjacency: &[Vec<IndexType>], start: IndexType) -> IndexType {
+    let mut node = start;
+    let mut levels = level_structure(adjacency, node);
+    loop {
+        let candidate = *levels
+            .last()
+            .unwrap()
+            .iter()
+            .min_by_key(|&&other| adjacency[other].len())
+            .unwrap();
+        let candidate_levels = level_structure(adjacency, candidate);
+        if candidate_levels.len() > levels.len() {
+            node = candidate;
+            levels = candidate_levels;
+        } else {
+            return node;
+        }
+    }
+}
+
+impl<Item: HScalar> CsrMatrix<Item> {
+    /// Return `P_r^T * A * P_c`, whose element `(i, j)` is the element
+    /// `(rows[i], cols[j])` of `A`.
+    pub fn permute(&self, rows: &Permutation, cols: &Permutation) -> Self {
+        Self::from_storage(self.storage().permute(rows, cols))
+    }
+
+    /// Return the reverse Cuthill–McKee ordering of a square matrix.
+    pub fn reverse_cuthill_mckee(&self) -> Permutation {
+        self.storage().reverse_cuthill_mckee()
+    }
+}
+
+impl<Item: HScalar> CscMatrix<Item> {
+    /// Return `P_r^T * A * P_c`, whose element `(i, j)` is the element
+    /// `(rows[i], cols[j])` of `A`.
+    pub fn permute(&self, rows: &Permutation, cols: &Permutation) -> Self {
+        Self::from_storage(self.storage().permute(cols, rows))
+    }
+
+    /// Return the reverse Cuthill–McKee ordering of a square matrix.
+    pub fn reverse_cuthill_mckee(&self) -> Permutation {
+        self.storage().reverse_cuthill_mckee()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::RowMajor;
+    use crate::matrix::Matrix;
+    use crate::traits::*;
+
+    fn bandwidth(csr: &CsrMatrix<f64>) -> IndexType {
+        csr.iter()
+            .map(|(row, col, _)| row.abs_diff(col))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The five point Laplacian on a `k x k` grid with scrambled numbering.
+    fn scrambled_laplacian(k: IndexType) -> CsrMatrix<f64> {
+        let n = k * k;
+        let label = |node: IndexType| (node * 7 + 3) % n;
+        let (mut rows, mut cols, mut values) = (Vec::new(), Vec::new(), Vec::new());
+        for i in 0..k {
+            for j in 0..k {
+                let node = i * k + j;
+                rows.push(label(node));
+                cols.push(label(node));
+                values.push(4.0);
+                let mut neighbours = Vec::new();
+                if i + 1 < k {
+                    neighbours.push(node + k);
+                }
+                if j + 1 < k {
+                    neighbours.push(node + 1);
+                }
+                for other in neighbours {
+                    rows.extend([label(node), label(other)]);
+                    cols.extend([label(other), label(node)]);
+                    values.extend([-1.0, -1.0]);
+                }
+            }
+        }
+        CsrMatrix::from_triplets((n, n), &rows, &cols, &values)
+    }
+
+    #[test]
+    fn test_permute() {
+        let csr = CsrMatrix::<f64>::from_triplets((2, 3), &[0, 1, 1], &[2, 0, 1], &[1.0, 2.0, 3.0]);
+        let rows = Permutation::from_indices(vec![1, 0]);
+        let cols = Permutation::from_indices(vec![2, 0, 1]);
+        let permuted = csr.permute(&rows, &cols);
+        let expect = Matrix::new(csr).permuted(&rows, &cols).eval();
+        assert!(Matrix::new(permuted) == expect);
+
+        let csc = CscMatrix::<f64>::from_dense(&expect);
+        let original = csc.permute(&rows.inverse(), &cols.inverse());
+        assert_eq!(original.to_dense::<RowMajor>().get(0, 2), 1.0);
+        assert_eq!(original.to_dense::<RowMajor>().get(1, 1), 3.0);
+    }
+
+    #[test]
+    fn test_reverse_cuthill_mckee() {
+        let k = 8;
+        let csr = scrambled_laplacian(k);
+        assert!(bandwidth(&csr) > 2 * k);
+        let perm = csr.reverse_cuthill_mckee();
+        let reordered = csr.permute(&perm, &perm);
+        assert_eq!(reordered.nnz(), csr.nnz());
+        assert!(bandwidth(&reordered) <= k + 1);
+
+        let csc = CscMatrix::<f64>::from_dense(&csr.to_dense::<RowMajor>());
+        assert_eq!(csc.reverse_cuthill_mckee(), perm);
+    }
+
+    #[test]
+    fn test_reverse_cuthill_mckee_components() {
+        // Two paths 0 - 2 - 4 and 1 - 3 and the isolated node 5.
+        let rows = [0, 2, 2, 4, 1, 3, 5];
+        let cols = [2, 0, 4, 2, 3, 1, 5];
+        let csr = CsrMatrix::<f64>::from_triplets((6, 6), &rows, &cols, &[1.0; 7]);
+        let perm = csr.reverse_cuthill_mckee();
+        assert_eq!(perm.len(), 6);
+        assert_eq!(bandwidth(&csr.permute(&perm, &perm)), 1);
+    }
+}
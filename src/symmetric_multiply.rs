@@ -0,0 +1,948 @@
+//! Symmetric and Hermitian matrix products.
+//!
+//! Products of the form $AA^T$ or $AA^H$ are symmetric (Hermitian), so only one triangular
+//! part needs to be computed. Computing them through [MatMul](crate::matrix_multiply::MatMul)
+//! doubles the number of flops and in floating point arithmetic does not guarantee an exactly
+//! symmetric result. The trait [SymmetricMatMul] provides the corresponding Blas level 3
+//! operations that only ever touch one triangular part of the symmetric (Hermitian) matrix.
+//!
+//! - [syrk](SymmetricMatMul::syrk): `mat_c = alpha * op(mat_a) * op(mat_a)^T + beta * mat_c`.
+//! - [herk](SymmetricMatMul::herk): `mat_c = alpha * op(mat_a) * op(mat_a)^H + beta * mat_c`.
+//! - [syr2k](SymmetricMatMul::syr2k): `mat_c = alpha * op(mat_a) * op(mat_b)^T + alpha * op(mat_b) * op(mat_a)^T + beta * mat_c`.
+//! - [her2k](SymmetricMatMul::her2k): `mat_c = alpha * op(mat_a) * op(mat_b)^H + conj(alpha) * op(mat_b) * op(mat_a)^H + beta * mat_c`.
+//! - [symm](SymmetricMatMul::symm): `mat_c = alpha * mat_a * mat_b + beta * mat_c` (or `mat_b * mat_a`)
+//!   with `mat_a` symmetric.
+//! - [hemm](SymmetricMatMul::hemm): As [symm](SymmetricMatMul::symm) but with `mat_a` Hermitian.
+//!
+//! For the rank-k updates only the triangular part of `mat_c` specified by a [TriangularType]
+//! is referenced and updated. For [symm](SymmetricMatMul::symm) and
+//! [hemm](SymmetricMatMul::hemm) only the specified triangular part of `mat_a` is read.
+//! As in Blas, if `beta` is zero then `mat_c` need not be initialised on input.
+//!
+//! The operations accept matrices of any layout. The factors are copied into column major
+//! matrices. The rank-k updates recursively split `mat_c` into two diagonal blocks and one
+//! off-diagonal block. The off-diagonal blocks are computed by
+//! [MatMul](crate::matrix_multiply::MatMul) and only the small diagonal blocks at the
+//! bottom of the recursion by element loops. The symmetric products expand the referenced
+//! triangle of `mat_a` and compute the product by [MatMul](crate::matrix_multiply::MatMul).
+
+use crate::data_container::VectorContainer;
+use crate::dense_solvers::column_major_copy;
+use crate::layouts::ColumnMajor;
+use crate::matrix::{Matrix, MatrixD};
+use crate::matrix_multiply::MatMul;
+use crate::traits::*;
+use crate::types::*;
+
+/// Maximum dimension of the diagonal blocks of the rank-k updates that are computed
+/// by element loops.
+const DIAGONAL_BLOCK: IndexType = 32;
+
+/// Check that a matrix with dimension `dim` is square and return the number of rows.
+fn square_dim(dim: (IndexType, IndexType)) -> IndexType {
+    assert_eq!(
+        dim.0, dim.1,
+        "Matrix must be square for a symmetric update. dim = {:#?}",
+        dim
+    );
+    dim.0
+}
+
+/// Return the range of row indices of column `col` in the triangular part `uplo`
+/// of an `n x n` matrix.
+#[inline]
+fn triangle_rows(uplo: TriangularType, col: IndexType, n: IndexType) -> std::ops::Range<IndexType> {
+    match uplo {
+        TriangularType::Upper => 0..col + 1,
+        TriangularType::Lower => col..n,
+    }
+}
+
+/// Return the element `(row, col)` of `op(mat)` for the given transpose mode.
+#[inline]
+fn op_elem<
+    Item: HScalar,
+    MatImpl: MatrixTrait<Item, L, RS, CS>,
+    L: LayoutType,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+>(
+    trans: TransposeMode,
+    mat: &Matrix<Item, MatImpl, L, RS, CS>,
+    row: IndexType,
+    col: IndexType,
+) -> Item {
+    unsafe {
+        match trans {
+            TransposeMode::NoTrans => mat.get_unchecked(row, col),
+            TransposeMode::Trans => mat.get_unchecked(col, row),
+            TransposeMode::ConjTrans => mat.get_unchecked(col, row).conj(),
+        }
+    }
+}
+
+/// Return the dimension `(n, k)` of `op(mat)`.
+#[inline]
+fn op_dim(trans: TransposeMode, dim: (IndexType, IndexType)) -> (IndexType, IndexType) {
+    match trans {
+        TransposeMode::NoTrans => dim,
+        _ => (dim.1, dim.0),
+    }
+}
+
+/// Scale an existing entry of `mat_c` by `beta` and add `value`.
+#[inline]
+fn update_elem<Item: HScalar>(value: Item, beta: Item, elem: &mut Item) {
+    if beta == Item::zero() {
+        *elem = value;
+    } else {
+        *elem = value + beta * *elem;
+    }
+}
+
+/// Return a column major copy of `op(mat)`. If `conj` is set the transpose of `op(mat)`
+/// is conjugated, i.e. the result is `op(mat)^H` instead of `op(mat)^T`.
+fn op_copy<
+    Item: HScalar,
+    MatImpl: MatrixTrait<Item, L, RS, CS>,
+    L: LayoutType,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+>(
+    trans: TransposeMode,
+    mat: &Matrix<Item, MatImpl, L, RS, CS>,
+    transpose: bool,
+    conj: bool,
+) -> MatrixD<Item, ColumnMajor> {
+    let (m, n) = op_dim(trans, mat.layout().dim());
+    let (rows, cols) = if transpose { (n, m) } else { (m, n) };
+    let mut res = MatrixD::<Item, ColumnMajor>::zeros_from_dim(rows, cols);
+    for col in 0..n {
+        for row in 0..m {
+            let value = op_elem(trans, mat, row, col);
+            unsafe {
+                if transpose {
+                    *res.get_unchecked_mut(col, row) = if conj { value.conj() } else { value };
+                } else {
+                    *res.get_unchecked_mut(row, col) = value;
+                }
+            }
+        }
+    }
+    res
+}
+
+/// A term `coeff * left * right` of a rank-k or rank-2k update with an `n x k` matrix
+/// `left` and a `k x n` matrix `right`.
+struct UpdateTerm<Item: HScalar> {
+    coeff: Item,
+    left: MatrixD<Item, ColumnMajor>,
+    right: MatrixD<Item, ColumnMajor>,
+}
+
+impl<Item: HScalar> UpdateTerm<Item> {
+    /// Return the term restricted to the rows and columns `start..end` of the product.
+    fn restrict(&self, start: IndexType, end: IndexType) -> Self {
+        let k = self.left.layout().dim().1;
+        Self {
+            coeff: self.coeff,
+            left: column_major_copy(&self.left.block((start, 0), (end - start, k))),
+            right: column_major_copy(&self.right.block((0, start), (k, end - start))),
+        }
+    }
+}
+
+/// Update the triangle `uplo` of the diagonal block of `mat_c` that starts at `(offset, offset)`
+/// with the sum of the `terms`. Off-diagonal blocks are computed by [MatMul] and the diagonal
+/// blocks are split until they are small enough for element loops.
+fn update_triangle<
+    Item: SymmetricMatMul,
+    MatImpl: MatrixTraitMut<Item, L, RS, CS>,
+    L: LayoutType,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+>(
+    uplo: TriangularType,
+    terms: &[UpdateTerm<Item>],
+    beta: Item,
+    mat_c: &mut Matrix<Item, MatImpl, L, RS, CS>,
+    offset: IndexType,
+    hermitian: bool,
+) {
+    let (n, k) = terms[0].left.layout().dim();
+
+    if n <= DIAGONAL_BLOCK {
+        for col in 0..n {
+            for row in triangle_rows(uplo, col, n) {
+                let mut value = Item::zero();
+                for term in terms {
+                    let mut sum = Item::zero();
+                    for l in 0..k {
+                        sum += unsafe {
+                            term.left.get_unchecked(row, l) * term.right.get_unchecked(l, col)
+                        };
+                    }
+                    value += term.coeff * sum;
+                }
+                let elem = unsafe { mat_c.get_unchecked_mut(offset + row, offset + col) };
+                update_elem(value, beta, elem);
+                if hermitian && row == col {
+                    *elem = Item::from_real(elem.re());
+                }
+            }
+        }
+        return;
+    }
+
+    let half = n / 2;
+    let first: Vec<UpdateTerm<Item>> = terms.iter().map(|term| term.restrict(0, half)).collect();
+    let second: Vec<UpdateTerm<Item>> = terms.iter().map(|term| term.restrict(half, n)).collect();
+
+    // The off-diagonal block is (second x first) for the lower and (first x second)
+    // for the upper triangle.
+    let (rows, cols, row_start, col_start) = match uplo {
+        TriangularType::Lower => (&second, &first, half, 0),
+        TriangularType::Upper => (&first, &second, 0, half),
+    };
+    let (m, p) = (
+        rows[0].left.layout().dim().0,
+        cols[0].right.layout().dim().1,
+    );
+    let mut block = MatrixD::<Item, ColumnMajor>::zeros_from_dim(m, p);
+    for (index, (row_term, col_term)) in rows.iter().zip(cols.iter()).enumerate() {
+        let block_beta = if index == 0 {
+            Item::zero()
+        } else {
+            Item::one()
+        };
+        Item::matmul(
+            row_term.coeff,
+            &row_term.left,
+            &col_term.right,
+            block_beta,
+            &mut block,
+        );
+    }
+    for col in 0..p {
+        for row in 0..m {
+            unsafe {
+                update_elem(
+                    block.get_unchecked(row, col),
+                    beta,
+                    mat_c.get_unchecked_mut(offset + row_start + row, offset + col_start + col),
+                );
+            }
+        }
+    }
+
+    update_triangle(uplo, &first, beta, mat_c, offset, hermitian);
+    update_triangle(uplo, &second, beta, mat_c, offset + half, hermitian);
+}
+
+/// Common implementation of the rank-k and rank-2k updates. If `mat_b` is `None`
+/// a rank-k update with `mat_a` is performed.
+#[allow(clippy::too_many_arguments)]
+fn syr2k_impl<
+    Item: SymmetricMatMul,
+    MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+    MatImpl2: MatrixTrait<Item, L2, RS2, CS2>,
+    MatImpl3: MatrixTraitMut<Item, L3, RS3, CS3>,
+    L1: LayoutType,
+    L2: LayoutType,
+    L3: LayoutType,
+    RS1: SizeIdentifier,
+    CS1: SizeIdentifier,
+    RS2: SizeIdentifier,
+    CS2: SizeIdentifier,
+    RS3: SizeIdentifier,
+    CS3: SizeIdentifier,
+>(
+    uplo: TriangularType,
+    trans: TransposeMode,
+    alpha: Item,
+    mat_a: &Matrix<Item, MatImpl1, L1, RS1, CS1>,
+    mat_b: Option<&Matrix<Item, MatImpl2, L2, RS2, CS2>>,
+    beta: Item,
+    mat_c: &mut Matrix<Item, MatImpl3, L3, RS3, CS3>,
+    hermitian: bool,
+) {
+    let n = square_dim(mat_c.layout().dim());
+    let (rows_a, k) = op_dim(trans, mat_a.layout().dim());
+    assert_eq!(
+        rows_a,
+        n,
+        "Incompatible dimensions for symmetric update: op(A) = {:#?}, C = {:#?}",
+        (rows_a, k),
+        mat_c.layout().dim()
+    );
+    if let Some(mat_b) = mat_b {
+        assert_eq!(
+            op_dim(trans, mat_b.layout().dim()),
+            (n, k),
+            "Incompatible dimensions for symmetric update: op(A) = {:#?}, op(B) = {:#?}",
+            (n, k),
+            op_dim(trans, mat_b.layout().dim())
+        );
+    }
+    if n == 0 {
+        return;
+    }
+
+    // For Hermitian updates the second factor is conjugated.
+    let terms = match mat_b {
+        None => vec![UpdateTerm {
+            coeff: alpha,
+            left: op_copy(trans, mat_a, false, false),
+            right: op_copy(trans, mat_a, true, hermitian),
+        }],
+        Some(mat_b) => vec![
+            UpdateTerm {
+                coeff: alpha,
+                left: op_copy(trans, mat_a, false, false),
+                right: op_copy(trans, mat_b, true, hermitian),
+            },
+            UpdateTerm {
+                coeff: if hermitian { alpha.conj() } else { alpha },
+                left: op_copy(trans, mat_b, false, false),
+                right: op_copy(trans, mat_a, true, hermitian),
+            },
+        ],
+    };
+
+    update_triangle(uplo, &terms, beta, mat_c, 0, hermitian);
+}
+
+/// Common implementation of [symm](SymmetricMatMul::symm) and [hemm](SymmetricMatMul::hemm).
+#[allow(clippy::too_many_arguments)]
+fn symm_impl<
+    Item: SymmetricMatMul,
+    MatImpl1: MatrixTrait<Item, L1, RS1, CS1>,
+    MatImpl2: MatrixTrait<Item, L2, RS2, CS2>,
+    MatImpl3: MatrixTraitMut<Item, L3, RS3, CS3>,
+    L1: LayoutType,
+    L2: LayoutType,
+    L3: LayoutType,
+    RS1: SizeIdentifier,
+    CS1: SizeIdentifier,
+    RS2: SizeIdentifier,
+    CS2: SizeIdentifier,
+    RS3: SizeIdentifier,
+    CS3: SizeIdentifier,
+>(
+    side: Side,
+    uplo: TriangularType,
+    alpha: Item,
+    mat_a: &Matrix<Item, MatImpl1, L1, RS1, CS1>,
+    mat_b: &Matrix<Item, MatImpl2, L2, RS2, CS2>,
+    beta: Item,
+    mat_c: &mut Matrix<Item, MatImpl3, L3, RS3, CS3>,
+    hermitian: bool,
+) {
+    let dim_a = square_dim(mat_a.layout().dim());
+    let dim_b = mat_b.layout().dim();
+    let dim_c = mat_c.layout().dim();
+    let compatible = match side {
+        Side::Left => dim_b.0 == dim_a,
+        Side::Right => dim_b.1 == dim_a,
+    };
+    assert!(
+        compatible & (dim_b == dim_c),
+        "Incompatible dimensions for symmetric product: A = {:#?}, B = {:#?}, C = {:#?}",
+        mat_a.layout().dim(),
+        dim_b,
+        dim_c
+    );
+
+    // The full symmetric (Hermitian) matrix, reading only `uplo`.
+    let mut full = MatrixD::<Item, ColumnMajor>::zeros_from_dim(dim_a, dim_a);
+    for col in 0..dim_a {
+        for row in triangle_rows(uplo, col, dim_a) {
+            unsafe {
+                let value = mat_a.get_unchecked(row, col);
+                if row == col {
+                    *full.get_unchecked_mut(row, col) = if hermitian {
+                        Item::from_real(value.re())
+                    } else {
+                        value
+                    };
+                } else {
+                    *full.get_unchecked_mut(row, col) = value;
+                    *full.get_unchecked_mut(col, row) =
+                        if hermitian { value.conj() } else { value };
+                }
+            }
+        }
+    }
+
+    let mat_b = column_major_copy(mat_b);
+    let mut res = MatrixD::<Item, ColumnMajor>::zeros_from_dim(dim_c.0, dim_c.1);
+    match side {
+        Side::Left => Item::matmul(alpha, &full, &mat_b, Item::zero(), &mut res),
+        Side::Right => Item::matmul(alpha, &mat_b, &full, Item::zero(), &mut res),
+    }
+
+    for col in 0..dim_c.1 {
+        for row in 0..dim_c.0 {
+            unsafe {
+                update_elem(
+                    res.get_unchecked(row, col),
+                    beta,
+                    mat_c.get_unchecked_mut(row, col),
+                );
+            }
+        }
+    }
+}
+
+/// Symmetric and Hermitian Blas level 3 operations. See the
+/// [module description](crate::symmetric_multiply) for details.
+///
+/// The trait is implemented for all [HScalar] types that provide column major matrix
+/// products through [MatMul]. Its methods are called as `f64::syrk(...)` analogous
+/// to [MatMul].
+pub trait SymmetricMatMul:
+    HScalar
+    + MatMul<
+        Self,
+        ColumnMajor,
+        ColumnMajor,
+        ColumnMajor,
+        VectorContainer<Self>,
+        VectorContainer<Self>,
+        VectorContainer<Self>,
+        Dynamic,
+        Dynamic,
+        Dynamic,
+        Dynamic,
+        Dynamic,
+        Dynamic,
+    >
+{
+    /// Perform the symmetric rank-k update `mat_c = alpha * op(mat_a) * op(mat_a)^T + beta * mat_c`.
+    ///
+    /// `trans` must be [TransposeMode::NoTrans] (`op(mat_a) = mat_a`) or
+    /// [TransposeMode::Trans] (`op(mat_a) = mat_a^T`). Only the triangular part `uplo` of
+    /// `mat_c` is updated.
+    fn syrk<
+        MatImpl1: MatrixTrait<Self, L1, RS1, CS1>,
+        MatImpl2: MatrixTraitMut<Self, L2, RS2, CS2>,
+        L1: LayoutType,
+        L2: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    >(
+        uplo: TriangularType,
+        trans: TransposeMode,
+        alpha: Self,
+        mat_a: &Matrix<Self, MatImpl1, L1, RS1, CS1>,
+        beta: Self,
+        mat_c: &mut Matrix<Self, MatImpl2, L2, RS2, CS2>,
+    ) {
+        assert!(
+            trans != TransposeMode::ConjTrans,
+            "'syrk' does not support conjugate transposes. Use 'herk' instead."
+        );
+        syr2k_impl(
+            uplo,
+            trans,
+            alpha,
+            mat_a,
+            None::<&Matrix<Self, MatImpl1, L1, RS1, CS1>>,
+            beta,
+            mat_c,
+            false,
+        );
+    }
+
+    /// Perform the Hermitian rank-k update `mat_c = alpha * op(mat_a) * op(mat_a)^H + beta * mat_c`.
+    ///
+    /// `trans` must be [TransposeMode::NoTrans] (`op(mat_a) = mat_a`) or
+    /// [TransposeMode::ConjTrans] (`op(mat_a) = mat_a^H`). The scalars `alpha` and `beta`
+    /// are real. Only the triangular part `uplo` of `mat_c` is updated and the imaginary
+    /// parts of its diagonal are set to zero.
+    fn herk<
+        MatImpl1: MatrixTrait<Self, L1, RS1, CS1>,
+        MatImpl2: MatrixTraitMut<Self, L2, RS2, CS2>,
+        L1: LayoutType,
+        L2: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    >(
+        uplo: TriangularType,
+        trans: TransposeMode,
+        alpha: <Self as Scalar>::Real,
+        mat_a: &Matrix<Self, MatImpl1, L1, RS1, CS1>,
+        beta: <Self as Scalar>::Real,
+        mat_c: &mut Matrix<Self, MatImpl2, L2, RS2, CS2>,
+    ) {
+        assert!(
+            trans != TransposeMode::Trans,
+            "'herk' does not support plain transposes. Use 'syrk' instead."
+        );
+        syr2k_impl(
+            uplo,
+            trans,
+            Self::from_real(alpha),
+            mat_a,
+            None::<&Matrix<Self, MatImpl1, L1, RS1, CS1>>,
+            Self::from_real(beta),
+            mat_c,
+            true,
+        );
+    }
+
+    /// Perform the symmetric rank-2k update
+    /// `mat_c = alpha * op(mat_a) * op(mat_b)^T + alpha * op(mat_b) * op(mat_a)^T + beta * mat_c`.
+    ///
+    /// `trans` must be [TransposeMode::NoTrans] or [TransposeMode::Trans]. Only the
+    /// triangular part `uplo` of `mat_c` is updated.
+    #[allow(clippy::too_many_arguments)]
+    fn syr2k<
+        MatImpl1: MatrixTrait<Self, L1, RS1, CS1>,
+        MatImpl2: MatrixTrait<Self, L2, RS2, CS2>,
+        MatImpl3: MatrixTraitMut<Self, L3, RS3, CS3>,
+        L1: LayoutType,
+        L2: LayoutType,
+        L3: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+        RS3: SizeIdentifier,
+        CS3: SizeIdentifier,
+    >(
+        uplo: TriangularType,
+        trans: TransposeMode,
+        alpha: Self,
+        mat_a: &Matrix<Self, MatImpl1, L1, RS1, CS1>,
+        mat_b: &Matrix<Self, MatImpl2, L2, RS2, CS2>,
+        beta: Self,
+        mat_c: &mut Matrix<Self, MatImpl3, L3, RS3, CS3>,
+    ) {
+        assert!(
+            trans != TransposeMode::ConjTrans,
+            "'syr2k' does not support conjugate transposes. Use 'her2k' instead."
+        );
+        syr2k_impl(uplo, trans, alpha, mat_a, Some(mat_b), beta, mat_c, false);
+    }
+
+    /// Perform the Hermitian rank-2k update
+    /// `mat_c = alpha * op(mat_a) * op(mat_b)^H + conj(alpha) * op(mat_b) * op(mat_a)^H + beta * mat_c`.
+    ///
+    /// `trans` must be [TransposeMode::NoTrans] or [TransposeMode::ConjTrans]. The scalar
+    /// `beta` is real. Only the triangular part `uplo` of `mat_c` is updated and the imaginary
+    /// parts of its diagonal are set to zero.
+    #[allow(clippy::too_many_arguments)]
+    fn her2k<
+        MatImpl1: MatrixTrait<Self, L1, RS1, CS1>,
+        MatImpl2: MatrixTrait<Self, L2, RS2, CS2>,
+        MatImpl3: MatrixTraitMut<Self, L3, RS3, CS3>,
+        L1: LayoutType,
+        L2: LayoutType,
+        L3: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+        RS3: SizeIdentifier,
+        CS3: SizeIdentifier,
+    >(
+        uplo: TriangularType,
+        trans: TransposeMode,
+        alpha: Self,
+        mat_a: &Matrix<Self, MatImpl1, L1, RS1, CS1>,
+        mat_b: &Matrix<Self, MatImpl2, L2, RS2, CS2>,
+        beta: <Self as Scalar>::Real,
+        mat_c: &mut Matrix<Self, MatImpl3, L3, RS3, CS3>,
+    ) {
+        assert!(
+            trans != TransposeMode::Trans,
+            "'her2k' does not support plain transposes. Use 'syr2k' instead."
+        );
+        syr2k_impl(
+            uplo,
+            trans,
+            alpha,
+            mat_a,
+            Some(mat_b),
+            Self::from_real(beta),
+            mat_c,
+            true,
+        );
+    }
+
+    /// Perform `mat_c = alpha * mat_a * mat_b + beta * mat_c` ([Side::Left]) or
+    /// `mat_c = alpha * mat_b * mat_a + beta * mat_c` ([Side::Right]) for a symmetric
+    /// matrix `mat_a` of which only the triangular part `uplo` is read.
+    #[allow(clippy::too_many_arguments)]
+    fn symm<
+        MatImpl1: MatrixTrait<Self, L1, RS1, CS1>,
+        MatImpl2: MatrixTrait<Self, L2, RS2, CS2>,
+        MatImpl3: MatrixTraitMut<Self, L3, RS3, CS3>,
+        L1: LayoutType,
+        L2: LayoutType,
+        L3: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+        RS3: SizeIdentifier,
+        CS3: SizeIdentifier,
+    >(
+        side: Side,
+        uplo: TriangularType,
+        alpha: Self,
+        mat_a: &Matrix<Self, MatImpl1, L1, RS1, CS1>,
+        mat_b: &Matrix<Self, MatImpl2, L2, RS2, CS2>,
+        beta: Self,
+        mat_c: &mut Matrix<Self, MatImpl3, L3, RS3, CS3>,
+    ) {
+        symm_impl(side, uplo, alpha, mat_a, mat_b, beta, mat_c, false);
+    }
+
+    /// Perform `mat_c = alpha * mat_a * mat_b + beta * mat_c` ([Side::Left]) or
+    /// `mat_c = alpha * mat_b * mat_a + beta * mat_c` ([Side::Right]) for a Hermitian
+    /// matrix `mat_a` of which only the triangular part `uplo` is read. The imaginary
+    /// parts of the diagonal of `mat_a` are assumed to be zero.
+    #[allow(clippy::too_many_arguments)]
+    fn hemm<
+        MatImpl1: MatrixTrait<Self, L1, RS1, CS1>,
+        MatImpl2: MatrixTrait<Self, L2, RS2, CS2>,
+        MatImpl3: MatrixTraitMut<Self, L3, RS3, CS3>,
+        L1: LayoutType,
+        L2: LayoutType,
+        L3: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+        RS3: SizeIdentifier,
+        CS3: SizeIdentifier,
+    >(
+        side: Side,
+        uplo: TriangularType,
+        alpha: Self,
+        mat_a: &Matrix<Self, MatImpl1, L1, RS1, CS1>,
+        mat_b: &Matrix<Self, MatImpl2, L2, RS2, CS2>,
+        beta: Self,
+        mat_c: &mut Matrix<Self, MatImpl3, L3, RS3, CS3>,
+    ) {
+        symm_impl(side, uplo, alpha, mat_a, mat_b, beta, mat_c, true);
+    }
+}
+
+impl<Item> SymmetricMatMul for Item where
+    Item: HScalar
+        + MatMul<
+            Item,
+            ColumnMajor,
+            ColumnMajor,
+            ColumnMajor,
+            VectorContainer<Item>,
+            VectorContainer<Item>,
+            VectorContainer<Item>,
+            Dynamic,
+            Dynamic,
+            Dynamic,
+            Dynamic,
+            Dynamic,
+            Dynamic,
+        >
+{
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+    use crate::test_utils::{assert_close, op_element};
+    use crate::tools::RandScalar;
+    use rand::prelude::*;
+    use rand_distr::StandardNormal;
+
+    /// Check that the triangle `uplo` of `actual` agrees with `expect` and the
+    /// other triangle agrees with `original`.
+    fn check_triangle<Item: HScalar>(
+        uplo: TriangularType,
+        actual: &MatrixD<Item, RowMajor>,
+        expect: &MatrixD<Item, RowMajor>,
+        original: &MatrixD<Item, RowMajor>,
+    ) {
+        let n = actual.dim().0;
+        for row in 0..n {
+            for col in 0..n {
+                let in_triangle = match uplo {
+                    TriangularType::Upper => row <= col,
+                    TriangularType::Lower => row >= col,
+                };
+                if in_triangle {
+                    assert_close(actual.get(row, col), expect.get(row, col), 1E-4);
+                } else {
+                    assert_eq!(actual.get(row, col), original.get(row, col));
+                }
+            }
+        }
+    }
+
+    macro_rules! syrk_test {
+        ($HScalar:ty, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+                let dist = StandardNormal;
+                let alpha = <$HScalar>::random_scalar(&mut rng, &dist);
+                let beta = <$HScalar>::random_scalar(&mut rng, &dist);
+
+                for (m, p) in [(5, 3), (70, 20)] {
+                    let mut mat_a = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(m, p);
+                    mat_a.fill_from_rand_standard_normal(&mut rng);
+
+                    for (trans, n, k) in
+                        [(TransposeMode::NoTrans, m, p), (TransposeMode::Trans, p, m)]
+                    {
+                        for uplo in [TriangularType::Upper, TriangularType::Lower] {
+                            let mut mat_c = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                            mat_c.fill_from_rand_standard_normal(&mut rng);
+                            let original = (1.0 * &mat_c).eval();
+
+                            let mut expect = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                            for row in 0..n {
+                                for col in 0..n {
+                                    let mut value = 0.0;
+                                    for l in 0..k {
+                                        value += op_element(&mat_a, trans, row, l)
+                                            * op_element(&mat_a, trans, col, l);
+                                    }
+                                    *expect.get_mut(row, col) =
+                                        alpha * value + beta * mat_c.get(row, col);
+                                }
+                            }
+
+                            <$HScalar>::syrk(uplo, trans, alpha, &mat_a, beta, &mut mat_c);
+                            check_triangle(uplo, &mat_c, &expect, &original);
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    macro_rules! herk_test {
+        ($HScalar:ty, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+                let alpha = 1.5;
+                let beta = 0.5;
+
+                for (m, p) in [(4, 6), (20, 70)] {
+                    let mut mat_a = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(m, p);
+                    mat_a.fill_from_rand_standard_normal(&mut rng);
+
+                    for (trans, n, k) in [
+                        (TransposeMode::NoTrans, m, p),
+                        (TransposeMode::ConjTrans, p, m),
+                    ] {
+                        for uplo in [TriangularType::Upper, TriangularType::Lower] {
+                            let mut mat_c = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                            mat_c.fill_from_rand_standard_normal(&mut rng);
+                            for index in 0..n {
+                                *mat_c.get_mut(index, index) =
+                                    <$HScalar>::from_real(mat_c.get(index, index).re());
+                            }
+                            let original = (<$HScalar>::from_real(1.0) * &mat_c).eval();
+
+                            let mut expect = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                            for row in 0..n {
+                                for col in 0..n {
+                                    let mut value = <$HScalar>::from_real(0.0);
+                                    for l in 0..k {
+                                        value += op_element(&mat_a, trans, row, l)
+                                            * op_element(&mat_a, trans, col, l).conj();
+                                    }
+                                    *expect.get_mut(row, col) = <$HScalar>::from_real(alpha)
+                                        * value
+                                        + <$HScalar>::from_real(beta) * mat_c.get(row, col);
+                                }
+                            }
+
+                            <$HScalar>::herk(uplo, trans, alpha, &mat_a, beta, &mut mat_c);
+                            check_triangle(uplo, &mat_c, &expect, &original);
+                            for index in 0..n {
+                                assert_eq!(mat_c.get(index, index).im(), 0.0);
+                            }
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    macro_rules! syr2k_test {
+        ($HScalar:ty, $fname:ident, $method:ident, $trans:expr, $conj:expr, $beta:expr) => {
+            #[test]
+            fn $fname() {
+                let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+                let dist = StandardNormal;
+                let alpha = <$HScalar>::random_scalar(&mut rng, &dist);
+                let alpha2 = if $conj { alpha.conj() } else { alpha };
+                let second = |value: $HScalar| if $conj { value.conj() } else { value };
+
+                for (m, p) in [(4, 3), (70, 10)] {
+                    let mut mat_a = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(m, p);
+                    let mut mat_b = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(m, p);
+                    mat_a.fill_from_rand_standard_normal(&mut rng);
+                    mat_b.fill_from_rand_standard_normal(&mut rng);
+
+                    for (trans, n, k) in [(TransposeMode::NoTrans, m, p), ($trans, p, m)] {
+                        for uplo in [TriangularType::Upper, TriangularType::Lower] {
+                            let mut mat_c = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                            mat_c.fill_from_rand_standard_normal(&mut rng);
+                            for index in 0..n {
+                                *mat_c.get_mut(index, index) =
+                                    <$HScalar>::from_real(mat_c.get(index, index).re());
+                            }
+                            let one = <$HScalar>::from_real(1.0);
+                            let original = (one * &mat_c).eval();
+
+                            let mut expect = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                            for row in 0..n {
+                                for col in 0..n {
+                                    let mut value = mat_c.get(row, col);
+                                    for l in 0..k {
+                                        value += alpha
+                                            * op_element(&mat_a, trans, row, l)
+                                            * second(op_element(&mat_b, trans, col, l))
+                                            + alpha2
+                                                * op_element(&mat_b, trans, row, l)
+                                                * second(op_element(&mat_a, trans, col, l));
+                                    }
+                                    *expect.get_mut(row, col) = value;
+                                }
+                            }
+
+                            <$HScalar>::$method(
+                                uplo, trans, alpha, &mat_a, &mat_b, $beta, &mut mat_c,
+                            );
+                            check_triangle(uplo, &mat_c, &expect, &original);
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    macro_rules! symm_test {
+        ($HScalar:ty, $fname:ident, $method:ident, $conj:expr) => {
+            #[test]
+            fn $fname() {
+                let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+                let dist = StandardNormal;
+                let n = 40;
+
+                // Build a full symmetric (Hermitian) matrix.
+                let mut mat_full = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                mat_full.fill_from_rand_standard_normal(&mut rng);
+                for row in 0..n {
+                    for col in 0..row {
+                        let value = mat_full.get(col, row);
+                        *mat_full.get_mut(row, col) = if $conj { value.conj() } else { value };
+                    }
+                    if $conj {
+                        *mat_full.get_mut(row, row) =
+                            <$HScalar>::from_real(mat_full.get(row, row).re());
+                    }
+                }
+
+                let alpha = <$HScalar>::random_scalar(&mut rng, &dist);
+                let beta = <$HScalar>::random_scalar(&mut rng, &dist);
+
+                for side in [Side::Left, Side::Right] {
+                    for uplo in [TriangularType::Upper, TriangularType::Lower] {
+                        // Only the triangle `uplo` is valid. The other triangle is garbage.
+                        let mut mat_a = MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(n, n);
+                        mat_a.fill_from_rand_standard_normal(&mut rng);
+                        for row in 0..n {
+                            for col in 0..n {
+                                let stored = match uplo {
+                                    TriangularType::Upper => row <= col,
+                                    TriangularType::Lower => row >= col,
+                                };
+                                if stored {
+                                    *mat_a.get_mut(row, col) = mat_full.get(row, col);
+                                }
+                            }
+                        }
+
+                        let dim_b = match side {
+                            Side::Left => (n, 3),
+                            Side::Right => (3, n),
+                        };
+                        let mut mat_b =
+                            MatrixD::<$HScalar, RowMajor>::zeros_from_dim(dim_b.0, dim_b.1);
+                        let mut mat_c =
+                            MatrixD::<$HScalar, RowMajor>::zeros_from_dim(dim_b.0, dim_b.1);
+                        mat_b.fill_from_rand_standard_normal(&mut rng);
+                        mat_c.fill_from_rand_standard_normal(&mut rng);
+
+                        let mut expect =
+                            MatrixD::<$HScalar, RowMajor>::zeros_from_dim(dim_b.0, dim_b.1);
+                        for row in 0..dim_b.0 {
+                            for col in 0..dim_b.1 {
+                                let mut value = <$HScalar>::from_real(0.0);
+                                for l in 0..n {
+                                    value += match side {
+                                        Side::Left => mat_full.get(row, l) * mat_b.get(l, col),
+                                        Side::Right => mat_b.get(row, l) * mat_full.get(l, col),
+                                    };
+                                }
+                                *expect.get_mut(row, col) =
+                                    alpha * value + beta * mat_c.get(row, col);
+                            }
+                        }
+
+                        <$HScalar>::$method(side, uplo, alpha, &mat_a, &mat_b, beta, &mut mat_c);
+
+                        for index in 0..mat_c.layout().number_of_elements() {
+                            assert_close(mat_c.get1d(index), expect.get1d(index), 1E-4);
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    syrk_test!(f32, test_syrk_f32);
+    syrk_test!(f64, test_syrk_f64);
+
+    herk_test!(c32, test_herk_c32);
+    herk_test!(c64, test_herk_c64);
+
+    syr2k_test!(f64, test_syr2k_f64, syr2k, TransposeMode::Trans, false, 1.0);
+    syr2k_test!(
+        c64,
+        test_syr2k_c64,
+        syr2k,
+        TransposeMode::Trans,
+        false,
+        c64::new(1.0, 0.0)
+    );
+    syr2k_test!(
+        c64,
+        test_her2k_c64,
+        her2k,
+        TransposeMode::ConjTrans,
+        true,
+        1.0
+    );
+
+    symm_test!(f32, test_symm_f32, symm, false);
+    symm_test!(f64, test_symm_f64, symm, false);
+    symm_test!(c64, test_symm_c64, symm, false);
+    symm_test!(c64, test_hemm_c64, hemm, true);
+}
@@ -0,0 +1,30 @@
+//! Helper functions shared by the unit tests.
+
+use crate::matrix::*;
+use crate::traits::*;
+use crate::types::*;
+
+/// Assert that `actual` and `expect` differ by less than `tol` in absolute value.
+pub(crate) fn assert_close<Item: HScalar>(actual: Item, expect: Item, tol: f64) {
+    let diff = (actual - expect).abs();
+    assert!(
+        diff < num::cast::<f64, <Item as Scalar>::Real>(tol).unwrap(),
+        "{} != {}",
+        actual,
+        expect
+    );
+}
+
+/// Return the element `(row, col)` of `op(mat)`.
+pub(crate) fn op_element<Item: HScalar, L: LayoutType>(
+    mat: &MatrixD<Item, L>,
+    trans: TransposeMode,
+    row: IndexType,
+    col: IndexType,
+) -> Item {
+    match trans {
+        TransposeMode::NoTrans => mat.get(row, col),
+        TransposeMode::Trans => mat.get(col, row),
+        TransposeMode::ConjTrans => mat.get(col, row).conj(),
+    }
+}
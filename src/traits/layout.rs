@@ -116,6 +116,16 @@ pub trait LayoutType {
     /// The number of elements in the matrix or vector.
     fn number_of_elements(&self) -> IndexType;
 
+    /// The number of raw memory positions needed to store a matrix with this layout.
+    ///
+    /// This is the [number of elements](LayoutType::number_of_elements) unless the raw
+    /// memory contains positions that are not part of the 1d index, such as the padding
+    /// of band storage.
+    #[inline]
+    fn raw_size(&self) -> IndexType {
+        self.number_of_elements()
+    }
+
     /// Convert a 1d logical `index` to a 2d `(row, col)` index.
     fn convert_1d_2d(&self, index: IndexType) -> (IndexType, IndexType);
 
@@ -131,6 +141,25 @@ pub trait LayoutType {
     /// Create a new index layout that is identical to the index layout of
     /// this layout.
     fn index_layout(&self) -> Self::IndexLayout;
+
+    /// Return `true` if the element at the logical `(row, col)` position is the complex
+    /// conjugate of the raw memory entry given by [convert_2d_raw](LayoutType::convert_2d_raw).
+    ///
+    /// This is used by Hermitian layouts that only store one triangular part. By default
+    /// no element is conjugated.
+    #[inline]
+    fn conjugate_2d(&self, _row: IndexType, _col: IndexType) -> bool {
+        false
+    }
+
+    /// Return `true` if the element at the logical `(row, col)` position can be accessed.
+    ///
+    /// Layouts that only store a band or a triangular part of a matrix return `false` for
+    /// the implicit zeros outside this part. By default every element is stored.
+    #[inline]
+    fn is_stored(&self, _row: IndexType, _col: IndexType) -> bool {
+        true
+    }
 }
 
 /// This layout provides a method to return layout information.
@@ -6,7 +6,7 @@
 //! - [RandomAccess]. Provides an interface to access matrix elements.
 //! - [Layout]. Provides an interface to obtain layout information for the matrix.
 //! - [SizeType]. Specifies whether the row/column dimension is known at compile time
-//!               or specified at runtime.
+//!   or specified at runtime.
 //!
 //! [MatrixTraitMut] additionally depends on the trait [RandomAccessMut] to provide
 //! mutable access to matrix elements.
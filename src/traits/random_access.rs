@@ -29,9 +29,15 @@ pub trait UnsafeRandomAccess {
     type Item: HScalar;
 
     /// Return the element at position (`row`, `col`).
+    ///
+    /// # Safety
+    /// `row` and `col` must be within the dimensions of the matrix.
     unsafe fn get_unchecked(&self, row: IndexType, col: IndexType) -> Self::Item;
 
     /// Return the element at position `index` in one-dimensional numbering.
+    ///
+    /// # Safety
+    /// `index` must be smaller than the number of elements of the matrix.
     unsafe fn get1d_unchecked(&self, index: IndexType) -> Self::Item;
 }
 
@@ -41,9 +47,15 @@ pub trait UnsafeRandomAccessMut {
     type Item: HScalar;
 
     /// Return a mutable reference to the element at position (`row`, `col`).
+    ///
+    /// # Safety
+    /// `row` and `col` must be within the dimensions of the matrix.
     unsafe fn get_unchecked_mut(&mut self, row: IndexType, col: IndexType) -> &mut Self::Item;
 
     /// Return a mutable reference at position `index` in one-dimensional numbering.
+    ///
+    /// # Safety
+    /// `index` must be smaller than the number of elements of the matrix.
     unsafe fn get1d_unchecked_mut(&mut self, index: IndexType) -> &mut Self::Item;
 }
 
@@ -112,6 +124,12 @@ impl<Item: HScalar, Mat: UnsafeRandomAccess<Item = Item> + Layout> RandomAccess
 impl<Item: HScalar, Mat: UnsafeRandomAccessMut<Item = Item> + Layout> RandomAccessMut for Mat {
     fn get_mut(&mut self, row: IndexType, col: IndexType) -> &mut Self::Item {
         assert_dimension(row, col, self.layout().dim());
+        assert!(
+            !self.layout().conjugate_2d(row, col),
+            "Element ({}, {}) is the conjugate of a stored element and cannot be mutably accessed.",
+            row,
+            col
+        );
         unsafe { self.get_unchecked_mut(row, col) }
     }
 
@@ -13,7 +13,7 @@
 //! - [Fixed2]. This type specifies a row/column of fixed dimension 2.
 //! - [Fixed3]. This type specifies a row/column of fixed dimension 3.
 //! - [Dynamic]. This type specifies a row/column dimension defined at runtime.
-//!             The corresponding constant [SizeIdentifier::N] is set to 0.
+//!   The corresponding constant [SizeIdentifier::N] is set to 0.
 //!
 
 /// Fixed Dimension 1.
@@ -0,0 +1,445 @@
+//! Multiplication with triangular matrices.
+//!
+//! This module provides the Blas operations `trmv` and `trmm` through the trait
+//! [TriangularMatMul].
+//!
+//! - [trmv](TriangularMatMul::trmv): `vec_x = op(mat_a) * vec_x`.
+//! - [trmm](TriangularMatMul::trmm): `mat_b = alpha * op(mat_a) * mat_b` ([Side::Left]) or
+//!   `mat_b = alpha * mat_b * op(mat_a)` ([Side::Right]).
+//!
+//! Here, `op(mat_a)` is `mat_a`, its transpose or its conjugate transpose as specified by
+//! a [TransposeMode]. Only the triangular part of `mat_a` given by a [TriangularType] is read
+//! and if [DiagonalType::Unit] is specified the diagonal is not referenced either. Hence,
+//! `mat_a` can be the triangular part of a dense matrix or a matrix in packed storage such as
+//! an [UpperTriangularMatrix](crate::matrix::UpperTriangularMatrix). Both operations overwrite
+//! their right-hand side in place and do not allocate.
+
+use crate::matrix::Matrix;
+use crate::traits::*;
+use crate::types::*;
+
+/// Wrapper for the element access of `op(mat_a)` for a triangular matrix `mat_a`.
+struct TriangularOp<'a, Item, MatImpl, L, RS, CS>
+where
+    Item: HScalar,
+    L: LayoutType,
+    RS: SizeIdentifier,
+    CS: SizeIdentifier,
+    MatImpl: MatrixTrait<Item, L, RS, CS>,
+{
+    mat: &'a Matrix<Item, MatImpl, L, RS, CS>,
+    trans: TransposeMode,
+    diag: DiagonalType,
+    dim: IndexType,
+    upper: bool,
+}
+
+impl<
+        'a,
+        Item: HScalar,
+        MatImpl: MatrixTrait<Item, L, RS, CS>,
+        L: LayoutType,
+        RS: SizeIdentifier,
+        CS: SizeIdentifier,
+    > TriangularOp<'a, Item, MatImpl, L, RS, CS>
+{
+    fn new(
+        mat: &'a Matrix<Item, MatImpl, L, RS, CS>,
+        uplo: TriangularType,
+        trans: TransposeMode,
+        diag: DiagonalType,
+    ) -> Self {
+        let dim = mat.layout().dim();
+        assert_eq!(
+            dim.0, dim.1,
+            "Triangular matrix must be square. dim = {:#?}",
+            dim
+        );
+        // Transposition turns an upper triangular matrix into a lower triangular one.
+        let upper = (uplo == TriangularType::Upper) == (trans == TransposeMode::NoTrans);
+        Self {
+            mat,
+            trans,
+            diag,
+            dim: dim.0,
+            upper,
+        }
+    }
+
+    /// Return the element `(row, col)` of `op(mat_a)`. The index
+    /// must be inside the nonzero triangle of `op(mat_a)`.
+    #[inline]
+    fn get(&self, row: IndexType, col: IndexType) -> Item {
+        if row == col && self.diag == DiagonalType::Unit {
+            return Item::one();
+        }
+        unsafe {
+            match self.trans {
+                TransposeMode::NoTrans => self.mat.get_unchecked(row, col),
+                TransposeMode::Trans => self.mat.get_unchecked(col, row),
+                TransposeMode::ConjTrans => self.mat.get_unchecked(col, row).conj(),
+            }
+        }
+    }
+
+    /// Compute `x = op(mat_a) * x` for a vector whose `i`th element is
+    /// accessed through `elem(i)`.
+    #[inline]
+    fn apply_left<F: FnMut(IndexType) -> *mut Item>(&self, mut elem: F) {
+        let n = self.dim;
+        unsafe {
+            if self.upper {
+                // x[i] only depends on x[j] for j >= i.
+                for row in 0..n {
+                    let mut value = Item::zero();
+                    for col in row..n {
+                        value += self.get(row, col) * *elem(col);
+                    }
+                    *elem(row) = value;
+                }
+            } else {
+                // x[i] only depends on x[j] for j <= i.
+                for row in (0..n).rev() {
+                    let mut value = Item::zero();
+                    for col in 0..row + 1 {
+                        value += self.get(row, col) * *elem(col);
+                    }
+                    *elem(row) = value;
+                }
+            }
+        }
+    }
+
+    /// Compute `x^T = x^T * op(mat_a)` for a vector whose `i`th element is
+    /// accessed through `elem(i)`.
+    #[inline]
+    fn apply_right<F: FnMut(IndexType) -> *mut Item>(&self, mut elem: F) {
+        let n = self.dim;
+        unsafe {
+            if self.upper {
+                // y[j] only depends on x[i] for i <= j.
+                for col in (0..n).rev() {
+                    let mut value = Item::zero();
+                    for row in 0..col + 1 {
+                        value += *elem(row) * self.get(row, col);
+                    }
+                    *elem(col) = value;
+                }
+            } else {
+                // y[j] only depends on x[i] for i >= j.
+                for col in 0..n {
+                    let mut value = Item::zero();
+                    for row in col..n {
+                        value += *elem(row) * self.get(row, col);
+                    }
+                    *elem(col) = value;
+                }
+            }
+        }
+    }
+}
+
+/// Triangular Blas level 2 and level 3 multiplications. See the
+/// [module description](crate::triangular_multiply) for details.
+///
+/// The trait is implemented for all [HScalar] types. Its methods are called as
+/// `f64::trmm(...)` analogous to [MatMul](crate::matrix_multiply::MatMul).
+pub trait TriangularMatMul: HScalar {
+    /// Compute `vec_x = op(mat_a) * vec_x` in place.
+    ///
+    /// `vec_x` is accessed through its one-dimensional indexing and can therefore
+    /// be a row or a column vector.
+    fn trmv<
+        MatImpl1: MatrixTrait<Self, L1, RS1, CS1>,
+        MatImpl2: MatrixTraitMut<Self, L2, RS2, CS2>,
+        L1: LayoutType,
+        L2: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    >(
+        uplo: TriangularType,
+        trans: TransposeMode,
+        diag: DiagonalType,
+        mat_a: &Matrix<Self, MatImpl1, L1, RS1, CS1>,
+        vec_x: &mut Matrix<Self, MatImpl2, L2, RS2, CS2>,
+    ) {
+        let op = TriangularOp::new(mat_a, uplo, trans, diag);
+        assert_eq!(
+            op.dim,
+            vec_x.layout().number_of_elements(),
+            "Incompatible dimensions for x = A * x: A = {:#?}, x = {:#?}",
+            mat_a.layout().dim(),
+            vec_x.layout().dim()
+        );
+        op.apply_left(|index| unsafe { vec_x.get1d_unchecked_mut(index) as *mut Self });
+    }
+
+    /// Compute `mat_b = alpha * op(mat_a) * mat_b` ([Side::Left]) or
+    /// `mat_b = alpha * mat_b * op(mat_a)` ([Side::Right]) in place.
+    ///
+    /// As in Blas, if `alpha` is zero then `mat_b` is set to zero and its input
+    /// values are not referenced.
+    fn trmm<
+        MatImpl1: MatrixTrait<Self, L1, RS1, CS1>,
+        MatImpl2: MatrixTraitMut<Self, L2, RS2, CS2>,
+        L1: LayoutType,
+        L2: LayoutType,
+        RS1: SizeIdentifier,
+        CS1: SizeIdentifier,
+        RS2: SizeIdentifier,
+        CS2: SizeIdentifier,
+    >(
+        side: Side,
+        uplo: TriangularType,
+        trans: TransposeMode,
+        diag: DiagonalType,
+        alpha: Self,
+        mat_a: &Matrix<Self, MatImpl1, L1, RS1, CS1>,
+        mat_b: &mut Matrix<Self, MatImpl2, L2, RS2, CS2>,
+    ) {
+        let op = TriangularOp::new(mat_a, uplo, trans, diag);
+        let dim_b = mat_b.layout().dim();
+        let compatible = match side {
+            Side::Left => dim_b.0 == op.dim,
+            Side::Right => dim_b.1 == op.dim,
+        };
+        assert!(
+            compatible,
+            "Incompatible dimensions for triangular product: A = {:#?}, B = {:#?}",
+            mat_a.layout().dim(),
+            dim_b
+        );
+
+        if alpha == Self::zero() {
+            for index in 0..mat_b.layout().number_of_elements() {
+                unsafe { *mat_b.get1d_unchecked_mut(index) = Self::zero() };
+            }
+            return;
+        }
+
+        match side {
+            Side::Left => {
+                for col in 0..dim_b.1 {
+                    op.apply_left(|row| unsafe { mat_b.get_unchecked_mut(row, col) as *mut Self });
+                }
+            }
+            Side::Right => {
+                for row in 0..dim_b.0 {
+                    op.apply_right(|col| unsafe { mat_b.get_unchecked_mut(row, col) as *mut Self });
+                }
+            }
+        }
+
+        if alpha != Self::one() {
+            for index in 0..mat_b.layout().number_of_elements() {
+                unsafe { *mat_b.get1d_unchecked_mut(index) *= alpha };
+            }
+        }
+    }
+}
+
+impl<Item: HScalar> TriangularMatMul for Item {}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::layouts::*;
+    use crate::matrix::*;
+    use crate::test_utils::{assert_close, op_element};
+    use crate::tools::RandScalar;
+    use rand::prelude::*;
+    use rand_distr::StandardNormal;
+
+    /// Return the triangular part `uplo` of `mat_a` as dense matrix. For a unit
+    /// diagonal the diagonal elements are one.
+    fn triangle<Item: HScalar>(
+        mat_a: &MatrixD<Item, RowMajor>,
+        uplo: TriangularType,
+        diag: DiagonalType,
+    ) -> MatrixD<Item, RowMajor> {
+        let n = mat_a.dim().0;
+        let mut res = MatrixD::<Item, RowMajor>::zeros_from_dim(n, n);
+        for row in 0..n {
+            for col in 0..n {
+                let stored = match uplo {
+                    TriangularType::Upper => row <= col,
+                    TriangularType::Lower => row >= col,
+                };
+                if row == col && diag == DiagonalType::Unit {
+                    *res.get_mut(row, col) = Item::one();
+                } else if stored {
+                    *res.get_mut(row, col) = mat_a.get(row, col);
+                }
+            }
+        }
+        res
+    }
+
+    const MODES: [(TriangularType, TransposeMode, DiagonalType); 6] = [
+        (
+            TriangularType::Upper,
+            TransposeMode::NoTrans,
+            DiagonalType::NonUnit,
+        ),
+        (
+            TriangularType::Upper,
+            TransposeMode::Trans,
+            DiagonalType::Unit,
+        ),
+        (
+            TriangularType::Upper,
+            TransposeMode::ConjTrans,
+            DiagonalType::NonUnit,
+        ),
+        (
+            TriangularType::Lower,
+            TransposeMode::NoTrans,
+            DiagonalType::Unit,
+        ),
+        (
+            TriangularType::Lower,
+            TransposeMode::Trans,
+            DiagonalType::NonUnit,
+        ),
+        (
+            TriangularType::Lower,
+            TransposeMode::ConjTrans,
+            DiagonalType::Unit,
+        ),
+    ];
+
+    macro_rules! trmm_test {
+        ($HScalar:ty, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+                let dist = StandardNormal;
+                let n = 5;
+                let mut mat_a = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                mat_a.fill_from_rand_standard_normal(&mut rng);
+                let alpha = <$HScalar>::random_scalar(&mut rng, &dist);
+
+                for (uplo, trans, diag) in MODES {
+                    let tri = triangle(&mat_a, uplo, diag);
+                    for side in [Side::Left, Side::Right] {
+                        let dim_b = match side {
+                            Side::Left => (n, 3),
+                            Side::Right => (3, n),
+                        };
+                        let mut mat_b =
+                            MatrixD::<$HScalar, ColumnMajor>::zeros_from_dim(dim_b.0, dim_b.1);
+                        mat_b.fill_from_rand_standard_normal(&mut rng);
+                        let mut mat_b_row =
+                            MatrixD::<$HScalar, RowMajor>::zeros_from_dim(dim_b.0, dim_b.1);
+                        for row in 0..dim_b.0 {
+                            for col in 0..dim_b.1 {
+                                *mat_b_row.get_mut(row, col) = alpha * mat_b.get(row, col);
+                            }
+                        }
+                        let mut expect =
+                            MatrixD::<$HScalar, RowMajor>::zeros_from_dim(dim_b.0, dim_b.1);
+                        for row in 0..dim_b.0 {
+                            for col in 0..dim_b.1 {
+                                for l in 0..n {
+                                    *expect.get_mut(row, col) += match side {
+                                        Side::Left => {
+                                            op_element(&tri, trans, row, l) * mat_b_row.get(l, col)
+                                        }
+                                        Side::Right => {
+                                            mat_b_row.get(row, l) * op_element(&tri, trans, l, col)
+                                        }
+                                    };
+                                }
+                            }
+                        }
+
+                        <$HScalar>::trmm(side, uplo, trans, diag, alpha, &mat_a, &mut mat_b);
+
+                        for row in 0..dim_b.0 {
+                            for col in 0..dim_b.1 {
+                                assert_close(mat_b.get(row, col), expect.get(row, col), 1E-4);
+                            }
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    macro_rules! trmv_packed_test {
+        ($HScalar:ty, $fname:ident) => {
+            #[test]
+            fn $fname() {
+                let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+                let n = 6;
+                let mut mat_a = MatrixD::<$HScalar, RowMajor>::zeros_from_dim(n, n);
+                mat_a.fill_from_rand_standard_normal(&mut rng);
+                let mut packed = UpperTriangularMatrix::<$HScalar>::zeros_from_dim(n, n);
+                for row in 0..n {
+                    for col in row..n {
+                        *packed.get_mut(row, col) = mat_a.get(row, col);
+                    }
+                }
+
+                for trans in [
+                    TransposeMode::NoTrans,
+                    TransposeMode::Trans,
+                    TransposeMode::ConjTrans,
+                ] {
+                    for diag in [DiagonalType::NonUnit, DiagonalType::Unit] {
+                        let tri = triangle(&mat_a, TriangularType::Upper, diag);
+                        let mut vec_x = ColumnVectorD::<$HScalar>::zeros_from_length(n);
+                        vec_x.fill_from_rand_standard_normal(&mut rng);
+
+                        let mut expect = vec![<$HScalar as num::Zero>::zero(); n];
+                        for row in 0..n {
+                            for col in 0..n {
+                                expect[row] += op_element(&tri, trans, row, col) * vec_x.get1d(col);
+                            }
+                        }
+
+                        <$HScalar>::trmv(TriangularType::Upper, trans, diag, &packed, &mut vec_x);
+
+                        for (index, &value) in expect.iter().enumerate() {
+                            assert_close(vec_x.get1d(index), value, 1E-4);
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    #[test]
+    fn test_trmm_zero_alpha() {
+        let mut mat_a = MatrixD::<f64, RowMajor>::zeros_from_dim(3, 3);
+        *mat_a.get_mut(0, 0) = 1.0;
+        for side in [Side::Left, Side::Right] {
+            let mut mat_b = MatrixD::<f64, ColumnMajor>::zeros_from_dim(3, 3);
+            *mat_b.get_mut(0, 0) = f64::NAN;
+            *mat_b.get_mut(1, 2) = f64::INFINITY;
+            f64::trmm(
+                side,
+                TriangularType::Upper,
+                TransposeMode::NoTrans,
+                DiagonalType::NonUnit,
+                0.0,
+                &mat_a,
+                &mut mat_b,
+            );
+            for index in 0..9 {
+                assert_eq!(mat_b.get1d(index), 0.0);
+            }
+        }
+    }
+
+    trmm_test!(f32, test_trmm_f32);
+    trmm_test!(f64, test_trmm_f64);
+    trmm_test!(c32, test_trmm_c32);
+    trmm_test!(c64, test_trmm_c64);
+
+    trmv_packed_test!(f64, test_trmv_packed_f64);
+    trmv_packed_test!(c64, test_trmv_packed_c64);
+}
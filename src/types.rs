@@ -4,8 +4,30 @@
 /// for [f32], [f64], [c32], [c64].
 pub use cauchy::Scalar;
 pub trait HScalar: cauchy::Scalar {
-    type Real;
+    /// The real type of the same precision, i.e. the type of the real and imaginary
+    /// parts and of the absolute value.
+    type Real: HScalar<Real = <Self as HScalar>::Real>;
     fn atan2(self, x: Self) -> Self;
+
+    /// Return the real part.
+    ///
+    /// This is cauchy's `re()` with the result typed as [HScalar::Real]. In generic code
+    /// the result of `re()` is only known to be a [Scalar], so it cannot be the element
+    /// of another [Matrix](crate::matrix::Matrix), e.g. of the lazy projections in
+    /// [conversion](crate::conversion).
+    fn real_part(self) -> <Self as HScalar>::Real;
+
+    /// Return the imaginary part, which is zero for real types. This is cauchy's `im()`
+    /// with the result typed as [HScalar::Real] (see [real_part](HScalar::real_part)).
+    fn imag_part(self) -> <Self as HScalar>::Real;
+
+    /// Return the absolute value. This is cauchy's `abs()` with the result typed as
+    /// [HScalar::Real] (see [real_part](HScalar::real_part)).
+    fn modulus(self) -> <Self as HScalar>::Real;
+
+    /// Convert a complex number of the same precision. For real types `None`
+    /// is returned if the imaginary part is nonzero.
+    fn from_complex(value: <Self as Scalar>::Complex) -> Option<Self>;
 }
 
 /// Single precision complex type.
@@ -21,30 +43,141 @@ pub type IndexType = usize;
 impl HScalar for f32 {
     type Real = f32;
 
+    fn real_part(self) -> f32 {
+        self
+    }
+
+    fn imag_part(self) -> f32 {
+        0.0
+    }
+
+    fn modulus(self) -> f32 {
+        self.abs()
+    }
+
     fn atan2(self, x: f32) -> f32 {
         self.atan2(x)
     }
+
+    fn from_complex(value: c32) -> Option<f32> {
+        if value.im == 0.0 {
+            Some(value.re)
+        } else {
+            None
+        }
+    }
 }
 
 impl HScalar for f64 {
     type Real = f64;
 
+    fn real_part(self) -> f64 {
+        self
+    }
+
+    fn imag_part(self) -> f64 {
+        0.0
+    }
+
+    fn modulus(self) -> f64 {
+        self.abs()
+    }
+
     fn atan2(self, x: f64) -> f64 {
         self.atan2(x)
     }
+
+    fn from_complex(value: c64) -> Option<f64> {
+        if value.im == 0.0 {
+            Some(value.re)
+        } else {
+            None
+        }
+    }
 }
 
 impl HScalar for c32 {
     type Real = f32;
 
+    fn real_part(self) -> f32 {
+        self.re
+    }
+
+    fn imag_part(self) -> f32 {
+        self.im
+    }
+
+    fn modulus(self) -> f32 {
+        self.norm()
+    }
+
     fn atan2(self, _x: c32) -> c32 {
         unimplemented!("atan2 is not defined for complex numbers");
     }
+
+    fn from_complex(value: c32) -> Option<c32> {
+        Some(value)
+    }
 }
 
 impl HScalar for c64 {
     type Real = f64;
+
+    fn real_part(self) -> f64 {
+        self.re
+    }
+
+    fn imag_part(self) -> f64 {
+        self.im
+    }
+
+    fn modulus(self) -> f64 {
+        self.norm()
+    }
+
     fn atan2(self, _x: c64) -> c64 {
         unimplemented!("atan2 is not defined for complex numbers");
     }
+
+    fn from_complex(value: c64) -> Option<c64> {
+        Some(value)
+    }
+}
+
+/// Specifies which triangular part of a matrix is referenced by an operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriangularType {
+    /// The upper triangular part including the diagonal.
+    Upper,
+    /// The lower triangular part including the diagonal.
+    Lower,
+}
+
+/// Specifies how an operand enters an operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransposeMode {
+    /// Use the operand as it is.
+    NoTrans,
+    /// Use the transpose of the operand.
+    Trans,
+    /// Use the conjugate transpose of the operand.
+    ConjTrans,
+}
+
+/// Specifies from which side a matrix is applied in a product.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    /// The matrix is applied from the left.
+    Left,
+    /// The matrix is applied from the right.
+    Right,
+}
+
+/// Specifies whether a triangular matrix has an implicit unit diagonal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagonalType {
+    /// The diagonal elements are read from the matrix.
+    NonUnit,
+    /// The diagonal elements are assumed to be one and are not referenced.
+    Unit,
 }